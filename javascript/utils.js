@@ -207,6 +207,10 @@ function __resource_type_num_to_str(num) {
         case 83: return RESOURCE_EMANATION;
         case 84: return RESOURCE_ESSENCE;
         case 1001: return SUBSCRIPTION_TOKEN;
+        // string literals, as older servers don't define these constants
+        case 1002: return "pixel";
+        case 1003: return "cpuUnlock";
+        case 1004: return "accessKey";
         default: throw new Error("unknown resource type integer encoding " + num);
     }
 }
@@ -298,6 +302,9 @@ function __resource_type_str_to_num(str) {
         case RESOURCE_EMANATION: return 83;
         case RESOURCE_ESSENCE: return 84;
         case SUBSCRIPTION_TOKEN: return 1001;
+        case "pixel": return 1002;
+        case "cpuUnlock": return 1003;
+        case "accessKey": return 1004;
         default: throw new Error("unknown resource type " + str);
     }
 }
@@ -0,0 +1,247 @@
+//! [`quickcheck::Arbitrary`] implementations for property testing code using
+//! the crate's geometric and constant types, enabled by the `quickcheck`
+//! feature.
+//!
+//! Room names cover the whole representable world, and shrink towards
+//! `E0N0`. Positions shrink their room name first, then their coordinates
+//! towards the room's origin at `(0, 0)`. Directions shrink towards
+//! [`Direction::Top`], and structure and resource types don't shrink.
+use std::iter;
+
+use num_traits::FromPrimitive;
+use quickcheck::{Arbitrary, Gen};
+
+use crate::{
+    constants::{Direction, ResourceType, StructureType},
+    local::{Position, RoomName},
+};
+
+/// The room names shrink towards.
+fn origin_room() -> RoomName {
+    RoomName::new("E0N0").expect("valid room name")
+}
+
+impl Arbitrary for RoomName {
+    fn arbitrary(g: &mut Gen) -> Self {
+        // every packed value is a valid room name
+        RoomName::from_packed(u16::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        // offsets shrink towards 0 without changing sign, so every room they
+        // reach lies between this room and the origin and is in bounds
+        let origin = origin_room();
+        let (dx, dy) = *self - origin;
+        let xs = shrink_offset(dx).map(move |dx| origin + (dx, dy));
+        let ys = shrink_offset(dy).map(move |dy| origin + (dx, dy));
+        Box::new(xs.chain(ys))
+    }
+}
+
+/// Offsets between 0 and `offset`, starting with 0 and getting closer to
+/// `offset` by halves.
+///
+/// Unlike quickcheck's shrinking of signed integers, this never flips the
+/// sign, which would take rooms on the west or north edge of the world out
+/// of bounds.
+fn shrink_offset(offset: i32) -> impl Iterator<Item = i32> {
+    let halves =
+        iter::successors(Some(offset / 2), |half| Some(half / 2)).take_while(|&half| half != 0);
+    iter::once(0)
+        .filter(move |_| offset != 0)
+        .chain(halves.map(move |half| offset - half))
+}
+
+impl Arbitrary for Position {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let room_name = RoomName::arbitrary(g);
+        let x = u32::from(u8::arbitrary(g) % 50);
+        let y = u32::from(u8::arbitrary(g) % 50);
+        Position::new(x, y, room_name)
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let position = *self;
+        let rooms = self
+            .room_name()
+            .shrink()
+            .map(move |room_name| position.with_room_name(room_name));
+        let coords = (self.x(), self.y())
+            .shrink()
+            .map(move |(x, y)| position.with_x(x).with_y(y));
+        Box::new(rooms.chain(coords))
+    }
+}
+
+impl Arbitrary for Direction {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Direction::from_u8(u8::arbitrary(g) % 8 + 1).expect("directions are 1 to 8")
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        match self {
+            Direction::Top => quickcheck::empty_shrinker(),
+            _ => quickcheck::single_shrinker(Direction::Top),
+        }
+    }
+}
+
+impl Arbitrary for StructureType {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let all: Vec<StructureType> = StructureType::iter_values().collect();
+        *g.choose(&all).expect("structure types aren't empty")
+    }
+}
+
+impl Arbitrary for ResourceType {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let all: Vec<ResourceType> = ResourceType::iter_values().collect();
+        *g.choose(&all).expect("resource types aren't empty")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_traits::FromPrimitive;
+    use quickcheck::{Arbitrary, QuickCheck, TestResult};
+
+    use crate::{
+        constants::Direction,
+        local::{Position, RoomName},
+    };
+
+    fn check<A: quickcheck::Testable>(property: A) {
+        QuickCheck::new().tests(1000).quickcheck(property);
+    }
+
+    /// Follows the first shrink of each step to the end.
+    fn fully_shrunk<A: Arbitrary>(mut value: A) -> A {
+        while let Some(next) = value.shrink().next() {
+            value = next;
+        }
+        value
+    }
+
+    #[test]
+    fn shrinking() {
+        let origin = RoomName::new("E0N0").unwrap();
+        let room = RoomName::new("W100S37").unwrap();
+        assert!(room.shrink().all(|name| name != room));
+        assert!(room
+            .shrink()
+            .all(|name| name.linear_distance_to(origin) <= room.linear_distance_to(origin)));
+        assert_eq!(fully_shrunk(room), origin);
+        assert_eq!(origin.shrink().count(), 0);
+
+        // rooms on the west and north edges have offsets of -128, which
+        // would be out of bounds as +128
+        let corner = RoomName::new("W127N127").unwrap();
+        let (dx, dy) = corner - origin;
+        let shrunk: Vec<RoomName> = corner.shrink().collect();
+        assert_eq!(shrunk[0], origin + (0, dy));
+        assert!(shrunk.contains(&(origin + (dx, 0))));
+        assert!(shrunk.iter().all(|&name| {
+            let (x, y) = name - origin;
+            (dx..=0).contains(&x) && (dy..=0).contains(&y) && name != corner
+        }));
+        assert_eq!(fully_shrunk(corner), origin);
+
+        let pos = Position::new(20, 30, room);
+        let shrunk: Vec<Position> = pos.shrink().collect();
+        assert!(shrunk.contains(&Position::new(0, 30, room)));
+        assert!(shrunk
+            .iter()
+            .all(|shrunk| shrunk.x() < 50 && shrunk.y() < 50 && *shrunk != pos));
+        assert_eq!(fully_shrunk(pos), Position::new(0, 0, origin));
+
+        assert_eq!(fully_shrunk(Direction::BottomLeft), Direction::Top);
+    }
+
+    #[test]
+    fn packing_round_trip() {
+        fn property(pos: Position) -> bool {
+            Position::from_packed(pos.packed_repr()) == pos
+                && Position::from_world_coords(pos.world_x(), pos.world_y()) == pos
+                && RoomName::new(&pos.room_name().to_string()).ok() == Some(pos.room_name())
+                && pos.room_name() == pos.room_name().to_array_string().as_str()
+        }
+        check(property as fn(Position) -> bool);
+    }
+
+    #[test]
+    fn distances() {
+        fn property(a: Position, b: Position, c: Position) -> bool {
+            let (dx, dy) = (a.world_x() - b.world_x(), a.world_y() - b.world_y());
+            a.get_range_to(&b) == b.get_range_to(&a)
+                && a.get_range_to(&b) == dx.unsigned_abs().max(dy.unsigned_abs())
+                && a.get_range_to(&c) <= a.get_range_to(&b) + b.get_range_to(&c)
+                && (a.get_range_to(&b) == 0) == (a == b)
+                && a.room_name().linear_distance_to(b.room_name())
+                    == b.room_name().linear_distance_to(a.room_name())
+        }
+        check(property as fn(Position, Position, Position) -> bool);
+
+        fn offsets(pos: Position, dx: i8, dy: i8) -> TestResult {
+            let (dx, dy) = (i32::from(dx), i32::from(dy));
+            let bounds = -128 * 50..128 * 50;
+            if !bounds.contains(&(pos.world_x() + dx)) || !bounds.contains(&(pos.world_y() + dy)) {
+                return TestResult::discard();
+            }
+            let moved = pos + (dx, dy);
+            TestResult::from_bool(
+                moved - pos == (dx, dy)
+                    && moved - (dx, dy) == pos
+                    && pos.get_range_to(&moved) == dx.unsigned_abs().max(dy.unsigned_abs()),
+            )
+        }
+        check(offsets as fn(Position, i8, i8) -> TestResult);
+    }
+
+    #[test]
+    fn room_transitions() {
+        fn property(pos: Position, direction: Direction) -> TestResult {
+            let on_edge = pos.x() == 0 || pos.x() == 49 || pos.y() == 0 || pos.y() == 49;
+            let moved = match pos.room_transition(Some(direction)) {
+                Some(moved) => moved,
+                None => return TestResult::from_bool(!on_edge || is_transition_blocked(pos)),
+            };
+            let moved_on_edge =
+                moved.x() == 0 || moved.x() == 49 || moved.y() == 0 || moved.y() == 49;
+            // from a corner, the direction might only cross one edge, so go
+            // back the way the creep came rather than the opposite direction
+            let (dx, dy) = pos - moved;
+            let back_direction = (1..=8)
+                .filter_map(Direction::from_u8)
+                .find(|back| back.delta() == (dx as i8, dy as i8));
+            let back = moved.room_transition(back_direction);
+            TestResult::from_bool(
+                on_edge
+                    && moved_on_edge
+                    && moved.room_name() != pos.room_name()
+                    && pos.get_range_to(&moved) == 1
+                    && back == Some(pos),
+            )
+        }
+
+        /// Whether an edge position has no transition for some directions:
+        /// corners, and the edges of the world.
+        fn is_transition_blocked(pos: Position) -> bool {
+            let corner = (pos.x() == 0 || pos.x() == 49) && (pos.y() == 0 || pos.y() == 49);
+            let world_edge = |coord: i32| coord == -128 * 50 || coord == 128 * 50 - 1;
+            corner || world_edge(pos.world_x()) || world_edge(pos.world_y())
+        }
+
+        // positions on the edges, as few arbitrary positions are
+        fn on_edge(pos: Position, side: u8, direction: Direction) -> TestResult {
+            let pos = match side % 4 {
+                0 => pos.with_x(0),
+                1 => pos.with_x(49),
+                2 => pos.with_y(0),
+                _ => pos.with_y(49),
+            };
+            property(pos, direction)
+        }
+        check(property as fn(Position, Direction) -> TestResult);
+        check(on_edge as fn(Position, u8, Direction) -> TestResult);
+    }
+}
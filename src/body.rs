@@ -0,0 +1,201 @@
+//! Calculations on creep bodies.
+//!
+//! [`body_cost`] and [`body_spawn_time`] give the energy and time needed to
+//! spawn a body, and [`is_valid_body`] checks a body before spending a spawn
+//! intent on it. [`creep_max_lifetime`], [`renew_ticks`] and [`renew_cost`]
+//! cover the lifetime of creeps once spawned.
+use std::{error::Error, fmt};
+
+use crate::constants::{
+    Part, CREEP_CLAIM_LIFE_TIME, CREEP_LIFE_TIME, CREEP_SPAWN_TIME, MAX_CREEP_SIZE,
+};
+
+/// [`SPAWN_RENEW_RATIO`] as the server's `f64`. Converting the `f32` constant
+/// keeps its rounding error, giving `1.2000000476837158`, which is enough to
+/// be off by one after rounding.
+///
+/// [`SPAWN_RENEW_RATIO`]: crate::constants::SPAWN_RENEW_RATIO
+const RENEW_RATIO: f64 = 1.2;
+
+/// The energy needed to spawn a creep with this body.
+pub fn body_cost(parts: &[Part]) -> u32 {
+    parts.iter().map(|part| part.cost()).sum()
+}
+
+/// The number of ticks needed to spawn a creep with this body, without any
+/// power effects.
+pub fn body_spawn_time(parts: &[Part]) -> u32 {
+    parts.len() as u32 * CREEP_SPAWN_TIME
+}
+
+/// The ticks to live of a newly spawned creep with this body: shorter for
+/// creeps with claim parts.
+pub fn creep_max_lifetime(parts: &[Part]) -> u32 {
+    if parts.contains(&Part::Claim) {
+        CREEP_CLAIM_LIFE_TIME
+    } else {
+        CREEP_LIFE_TIME
+    }
+}
+
+/// The ticks to live added each time a creep with `body_len` parts is
+/// renewed, rounded down as the server does.
+///
+/// Returns 0 for an empty body.
+pub fn renew_ticks(body_len: usize) -> u32 {
+    if body_len == 0 {
+        return 0;
+    }
+    let ticks =
+        RENEW_RATIO * f64::from(CREEP_LIFE_TIME) / f64::from(CREEP_SPAWN_TIME) / body_len as f64;
+    ticks.floor() as u32
+}
+
+/// The energy spent each time a creep with `body_len` parts costing
+/// `body_cost` energy, as given by [`body_cost`], is renewed.
+///
+/// Rounded up as the server does, with the same floating point operations,
+/// so the result matches the energy the spawn actually uses. Returns 0 for an
+/// empty body.
+pub fn renew_cost(body_cost: u32, body_len: usize) -> u32 {
+    if body_len == 0 {
+        return 0;
+    }
+    let cost = RENEW_RATIO * f64::from(body_cost) / f64::from(CREEP_SPAWN_TIME) / body_len as f64;
+    cost.ceil() as u32
+}
+
+/// Reason for a body being rejected by [`is_valid_body`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BodyError {
+    /// The body has no parts.
+    Empty,
+    /// The body has more than [`MAX_CREEP_SIZE`] parts.
+    TooLarge { parts: usize },
+    /// The body only has tough parts, so the creep couldn't do anything.
+    OnlyTough,
+}
+
+impl fmt::Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyError::Empty => write!(f, "body has no parts"),
+            BodyError::TooLarge { parts } => write!(
+                f,
+                "body has {} parts, more than the limit of {}",
+                parts, MAX_CREEP_SIZE
+            ),
+            BodyError::OnlyTough => write!(f, "body only has tough parts"),
+        }
+    }
+}
+
+impl Error for BodyError {}
+
+/// Checks that a body can be spawned, with between 1 and [`MAX_CREEP_SIZE`]
+/// parts, and that it has at least one part other than tough.
+///
+/// This doesn't check whether the spawning room has enough energy; compare
+/// [`body_cost`] with the room's energy capacity for that.
+pub fn is_valid_body(parts: &[Part]) -> Result<(), BodyError> {
+    if parts.is_empty() {
+        return Err(BodyError::Empty);
+    }
+    if parts.len() > MAX_CREEP_SIZE as usize {
+        return Err(BodyError::TooLarge { parts: parts.len() });
+    }
+    if parts.iter().all(|&part| part == Part::Tough) {
+        return Err(BodyError::OnlyTough);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        body_cost, body_spawn_time, creep_max_lifetime, is_valid_body, renew_cost, renew_ticks,
+        BodyError, RENEW_RATIO,
+    };
+    use crate::constants::{
+        Part::{self, *},
+        SPAWN_RENEW_RATIO,
+    };
+
+    #[test]
+    fn renew_ratio() {
+        assert_eq!(RENEW_RATIO as f32, SPAWN_RENEW_RATIO);
+        assert_ne!(f64::from(SPAWN_RENEW_RATIO), RENEW_RATIO);
+    }
+
+    #[test]
+    fn worker_bodies() {
+        let worker = [Work, Work, Carry, Move, Move, Move];
+        assert_eq!(body_cost(&worker), 400);
+        assert_eq!(body_spawn_time(&worker), 18);
+        assert_eq!(is_valid_body(&worker), Ok(()));
+
+        let mut miner = vec![Work; 5];
+        miner.extend(&[Carry, Move, Move, Move]);
+        assert_eq!(body_cost(&miner), 700);
+        assert_eq!(body_spawn_time(&miner), 27);
+        assert_eq!(is_valid_body(&miner), Ok(()));
+
+        let mut defender = vec![Tough; 10];
+        defender.extend(&[Attack, Move]);
+        assert_eq!(body_cost(&defender), 230);
+        assert_eq!(is_valid_body(&defender), Ok(()));
+    }
+
+    #[test]
+    fn invalid_bodies() {
+        assert_eq!(body_cost(&[]), 0);
+        assert_eq!(body_spawn_time(&[]), 0);
+        assert_eq!(is_valid_body(&[]), Err(BodyError::Empty));
+
+        assert_eq!(is_valid_body(&[Tough, Tough]), Err(BodyError::OnlyTough));
+
+        let largest = vec![Move; 50];
+        assert_eq!(is_valid_body(&largest), Ok(()));
+        assert_eq!(body_spawn_time(&largest), 150);
+        let oversized: Vec<Part> = vec![Move; 51];
+        assert_eq!(
+            is_valid_body(&oversized),
+            Err(BodyError::TooLarge { parts: 51 })
+        );
+        assert_eq!(
+            BodyError::TooLarge { parts: 51 }.to_string(),
+            "body has 51 parts, more than the limit of 50"
+        );
+    }
+
+    #[test]
+    fn lifetime_and_renewal() {
+        assert_eq!(creep_max_lifetime(&[Work, Carry, Move]), 1500);
+        assert_eq!(creep_max_lifetime(&[Claim, Move]), 600);
+
+        // values used by the server's renewCreep intent
+        let bodies: [(&[Part], u32, u32); 5] = [
+            (&[Work, Carry, Move], 200, 27),
+            (&[Work, Work, Carry, Carry, Move, Move], 100, 27),
+            (
+                &[Carry, Carry, Carry, Carry, Move, Move, Move, Move],
+                75,
+                20,
+            ),
+            (&[Work, Work, Work, Work, Work, Move], 100, 37),
+            (&[Move; 50], 12, 20),
+        ];
+        for &(body, ticks, cost) in &bodies {
+            assert_eq!(renew_ticks(body.len()), ticks);
+            assert_eq!(renew_cost(body_cost(body), body.len()), cost);
+        }
+        // exact results aren't rounded up
+        assert_eq!(renew_cost(250, 4), 25);
+        assert_eq!(renew_cost(3250, 50), 26);
+        assert_eq!(renew_ticks(1), 600);
+        assert_eq!(renew_ticks(7), 85);
+
+        assert_eq!(renew_ticks(0), 0);
+        assert_eq!(renew_cost(0, 0), 0);
+    }
+}
@@ -0,0 +1,686 @@
+//! Choosing what to attack when sieging a hostile room, and what to avoid.
+//!
+//! [`siege_targets`] orders a room's hostile structures for attack or
+//! dismantling: towers first, then spawns, each preceded by the ramparts and
+//! walls which have to be broken to reach it. The barriers are picked along
+//! the entry path with the fewest total hits to break through, found with
+//! [`siege_plan`], which works on plain [`SiegeStructure`] snapshots and can
+//! be used on its own.
+//!
+//! [`threat_matrix`] estimates the damage hostile towers and creeps can deal
+//! to each tile of a room, as path costs for moving around them.
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    constants::{
+        tower_attack_power_at_range, Boost, Part, ResourceType, StructureType, Terrain,
+        ATTACK_POWER, RANGED_ATTACK_POWER, TOWER_ENERGY_COST,
+    },
+    diplomacy::IgnoreAllies,
+    game,
+    local::Position,
+    objects::{Creep, HasPosition, Structure, StructureProperties},
+    pathfinder::LocalCostMatrix,
+};
+
+/// Width and height of a room, in tiles.
+const ROOM_SIZE: usize = 50;
+
+/// Which structures to attack, and in which order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SiegePolicy {
+    priorities: Vec<StructureType>,
+    breach: bool,
+    ignore_allies: IgnoreAllies,
+}
+
+impl Default for SiegePolicy {
+    fn default() -> Self {
+        SiegePolicy {
+            priorities: vec![StructureType::Tower, StructureType::Spawn],
+            breach: true,
+            ignore_allies: IgnoreAllies::Yes,
+        }
+    }
+}
+
+impl SiegePolicy {
+    /// Creates a policy targeting towers, then spawns, breaching the walls
+    /// in front of them.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the structure types to target, highest priority first - default
+    /// towers, then spawns.
+    ///
+    /// Structures of the same type are targeted from the weakest to the
+    /// strongest.
+    pub fn priorities(mut self, priorities: Vec<StructureType>) -> Self {
+        self.priorities = priorities;
+        self
+    }
+
+    /// Sets whether the ramparts and walls on the way to each target are
+    /// included before it - default true.
+    ///
+    /// When false, only the targets themselves are returned, for instance
+    /// when attacking from range over the walls.
+    pub fn breach(mut self, breach: bool) -> Self {
+        self.breach = breach;
+        self
+    }
+
+    /// Sets whether structures owned by allies, as set with
+    /// [`diplomacy::set_allies`][crate::diplomacy::set_allies], are left
+    /// alone by [`siege_targets`] - default [`IgnoreAllies::Yes`].
+    pub fn ignore_allies(mut self, ignore_allies: IgnoreAllies) -> Self {
+        self.ignore_allies = ignore_allies;
+        self
+    }
+}
+
+/// A structure in a room being sieged, as used by [`siege_plan`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SiegeStructure {
+    pub structure_type: StructureType,
+    pub x: u8,
+    pub y: u8,
+    /// The structure's current hits, or 0 if it can't be attacked, such as a
+    /// controller or a novice area wall.
+    pub hits: u32,
+}
+
+impl SiegeStructure {
+    /// Whether the structure blocks movement until it is destroyed.
+    fn is_barrier(&self) -> bool {
+        self.structure_type == StructureType::Rampart || self.structure_type.is_obstacle()
+    }
+
+    fn tile(&self) -> usize {
+        usize::from(self.y) * ROOM_SIZE + usize::from(self.x)
+    }
+}
+
+/// Orders structures for attack, returning indices into `structures`.
+///
+/// Targets matching the policy's priorities are listed by priority, then by
+/// hits. When breaching, each target is preceded by the barriers on its
+/// weakest entry path: the path from any exit tile of the room to a tile next
+/// to the target which has the fewest total hits of ramparts and obstacle
+/// structures on it, then the fewest tiles. Ramparts on the target's own tile
+/// are listed just before it. Barriers already listed for an earlier target
+/// count as broken.
+///
+/// Terrain walls, where `is_terrain_wall` returns true for the given
+/// `(x, y)` coordinates, and barriers with no hits can't be passed. Targets
+/// which can't be reached through any entry path are left out when
+/// breaching.
+pub fn siege_plan<F>(
+    structures: &[SiegeStructure],
+    is_terrain_wall: F,
+    policy: &SiegePolicy,
+) -> Vec<usize>
+where
+    F: Fn(u8, u8) -> bool,
+{
+    let mut listed = vec![false; structures.len()];
+    let mut order = Vec::new();
+
+    for &priority in &policy.priorities {
+        let mut targets: Vec<usize> = (0..structures.len())
+            .filter(|&i| structures[i].structure_type == priority && !listed[i])
+            .collect();
+        targets.sort_by_key(|&i| structures[i].hits);
+
+        for target in targets {
+            if listed[target] {
+                continue;
+            }
+            if policy.breach {
+                let barriers = match weakest_entry(structures, &is_terrain_wall, &listed, target) {
+                    Some(barriers) => barriers,
+                    None => continue,
+                };
+                let covering = (0..structures.len()).filter(|&i| {
+                    i != target
+                        && structures[i].structure_type == StructureType::Rampart
+                        && structures[i].tile() == structures[target].tile()
+                });
+                for barrier in barriers.into_iter().chain(covering) {
+                    if !listed[barrier] {
+                        listed[barrier] = true;
+                        order.push(barrier);
+                    }
+                }
+            }
+            listed[target] = true;
+            order.push(target);
+        }
+    }
+
+    order
+}
+
+/// Finds the entry path to `target` with the fewest total barrier hits,
+/// returning the unbroken barriers along it from the room's edge inwards, or
+/// `None` if the target can't be reached.
+fn weakest_entry<F>(
+    structures: &[SiegeStructure],
+    is_terrain_wall: &F,
+    broken: &[bool],
+    target: usize,
+) -> Option<Vec<usize>>
+where
+    F: Fn(u8, u8) -> bool,
+{
+    const TILES: usize = ROOM_SIZE * ROOM_SIZE;
+
+    let mut blocked = vec![false; TILES];
+    let mut hits = vec![0u64; TILES];
+    let mut barriers: Vec<Vec<usize>> = vec![Vec::new(); TILES];
+    for y in 0..ROOM_SIZE as u8 {
+        for x in 0..ROOM_SIZE as u8 {
+            blocked[usize::from(y) * ROOM_SIZE + usize::from(x)] = is_terrain_wall(x, y);
+        }
+    }
+    for (i, structure) in structures.iter().enumerate() {
+        if !structure.is_barrier() || broken[i] {
+            continue;
+        }
+        let tile = structure.tile();
+        if structure.hits == 0 {
+            blocked[tile] = true;
+        }
+        hits[tile] += u64::from(structure.hits);
+        barriers[tile].push(i);
+    }
+    let target_tile = structures[target].tile();
+    blocked[target_tile] = true;
+
+    let (tx, ty) = (structures[target].x, structures[target].y);
+    let is_goal = |tile: usize| {
+        let x = (tile % ROOM_SIZE) as u8;
+        let y = (tile / ROOM_SIZE) as u8;
+        x.max(tx) - x.min(tx) <= 1 && y.max(ty) - y.min(ty) <= 1
+    };
+
+    // costs are (barrier hits, tiles) so the shortest of the weakest paths
+    // wins
+    let mut cost = vec![None; TILES];
+    let mut previous = vec![None; TILES];
+    let mut queue = BinaryHeap::new();
+    for tile in 0..TILES {
+        let (x, y) = (tile % ROOM_SIZE, tile / ROOM_SIZE);
+        let is_exit = x == 0 || y == 0 || x == ROOM_SIZE - 1 || y == ROOM_SIZE - 1;
+        if is_exit && !blocked[tile] {
+            cost[tile] = Some((hits[tile], 0u32));
+            queue.push(Reverse(((hits[tile], 0u32), tile)));
+        }
+    }
+
+    while let Some(Reverse((current, tile))) = queue.pop() {
+        if cost[tile] != Some(current) {
+            continue;
+        }
+        if is_goal(tile) {
+            let mut path = Vec::new();
+            let mut step = Some(tile);
+            while let Some(tile) = step {
+                path.extend(barriers[tile].iter().rev());
+                step = previous[tile];
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let (x, y) = ((tile % ROOM_SIZE) as isize, (tile / ROOM_SIZE) as isize);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, ny) = (x + dx, y + dy);
+                if (dx, dy) == (0, 0)
+                    || nx < 0
+                    || ny < 0
+                    || nx >= ROOM_SIZE as isize
+                    || ny >= ROOM_SIZE as isize
+                {
+                    continue;
+                }
+                let next = ny as usize * ROOM_SIZE + nx as usize;
+                if blocked[next] {
+                    continue;
+                }
+                let next_cost = (current.0 + hits[next], current.1 + 1);
+                if !matches!(cost[next], Some(known) if known <= next_cost) {
+                    cost[next] = Some(next_cost);
+                    previous[next] = Some(tile);
+                    queue.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Orders a room's hostile structures for attack or dismantling.
+///
+/// Hostile structures are walls and structures owned by another player,
+/// other than allies depending on the policy.
+/// See [`siege_plan`] for how targets and the barriers in front of them are
+/// picked. All of `room_structures` should be in the same room, typically
+/// from [`Room::find`][crate::objects::Room::find] with
+/// [`find::STRUCTURES`][crate::constants::find::STRUCTURES].
+pub fn siege_targets(room_structures: &[Structure], policy: &SiegePolicy) -> Vec<Structure> {
+    let hostile: Vec<&Structure> = room_structures
+        .iter()
+        .filter(|structure| match structure.as_owned() {
+            Some(owned) => match owned.owner_name() {
+                Some(owner) => !owned.my() && policy.ignore_allies.is_hostile(&owner),
+                None => false,
+            },
+            None => structure.structure_type() == StructureType::Wall,
+        })
+        .collect();
+    let room = match hostile.first() {
+        Some(structure) => structure.pos().room_name(),
+        None => return Vec::new(),
+    };
+
+    let snapshots: Vec<SiegeStructure> = hostile
+        .iter()
+        .map(|structure| {
+            let pos = structure.pos();
+            SiegeStructure {
+                structure_type: structure.structure_type(),
+                x: pos.x() as u8,
+                y: pos.y() as u8,
+                hits: structure.as_attackable().map_or(0, |a| a.hits()),
+            }
+        })
+        .collect();
+    let terrain = game::map::get_room_terrain(room).get_local();
+    let is_terrain_wall = |x: u8, y: u8| terrain.get(u32::from(x), u32::from(y)) == Terrain::Wall;
+
+    siege_plan(&snapshots, is_terrain_wall, policy)
+        .into_iter()
+        .map(|i| hostile[i].clone())
+        .collect()
+}
+
+/// Damage per tick dealt by towers or creeps for each cost of 1 in the cost
+/// matrices made by [`ThreatMatrix`], rounding up.
+///
+/// A tower at optimal range costs 30, and damage of 5080 or more, such as
+/// from nine towers at optimal range, costs 254, the highest passable cost.
+pub const THREAT_COST_SCALE: u32 = 20;
+
+/// A hostile creep's position and damage per tick, as used by
+/// [`threat_matrix`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HostileInfo {
+    pub pos: Position,
+    /// Damage from [`Creep::attack`] to an adjacent target.
+    ///
+    /// [`Creep::attack`]: crate::objects::Creep::attack
+    pub melee_damage: u32,
+    /// Damage from [`Creep::ranged_attack`] to a target within range 3.
+    ///
+    /// [`Creep::ranged_attack`]: crate::objects::Creep::ranged_attack
+    pub ranged_damage: u32,
+}
+
+impl HostileInfo {
+    /// Totals the damage of a creep at `pos` from its active parts and their
+    /// boosts.
+    pub fn from_parts<I>(pos: Position, active_parts: I) -> Self
+    where
+        I: IntoIterator<Item = (Part, Option<ResourceType>)>,
+    {
+        let mut info = HostileInfo {
+            pos,
+            melee_damage: 0,
+            ranged_damage: 0,
+        };
+        for (part, boost) in active_parts {
+            let (damage, power) = match part {
+                Part::Attack => (&mut info.melee_damage, ATTACK_POWER),
+                Part::RangedAttack => (&mut info.ranged_damage, RANGED_ATTACK_POWER),
+                _ => continue,
+            };
+            let multiplier = boost
+                .and_then(ResourceType::boost)
+                .filter(|boost| boost.part() == part)
+                .map_or(1.0, Boost::multiplier);
+            *damage += (f64::from(power) * multiplier) as u32;
+        }
+        info
+    }
+
+    /// Totals the damage of `creep` from its active parts.
+    pub fn of_creep(creep: &Creep) -> Self {
+        let active = creep
+            .body()
+            .into_iter()
+            .filter(|part| part.hits > 0)
+            .map(|part| (part.part, part.boost));
+        HostileInfo::from_parts(creep.pos(), active)
+    }
+}
+
+/// Expected incoming damage per tick on each tile of a room, made by
+/// [`ThreatMatrix::new`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThreatMatrix {
+    /// Damage by tile, indexed by `y * 50 + x`.
+    damage: Vec<u32>,
+}
+
+impl ThreatMatrix {
+    /// Adds up the damage which `hostiles` and `towers` can deal to each tile.
+    ///
+    /// Towers are given with their stored energy, and those without enough
+    /// energy to fire are left out. Each tower damages every tile of the room
+    /// as per [`tower_attack_power_at_range`], each creep's ranged damage
+    /// applies within range 3 and its melee damage within range 1. Only the
+    /// coordinates of the positions are used: all of them should be in the
+    /// same room.
+    pub fn new(hostiles: &[HostileInfo], towers: &[(Position, u32)]) -> Self {
+        let mut damage = vec![0u32; ROOM_SIZE * ROOM_SIZE];
+
+        // every tile is within range 49 of every tower, so the falloff is
+        // looked up rather than recomputed for each tile
+        let falloff: Vec<u32> = (0..ROOM_SIZE as u32)
+            .map(tower_attack_power_at_range)
+            .collect();
+        for &(pos, energy) in towers {
+            if energy < TOWER_ENERGY_COST {
+                continue;
+            }
+            let (tx, ty) = (pos.x() as usize, pos.y() as usize);
+            for y in 0..ROOM_SIZE {
+                let dy = y.max(ty) - y.min(ty);
+                let row = &mut damage[y * ROOM_SIZE..(y + 1) * ROOM_SIZE];
+                for (x, tile) in row.iter_mut().enumerate() {
+                    let dx = x.max(tx) - x.min(tx);
+                    *tile += falloff[dx.max(dy)];
+                }
+            }
+        }
+
+        for hostile in hostiles {
+            let (x, y) = (hostile.pos.x() as usize, hostile.pos.y() as usize);
+            add_in_range(&mut damage, x, y, 3, hostile.ranged_damage);
+            add_in_range(&mut damage, x, y, 1, hostile.melee_damage);
+        }
+
+        ThreatMatrix { damage }
+    }
+
+    /// The expected damage on a tile.
+    #[inline]
+    pub fn damage(&self, x: u8, y: u8) -> u32 {
+        self.damage[usize::from(y) * ROOM_SIZE + usize::from(x)]
+    }
+
+    /// The pathfinding cost of a tile's damage: 1 for each
+    /// [`THREAT_COST_SCALE`] damage, rounding up, up to 254.
+    #[inline]
+    pub fn cost(&self, x: u8, y: u8) -> u8 {
+        damage_cost(self.damage(x, y))
+    }
+
+    /// Makes a cost matrix of the [`ThreatMatrix::cost`] of each tile.
+    ///
+    /// Tiles without damage are left at 0, so the terrain cost applies to
+    /// them while tiles under fire replace it; use [`ThreatMatrix::add_to`]
+    /// to add to the terrain costs instead.
+    pub fn to_cost_matrix(&self) -> LocalCostMatrix {
+        let mut matrix = LocalCostMatrix::new();
+        self.add_to(&mut matrix);
+        matrix
+    }
+
+    /// Adds the [`ThreatMatrix::cost`] of each tile to `matrix`, such as one
+    /// made by [`formation::terrain_matrix`], up to 254.
+    ///
+    /// Impassable tiles, with a cost of 255, are left impassable.
+    ///
+    /// [`formation::terrain_matrix`]: crate::formation::terrain_matrix
+    pub fn add_to(&self, matrix: &mut LocalCostMatrix) {
+        for y in 0..ROOM_SIZE as u8 {
+            for x in 0..ROOM_SIZE as u8 {
+                let cost = self.cost(x, y);
+                let current = matrix.get(x, y);
+                if cost > 0 && current < 255 {
+                    matrix.set(x, y, current.saturating_add(cost).min(254));
+                }
+            }
+        }
+    }
+}
+
+fn damage_cost(damage: u32) -> u8 {
+    ((damage + THREAT_COST_SCALE - 1) / THREAT_COST_SCALE).min(254) as u8
+}
+
+/// Adds `amount` to the tiles within `range` of `(x, y)`.
+fn add_in_range(damage: &mut [u32], x: usize, y: usize, range: usize, amount: u32) {
+    if amount == 0 {
+        return;
+    }
+    let xs = x.saturating_sub(range)..=(x + range).min(ROOM_SIZE - 1);
+    for row in y.saturating_sub(range)..=(y + range).min(ROOM_SIZE - 1) {
+        for tile in &mut damage[row * ROOM_SIZE + xs.start()..=row * ROOM_SIZE + xs.end()] {
+            *tile += amount;
+        }
+    }
+}
+
+/// Makes a cost matrix of the expected incoming damage on each tile of a
+/// room, for use in the cost callbacks of flee and approach paths.
+///
+/// See [`ThreatMatrix::new`] for how damage is added up, and
+/// [`ThreatMatrix::cost`] for how it's scaled to costs.
+pub fn threat_matrix(room_hostiles: &[HostileInfo], towers: &[(Position, u32)]) -> LocalCostMatrix {
+    ThreatMatrix::new(room_hostiles, towers).to_cost_matrix()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        siege_plan, threat_matrix, HostileInfo, SiegePolicy, SiegeStructure, ThreatMatrix,
+    };
+    use crate::{
+        constants::{
+            Part, ResourceType,
+            StructureType::{self, *},
+        },
+        local::{Position, RoomName},
+        pathfinder::LocalCostMatrix,
+    };
+
+    fn structure(structure_type: StructureType, x: u8, y: u8, hits: u32) -> SiegeStructure {
+        SiegeStructure {
+            structure_type,
+            x,
+            y,
+            hits,
+        }
+    }
+
+    /// A room with a base enclosed by terrain two tiles thick from x = 10 to
+    /// 40 and y = 10 to 40, with a gap in each side closed by ramparts and
+    /// walls of different strengths, and a tower and two spawns inside.
+    fn walled_room() -> (Vec<SiegeStructure>, impl Fn(u8, u8) -> bool) {
+        let structures = vec![
+            // north gap, two ramparts deep
+            structure(Rampart, 25, 10, 1_000_000),
+            structure(Rampart, 25, 11, 1_000_000),
+            // east gap, the weakest entry
+            structure(Rampart, 40, 20, 300_000),
+            // south gap, a single strong wall
+            structure(Wall, 25, 40, 5_000_000),
+            // west gap, a rampart behind a wall
+            structure(Wall, 10, 30, 100_000),
+            structure(Rampart, 11, 30, 400_000),
+            structure(Spawn, 20, 20, 5000),
+            structure(Tower, 30, 30, 3000),
+            structure(Rampart, 30, 30, 50_000),
+            structure(Spawn, 35, 15, 4000),
+            // outside of the base
+            structure(Extension, 5, 5, 1000),
+        ];
+        let is_terrain_wall = |x: u8, y: u8| {
+            let border = [10, 11, 39, 40];
+            let on_border = (border.contains(&x) && (10..=40).contains(&y))
+                || (border.contains(&y) && (10..=40).contains(&x));
+            let gap =
+                (x == 25 && (y <= 11 || y >= 39)) || (y == 20 && x >= 39) || (y == 30 && x <= 11);
+            on_border && !gap
+        };
+        (structures, is_terrain_wall)
+    }
+
+    #[test]
+    fn weakest_entry() {
+        let (structures, is_terrain_wall) = walled_room();
+
+        // the tower is breached through the east rampart and its own
+        // rampart, then the weaker spawn and the other spawn are open
+        assert_eq!(
+            siege_plan(&structures, &is_terrain_wall, &SiegePolicy::new()),
+            vec![2, 8, 7, 9, 6]
+        );
+
+        // once the east rampart is stronger than the west wall and rampart,
+        // the west entry is used instead
+        let mut reinforced = structures.clone();
+        reinforced[2].hits = 600_000;
+        assert_eq!(
+            siege_plan(&reinforced, &is_terrain_wall, &SiegePolicy::new()),
+            vec![4, 5, 8, 7, 9, 6]
+        );
+    }
+
+    #[test]
+    fn policy() {
+        let (mut structures, is_terrain_wall) = walled_room();
+
+        let spawns_first = SiegePolicy::new().priorities(vec![Spawn, Extension]);
+        assert_eq!(
+            siege_plan(&structures, &is_terrain_wall, &spawns_first),
+            vec![2, 9, 6, 10]
+        );
+
+        let no_breach = SiegePolicy::new().breach(false);
+        assert_eq!(
+            siege_plan(&structures, &is_terrain_wall, &no_breach),
+            vec![7, 9, 6]
+        );
+
+        // walls with no hits, like novice area walls, can't be broken
+        structures[2].hits = 0;
+        structures[3].hits = 0;
+        structures[4].hits = 0;
+        structures[0].hits = 0;
+        assert_eq!(
+            siege_plan(&structures, &is_terrain_wall, &SiegePolicy::new()),
+            Vec::<usize>::new()
+        );
+    }
+
+    fn pos(x: u32, y: u32) -> Position {
+        Position::new(x, y, RoomName::new("W1N1").unwrap())
+    }
+
+    #[test]
+    fn hostile_damage() {
+        let parts = vec![
+            (Part::Attack, None),
+            (Part::Attack, Some(ResourceType::UtriumAcid)),
+            (
+                Part::RangedAttack,
+                Some(ResourceType::CatalyzedKeaniumAlkalide),
+            ),
+            // a boost for another part doesn't apply
+            (Part::RangedAttack, Some(ResourceType::UtriumAcid)),
+            (Part::Heal, None),
+            (Part::Move, None),
+        ];
+        let info = HostileInfo::from_parts(pos(10, 10), parts);
+        assert_eq!(info.melee_damage, 30 + 90);
+        assert_eq!(info.ranged_damage, 40 + 10);
+    }
+
+    #[test]
+    fn single_tower() {
+        let threat = ThreatMatrix::new(&[], &[(pos(25, 25), 500)]);
+        assert_eq!(threat.damage(25, 25), 600);
+        assert_eq!(threat.damage(30, 20), 600);
+        // 600 - 600 * 0.75 * 5 / 15
+        assert_eq!(threat.damage(35, 25), 450);
+        assert_eq!(threat.damage(25, 13), 390);
+        assert_eq!(threat.damage(45, 30), 150);
+        assert_eq!(threat.damage(49, 0), 150);
+        assert_eq!(threat.cost(25, 25), 30);
+        assert_eq!(threat.cost(35, 25), 23);
+
+        // towers without energy to fire don't count, others add up
+        let threat = ThreatMatrix::new(&[], &[(pos(25, 25), 9), (pos(0, 0), 10)]);
+        assert_eq!(threat.damage(25, 25), 150);
+        assert_eq!(threat.damage(3, 4), 600);
+    }
+
+    #[test]
+    fn ranged_creep() {
+        let creep = HostileInfo {
+            pos: pos(1, 10),
+            melee_damage: 60,
+            ranged_damage: 40,
+        };
+        let threat = ThreatMatrix::new(&[creep], &[]);
+        assert_eq!(threat.damage(1, 10), 100);
+        assert_eq!(threat.damage(0, 11), 100);
+        assert_eq!(threat.damage(2, 9), 100);
+        assert_eq!(threat.damage(3, 10), 40);
+        assert_eq!(threat.damage(4, 13), 40);
+        assert_eq!(threat.damage(4, 14), 0);
+        assert_eq!(threat.damage(5, 10), 0);
+        assert_eq!(threat.cost(4, 7), 2);
+        assert_eq!(threat.cost(5, 7), 0);
+
+        let total: u32 = (0..50)
+            .flat_map(|x| (0..50).map(move |y| (x, y)))
+            .map(|(x, y)| threat.damage(x, y))
+            .sum();
+        assert_eq!(total, 5 * 7 * 40 + 3 * 3 * 60);
+
+        let matrix = threat_matrix(&[creep], &[(pos(40, 40), 1000)]);
+        // (100 + 150) / 20, rounding up
+        assert_eq!(matrix.get(1, 10), 13);
+        assert_eq!(matrix.get(40, 40), 30);
+    }
+
+    #[test]
+    fn adding_to_costs() {
+        let threat = ThreatMatrix::new(
+            &[HostileInfo {
+                pos: pos(10, 10),
+                melee_damage: 0,
+                ranged_damage: 5000,
+            }],
+            &[],
+        );
+        let mut matrix = LocalCostMatrix::new();
+        matrix.set(10, 10, 255);
+        matrix.set(11, 10, 10);
+        matrix.set(20, 20, 5);
+        threat.add_to(&mut matrix);
+        assert_eq!(matrix.get(10, 10), 255);
+        assert_eq!(matrix.get(11, 10), 254);
+        assert_eq!(matrix.get(12, 10), 250);
+        assert_eq!(matrix.get(20, 20), 5);
+    }
+}
@@ -4,10 +4,9 @@
 //! <https://github.com/screeps/common/commits/master/lib/constants.js>.
 //!
 //! Currently missing:
-//! - OBSTACLE_OBJECT_TYPES
+//! - OBSTACLE_OBJECT_TYPES, other than for structures in
+//!   [`StructureType::is_obstacle`]
 //! - WORLD_WIDTH / WORLD_HEIGHT (deprecated in Screeps)
-//! - BODYPARTS_ALL, RESOURCES_ALL, COLORS_ALL
-//! - POWER_INFO
 //!
 //! # Notes on Deserialization
 //!
@@ -60,9 +59,12 @@
 //! [`FromStr`]: std::str::FromStr
 //! [`Display`]: std::fmt::Display
 pub mod find;
+#[cfg(test)]
+mod js_sync;
 pub mod look;
 mod numbers;
 mod recipes;
+pub mod serde_string;
 mod small_enums;
 mod types;
 
@@ -87,7 +89,7 @@ pub mod creep {
             MAX_CREEP_SIZE, RANGED_HEAL_POWER, REPAIR_COST, REPAIR_POWER, SPAWN_RENEW_RATIO,
             UPGRADE_CONTROLLER_POWER,
         },
-        small_enums::{Part, ReturnCode},
+        small_enums::{Part, ReturnCode, BODYPARTS_ALL},
     };
 }
 
@@ -97,12 +99,13 @@ pub mod structure {
         numbers::{
             extension_energy_capacity, invader_core_creep_spawn_time, invader_core_expand_time,
             rampart_hits_max, ruin_decay_structures, stronghold_rampart_hits,
-            CONSTRUCTION_COST_ROAD_SWAMP_RATIO, CONSTRUCTION_COST_ROAD_WALL_RATIO,
-            CONTAINER_CAPACITY, CONTAINER_DECAY, CONTAINER_DECAY_TIME, CONTAINER_DECAY_TIME_OWNED,
-            CONTAINER_HITS, EXTENSION_HITS, EXTRACTOR_COOLDOWN, EXTRACTOR_HITS, FACTORY_CAPACITY,
-            FACTORY_HITS, INVADER_CORE_CONTROLLER_DOWNGRADE, INVADER_CORE_CONTROLLER_POWER,
-            INVADER_CORE_HITS, LAB_ENERGY_CAPACITY, LAB_HITS, LAB_MINERAL_CAPACITY, LINK_CAPACITY,
-            LINK_COOLDOWN, LINK_HITS, LINK_LOSS_RATIO, NUKER_COOLDOWN, NUKER_ENERGY_CAPACITY,
+            total_extension_capacity, CONSTRUCTION_COST_ROAD_SWAMP_RATIO,
+            CONSTRUCTION_COST_ROAD_WALL_RATIO, CONTAINER_CAPACITY, CONTAINER_DECAY,
+            CONTAINER_DECAY_TIME, CONTAINER_DECAY_TIME_OWNED, CONTAINER_HITS, EXTENSION_HITS,
+            EXTRACTOR_COOLDOWN, EXTRACTOR_HITS, FACTORY_CAPACITY, FACTORY_HITS,
+            INVADER_CORE_CONTROLLER_DOWNGRADE, INVADER_CORE_CONTROLLER_POWER, INVADER_CORE_HITS,
+            LAB_ENERGY_CAPACITY, LAB_HITS, LAB_MINERAL_CAPACITY, LINK_CAPACITY, LINK_COOLDOWN,
+            LINK_HITS, LINK_LOSS_RATIO, NUKER_COOLDOWN, NUKER_ENERGY_CAPACITY,
             NUKER_GHODIUM_CAPACITY, NUKER_HITS, OBSERVER_HITS, POWER_BANK_HITS,
             POWER_SPAWN_ENERGY_CAPACITY, POWER_SPAWN_HITS, POWER_SPAWN_POWER_CAPACITY,
             RAMPART_DECAY_AMOUNT, RAMPART_DECAY_TIME, RAMPART_HITS, RAMPART_HITS_MAX_RCL2,
@@ -113,7 +116,7 @@ pub mod structure {
             STRONGHOLD_DECAY_TICKS, TERMINAL_CAPACITY, TERMINAL_HITS, TERMINAL_SEND_COST,
             TOWER_CAPACITY, TOWER_HITS, WALL_HITS, WALL_HITS_MAX,
         },
-        types::StructureType,
+        types::{DecayInfo, StoreCapacity, StructureType},
     };
 }
 
@@ -134,18 +137,29 @@ pub mod minerals {
     };
 }
 
+/// Re-export of all constants related to [`Deposit`] behavior.
+///
+/// [`Deposit`]: crate::objects::Deposit
+pub mod deposit {
+    pub use super::numbers::{
+        deposit_cooldown_for_harvested, DEPOSIT_DECAY_TIME, DEPOSIT_EXHAUST_MULTIPLY,
+        DEPOSIT_EXHAUST_POW, HARVEST_DEPOSIT_POWER,
+    };
+}
+
 /// Re-export of all constants related to [`StructureController`] behavior and
 /// GCL.
 ///
 /// [`StructureController`]: crate::objects::StructureController
 pub mod control {
     pub use super::numbers::{
-        controller_downgrade, controller_levels, CONTROLLER_ATTACK_BLOCKED_UPGRADE,
+        controller_downgrade, controller_levels, controller_ticks_to_upgrade,
+        gcl_level_for_progress, gcl_progress_required, CONTROLLER_ATTACK_BLOCKED_UPGRADE,
         CONTROLLER_CLAIM_DOWNGRADE, CONTROLLER_DOWNGRADE_RESTORE,
         CONTROLLER_DOWNGRADE_SAFEMODE_THRESHOLD, CONTROLLER_MAX_UPGRADE_PER_TICK,
         CONTROLLER_NUKE_BLOCKED_UPGRADE, CONTROLLER_RESERVE, CONTROLLER_RESERVE_MAX, GCL_MULTIPLY,
-        GCL_NOVICE, GCL_POW, SAFE_MODE_COOLDOWN, SAFE_MODE_COST, SAFE_MODE_DURATION,
-        SIGN_PLANNED_AREA, SYSTEM_USERNAME,
+        GCL_NOVICE, GCL_POW, INVADER_USERNAME, SAFE_MODE_COOLDOWN, SAFE_MODE_COST,
+        SAFE_MODE_DURATION, SIGN_PLANNED_AREA, SOURCE_KEEPER_USERNAME, SYSTEM_USERNAME,
     };
 }
 
@@ -153,11 +167,12 @@ pub mod control {
 pub mod power {
     pub use super::{
         numbers::{
-            POWER_BANK_CAPACITY_MAX, POWER_BANK_CAPACITY_MIN, POWER_BANK_DECAY, POWER_BANK_HITS,
-            POWER_BANK_HIT_BACK, POWER_BANK_RESPAWN_TIME, POWER_CREEP_DELETE_COOLDOWN,
-            POWER_CREEP_LIFE_TIME, POWER_CREEP_MAX_LEVEL, POWER_CREEP_SPAWN_COOLDOWN,
-            POWER_LEVEL_MULTIPLY, POWER_LEVEL_POW, POWER_SPAWN_ENERGY_CAPACITY,
-            POWER_SPAWN_ENERGY_RATIO, POWER_SPAWN_POWER_CAPACITY,
+            gpl_level_for_progress, gpl_progress_required, POWER_BANK_CAPACITY_MAX,
+            POWER_BANK_CAPACITY_MIN, POWER_BANK_DECAY, POWER_BANK_HITS, POWER_BANK_HIT_BACK,
+            POWER_BANK_RESPAWN_TIME, POWER_CREEP_DELETE_COOLDOWN, POWER_CREEP_LIFE_TIME,
+            POWER_CREEP_MAX_LEVEL, POWER_CREEP_SPAWN_COOLDOWN, POWER_LEVEL_MULTIPLY,
+            POWER_LEVEL_POW, POWER_SPAWN_ENERGY_CAPACITY, POWER_SPAWN_ENERGY_RATIO,
+            POWER_SPAWN_POWER_CAPACITY,
         },
         small_enums::PowerClass,
         types::PowerType,
@@ -178,8 +193,9 @@ pub mod portal {
 /// [`Source`]: crate::objects::Source
 pub mod source {
     pub use super::numbers::{
-        ENERGY_DECAY, ENERGY_REGEN_TIME, INVADERS_ENERGY_GOAL, SOURCE_ENERGY_CAPACITY,
-        SOURCE_ENERGY_KEEPER_CAPACITY, SOURCE_ENERGY_NEUTRAL_CAPACITY,
+        expected_energy_per_tick, source_energy_capacity, ENERGY_DECAY, ENERGY_REGEN_TIME,
+        INVADERS_ENERGY_GOAL, SOURCE_ENERGY_CAPACITY, SOURCE_ENERGY_KEEPER_CAPACITY,
+        SOURCE_ENERGY_NEUTRAL_CAPACITY,
     };
 }
 
@@ -187,6 +203,8 @@ pub mod source {
 ///
 /// [market]: crate::game::market
 pub mod market {
+    pub use crate::game::market::OrderType;
+
     pub use super::numbers::{
         MARKET_FEE, MARKET_MAX_ORDERS, MARKET_ORDER_LIFE_TIME, TERMINAL_CAPACITY,
         TERMINAL_COOLDOWN, TERMINAL_MIN_SEND, TERMINAL_SEND_COST,
@@ -198,8 +216,9 @@ pub mod market {
 /// [`StructureSpawn`]: crate::objects::StructureSpawn
 pub mod spawn {
     pub use super::numbers::{
-        extension_energy_capacity, CREEP_SPAWN_TIME, ENERGY_REGEN_TIME, MAX_CREEP_SIZE,
-        SPAWN_ENERGY_CAPACITY, SPAWN_ENERGY_START, SPAWN_RENEW_RATIO,
+        extension_energy_capacity, spawning_ticks_remaining, total_extension_capacity,
+        CREEP_SPAWN_TIME, ENERGY_REGEN_TIME, MAX_CREEP_SIZE, SPAWN_ENERGY_CAPACITY,
+        SPAWN_ENERGY_START, SPAWN_RENEW_RATIO,
     };
 }
 
@@ -208,6 +227,7 @@ pub mod spawn {
 /// [`StructureTower`]: crate::objects::StructureTower
 pub mod tower {
     pub use super::numbers::{
+        tower_attack_power_at_range, tower_heal_power_at_range, tower_repair_power_at_range,
         TOWER_CAPACITY, TOWER_ENERGY_COST, TOWER_FALLOFF, TOWER_FALLOFF_RANGE, TOWER_OPTIMAL_RANGE,
         TOWER_POWER_ATTACK, TOWER_POWER_HEAL, TOWER_POWER_REPAIR,
     };
@@ -219,8 +239,8 @@ pub mod tower {
 /// [`Nuke`]: crate::objects::Nuke
 pub mod nuke {
     pub use super::numbers::{
-        NUKER_COOLDOWN, NUKER_ENERGY_CAPACITY, NUKER_GHODIUM_CAPACITY, NUKE_DAMAGE_RANGE_0,
-        NUKE_DAMAGE_RANGE_2, NUKE_LAND_TIME, NUKE_RANGE,
+        nuke_damage_at_range, NUKER_COOLDOWN, NUKER_ENERGY_CAPACITY, NUKER_GHODIUM_CAPACITY,
+        NUKE_DAMAGE_RANGE_0, NUKE_DAMAGE_RANGE_2, NUKE_LAND_TIME, NUKE_RANGE,
     };
 }
 
@@ -0,0 +1,428 @@
+// Excerpt of lib/constants.js from https://github.com/screeps/common,
+// containing the sections cross-checked by `src/constants/js_sync.rs`.
+//
+// When updating the constants, replace these sections with the current text
+// from upstream, add any sections newly checked by the tests, and update the
+// version noted in `src/constants.rs`. Sections are kept in their upstream
+// order, with unrelated constants between them left out.
+module.exports = {
+    OK: 0,
+
+    STRUCTURE_EXTENSION: "extension",
+    STRUCTURE_RAMPART: "rampart",
+    STRUCTURE_ROAD: "road",
+    STRUCTURE_SPAWN: "spawn",
+    STRUCTURE_LINK: "link",
+    STRUCTURE_WALL: "constructedWall",
+    STRUCTURE_KEEPER_LAIR: "keeperLair",
+    STRUCTURE_CONTROLLER: "controller",
+    STRUCTURE_STORAGE: "storage",
+    STRUCTURE_TOWER: "tower",
+    STRUCTURE_OBSERVER: "observer",
+    STRUCTURE_POWER_BANK: "powerBank",
+    STRUCTURE_POWER_SPAWN: "powerSpawn",
+    STRUCTURE_EXTRACTOR: "extractor",
+    STRUCTURE_LAB: "lab",
+    STRUCTURE_TERMINAL: "terminal",
+    STRUCTURE_CONTAINER: "container",
+    STRUCTURE_NUKER: "nuker",
+    STRUCTURE_FACTORY: "factory",
+    STRUCTURE_INVADER_CORE: "invaderCore",
+    STRUCTURE_PORTAL: "portal",
+
+    CONSTRUCTION_COST: {
+        spawn: 15000,
+        extension: 3000,
+        road: 300,
+        constructedWall: 1,
+        rampart: 1,
+        link: 5000,
+        storage: 30000,
+        tower: 5000,
+        observer: 8000,
+        powerSpawn: 100000,
+        extractor: 5000,
+        lab: 50000,
+        terminal: 100000,
+        container: 5000,
+        nuker: 100000,
+        factory: 100000
+    },
+    CONSTRUCTION_COST_ROAD_SWAMP_RATIO: 5,
+    CONSTRUCTION_COST_ROAD_WALL_RATIO: 150,
+
+    RAMPART_HITS: 1,
+    RAMPART_HITS_MAX: {2: 300000, 3: 1000000, 4: 3000000, 5: 10000000, 6: 30000000, 7: 100000000, 8: 300000000},
+
+    SPAWN_HITS: 5000,
+
+    WALL_HITS: 1,
+    WALL_HITS_MAX: 300000000,
+
+    EXTENSION_HITS: 1000,
+
+    ROAD_HITS: 5000,
+
+    LINK_HITS: 1000,
+
+    STORAGE_HITS: 10000,
+
+    TOWER_HITS: 3000,
+
+    CONTROLLER_STRUCTURES: {
+        "spawn": {0: 0, 1: 1, 2: 1, 3: 1, 4: 1, 5: 1, 6: 1, 7: 2, 8: 3},
+        "extension": {0: 0, 1: 0, 2: 5, 3: 10, 4: 20, 5: 30, 6: 40, 7: 50, 8: 60},
+        "link": {1: 0, 2: 0, 3: 0, 4: 0, 5: 2, 6: 3, 7: 4, 8: 6},
+        "road": {0: 2500, 1: 2500, 2: 2500, 3: 2500, 4: 2500, 5: 2500, 6: 2500, 7: 2500, 8: 2500},
+        "constructedWall": {1: 0, 2: 2500, 3: 2500, 4: 2500, 5: 2500, 6: 2500, 7: 2500, 8: 2500},
+        "rampart": {1: 0, 2: 2500, 3: 2500, 4: 2500, 5: 2500, 6: 2500, 7: 2500, 8: 2500},
+        "storage": {1: 0, 2: 0, 3: 0, 4: 1, 5: 1, 6: 1, 7: 1, 8: 1},
+        "tower": {1: 0, 2: 0, 3: 1, 4: 1, 5: 2, 6: 2, 7: 3, 8: 6},
+        "observer": {1: 0, 2: 0, 3: 0, 4: 0, 5: 0, 6: 0, 7: 0, 8: 1},
+        "powerSpawn": {1: 0, 2: 0, 3: 0, 4: 0, 5: 0, 6: 0, 7: 0, 8: 1},
+        "extractor": {1: 0, 2: 0, 3: 0, 4: 0, 5: 0, 6: 1, 7: 1, 8: 1},
+        "terminal": {1: 0, 2: 0, 3: 0, 4: 0, 5: 0, 6: 1, 7: 1, 8: 1},
+        "lab": {1: 0, 2: 0, 3: 0, 4: 0, 5: 0, 6: 3, 7: 6, 8: 10},
+        "container": {0: 5, 1: 5, 2: 5, 3: 5, 4: 5, 5: 5, 6: 5, 7: 5, 8: 5},
+        "nuker": {1: 0, 2: 0, 3: 0, 4: 0, 5: 0, 6: 0, 7: 0, 8: 1},
+        "factory": {1: 0, 2: 0, 3: 0, 4: 0, 5: 0, 6: 0, 7: 1, 8: 1}
+    },
+
+    OBSERVER_HITS: 500,
+
+    POWER_BANK_HITS: 2000000,
+
+    POWER_SPAWN_HITS: 5000,
+
+    EXTRACTOR_HITS: 500,
+
+    LAB_HITS: 500,
+
+    BOOSTS: {
+        work: {
+            UO: {
+                harvest: 3
+            },
+            UHO2: {
+                harvest: 5
+            },
+            XUHO2: {
+                harvest: 7
+            },
+            LH: {
+                build: 1.5,
+                repair: 1.5
+            },
+            LH2O: {
+                build: 1.8,
+                repair: 1.8
+            },
+            XLH2O: {
+                build: 2,
+                repair: 2
+            },
+            ZH: {
+                dismantle: 2
+            },
+            ZH2O: {
+                dismantle: 3
+            },
+            XZH2O: {
+                dismantle: 4
+            },
+            GH: {
+                upgradeController: 1.5
+            },
+            GH2O: {
+                upgradeController: 1.8
+            },
+            XGH2O: {
+                upgradeController: 2
+            }
+        },
+        attack: {
+            UH: {
+                attack: 2
+            },
+            UH2O: {
+                attack: 3
+            },
+            XUH2O: {
+                attack: 4
+            }
+        },
+        ranged_attack: {
+            KO: {
+                rangedAttack: 2,
+                rangedMassAttack: 2
+            },
+            KHO2: {
+                rangedAttack: 3,
+                rangedMassAttack: 3
+            },
+            XKHO2: {
+                rangedAttack: 4,
+                rangedMassAttack: 4
+            }
+        },
+        heal: {
+            LO: {
+                heal: 2,
+                rangedHeal: 2
+            },
+            LHO2: {
+                heal: 3,
+                rangedHeal: 3
+            },
+            XLHO2: {
+                heal: 4,
+                rangedHeal: 4
+            }
+        },
+        carry: {
+            KH: {
+                capacity: 2
+            },
+            KH2O: {
+                capacity: 3
+            },
+            XKH2O: {
+                capacity: 4
+            }
+        },
+        move: {
+            ZO: {
+                fatigue: 2
+            },
+            ZHO2: {
+                fatigue: 3
+            },
+            XZHO2: {
+                fatigue: 4
+            }
+        },
+        tough: {
+            GO: {
+                damage: .7
+            },
+            GHO2: {
+                damage: .5
+            },
+            XGHO2: {
+                damage: .3
+            }
+        }
+    },
+
+    TERMINAL_HITS: 3000,
+
+    CONTAINER_HITS: 250000,
+
+    NUKER_HITS: 1000,
+
+    FACTORY_HITS: 1000,
+
+    RESOURCE_ENERGY: "energy",
+    RESOURCE_POWER: "power",
+
+    RESOURCE_HYDROGEN: "H",
+    RESOURCE_OXYGEN: "O",
+    RESOURCE_UTRIUM: "U",
+    RESOURCE_LEMERGIUM: "L",
+    RESOURCE_KEANIUM: "K",
+    RESOURCE_ZYNTHIUM: "Z",
+    RESOURCE_CATALYST: "X",
+    RESOURCE_GHODIUM: "G",
+
+    RESOURCE_SILICON: "silicon",
+    RESOURCE_METAL: "metal",
+    RESOURCE_BIOMASS: "biomass",
+    RESOURCE_MIST: "mist",
+
+    RESOURCE_HYDROXIDE: "OH",
+    RESOURCE_ZYNTHIUM_KEANITE: "ZK",
+    RESOURCE_UTRIUM_LEMERGITE: "UL",
+
+    RESOURCE_UTRIUM_HYDRIDE: "UH",
+    RESOURCE_UTRIUM_OXIDE: "UO",
+    RESOURCE_KEANIUM_HYDRIDE: "KH",
+    RESOURCE_KEANIUM_OXIDE: "KO",
+    RESOURCE_LEMERGIUM_HYDRIDE: "LH",
+    RESOURCE_LEMERGIUM_OXIDE: "LO",
+    RESOURCE_ZYNTHIUM_HYDRIDE: "ZH",
+    RESOURCE_ZYNTHIUM_OXIDE: "ZO",
+    RESOURCE_GHODIUM_HYDRIDE: "GH",
+    RESOURCE_GHODIUM_OXIDE: "GO",
+
+    RESOURCE_UTRIUM_ACID: "UH2O",
+    RESOURCE_UTRIUM_ALKALIDE: "UHO2",
+    RESOURCE_KEANIUM_ACID: "KH2O",
+    RESOURCE_KEANIUM_ALKALIDE: "KHO2",
+    RESOURCE_LEMERGIUM_ACID: "LH2O",
+    RESOURCE_LEMERGIUM_ALKALIDE: "LHO2",
+    RESOURCE_ZYNTHIUM_ACID: "ZH2O",
+    RESOURCE_ZYNTHIUM_ALKALIDE: "ZHO2",
+    RESOURCE_GHODIUM_ACID: "GH2O",
+    RESOURCE_GHODIUM_ALKALIDE: "GHO2",
+
+    RESOURCE_CATALYZED_UTRIUM_ACID: "XUH2O",
+    RESOURCE_CATALYZED_UTRIUM_ALKALIDE: "XUHO2",
+    RESOURCE_CATALYZED_KEANIUM_ACID: "XKH2O",
+    RESOURCE_CATALYZED_KEANIUM_ALKALIDE: "XKHO2",
+    RESOURCE_CATALYZED_LEMERGIUM_ACID: "XLH2O",
+    RESOURCE_CATALYZED_LEMERGIUM_ALKALIDE: "XLHO2",
+    RESOURCE_CATALYZED_ZYNTHIUM_ACID: "XZH2O",
+    RESOURCE_CATALYZED_ZYNTHIUM_ALKALIDE: "XZHO2",
+    RESOURCE_CATALYZED_GHODIUM_ACID: "XGH2O",
+    RESOURCE_CATALYZED_GHODIUM_ALKALIDE: "XGHO2",
+
+    RESOURCE_OPS: "ops",
+
+    RESOURCE_UTRIUM_BAR: "utrium_bar",
+    RESOURCE_LEMERGIUM_BAR: "lemergium_bar",
+    RESOURCE_ZYNTHIUM_BAR: "zynthium_bar",
+    RESOURCE_KEANIUM_BAR: "keanium_bar",
+    RESOURCE_GHODIUM_MELT: "ghodium_melt",
+    RESOURCE_OXIDANT: "oxidant",
+    RESOURCE_REDUCTANT: "reductant",
+    RESOURCE_PURIFIER: "purifier",
+    RESOURCE_BATTERY: "battery",
+
+    RESOURCE_COMPOSITE: "composite",
+    RESOURCE_CRYSTAL: "crystal",
+    RESOURCE_LIQUID: "liquid",
+
+    RESOURCE_WIRE: "wire",
+    RESOURCE_SWITCH: "switch",
+    RESOURCE_TRANSISTOR: "transistor",
+    RESOURCE_MICROCHIP: "microchip",
+    RESOURCE_CIRCUIT: "circuit",
+    RESOURCE_DEVICE: "device",
+
+    RESOURCE_CELL: "cell",
+    RESOURCE_PHLEGM: "phlegm",
+    RESOURCE_TISSUE: "tissue",
+    RESOURCE_MUSCLE: "muscle",
+    RESOURCE_ORGANOID: "organoid",
+    RESOURCE_ORGANISM: "organism",
+
+    RESOURCE_ALLOY: "alloy",
+    RESOURCE_TUBE: "tube",
+    RESOURCE_FIXTURES: "fixtures",
+    RESOURCE_FRAME: "frame",
+    RESOURCE_HYDRAULICS: "hydraulics",
+    RESOURCE_MACHINE: "machine",
+
+    RESOURCE_CONDENSATE: "condensate",
+    RESOURCE_CONCENTRATE: "concentrate",
+    RESOURCE_EXTRACT: "extract",
+    RESOURCE_SPIRIT: "spirit",
+    RESOURCE_EMANATION: "emanation",
+    RESOURCE_ESSENCE: "essence",
+
+    INVADER_CORE_HITS: 100000
+};
+
+Object.assign(module.exports, {
+    RESOURCES_ALL: [
+        exports.RESOURCE_ENERGY,
+        exports.RESOURCE_POWER,
+
+        exports.RESOURCE_HYDROGEN,
+        exports.RESOURCE_OXYGEN,
+        exports.RESOURCE_UTRIUM,
+        exports.RESOURCE_KEANIUM,
+        exports.RESOURCE_LEMERGIUM,
+        exports.RESOURCE_ZYNTHIUM,
+        exports.RESOURCE_CATALYST,
+        exports.RESOURCE_GHODIUM,
+
+        exports.RESOURCE_HYDROXIDE,
+        exports.RESOURCE_ZYNTHIUM_KEANITE,
+        exports.RESOURCE_UTRIUM_LEMERGITE,
+
+        exports.RESOURCE_UTRIUM_HYDRIDE,
+        exports.RESOURCE_UTRIUM_OXIDE,
+        exports.RESOURCE_KEANIUM_HYDRIDE,
+        exports.RESOURCE_KEANIUM_OXIDE,
+        exports.RESOURCE_LEMERGIUM_HYDRIDE,
+        exports.RESOURCE_LEMERGIUM_OXIDE,
+        exports.RESOURCE_ZYNTHIUM_HYDRIDE,
+        exports.RESOURCE_ZYNTHIUM_OXIDE,
+        exports.RESOURCE_GHODIUM_HYDRIDE,
+        exports.RESOURCE_GHODIUM_OXIDE,
+
+        exports.RESOURCE_UTRIUM_ACID,
+        exports.RESOURCE_UTRIUM_ALKALIDE,
+        exports.RESOURCE_KEANIUM_ACID,
+        exports.RESOURCE_KEANIUM_ALKALIDE,
+        exports.RESOURCE_LEMERGIUM_ACID,
+        exports.RESOURCE_LEMERGIUM_ALKALIDE,
+        exports.RESOURCE_ZYNTHIUM_ACID,
+        exports.RESOURCE_ZYNTHIUM_ALKALIDE,
+        exports.RESOURCE_GHODIUM_ACID,
+        exports.RESOURCE_GHODIUM_ALKALIDE,
+
+        exports.RESOURCE_CATALYZED_UTRIUM_ACID,
+        exports.RESOURCE_CATALYZED_UTRIUM_ALKALIDE,
+        exports.RESOURCE_CATALYZED_KEANIUM_ACID,
+        exports.RESOURCE_CATALYZED_KEANIUM_ALKALIDE,
+        exports.RESOURCE_CATALYZED_LEMERGIUM_ACID,
+        exports.RESOURCE_CATALYZED_LEMERGIUM_ALKALIDE,
+        exports.RESOURCE_CATALYZED_ZYNTHIUM_ACID,
+        exports.RESOURCE_CATALYZED_ZYNTHIUM_ALKALIDE,
+        exports.RESOURCE_CATALYZED_GHODIUM_ACID,
+        exports.RESOURCE_CATALYZED_GHODIUM_ALKALIDE,
+
+        exports.RESOURCE_OPS,
+
+        exports.RESOURCE_SILICON,
+        exports.RESOURCE_METAL,
+        exports.RESOURCE_BIOMASS,
+        exports.RESOURCE_MIST,
+
+        exports.RESOURCE_UTRIUM_BAR,
+        exports.RESOURCE_LEMERGIUM_BAR,
+        exports.RESOURCE_ZYNTHIUM_BAR,
+        exports.RESOURCE_KEANIUM_BAR,
+        exports.RESOURCE_GHODIUM_MELT,
+        exports.RESOURCE_OXIDANT,
+        exports.RESOURCE_REDUCTANT,
+        exports.RESOURCE_PURIFIER,
+        exports.RESOURCE_BATTERY,
+        exports.RESOURCE_COMPOSITE,
+        exports.RESOURCE_CRYSTAL,
+        exports.RESOURCE_LIQUID,
+
+        exports.RESOURCE_WIRE,
+        exports.RESOURCE_SWITCH,
+        exports.RESOURCE_TRANSISTOR,
+        exports.RESOURCE_MICROCHIP,
+        exports.RESOURCE_CIRCUIT,
+        exports.RESOURCE_DEVICE,
+
+        exports.RESOURCE_CELL,
+        exports.RESOURCE_PHLEGM,
+        exports.RESOURCE_TISSUE,
+        exports.RESOURCE_MUSCLE,
+        exports.RESOURCE_ORGANOID,
+        exports.RESOURCE_ORGANISM,
+
+        exports.RESOURCE_ALLOY,
+        exports.RESOURCE_TUBE,
+        exports.RESOURCE_FIXTURES,
+        exports.RESOURCE_FRAME,
+        exports.RESOURCE_HYDRAULICS,
+        exports.RESOURCE_MACHINE,
+
+        exports.RESOURCE_CONDENSATE,
+        exports.RESOURCE_CONCENTRATE,
+        exports.RESOURCE_EXTRACT,
+        exports.RESOURCE_SPIRIT,
+        exports.RESOURCE_EMANATION,
+        exports.RESOURCE_ESSENCE
+    ],
+
+    POWER_CREEP_SPAWN_COOLDOWN: 8*3600*1000,
+    POWER_CREEP_DELETE_COOLDOWN: 24*3600*1000
+});
@@ -0,0 +1,542 @@
+//! Cross-checks of the constants against a vendored copy of the game's
+//! `constants.js`, so drift from the JavaScript source fails `cargo test`.
+//!
+//! The vendored file, `js/constants.js`, holds the sections of the upstream
+//! file which are checked here, and is updated by hand. It's read with a small
+//! parser for the JavaScript literals the file is made of: objects, arrays,
+//! strings, numbers with arithmetic, and references to earlier exports.
+use std::collections::{HashMap, HashSet};
+
+use super::{Boost, BoostAction, Part, ResourceType, StructureType};
+
+const CONSTANTS_JS: &str = include_str!("js/constants.js");
+
+#[derive(Clone, Debug, PartialEq)]
+enum JsValue {
+    Number(f64),
+    String(String),
+    Array(Vec<JsValue>),
+    Object(Vec<(String, JsValue)>),
+}
+
+impl JsValue {
+    fn as_f64(&self) -> f64 {
+        match self {
+            JsValue::Number(n) => *n,
+            other => panic!("expected a number, found {:?}", other),
+        }
+    }
+
+    fn as_u32(&self) -> u32 {
+        let n = self.as_f64();
+        assert!(
+            n >= 0.0 && n <= f64::from(u32::MAX) && n.fract() == 0.0,
+            "expected an integer, found {}",
+            n
+        );
+        n as u32
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            JsValue::String(s) => s,
+            other => panic!("expected a string, found {:?}", other),
+        }
+    }
+
+    fn as_array(&self) -> &[JsValue] {
+        match self {
+            JsValue::Array(values) => values,
+            other => panic!("expected an array, found {:?}", other),
+        }
+    }
+
+    fn as_object(&self) -> &[(String, JsValue)] {
+        match self {
+            JsValue::Object(entries) => entries,
+            other => panic!("expected an object, found {:?}", other),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&JsValue> {
+        self.as_object()
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Reads the exports of a file made of `module.exports = {...}`,
+/// `Object.assign(module.exports, {...})` and `exports.NAME = ...`
+/// statements.
+struct Parser<'a> {
+    src: &'a [u8],
+    pos: usize,
+    exports: HashMap<String, JsValue>,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(src: &'a str) -> HashMap<String, JsValue> {
+        let mut parser = Parser {
+            src: src.as_bytes(),
+            pos: 0,
+            exports: HashMap::new(),
+        };
+        while parser.skip_whitespace() {
+            parser.statement();
+        }
+        parser.exports
+    }
+
+    fn fail(&self, message: &str) -> ! {
+        let line = self.src[..self.pos].iter().filter(|&&b| b == b'\n').count() + 1;
+        panic!("constants.js:{}: {}", line, message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    /// Skips whitespace and comments, returning whether anything is left.
+    fn skip_whitespace(&mut self) -> bool {
+        loop {
+            match self.peek() {
+                Some(b) if b.is_ascii_whitespace() => self.pos += 1,
+                Some(b'/') if self.src.get(self.pos + 1) == Some(&b'/') => {
+                    while !matches!(self.peek(), None | Some(b'\n')) {
+                        self.pos += 1;
+                    }
+                }
+                Some(b'/') if self.src.get(self.pos + 1) == Some(&b'*') => {
+                    match self.src[self.pos + 2..].windows(2).position(|w| w == b"*/") {
+                        Some(end) => self.pos += end + 4,
+                        None => self.fail("unterminated comment"),
+                    }
+                }
+                Some(_) => return true,
+                None => return false,
+            }
+        }
+    }
+
+    /// Consumes `token` after any whitespace, returning whether it was there.
+    fn eat(&mut self, token: u8) -> bool {
+        if self.skip_whitespace() && self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: u8) {
+        if !self.eat(token) {
+            self.fail(&format!("expected `{}`", token as char));
+        }
+    }
+
+    /// Reads a dotted path of identifiers, like `module.exports.OK`.
+    fn path(&mut self) -> String {
+        self.skip_whitespace();
+        let start = self.pos;
+        while matches!(self.peek(), Some(b) if b.is_ascii_alphanumeric() || b == b'_' || b == b'.')
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            self.fail("expected an identifier");
+        }
+        String::from_utf8_lossy(&self.src[start..self.pos]).into_owned()
+    }
+
+    fn statement(&mut self) {
+        let path = self.path();
+        match path.as_str() {
+            "Object.assign" => {
+                self.expect(b'(');
+                let target = self.path();
+                if target != "exports" && target != "module.exports" {
+                    self.fail(&format!("can't assign to `{}`", target));
+                }
+                self.expect(b',');
+                let object = self.value();
+                self.merge(object);
+                self.expect(b')');
+            }
+            "exports" | "module.exports" => {
+                self.expect(b'=');
+                let object = self.value();
+                self.merge(object);
+            }
+            _ => {
+                let name = match export_name(&path) {
+                    Some(name) => name.to_owned(),
+                    None => self.fail(&format!("unexpected statement `{}`", path)),
+                };
+                self.expect(b'=');
+                let value = self.expression();
+                self.exports.insert(name, value);
+            }
+        }
+        self.eat(b';');
+    }
+
+    fn merge(&mut self, object: JsValue) {
+        match object {
+            JsValue::Object(entries) => self.exports.extend(entries),
+            _ => self.fail("expected an object of exports"),
+        }
+    }
+
+    fn expression(&mut self) -> JsValue {
+        let mut value = self.term();
+        loop {
+            let add = if self.eat(b'+') {
+                true
+            } else if self.eat(b'-') {
+                false
+            } else {
+                return value;
+            };
+            let (left, right) = (value.as_f64(), self.term().as_f64());
+            value = JsValue::Number(if add { left + right } else { left - right });
+        }
+    }
+
+    fn term(&mut self) -> JsValue {
+        let mut value = self.value();
+        loop {
+            let multiply = if self.eat(b'*') {
+                true
+            } else if self.eat(b'/') {
+                false
+            } else {
+                return value;
+            };
+            let (left, right) = (value.as_f64(), self.value().as_f64());
+            value = JsValue::Number(if multiply { left * right } else { left / right });
+        }
+    }
+
+    fn value(&mut self) -> JsValue {
+        if !self.skip_whitespace() {
+            self.fail("unexpected end of file");
+        }
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                while !self.eat(b']') {
+                    values.push(self.expression());
+                    if !self.eat(b',') {
+                        self.expect(b']');
+                        break;
+                    }
+                }
+                JsValue::Array(values)
+            }
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.expression();
+                self.expect(b')');
+                value
+            }
+            Some(b'-') => {
+                self.pos += 1;
+                JsValue::Number(-self.value().as_f64())
+            }
+            Some(b'"') | Some(b'\'') => JsValue::String(self.string()),
+            Some(b) if b.is_ascii_digit() || b == b'.' => JsValue::Number(self.number()),
+            _ => {
+                let path = self.path();
+                match export_name(&path).and_then(|name| self.exports.get(name)) {
+                    Some(value) => value.clone(),
+                    None => self.fail(&format!("unknown reference `{}`", path)),
+                }
+            }
+        }
+    }
+
+    fn object(&mut self) -> JsValue {
+        self.expect(b'{');
+        let mut entries = Vec::new();
+        while !self.eat(b'}') {
+            let key = match self.peek() {
+                Some(b'"') | Some(b'\'') => self.string(),
+                Some(b'[') => {
+                    self.pos += 1;
+                    let key = self.expression();
+                    self.expect(b']');
+                    match key {
+                        JsValue::String(s) => s,
+                        JsValue::Number(n) => n.to_string(),
+                        _ => self.fail("expected a string or number key"),
+                    }
+                }
+                Some(b) if b.is_ascii_digit() => self.number().to_string(),
+                _ => self.path(),
+            };
+            self.expect(b':');
+            entries.push((key, self.expression()));
+            if !self.eat(b',') {
+                self.expect(b'}');
+                break;
+            }
+        }
+        JsValue::Object(entries)
+    }
+
+    fn string(&mut self) -> String {
+        let quote = self.src[self.pos];
+        let start = self.pos + 1;
+        match self.src[start..]
+            .iter()
+            .position(|&b| b == quote || b == b'\\')
+        {
+            Some(len) if self.src[start + len] == quote => {
+                self.pos = start + len + 1;
+                String::from_utf8_lossy(&self.src[start..start + len]).into_owned()
+            }
+            Some(_) => self.fail("escapes in strings aren't supported"),
+            None => self.fail("unterminated string"),
+        }
+    }
+
+    fn number(&mut self) -> f64 {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b) if b.is_ascii_digit() || b == b'.' || b == b'e') {
+            self.pos += 1;
+        }
+        let text = String::from_utf8_lossy(&self.src[start..self.pos]);
+        match text.parse() {
+            Ok(n) => n,
+            Err(_) => self.fail(&format!("invalid number `{}`", text)),
+        }
+    }
+}
+
+/// The exported name referred to by a path like `exports.OK`.
+fn export_name(path: &str) -> Option<&str> {
+    path.strip_prefix("module.exports.")
+        .or_else(|| path.strip_prefix("exports."))
+}
+
+fn exports() -> HashMap<String, JsValue> {
+    Parser::parse(CONSTANTS_JS)
+}
+
+/// The name of the `*_HITS` constant of a structure type, like
+/// `POWER_SPAWN_HITS` for `powerSpawn`.
+fn hits_constant(structure_type: StructureType) -> String {
+    let name = match structure_type {
+        StructureType::Wall => "wall".to_owned(),
+        other => other.to_string(),
+    };
+    let mut constant = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            constant.push('_');
+        }
+        constant.push(c.to_ascii_uppercase());
+    }
+    constant + "_HITS"
+}
+
+/// The action of a boost, by the name of one of the methods it affects.
+fn boost_action(method: &str) -> BoostAction {
+    match method {
+        "harvest" => BoostAction::Harvest,
+        "build" | "repair" => BoostAction::BuildAndRepair,
+        "dismantle" => BoostAction::Dismantle,
+        "upgradeController" => BoostAction::UpgradeController,
+        "attack" => BoostAction::Attack,
+        "rangedAttack" | "rangedMassAttack" => BoostAction::RangedAttack,
+        "heal" | "rangedHeal" => BoostAction::Heal,
+        "capacity" => BoostAction::Carry,
+        "fatigue" => BoostAction::Move,
+        "damage" => BoostAction::Tough,
+        other => panic!("unknown boosted method `{}`", other),
+    }
+}
+
+#[test]
+fn parser() {
+    let exports = Parser::parse(
+        r#"
+        // comment
+        module.exports = {
+            A: 1, /* inline */ B: 'b',
+            C: [1, -2.5, .5, "c",],
+            D: { 1: 2, "x": { y: [] } },
+        };
+        Object.assign(exports, { E: 8*3600*1000 + 1, F: [exports.B, module.exports.A] });
+        exports.G = (exports.A + 2) / 2 - 1;
+        "#,
+    );
+    let get = |name: &str| exports[name].clone();
+    assert_eq!(get("A"), JsValue::Number(1.0));
+    assert_eq!(get("B"), JsValue::String("b".to_owned()));
+    assert_eq!(
+        get("C"),
+        JsValue::Array(vec![
+            JsValue::Number(1.0),
+            JsValue::Number(-2.5),
+            JsValue::Number(0.5),
+            JsValue::String("c".to_owned()),
+        ])
+    );
+    assert_eq!(get("D").get("1"), Some(&JsValue::Number(2.0)));
+    assert_eq!(
+        get("D").get("x").and_then(|x| x.get("y")),
+        Some(&JsValue::Array(vec![]))
+    );
+    assert_eq!(get("E").as_u32(), 28_800_001);
+    assert_eq!(
+        get("F"),
+        JsValue::Array(vec![JsValue::String("b".to_owned()), JsValue::Number(1.0)])
+    );
+    assert_eq!(get("G").as_f64(), 0.5);
+}
+
+#[test]
+fn structure_types() {
+    let exports = exports();
+    let js: HashSet<&str> = exports
+        .iter()
+        .filter(|(name, _)| name.starts_with("STRUCTURE_"))
+        .map(|(_, value)| value.as_str())
+        .collect();
+    let rust: Vec<String> = StructureType::iter_values()
+        .map(|ty| ty.to_string())
+        .collect();
+    let rust: HashSet<&str> = rust.iter().map(String::as_str).collect();
+    assert_eq!(js, rust);
+}
+
+#[test]
+fn construction_cost() {
+    let exports = exports();
+    let costs = &exports["CONSTRUCTION_COST"];
+    for structure_type in StructureType::iter_values() {
+        let js = costs.get(&structure_type.to_string()).map(JsValue::as_u32);
+        assert_eq!(
+            structure_type.construction_cost(),
+            js,
+            "{:?}",
+            structure_type
+        );
+    }
+    assert_eq!(
+        exports["CONSTRUCTION_COST_ROAD_SWAMP_RATIO"].as_u32(),
+        super::CONSTRUCTION_COST_ROAD_SWAMP_RATIO
+    );
+    assert_eq!(
+        exports["CONSTRUCTION_COST_ROAD_WALL_RATIO"].as_u32(),
+        super::CONSTRUCTION_COST_ROAD_WALL_RATIO
+    );
+}
+
+#[test]
+fn controller_structures() {
+    let exports = exports();
+    let allowed = &exports["CONTROLLER_STRUCTURES"];
+    for structure_type in StructureType::iter_values() {
+        let by_level = match allowed.get(&structure_type.to_string()) {
+            Some(by_level) => by_level,
+            None => {
+                for rcl in 0..=8 {
+                    assert_eq!(structure_type.controller_structures(rcl), 0);
+                }
+                continue;
+            }
+        };
+        for (rcl, count) in by_level.as_object() {
+            let rcl: u32 = rcl.parse().unwrap();
+            assert_eq!(
+                structure_type.controller_structures(rcl),
+                count.as_u32(),
+                "{:?} at RCL {}",
+                structure_type,
+                rcl
+            );
+        }
+    }
+}
+
+#[test]
+fn initial_hits() {
+    let exports = exports();
+    for structure_type in StructureType::iter_values() {
+        let js = exports
+            .get(&hits_constant(structure_type))
+            .map(JsValue::as_u32);
+        assert_eq!(structure_type.initial_hits(), js, "{:?}", structure_type);
+    }
+    assert_eq!(exports["WALL_HITS_MAX"].as_u32(), super::WALL_HITS_MAX);
+    for (rcl, hits) in exports["RAMPART_HITS_MAX"].as_object() {
+        assert_eq!(
+            super::rampart_hits_max(rcl.parse().unwrap()),
+            hits.as_u32(),
+            "RCL {}",
+            rcl
+        );
+    }
+}
+
+#[test]
+fn boosts() {
+    let exports = exports();
+    let mut boosted = HashSet::new();
+    for (part, compounds) in exports["BOOSTS"].as_object() {
+        let part: Part = part
+            .parse()
+            .unwrap_or_else(|_| panic!("unknown part `{}`", part));
+        for (compound, methods) in compounds.as_object() {
+            let resource: ResourceType = compound.parse().unwrap();
+            let boost: Boost = resource
+                .boost()
+                .unwrap_or_else(|| panic!("{:?} has no boost", resource));
+            assert_eq!(boost.part(), part, "{:?}", resource);
+            for (method, multiplier) in methods.as_object() {
+                assert_eq!(boost.action(), boost_action(method), "{:?}", resource);
+                assert_eq!(boost.multiplier(), multiplier.as_f64(), "{:?}", resource);
+            }
+            boosted.insert(resource);
+        }
+    }
+    for resource in ResourceType::iter_values() {
+        assert_eq!(
+            resource.boost().is_some(),
+            boosted.contains(&resource),
+            "{:?}",
+            resource
+        );
+    }
+}
+
+#[test]
+fn resource_names() {
+    let exports = exports();
+    let all: Vec<&str> = exports["RESOURCES_ALL"]
+        .as_array()
+        .iter()
+        .map(JsValue::as_str)
+        .collect();
+    let js: HashSet<&str> = all.iter().copied().collect();
+    assert_eq!(js.len(), all.len(), "duplicates in RESOURCES_ALL");
+    let rust: Vec<String> = ResourceType::iter_values().map(|r| r.to_string()).collect();
+    let rust: HashSet<&str> = rust.iter().map(String::as_str).collect();
+    assert_eq!(js, rust);
+
+    // every RESOURCE_* constant is listed
+    for (name, value) in &exports {
+        if name.starts_with("RESOURCE_") {
+            assert!(
+                js.contains(value.as_str()),
+                "{} isn't in RESOURCES_ALL",
+                name
+            );
+        }
+    }
+}
@@ -1,4 +1,6 @@
 //! Plain data constants and functions returning plain data.
+use crate::game::{gcl, gpl};
+
 use super::types::{ResourceType, StructureType};
 
 // OK and ERR_* defined in ReturnCode in `small_enums.rs`
@@ -9,13 +11,18 @@ use super::types::{ResourceType, StructureType};
 
 // LOOK_* defined in `look.rs`
 
-// OBSTACLE_OBJECT_TYPES not yet implemented
+// OBSTACLE_OBJECT_TYPES for structures defined in `StructureType::is_obstacle`
 
 // body parts and their costs defined in `small_enums.rs`
 
 /// Initial ticks_to_live of a creep without any claim parts.
 pub const CREEP_LIFE_TIME: u32 = 1500;
 /// Initial ticks_to_live of a creep with at least one claim part.
+///
+/// Use the [`body::creep_max_lifetime`] function to pick the lifetime of a
+/// body.
+///
+/// [`body::creep_max_lifetime`]: crate::body::creep_max_lifetime
 pub const CREEP_CLAIM_LIFE_TIME: u32 = 600;
 /// Percentage of TTL-adjusted creep resource costs added to tombstone.
 ///
@@ -144,6 +151,17 @@ pub const SPAWN_ENERGY_CAPACITY: u32 = 300;
 /// Ticks taken to spawn each creep body part, before power creep effects.
 pub const CREEP_SPAWN_TIME: u32 = 3;
 
+/// Ticks left until a creep with `parts` body parts, which started spawning at
+/// game tick `started_at`, is spawned at game tick `now`, before power creep
+/// effects.
+///
+/// Returns 0 once the creep is spawned.
+#[inline]
+pub fn spawning_ticks_remaining(parts: usize, started_at: u32, now: u32) -> u32 {
+    let needed = parts as u32 * CREEP_SPAWN_TIME;
+    needed.saturating_sub(now.saturating_sub(started_at))
+}
+
 /// Additional TTL bonus, and reduction in energy cost, when renewing a creep
 /// compared to spawning.
 ///
@@ -164,6 +182,12 @@ pub const CREEP_SPAWN_TIME: u32 = 3;
 /// [`SPAWN_RENEW_RATIO`], or `3 / 1.2 == 2.5`
 ///
 /// [`StructureSpawn.renewCreep`]: https://docs.screeps.com/api/#StructureSpawn.renewCreep
+///
+/// Use the [`body::renew_ticks`] and [`body::renew_cost`] functions to
+/// calculate each.
+///
+/// [`body::renew_ticks`]: crate::body::renew_ticks
+/// [`body::renew_cost`]: crate::body::renew_cost
 pub const SPAWN_RENEW_RATIO: f32 = 1.2;
 
 /// Source energy capacity immediately after regeneration in owned and reserved
@@ -175,6 +199,28 @@ pub const SOURCE_ENERGY_NEUTRAL_CAPACITY: u32 = 1500;
 /// (sector center) rooms.
 pub const SOURCE_ENERGY_KEEPER_CAPACITY: u32 = 4000;
 
+/// The energy capacity of a source: [`SOURCE_ENERGY_KEEPER_CAPACITY`] in source
+/// keeper rooms, otherwise [`SOURCE_ENERGY_CAPACITY`] in rooms which are
+/// `owned` or reserved, and [`SOURCE_ENERGY_NEUTRAL_CAPACITY`] in neutral
+/// rooms.
+///
+/// This is before the `PWR_REGEN_SOURCE` and `PWR_DISRUPT_SOURCE` effects.
+#[inline]
+pub fn source_energy_capacity(owned: bool, keeper: bool) -> u32 {
+    match (owned, keeper) {
+        (_, true) => SOURCE_ENERGY_KEEPER_CAPACITY,
+        (true, false) => SOURCE_ENERGY_CAPACITY,
+        (false, false) => SOURCE_ENERGY_NEUTRAL_CAPACITY,
+    }
+}
+
+/// The average energy per tick from fully harvesting a source with the given
+/// capacity every [`ENERGY_REGEN_TIME`] ticks.
+#[inline]
+pub fn expected_energy_per_tick(capacity: u32) -> f32 {
+    capacity as f32 / ENERGY_REGEN_TIME as f32
+}
+
 /// Initial hits for wall structures when built; consider using the
 /// [`StructureType::initial_hits`] function.
 pub const WALL_HITS: u32 = 1;
@@ -186,7 +232,7 @@ pub const WALL_HITS_MAX: u32 = 300_000_000;
 pub const EXTENSION_HITS: u32 = 1000;
 
 /// Translates the `EXTENSION_ENERGY_CAPACITY` constant, the energy capacity of
-/// each source structure at a given room control level.
+/// each extension at a given room control level.
 #[inline]
 pub fn extension_energy_capacity(rcl: u32) -> u32 {
     match rcl {
@@ -196,6 +242,17 @@ pub fn extension_energy_capacity(rcl: u32) -> u32 {
     }
 }
 
+/// The combined energy capacity of all the extensions allowed at a given room
+/// control level, [`extension_energy_capacity`] times the number of
+/// extensions from [`StructureType::controller_structures`].
+///
+/// Add [`SPAWN_ENERGY_CAPACITY`] for each spawn to get the energy available
+/// for spawning a single creep when every structure is built and full.
+#[inline]
+pub fn total_extension_capacity(rcl: u32) -> u32 {
+    extension_energy_capacity(rcl) * StructureType::Extension.controller_structures(rcl)
+}
+
 /// Maximum hits for road structures, before swamp/tunnel multipliers
 ///
 /// Cost, hits, and decay rate are multiplied in the cases of building on
@@ -251,9 +308,13 @@ pub const CONSTRUCTION_COST_ROAD_WALL_RATIO: u32 = 150;
 /// Translates the `CONTROLLER_LEVELS` constant.
 ///
 /// The number of control points required to upgrade to the next level at each
-/// room control level.
+/// room control level, the [`StructureController::progress_total`] of
+/// controllers at that level.
 ///
 /// Returns `Some` for levels 1-7, `None` for all others.
+///
+/// [`StructureController::progress_total`]:
+/// crate::objects::StructureController::progress_total
 #[inline]
 pub fn controller_levels(current_rcl: u32) -> Option<u32> {
     match current_rcl {
@@ -268,6 +329,26 @@ pub fn controller_levels(current_rcl: u32) -> Option<u32> {
     }
 }
 
+/// Ticks until a controller of level `current_rcl` with `progress` control
+/// points upgrades to the next level, when upgraded by `upgrade_per_tick`
+/// points each tick.
+///
+/// Returns `None` for levels without a next level (see
+/// [`controller_levels`]), or if `upgrade_per_tick` is 0.
+#[inline]
+pub fn controller_ticks_to_upgrade(
+    current_rcl: u32,
+    progress: u32,
+    upgrade_per_tick: u32,
+) -> Option<u32> {
+    let total = controller_levels(current_rcl)?;
+    if upgrade_per_tick == 0 {
+        return None;
+    }
+    let remaining = total.saturating_sub(progress);
+    Some((remaining + upgrade_per_tick - 1) / upgrade_per_tick)
+}
+
 // CONTROLLER_STRUCTURES defined in `types.rs`
 
 /// Translates the `CONTROLLER_DOWNGRADE` constant, the maximum value of
@@ -410,8 +491,47 @@ pub const TOWER_FALLOFF_RANGE: u32 = 20;
 /// ```
 ///
 /// [source]: https://github.com/screeps/engine/blob/f02d16a44a00c35615ae227fc72a3c9a07a6a39a/src/processor/intents/towers/attack.js#L38
+///
+/// Use the [`tower_attack_power_at_range`], [`tower_heal_power_at_range`] and
+/// [`tower_repair_power_at_range`] functions to calculate the reduced amounts.
 pub const TOWER_FALLOFF: f32 = 0.75;
 
+/// Applies the range falloff of tower actions to `amount`, rounding down.
+fn tower_power_at_range(amount: u32, range: u32) -> u32 {
+    let range = range.clamp(TOWER_OPTIMAL_RANGE, TOWER_FALLOFF_RANGE);
+    let amount = f64::from(amount);
+    let falloff = amount * f64::from(TOWER_FALLOFF) * f64::from(range - TOWER_OPTIMAL_RANGE)
+        / f64::from(TOWER_FALLOFF_RANGE - TOWER_OPTIMAL_RANGE);
+    (amount - falloff).floor() as u32
+}
+
+/// Damage done by [`StructureTower::attack`] to a target at the given range,
+/// before power and boost effects; see [`TOWER_FALLOFF`].
+///
+/// [`StructureTower::attack`]: crate::objects::StructureTower::attack
+#[inline]
+pub fn tower_attack_power_at_range(range: u32) -> u32 {
+    tower_power_at_range(TOWER_POWER_ATTACK, range)
+}
+
+/// Hits healed by [`StructureTower::heal`] on a target at the given range,
+/// before power effects; see [`TOWER_FALLOFF`].
+///
+/// [`StructureTower::heal`]: crate::objects::StructureTower::heal
+#[inline]
+pub fn tower_heal_power_at_range(range: u32) -> u32 {
+    tower_power_at_range(TOWER_POWER_HEAL, range)
+}
+
+/// Hits repaired by [`StructureTower::repair`] on a target at the given
+/// range, before power effects; see [`TOWER_FALLOFF`].
+///
+/// [`StructureTower::repair`]: crate::objects::StructureTower::repair
+#[inline]
+pub fn tower_repair_power_at_range(range: u32) -> u32 {
+    tower_power_at_range(TOWER_POWER_REPAIR, range)
+}
+
 /// Initial hits for observer structures; consider using the
 /// [`StructureType::initial_hits`] function.
 pub const OBSERVER_HITS: u32 = 500;
@@ -507,6 +627,36 @@ pub const GCL_MULTIPLY: u32 = 1_000_000;
 /// Maximum GCL for players allowed to spawn in a Novice area.
 pub const GCL_NOVICE: u32 = 3;
 
+/// The global control level reached with `progress` lifetime control points,
+/// calculated as the server does.
+#[inline]
+pub fn gcl_level_for_progress(progress: u64) -> u32 {
+    ((progress as f64 / f64::from(GCL_MULTIPLY)).powf(1.0 / GCL_POW)).floor() as u32 + 1
+}
+
+/// The lifetime control points needed to reach global control level `level`:
+/// the smallest amount for which [`gcl_level_for_progress`] returns `level`.
+///
+/// This is [`game::gcl::total_for_level`] rounded to the amount the server
+/// actually requires. Returns 0 for levels 0 and 1.
+///
+/// [`game::gcl::total_for_level`]: crate::game::gcl::total_for_level
+pub fn gcl_progress_required(level: u32) -> u64 {
+    if level <= 1 {
+        return 0;
+    }
+    let total = gcl::total_for_level(level);
+    // step over rounding differences between the level formula and its inverse
+    let mut progress = total.ceil() as u64;
+    while gcl_level_for_progress(progress) < level {
+        progress += 1;
+    }
+    while gcl_level_for_progress(progress - 1) >= level {
+        progress -= 1;
+    }
+    progress
+}
+
 /// Terrain bitmask value representing natural walls; represented by
 /// [`Terrain::Wall`] when using terrain functions.
 ///
@@ -568,7 +718,7 @@ pub const MINERAL_DENSITY_CHANGE: f32 = 0.05;
 /// ```
 ///
 /// [source]: https://github.com/screeps/engine/blob/f02d16a44a00c35615ae227fc72a3c9a07a6a39a/src/processor/intents/creeps/harvest.js#L134
-pub const DEPOSIT_EXHAUST_MULTIPLY: f32 = 0.001;
+pub const DEPOSIT_EXHAUST_MULTIPLY: f64 = 0.001;
 
 /// Exponential growth rate for deposit cooldown determination.
 ///
@@ -579,10 +729,21 @@ pub const DEPOSIT_EXHAUST_MULTIPLY: f32 = 0.001;
 /// ```
 ///
 /// [source]: https://github.com/screeps/engine/blob/f02d16a44a00c35615ae227fc72a3c9a07a6a39a/src/processor/intents/creeps/harvest.js#L134
-pub const DEPOSIT_EXHAUST_POW: f32 = 1.2;
+pub const DEPOSIT_EXHAUST_POW: f64 = 1.2;
 /// Time since last harvest that a deposit will decay.
 pub const DEPOSIT_DECAY_TIME: u32 = 50_000;
 
+/// The cooldown of a deposit after a harvest, once `total_harvested` units
+/// have been harvested from it in total, including by that harvest.
+///
+/// Computed from [`DEPOSIT_EXHAUST_MULTIPLY`] and [`DEPOSIT_EXHAUST_POW`] in
+/// double precision, like the server, so the result is rounded up on the same
+/// side.
+#[inline]
+pub fn deposit_cooldown_for_harvested(total_harvested: u32) -> u32 {
+    (DEPOSIT_EXHAUST_MULTIPLY * f64::from(total_harvested).powf(DEPOSIT_EXHAUST_POW)).ceil() as u32
+}
+
 /// Initial hits for terminal structures; consider using the
 /// [`StructureType::initial_hits`] function.
 pub const TERMINAL_HITS: u32 = 3000;
@@ -627,6 +788,20 @@ pub const NUKE_DAMAGE_RANGE_0: u32 = 10_000_000;
 /// Damage in hits done by nukes within range 2.
 pub const NUKE_DAMAGE_RANGE_2: u32 = 5_000_000;
 
+/// Damage in hits done by a nuke to structures and creeps at `range` tiles
+/// from its point of impact: [`NUKE_DAMAGE_RANGE_0`] at the point of impact,
+/// [`NUKE_DAMAGE_RANGE_2`] within range 2, and none further away.
+///
+/// Ramparts absorb this damage for everything on their tile.
+#[inline]
+pub fn nuke_damage_at_range(range: u32) -> u32 {
+    match range {
+        0 => NUKE_DAMAGE_RANGE_0,
+        1..=2 => NUKE_DAMAGE_RANGE_2,
+        _ => 0,
+    }
+}
+
 /// Initial hits for factory structures; consider using the
 /// [`StructureType::initial_hits`] function.
 pub const FACTORY_HITS: u32 = 1000;
@@ -658,8 +833,13 @@ pub const PORTAL_DECAY: u32 = 30_000;
 
 // ORDER_SELL / ORDER_BUY defined in `src/game.rs`
 
-/// Percentage of order value in credits charged as a fee for market listings.
-pub const MARKET_FEE: f32 = 0.05;
+/// Fraction of order value in credits charged as a fee for market listings.
+///
+/// Use the [`game::market::order_fee`] function to calculate the fee for an
+/// order.
+///
+/// [`game::market::order_fee`]: crate::game::market::order_fee
+pub const MARKET_FEE: f64 = 0.05;
 
 /// Maximum number of total orders a player is allowed to have on the market.
 pub const MARKET_MAX_ORDERS: u32 = 300;
@@ -718,6 +898,12 @@ pub const INVADERS_ENERGY_GOAL: u32 = 100_000;
 /// Owner username of system-owned structures and creeps.
 pub const SYSTEM_USERNAME: &str = "Screeps";
 
+/// Owner username of NPC invader creeps, invader cores and strongholds.
+pub const INVADER_USERNAME: &str = "Invader";
+
+/// Owner username of source keeper creeps and keeper lairs.
+pub const SOURCE_KEEPER_USERNAME: &str = "Source Keeper";
+
 /// Text added to signs of unowned rooms when a novice or respawn area is
 /// planned for the sector.
 pub const SIGN_PLANNED_AREA: &str = "A new Novice or Respawn Area is being planned somewhere \
@@ -738,6 +924,26 @@ pub const POWER_LEVEL_MULTIPLY: u32 = 1000;
 ///
 /// [`game::gpl::total_for_level`]: crate::game::gpl::total_for_level
 pub const POWER_LEVEL_POW: u32 = 2;
+
+/// The global power level reached with `progress` lifetime processed power,
+/// calculated as the server does.
+#[inline]
+pub fn gpl_level_for_progress(progress: u64) -> u32 {
+    (progress as f64 / f64::from(POWER_LEVEL_MULTIPLY))
+        .powf(1.0 / f64::from(POWER_LEVEL_POW))
+        .floor() as u32
+}
+
+/// The lifetime processed power needed to reach global power level `level`.
+///
+/// The same as [`game::gpl::total_for_level`], next to the other global
+/// power level constants.
+///
+/// [`game::gpl::total_for_level`]: crate::game::gpl::total_for_level
+#[inline]
+pub fn gpl_progress_required(level: u32) -> u64 {
+    gpl::total_for_level(level)
+}
 /// Time, in milliseconds, that a power creep must wait to respawn after dying.
 pub const POWER_CREEP_SPAWN_COOLDOWN: u32 = 8 * 3600 * 1000;
 /// Time, in milliseconds, after a deletion is started via
@@ -783,10 +989,13 @@ pub const INVADER_CORE_CONTROLLER_DOWNGRADE: u32 = 5000;
 
 /// Ticks per body part that invader cores of each level take to spawn defensive
 /// creeps.
+///
+/// Level 0 is the level of the invader cores reserving rooms outside of
+/// strongholds.
 #[inline]
 pub fn invader_core_creep_spawn_time(core_level: u32) -> Option<u32> {
     match core_level {
-        1 => Some(0),
+        0 | 1 => Some(0),
         2 => Some(6),
         3 => Some(3),
         4 => Some(2),
@@ -796,9 +1005,13 @@ pub fn invader_core_creep_spawn_time(core_level: u32) -> Option<u32> {
 }
 
 /// Rampart hits for each level of stronghold.
+///
+/// Level 0 invader cores, which reserve rooms rather than forming a
+/// stronghold, have no ramparts.
 #[inline]
 pub fn stronghold_rampart_hits(core_level: u32) -> Option<u32> {
     match core_level {
+        0 => Some(0),
         1 => Some(100_000),
         2 => Some(200_000),
         3 => Some(500_000),
@@ -820,7 +1033,9 @@ pub fn stronghold_rampart_hits(core_level: u32) -> Option<u32> {
 /// [source]: https://github.com/screeps/engine/blob/b2ac4720abe399837b0ba38712aaadfd4a9e9a7e/src/processor/intents/invader-core/stronghold/stronghold.js#L27
 pub const STRONGHOLD_DECAY_TICKS: u32 = 75_000;
 
-// POWER_INFO not yet implemented
-// BODYPARTS_ALL, RESOURCES_ALL, COLORS_ALL not yet implemented
+// POWER_INFO defined in `types.rs` as `PowerType::info`
+// BODYPARTS_ALL defined in `small_enums.rs`
+// RESOURCES_ALL defined in `types.rs`
+// COLORS_ALL defined in `small_enums.rs`
 // INTERSHARD_RESOURCES defined in `types.rs`
 // COMMODITIES defined in `recipes.rs`
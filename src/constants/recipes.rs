@@ -15,84 +15,104 @@ pub struct FactoryRecipe {
     pub level: Option<u32>,
 }
 
+/// Translation of the `REACTIONS` constant, as each compound along with the
+/// two resources reacting to produce it.
+const REACTIONS: [(ResourceType, [ResourceType; 2]); 34] = {
+    use ResourceType::*;
+    [
+        // OH: O + H,
+        (Hydroxide, [Oxygen, Hydrogen]),
+        // ZK: Z + K,
+        (ZynthiumKeanite, [Zynthium, Keanium]),
+        // UL: U + L,
+        (UtriumLemergite, [Utrium, Lemergium]),
+        // G: UL + ZK,
+        (Ghodium, [UtriumLemergite, ZynthiumKeanite]),
+        // UH: U + H,
+        (UtriumHydride, [Utrium, Hydrogen]),
+        // UH2O: UH + OH,
+        (UtriumAcid, [UtriumHydride, Hydroxide]),
+        // XUH2O: UH2O + X,
+        (CatalyzedUtriumAcid, [UtriumAcid, Catalyst]),
+        // UO: U + O,
+        (UtriumOxide, [Utrium, Oxygen]),
+        // UHO2: UO + OH,
+        (UtriumAlkalide, [UtriumOxide, Hydroxide]),
+        // XUHO2: UHO2 + X,
+        (CatalyzedUtriumAlkalide, [UtriumAlkalide, Catalyst]),
+        // KH: K + H,
+        (KeaniumHydride, [Keanium, Hydrogen]),
+        // KH2O: KH + OH,
+        (KeaniumAcid, [KeaniumHydride, Hydroxide]),
+        // XKH2O: KH2O + X,
+        (CatalyzedKeaniumAcid, [KeaniumAcid, Catalyst]),
+        // KO: K + O,
+        (KeaniumOxide, [Keanium, Oxygen]),
+        // KHO2: KO + OH,
+        (KeaniumAlkalide, [KeaniumOxide, Hydroxide]),
+        // XKHO2: KHO2 + X,
+        (CatalyzedKeaniumAlkalide, [KeaniumAlkalide, Catalyst]),
+        // LH: L + H,
+        (LemergiumHydride, [Lemergium, Hydrogen]),
+        // LH2O: LH + OH,
+        (LemergiumAcid, [LemergiumHydride, Hydroxide]),
+        // XLH2O: LH2O + X,
+        (CatalyzedLemergiumAcid, [LemergiumAcid, Catalyst]),
+        // LO: L + O,
+        (LemergiumOxide, [Lemergium, Oxygen]),
+        // LHO2: LO + OH,
+        (LemergiumAlkalide, [LemergiumOxide, Hydroxide]),
+        // XLHO2: LHO2 + X,
+        (CatalyzedLemergiumAlkalide, [LemergiumAlkalide, Catalyst]),
+        // ZH: Z + H,
+        (ZynthiumHydride, [Zynthium, Hydrogen]),
+        // ZH2O: ZH + OH,
+        (ZynthiumAcid, [ZynthiumHydride, Hydroxide]),
+        // XZH2O: ZH2O + X,
+        (CatalyzedZynthiumAcid, [ZynthiumAcid, Catalyst]),
+        // ZO: Z + O,
+        (ZynthiumOxide, [Zynthium, Oxygen]),
+        // ZHO2: ZO + OH,
+        (ZynthiumAlkalide, [ZynthiumOxide, Hydroxide]),
+        // XZHO2: ZHO2 + X,
+        (CatalyzedZynthiumAlkalide, [ZynthiumAlkalide, Catalyst]),
+        // GH: G + H,
+        (GhodiumHydride, [Ghodium, Hydrogen]),
+        // GH2O: GH + OH,
+        (GhodiumAcid, [GhodiumHydride, Hydroxide]),
+        // XGH2O: GH2O + X,
+        (CatalyzedGhodiumAcid, [GhodiumAcid, Catalyst]),
+        // GO: G + O,
+        (GhodiumOxide, [Ghodium, Oxygen]),
+        // GHO2: GO + OH,
+        (GhodiumAlkalide, [GhodiumOxide, Hydroxide]),
+        // XGHO2: GHO2 + X,
+        (CatalyzedGhodiumAlkalide, [GhodiumAlkalide, Catalyst]),
+    ]
+};
+
 impl ResourceType {
     /// Translates the `REACTIONS` constant.
+    ///
+    /// Returns the two resources which react in a lab to produce this
+    /// compound, or `None` for resources which aren't produced by a reaction.
     #[inline]
     pub fn reaction_components(self) -> Option<[ResourceType; 2]> {
-        use ResourceType::*;
-        let components = match self {
-            // OH: O + H,
-            Hydroxide => [Oxygen, Hydrogen],
-            // ZK: Z + K,
-            ZynthiumKeanite => [Zynthium, Keanium],
-            // UL: U + L,
-            UtriumLemergite => [Utrium, Lemergium],
-            // G: UL + ZK,
-            Ghodium => [UtriumLemergite, ZynthiumKeanite],
-            // UH: U + H,
-            UtriumHydride => [Utrium, Hydrogen],
-            // UH2O: UH + OH,
-            UtriumAcid => [UtriumHydride, Hydroxide],
-            // XUH2O: UH20 + X,
-            CatalyzedUtriumAcid => [UtriumAcid, Catalyst],
-            // UO: U + O,
-            UtriumOxide => [Utrium, Oxygen],
-            // UHO2: UO + OH,
-            UtriumAlkalide => [UtriumOxide, Hydroxide],
-            // XUHO2: UHO2 + X,
-            CatalyzedUtriumAlkalide => [UtriumAlkalide, Catalyst],
-            // KH: K + H,
-            KeaniumHydride => [Keanium, Hydrogen],
-            // KH2O: KH + OH,
-            KeaniumAcid => [KeaniumHydride, Hydroxide],
-            // XKH2O: KH2O + X,
-            CatalyzedKeaniumAcid => [KeaniumAcid, Catalyst],
-            // KO: K + O,
-            KeaniumOxide => [Keanium, Oxygen],
-            // KHO2: KO + OH,
-            KeaniumAlkalide => [KeaniumOxide, Hydroxide],
-            // XKHO2: KHO2 + X,
-            CatalyzedKeaniumAlkalide => [KeaniumAlkalide, Catalyst],
-            // LH: L + H,
-            LemergiumHydride => [Lemergium, Hydrogen],
-            // LH2O: LH + OH,
-            LemergiumAcid => [LemergiumHydride, Hydroxide],
-            // XLH2O: LH2O + X,
-            CatalyzedLemergiumAcid => [LemergiumAcid, Catalyst],
-            // LO: L + O,
-            LemergiumOxide => [Lemergium, Oxygen],
-            // LHO2: LO + OH,
-            LemergiumAlkalide => [LemergiumOxide, Hydroxide],
-            // XLHO2: LHO2 + X,
-            CatalyzedLemergiumAlkalide => [LemergiumAlkalide, Catalyst],
-            // ZH: Z + H,
-            ZynthiumHydride => [Zynthium, Hydrogen],
-            // ZH2O: ZH + OH,
-            ZynthiumAcid => [ZynthiumHydride, Hydroxide],
-            // XZH2O: ZH2O + Z,
-            CatalyzedZynthiumAcid => [ZynthiumAcid, Catalyst],
-            // ZO: Z + O,
-            ZynthiumOxide => [Zynthium, Oxygen],
-            // ZHO2: ZO + OH,
-            ZynthiumAlkalide => [ZynthiumOxide, Hydroxide],
-            // XZHO2: ZHO2 + X,
-            CatalyzedZynthiumAlkalide => [ZynthiumAlkalide, Catalyst],
-            // GH: G + H,
-            GhodiumHydride => [Ghodium, Hydrogen],
-            // GH2O: GH + OH,
-            GhodiumAcid => [GhodiumHydride, Hydroxide],
-            // XGH2O: GH2O + X,
-            CatalyzedGhodiumAcid => [GhodiumAcid, Catalyst],
-            // GO: G + O,
-            GhodiumOxide => [Ghodium, Oxygen],
-            // GHO2: GO + OH,
-            GhodiumAlkalide => [GhodiumOxide, Hydroxide],
-            // XGHO2: GHO2 + X,
-            CatalyzedGhodiumAlkalide => [GhodiumAlkalide, Catalyst],
-            // non-molecule resources
-            _ => return None,
-        };
-        Some(components)
+        REACTIONS
+            .iter()
+            .find(|(product, _)| *product == self)
+            .map(|(_, components)| *components)
+    }
+
+    /// Translates the `REACTIONS` constant.
+    ///
+    /// Returns the compound produced by a lab reaction between `lhs` and
+    /// `rhs`, in either order, or `None` if the two don't react.
+    pub fn lab_reaction_product(lhs: ResourceType, rhs: ResourceType) -> Option<ResourceType> {
+        REACTIONS
+            .iter()
+            .find(|(_, [a, b])| (*a == lhs && *b == rhs) || (*a == rhs && *b == lhs))
+            .map(|(product, _)| *product)
     }
 
     /// Translates the `REACTION_TIME` constant.
@@ -175,6 +195,82 @@ impl ResourceType {
         Some(time)
     }
 
+    /// The resources needed to produce `amount` of this compound in labs,
+    /// down to base minerals, with the total amount of each.
+    ///
+    /// Each reaction takes one unit of each component per unit produced, and
+    /// intermediate compounds needed by several reactions, such as hydroxide,
+    /// are listed once with their total amount. Every compound in the chain
+    /// comes after both its components, so labs can be scheduled in order.
+    /// This compound itself isn't included.
+    ///
+    /// Returns an empty chain for base minerals, and `None` for resources
+    /// which are neither base minerals nor produced by a lab reaction.
+    pub fn reaction_chain(self, amount: u32) -> Option<Vec<(ResourceType, u32)>> {
+        fn expand(resource: ResourceType, amount: u32, chain: &mut Vec<(ResourceType, u32)>) {
+            if let Some(components) = resource.reaction_components() {
+                for &component in &components {
+                    expand(component, amount, chain);
+                    // listed after its own components the first time it's needed
+                    match chain.iter_mut().find(|(listed, _)| *listed == component) {
+                        Some((_, total)) => *total += amount,
+                        None => chain.push((component, amount)),
+                    }
+                }
+            }
+        }
+
+        if !self.is_base_mineral() && self.reaction_components().is_none() {
+            return None;
+        }
+        let mut chain = Vec::new();
+        expand(self, amount, &mut chain);
+        Some(chain)
+    }
+
+    /// The factory recipes needed to produce this commodity from deposit
+    /// resources, minerals and energy, each with the commodity it produces.
+    ///
+    /// Every commodity component, including compressed commodities such as
+    /// bars, is produced in the chain, and every recipe comes after those of
+    /// its commodity components, ending with this commodity's own recipe.
+    /// Commodities needed by several recipes are listed once. The
+    /// [`FactoryRecipe::level`] of each recipe tells which factories can
+    /// produce it.
+    ///
+    /// Returns `None` for resources which aren't commodities made by
+    /// factories.
+    pub fn commodity_chain(self) -> Option<Vec<(ResourceType, FactoryRecipe)>> {
+        fn expand(commodity: ResourceType, chain: &mut Vec<(ResourceType, FactoryRecipe)>) {
+            if chain.iter().any(|(listed, _)| *listed == commodity) {
+                return;
+            }
+            let recipe = match commodity.commodity_recipe() {
+                Some(recipe) => recipe,
+                None => return,
+            };
+            // sorted for a stable order, as components are kept in a map
+            let mut components: Vec<ResourceType> = recipe
+                .components
+                .keys()
+                .copied()
+                .filter(|component| component.is_commodity())
+                .collect();
+            components.sort_by_key(|&component| component as u16);
+            for component in components {
+                expand(component, chain);
+            }
+            chain.push((commodity, recipe));
+        }
+
+        if !self.is_commodity() || self.commodity_recipe().is_none() {
+            return None;
+        }
+        let mut chain = Vec::new();
+        expand(self, &mut chain);
+        Some(chain)
+    }
+
     /// Translates the `COMMODITIES` constant to recipes that can be used by a
     /// factory to make each commodity
     pub fn commodity_recipe(self) -> Option<FactoryRecipe> {
@@ -739,3 +835,374 @@ impl ResourceType {
         Some(recipe)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::constants::ResourceType::{self, *};
+
+    /// All compounds which can be produced in a lab, in `REACTIONS` order.
+    const COMPOUNDS: [ResourceType; 34] = [
+        Hydroxide,
+        ZynthiumKeanite,
+        UtriumLemergite,
+        Ghodium,
+        UtriumHydride,
+        UtriumAcid,
+        CatalyzedUtriumAcid,
+        UtriumOxide,
+        UtriumAlkalide,
+        CatalyzedUtriumAlkalide,
+        KeaniumHydride,
+        KeaniumAcid,
+        CatalyzedKeaniumAcid,
+        KeaniumOxide,
+        KeaniumAlkalide,
+        CatalyzedKeaniumAlkalide,
+        LemergiumHydride,
+        LemergiumAcid,
+        CatalyzedLemergiumAcid,
+        LemergiumOxide,
+        LemergiumAlkalide,
+        CatalyzedLemergiumAlkalide,
+        ZynthiumHydride,
+        ZynthiumAcid,
+        CatalyzedZynthiumAcid,
+        ZynthiumOxide,
+        ZynthiumAlkalide,
+        CatalyzedZynthiumAlkalide,
+        GhodiumHydride,
+        GhodiumAcid,
+        CatalyzedGhodiumAcid,
+        GhodiumOxide,
+        GhodiumAlkalide,
+        CatalyzedGhodiumAlkalide,
+    ];
+
+    #[test]
+    fn reaction_components_of_non_compounds() {
+        for resource in &[
+            Energy, Power, Ops, Hydrogen, Oxygen, Utrium, Lemergium, Keanium, Zynthium, Catalyst,
+            Silicon, Metal, Biomass, Mist, UtriumBar, Battery, Wire, Essence,
+        ] {
+            assert_eq!(resource.reaction_components(), None, "{:?}", resource);
+        }
+    }
+
+    #[test]
+    fn reaction_components_by_tier() {
+        // base compounds
+        assert_eq!(Hydroxide.reaction_components(), Some([Oxygen, Hydrogen]));
+        assert_eq!(
+            ZynthiumKeanite.reaction_components(),
+            Some([Zynthium, Keanium])
+        );
+        assert_eq!(
+            Ghodium.reaction_components(),
+            Some([UtriumLemergite, ZynthiumKeanite])
+        );
+        // tier 1
+        assert_eq!(
+            UtriumHydride.reaction_components(),
+            Some([Utrium, Hydrogen])
+        );
+        assert_eq!(GhodiumOxide.reaction_components(), Some([Ghodium, Oxygen]));
+        // tier 2
+        assert_eq!(
+            KeaniumAcid.reaction_components(),
+            Some([KeaniumHydride, Hydroxide])
+        );
+        assert_eq!(
+            LemergiumAlkalide.reaction_components(),
+            Some([LemergiumOxide, Hydroxide])
+        );
+        // tier 3
+        assert_eq!(
+            CatalyzedGhodiumAcid.reaction_components(),
+            Some([GhodiumAcid, Catalyst])
+        );
+        assert_eq!(
+            CatalyzedZynthiumAlkalide.reaction_components(),
+            Some([ZynthiumAlkalide, Catalyst])
+        );
+    }
+
+    #[test]
+    fn reaction_components_are_producible() {
+        let base = [
+            Hydrogen, Oxygen, Utrium, Lemergium, Keanium, Zynthium, Catalyst,
+        ];
+        for compound in COMPOUNDS.iter() {
+            let components = compound
+                .reaction_components()
+                .unwrap_or_else(|| panic!("{:?} should have components", compound));
+            for component in components.iter() {
+                assert!(
+                    base.contains(component) || COMPOUNDS.contains(component),
+                    "{:?} has unexpected component {:?}",
+                    compound,
+                    component
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reaction_time_matches_constants() {
+        // values from `REACTION_TIME` in constants.js
+        let expected = [
+            (Hydroxide, 20),
+            (ZynthiumKeanite, 5),
+            (UtriumLemergite, 5),
+            (Ghodium, 5),
+            (UtriumHydride, 10),
+            (UtriumAcid, 5),
+            (CatalyzedUtriumAcid, 60),
+            (UtriumOxide, 10),
+            (UtriumAlkalide, 5),
+            (CatalyzedUtriumAlkalide, 60),
+            (KeaniumHydride, 10),
+            (KeaniumAcid, 5),
+            (CatalyzedKeaniumAcid, 60),
+            (KeaniumOxide, 10),
+            (KeaniumAlkalide, 5),
+            (CatalyzedKeaniumAlkalide, 60),
+            (LemergiumHydride, 15),
+            (LemergiumAcid, 10),
+            (CatalyzedLemergiumAcid, 65),
+            (LemergiumOxide, 10),
+            (LemergiumAlkalide, 5),
+            (CatalyzedLemergiumAlkalide, 60),
+            (ZynthiumHydride, 20),
+            (ZynthiumAcid, 40),
+            (CatalyzedZynthiumAcid, 160),
+            (ZynthiumOxide, 10),
+            (ZynthiumAlkalide, 5),
+            (CatalyzedZynthiumAlkalide, 60),
+            (GhodiumHydride, 10),
+            (GhodiumAcid, 15),
+            (CatalyzedGhodiumAcid, 80),
+            (GhodiumOxide, 10),
+            (GhodiumAlkalide, 30),
+            (CatalyzedGhodiumAlkalide, 150),
+        ];
+        assert_eq!(expected.len(), COMPOUNDS.len());
+        for &(compound, time) in expected.iter() {
+            assert_eq!(compound.reaction_time(), Some(time), "{:?}", compound);
+        }
+    }
+
+    #[test]
+    fn reaction_time_of_non_compounds() {
+        for resource in &[
+            Energy, Power, Ops, Hydrogen, Catalyst, Silicon, UtriumBar, Device,
+        ] {
+            assert_eq!(resource.reaction_time(), None, "{:?}", resource);
+        }
+    }
+
+    #[test]
+    fn commodity_recipe_device() {
+        let recipe = Device.commodity_recipe().unwrap();
+        assert_eq!(recipe.amount, 1);
+        assert_eq!(recipe.cooldown, 600);
+        assert_eq!(recipe.level, Some(5));
+        assert_eq!(recipe.components.len(), 5);
+        assert_eq!(recipe.components[&Circuit], 1);
+        assert_eq!(recipe.components[&Microchip], 3);
+        assert_eq!(recipe.components[&Crystal], 110);
+        assert_eq!(recipe.components[&GhodiumMelt], 150);
+        assert_eq!(recipe.components[&Energy], 64);
+    }
+
+    #[test]
+    fn commodity_recipe_compression() {
+        let bars = [
+            (Utrium, UtriumBar),
+            (Lemergium, LemergiumBar),
+            (Zynthium, ZynthiumBar),
+            (Keanium, KeaniumBar),
+            (Ghodium, GhodiumMelt),
+            (Oxygen, Oxidant),
+            (Hydrogen, Reductant),
+            (Catalyst, Purifier),
+        ];
+        for &(mineral, bar) in bars.iter() {
+            let compress = bar.commodity_recipe().unwrap();
+            assert_eq!(compress.amount, 100);
+            assert_eq!(compress.level, None);
+            assert_eq!(compress.components[&mineral], 500);
+            assert_eq!(compress.components[&Energy], 200);
+
+            let decompress = mineral.commodity_recipe().unwrap();
+            assert_eq!(decompress.amount, 500);
+            assert_eq!(decompress.level, None);
+            assert_eq!(decompress.components[&bar], 100);
+            assert_eq!(decompress.components[&Energy], 200);
+        }
+
+        let battery = Battery.commodity_recipe().unwrap();
+        assert_eq!(battery.amount, 50);
+        assert_eq!(battery.components[&Energy], 600);
+        let energy = Energy.commodity_recipe().unwrap();
+        assert_eq!(energy.amount, 500);
+        assert_eq!(energy.components[&Battery], 50);
+    }
+
+    #[test]
+    fn commodity_recipe_chain_levels() {
+        let chains = [
+            [Wire, Switch, Transistor, Microchip, Circuit, Device],
+            [Cell, Phlegm, Tissue, Muscle, Organoid, Organism],
+            [Alloy, Tube, Fixtures, Frame, Hydraulics, Machine],
+            [Condensate, Concentrate, Extract, Spirit, Emanation, Essence],
+        ];
+        for chain in chains.iter() {
+            for (level, commodity) in chain.iter().enumerate() {
+                let recipe = commodity.commodity_recipe().unwrap();
+                let expected = if level == 0 { None } else { Some(level as u32) };
+                assert_eq!(recipe.level, expected, "{:?}", commodity);
+            }
+        }
+
+        assert!(Silicon.commodity_recipe().is_none());
+        assert!(Power.commodity_recipe().is_none());
+        assert!(Hydroxide.commodity_recipe().is_none());
+    }
+
+    #[test]
+    fn lab_reaction_product_round_trip() {
+        for &compound in COMPOUNDS.iter() {
+            let [a, b] = compound.reaction_components().unwrap();
+            assert_eq!(ResourceType::lab_reaction_product(a, b), Some(compound));
+            assert_eq!(ResourceType::lab_reaction_product(b, a), Some(compound));
+        }
+    }
+
+    #[test]
+    fn lab_reaction_product_no_reaction() {
+        assert_eq!(ResourceType::lab_reaction_product(Hydrogen, Hydrogen), None);
+        assert_eq!(ResourceType::lab_reaction_product(Utrium, Keanium), None);
+        assert_eq!(ResourceType::lab_reaction_product(Energy, Catalyst), None);
+        assert_eq!(
+            ResourceType::lab_reaction_product(GhodiumAcid, Hydroxide),
+            None
+        );
+    }
+
+    #[test]
+    fn reaction_chain() {
+        let chain = CatalyzedGhodiumAcid.reaction_chain(300).unwrap();
+        let totals: HashMap<ResourceType, u32> = chain.iter().copied().collect();
+        assert_eq!(totals.len(), chain.len(), "duplicates in {:?}", chain);
+        let mut expected = HashMap::new();
+        for &resource in &[
+            GhodiumAcid,
+            GhodiumHydride,
+            Hydroxide,
+            Ghodium,
+            UtriumLemergite,
+            ZynthiumKeanite,
+            Utrium,
+            Lemergium,
+            Zynthium,
+            Keanium,
+            Oxygen,
+            Catalyst,
+        ] {
+            expected.insert(resource, 300);
+        }
+        // for both the ghodium hydride and the hydroxide
+        expected.insert(Hydrogen, 600);
+        assert_eq!(totals, expected);
+
+        // dependency order
+        for (i, (resource, _)) in chain.iter().enumerate() {
+            for component in resource.reaction_components().iter().flatten() {
+                let position = chain.iter().position(|(listed, _)| listed == component);
+                assert!(
+                    matches!(position, Some(p) if p < i),
+                    "{:?} before {:?} in {:?}",
+                    resource,
+                    component,
+                    chain
+                );
+            }
+        }
+
+        assert_eq!(
+            UtriumHydride.reaction_chain(10),
+            Some(vec![(Utrium, 10), (Hydrogen, 10)])
+        );
+        assert_eq!(Hydrogen.reaction_chain(100), Some(vec![]));
+        assert_eq!(Catalyst.reaction_chain(100), Some(vec![]));
+        assert_eq!(Energy.reaction_chain(100), None);
+        assert_eq!(UtriumBar.reaction_chain(100), None);
+    }
+
+    #[test]
+    fn commodity_chain() {
+        let chain = Device.commodity_chain().unwrap();
+        let commodities: Vec<ResourceType> =
+            chain.iter().map(|(commodity, _)| *commodity).collect();
+        assert_eq!(commodities.last(), Some(&Device));
+        for (i, (commodity, recipe)) in chain.iter().enumerate() {
+            assert_eq!(
+                commodities
+                    .iter()
+                    .filter(|&listed| listed == commodity)
+                    .count(),
+                1,
+                "{:?}",
+                commodities
+            );
+            for component in recipe.components.keys().filter(|c| c.is_commodity()) {
+                let position = commodities.iter().position(|listed| listed == component);
+                assert!(
+                    matches!(position, Some(p) if p < i),
+                    "{:?} before {:?} in {:?}",
+                    commodity,
+                    component,
+                    commodities
+                );
+            }
+        }
+
+        let level_of = |commodity: ResourceType| {
+            chain
+                .iter()
+                .find(|(listed, _)| *listed == commodity)
+                .map(|(_, recipe)| recipe.level)
+        };
+        assert_eq!(level_of(Wire), Some(None));
+        assert_eq!(level_of(Switch), Some(Some(1)));
+        assert_eq!(level_of(Transistor), Some(Some(2)));
+        assert_eq!(level_of(Microchip), Some(Some(3)));
+        assert_eq!(level_of(Circuit), Some(Some(4)));
+        assert_eq!(level_of(Device), Some(Some(5)));
+        assert_eq!(level_of(Composite), Some(Some(1)));
+        assert_eq!(level_of(Crystal), Some(Some(2)));
+        // compressed commodities
+        for &commodity in &[
+            UtriumBar,
+            ZynthiumBar,
+            LemergiumBar,
+            KeaniumBar,
+            GhodiumMelt,
+            Oxidant,
+            Reductant,
+            Purifier,
+        ] {
+            assert_eq!(level_of(commodity), Some(None), "{:?}", commodity);
+        }
+        assert_eq!(chain.len(), 16);
+
+        let bars = UtriumBar.commodity_chain().unwrap();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].0, UtriumBar);
+        assert!(Utrium.commodity_chain().is_none());
+        assert!(Energy.commodity_chain().is_none());
+        assert!(Silicon.commodity_chain().is_none());
+    }
+}
@@ -0,0 +1,228 @@
+//! Serializing constants as the strings the game uses.
+//!
+//! The `Serialize` and `Deserialize` implementations of constant enums use
+//! made-up integers, which suit passing values to JavaScript but make
+//! persisted data hard to read, and break if the integers are ever
+//! renumbered. The functions of this module use the game's strings instead,
+//! through the constants' [`Display`] and [`FromStr`] implementations, for
+//! fields annotated with `#[serde(with = "serde_string")]`. The [`keys`]
+//! module does the same for the keys of maps:
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use screeps::constants::{serde_string, ResourceType, StructureType};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Stock {
+//!     #[serde(with = "serde_string")]
+//!     stored_in: StructureType,
+//!     #[serde(with = "serde_string::keys")]
+//!     amounts: HashMap<ResourceType, u32>,
+//! }
+//!
+//! let mut amounts = HashMap::new();
+//! amounts.insert(ResourceType::Energy, 5000);
+//! let stock = Stock {
+//!     stored_in: StructureType::Storage,
+//!     amounts,
+//! };
+//! assert_eq!(
+//!     serde_json::to_string(&stock).unwrap(),
+//!     r#"{"stored_in":"storage","amounts":{"energy":5000}}"#
+//! );
+//! ```
+//!
+//! [`Display`]: std::fmt::Display
+//! [`FromStr`]: std::str::FromStr
+use std::{borrow::Cow, fmt::Display, str::FromStr};
+
+use serde::{de::Unexpected, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{IntershardResourceType, MarketResourceType, ResourceType, StructureType};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Constants which can be serialized as strings with this module.
+///
+/// This trait is sealed, and implemented for [`ResourceType`],
+/// [`StructureType`], [`MarketResourceType`] and [`IntershardResourceType`].
+pub trait StringConstant: Display + FromStr + private::Sealed {
+    /// Describes the expected strings, for deserialization errors.
+    const EXPECTED: &'static str;
+}
+
+impl private::Sealed for ResourceType {}
+impl StringConstant for ResourceType {
+    const EXPECTED: &'static str = "a known constant string in RESOURCES_ALL";
+}
+
+impl private::Sealed for StructureType {}
+impl StringConstant for StructureType {
+    const EXPECTED: &'static str = "a known STRUCTURE_* constant string";
+}
+
+impl private::Sealed for MarketResourceType {}
+impl StringConstant for MarketResourceType {
+    const EXPECTED: &'static str =
+        "a known constant string in RESOURCES_ALL or INTERSHARD_RESOURCES";
+}
+
+impl private::Sealed for IntershardResourceType {}
+impl StringConstant for IntershardResourceType {
+    const EXPECTED: &'static str = "a known constant string in INTERSHARD_RESOURCES";
+}
+
+/// Serializes a constant as its string.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: StringConstant,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// Deserializes a constant from its string.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: StringConstant,
+    D: Deserializer<'de>,
+{
+    let s: Cow<'de, str> = Cow::deserialize(deserializer)?;
+    parse(&s)
+}
+
+fn parse<T: StringConstant, E: serde::de::Error>(s: &str) -> Result<T, E> {
+    T::from_str(s).map_err(|_| E::invalid_value(Unexpected::Str(s), &T::EXPECTED))
+}
+
+/// Serializes a constant through [`serialize`], for use as a map key.
+struct AsString<'a, T>(&'a T);
+
+impl<T: StringConstant> Serialize for AsString<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self.0, serializer)
+    }
+}
+
+/// Serializing maps keyed by constants with the constants' strings as keys,
+/// with `#[serde(with = "serde_string::keys")]`.
+///
+/// Works with any map which can be iterated by reference and collected into,
+/// such as `HashMap`. Values use their own `Serialize` and `Deserialize`
+/// implementations.
+pub mod keys {
+    use std::{collections::HashMap, iter::FromIterator};
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{parse, AsString, StringConstant};
+
+    /// Serializes a map with the strings of its constant keys as keys.
+    pub fn serialize<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+        K: 'a + StringConstant,
+        V: 'a + Serialize,
+        S: Serializer,
+    {
+        serializer.collect_map(map.into_iter().map(|(key, value)| (AsString(key), value)))
+    }
+
+    /// Deserializes a map whose keys are strings of constants.
+    pub fn deserialize<'de, M, K, V, D>(deserializer: D) -> Result<M, D::Error>
+    where
+        M: FromIterator<(K, V)>,
+        K: StringConstant,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        HashMap::<String, V>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(key, value)| Ok((parse(&key)?, value)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use crate::constants::{
+        IntershardResourceType, MarketResourceType, ResourceType, StructureType,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Stock {
+        #[serde(with = "super")]
+        structure: StructureType,
+        #[serde(with = "super")]
+        traded: MarketResourceType,
+        #[serde(with = "super::keys")]
+        amounts: HashMap<ResourceType, u32>,
+        #[serde(with = "super::keys")]
+        account: HashMap<IntershardResourceType, u32>,
+    }
+
+    #[test]
+    fn string_round_trip() {
+        let mut amounts = HashMap::new();
+        amounts.insert(ResourceType::Energy, 5000);
+        amounts.insert(ResourceType::CatalyzedGhodiumAcid, 30);
+        amounts.insert(ResourceType::Ops, 0);
+        let mut account = HashMap::new();
+        account.insert(IntershardResourceType::Pixel, 12);
+        let stock = Stock {
+            structure: StructureType::PowerSpawn,
+            traded: IntershardResourceType::SubscriptionToken.into(),
+            amounts,
+            account,
+        };
+
+        let value = serde_json::to_value(&stock).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "structure": "powerSpawn",
+                "traded": "token",
+                "amounts": {"energy": 5000, "XGH2O": 30, "ops": 0},
+                "account": {"pixel": 12},
+            })
+        );
+        assert_eq!(serde_json::from_value::<Stock>(value).unwrap(), stock);
+    }
+
+    #[test]
+    fn unknown_strings() {
+        let stock = json!({
+            "structure": "powerSpawn",
+            "traded": "energy",
+            "amounts": {"energy": 5000, "unobtainium": 1},
+            "account": {},
+        });
+        let error = serde_json::from_value::<Stock>(stock).unwrap_err();
+        assert!(error.to_string().contains("unobtainium"), "{}", error);
+
+        let stock = json!({
+            "structure": "tower",
+            "traded": "pixels",
+            "amounts": {},
+            "account": {},
+        });
+        assert!(serde_json::from_value::<Stock>(stock).is_err());
+        // the integer representation isn't accepted
+        let stock = json!({
+            "structure": 11,
+            "traded": "energy",
+            "amounts": {},
+            "account": {},
+        });
+        assert!(serde_json::from_value::<Stock>(stock).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 //! Various constants translated as small enums.
-use std::{borrow::Cow, fmt, str::FromStr};
+use std::{borrow::Cow, error::Error, fmt, str::FromStr};
 
 use enum_iterator::IntoEnumIterator;
 use num_derive::FromPrimitive;
@@ -41,7 +41,12 @@ impl ReturnCode {
     /// Turns this return code into a result.
     ///
     /// `ReturnCode::Ok` is turned into `Result::Ok`, all other codes are turned
-    /// into `Result::Err(code)`
+    /// into `Result::Err(code)`. Since `ReturnCode` implements
+    /// [`std::error::Error`], the result can be propagated with `?`.
+    ///
+    /// `ReturnCode::Ok`, the game's `OK` (0), is the only code mapped to
+    /// `Result::Ok`: every other variant is one of the negative `ERR_*`
+    /// codes.
     #[inline]
     pub fn as_result(self) -> Result<(), Self> {
         match self {
@@ -49,8 +54,40 @@ impl ReturnCode {
             other => Err(other),
         }
     }
+
+    /// The name of the game constant for this return code, such as
+    /// `ERR_NOT_IN_RANGE`.
+    pub fn constant_name(self) -> &'static str {
+        match self {
+            ReturnCode::Ok => "OK",
+            ReturnCode::NotOwner => "ERR_NOT_OWNER",
+            ReturnCode::NoPath => "ERR_NO_PATH",
+            ReturnCode::NameExists => "ERR_NAME_EXISTS",
+            ReturnCode::Busy => "ERR_BUSY",
+            ReturnCode::NotFound => "ERR_NOT_FOUND",
+            ReturnCode::NotEnough => "ERR_NOT_ENOUGH_RESOURCES",
+            ReturnCode::InvalidTarget => "ERR_INVALID_TARGET",
+            ReturnCode::Full => "ERR_FULL",
+            ReturnCode::NotInRange => "ERR_NOT_IN_RANGE",
+            ReturnCode::InvalidArgs => "ERR_INVALID_ARGS",
+            ReturnCode::Tired => "ERR_TIRED",
+            ReturnCode::NoBodypart => "ERR_NO_BODYPART",
+            ReturnCode::RclNotEnough => "ERR_RCL_NOT_ENOUGH",
+            ReturnCode::GclNotEnough => "ERR_GCL_NOT_ENOUGH",
+        }
+    }
+}
+
+impl fmt::Display for ReturnCode {
+    /// Formats the return code as the name of its game constant, followed by
+    /// its value, such as `ERR_NOT_IN_RANGE (-9)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.constant_name(), *self as i16)
+    }
 }
 
+impl Error for ReturnCode {}
+
 js_deserializable!(ReturnCode);
 
 #[derive(
@@ -70,6 +107,83 @@ pub enum Direction {
 
 js_deserializable!(Direction);
 
+impl Direction {
+    /// The `(x, y)` offset of a single step in this direction.
+    #[inline]
+    pub(crate) fn offset(self) -> (i32, i32) {
+        use Direction::*;
+
+        match self {
+            Top => (0, -1),
+            TopRight => (1, -1),
+            Right => (1, 0),
+            BottomRight => (1, 1),
+            Bottom => (0, 1),
+            BottomLeft => (-1, 1),
+            Left => (-1, 0),
+            TopLeft => (-1, -1),
+        }
+    }
+
+    /// The `(dx, dy)` offset of a single step in this direction, with y
+    /// increasing towards the bottom.
+    #[inline]
+    pub fn delta(self) -> (i8, i8) {
+        let (dx, dy) = self.offset();
+        (dx as i8, dy as i8)
+    }
+
+    /// The direction of a single step by `(dx, dy)`, or `None` unless both
+    /// are between -1 and 1 and at least one of them isn't 0.
+    pub fn from_delta(dx: i8, dy: i8) -> Option<Direction> {
+        use Direction::*;
+
+        match (dx, dy) {
+            (0, -1) => Some(Top),
+            (1, -1) => Some(TopRight),
+            (1, 0) => Some(Right),
+            (1, 1) => Some(BottomRight),
+            (0, 1) => Some(Bottom),
+            (-1, 1) => Some(BottomLeft),
+            (-1, 0) => Some(Left),
+            (-1, -1) => Some(TopLeft),
+            _ => None,
+        }
+    }
+
+    /// Rotates this direction clockwise by `n` eighths of a turn, so
+    /// rotating `Top` by 2 gives `Right`.
+    #[inline]
+    pub fn rotate_cw(self, n: u8) -> Direction {
+        use Direction::*;
+
+        const CLOCKWISE: [Direction; 8] = [
+            Top,
+            TopRight,
+            Right,
+            BottomRight,
+            Bottom,
+            BottomLeft,
+            Left,
+            TopLeft,
+        ];
+        CLOCKWISE[usize::from((self as u8 - 1 + n % 8) % 8)]
+    }
+
+    /// Rotates this direction counterclockwise by `n` eighths of a turn, so
+    /// rotating `Top` by 1 gives `TopLeft`.
+    #[inline]
+    pub fn rotate_ccw(self, n: u8) -> Direction {
+        self.rotate_cw(8 - n % 8)
+    }
+
+    /// The opposite direction, the same as `-self`.
+    #[inline]
+    pub fn opposite(self) -> Direction {
+        -self
+    }
+}
+
 impl ::std::ops::Neg for Direction {
     type Output = Direction;
 
@@ -163,8 +277,18 @@ impl From<ExitDirection> for Direction {
     }
 }
 
+/// Translates the `COLOR_*` constants, used for flags.
 #[derive(
-    Debug, PartialEq, Eq, Clone, Copy, FromPrimitive, Hash, Deserialize_repr, Serialize_repr,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    FromPrimitive,
+    Hash,
+    Deserialize_repr,
+    Serialize_repr,
+    IntoEnumIterator,
 )]
 #[repr(u8)]
 pub enum Color {
@@ -182,6 +306,27 @@ pub enum Color {
 
 js_deserializable!(Color);
 
+/// Translates the `COLORS_ALL` constant, every [`Color`] in the game's order.
+pub const COLORS_ALL: [Color; 10] = [
+    Color::Red,
+    Color::Purple,
+    Color::Blue,
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Orange,
+    Color::Brown,
+    Color::Grey,
+    Color::White,
+];
+
+impl Color {
+    /// Iterates over every color, in the same order as [`COLORS_ALL`].
+    pub fn iter_values() -> impl Iterator<Item = Color> {
+        <Color as IntoEnumIterator>::into_enum_iter()
+    }
+}
+
 /// Terrain constant.
 ///
 /// This constant is in a unique position of being represented both by strings
@@ -215,6 +360,27 @@ pub enum Terrain {
 }
 
 impl Terrain {
+    /// Interprets a byte of the raw terrain buffer, as returned by
+    /// [`RoomTerrain::get_raw_buffer`], where [`TERRAIN_MASK_WALL`] and
+    /// [`TERRAIN_MASK_SWAMP`] are separate bits.
+    ///
+    /// Walls take precedence when both bits are set, as in the game's own
+    /// [`RoomTerrain::get`]. Other bits, such as [`TERRAIN_MASK_LAVA`], are
+    /// ignored.
+    ///
+    /// [`RoomTerrain::get_raw_buffer`]: crate::objects::RoomTerrain::get_raw_buffer
+    /// [`RoomTerrain::get`]: crate::objects::RoomTerrain::get
+    /// [`TERRAIN_MASK_LAVA`]: crate::constants::TERRAIN_MASK_LAVA
+    pub fn from_mask(mask: u8) -> Terrain {
+        if mask & TERRAIN_MASK_WALL != 0 {
+            Terrain::Wall
+        } else if mask & TERRAIN_MASK_SWAMP != 0 {
+            Terrain::Swamp
+        } else {
+            Terrain::Plain
+        }
+    }
+
     /// Helper function for deserializing from a string rather than from an
     /// integer.
     pub fn deserialize_from_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
@@ -236,7 +402,18 @@ js_deserializable!(Terrain);
 /// [`Part::deserialize_from_str`].
 ///
 /// See the [module-level documentation][crate::constants] for more details.
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize_repr, Deserialize_repr, FromStr)]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize_repr,
+    Deserialize_repr,
+    FromStr,
+    IntoEnumIterator,
+)]
 #[repr(u8)]
 #[display(style = "snake_case")]
 pub enum Part {
@@ -250,6 +427,19 @@ pub enum Part {
     Claim = 7,
 }
 
+/// Translates the `BODYPARTS_ALL` constant, every [`Part`] in the game's
+/// order.
+pub const BODYPARTS_ALL: [Part; 8] = [
+    Part::Move,
+    Part::Work,
+    Part::Carry,
+    Part::Attack,
+    Part::RangedAttack,
+    Part::Tough,
+    Part::Heal,
+    Part::Claim,
+];
+
 impl Part {
     /// Translates the `BODYPART_COST` constant.
     #[inline]
@@ -266,6 +456,12 @@ impl Part {
         }
     }
 
+    /// Iterates over every body part, in the same order as
+    /// [`BODYPARTS_ALL`].
+    pub fn iter_values() -> impl Iterator<Item = Part> {
+        <Part as IntoEnumIterator>::into_enum_iter()
+    }
+
     /// Helper function for deserializing from a string rather than a fake
     /// integer value.
     pub fn deserialize_from_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
@@ -355,3 +551,162 @@ impl Density {
 pub enum PowerClass {
     Operator,
 }
+
+#[cfg(test)]
+mod test {
+    use num_traits::FromPrimitive;
+
+    use super::{Color, Density, Direction, Part, ReturnCode, Terrain, BODYPARTS_ALL, COLORS_ALL};
+    use crate::constants::MAX_CREEP_SIZE;
+
+    #[test]
+    fn density_round_trip() {
+        for (value, density) in [
+            (1, Density::Low),
+            (2, Density::Moderate),
+            (3, Density::High),
+            (4, Density::Ultra),
+        ]
+        .iter()
+        {
+            let parsed: Density = serde_json::from_str(&value.to_string()).unwrap();
+            assert_eq!(parsed, *density);
+            assert_eq!(serde_json::to_string(density).unwrap(), value.to_string());
+        }
+
+        assert!(serde_json::from_str::<Density>("0").is_err());
+        assert!(serde_json::from_str::<Density>("5").is_err());
+    }
+
+    #[test]
+    fn density_constants() {
+        let amounts: Vec<u32> = Density::iter_values().map(Density::amount).collect();
+        assert_eq!(amounts, vec![15_000, 35_000, 70_000, 100_000]);
+
+        let probabilities: Vec<f32> = Density::iter_values().map(Density::probability).collect();
+        assert_eq!(probabilities, vec![0.1, 0.5, 0.9, 1.0]);
+    }
+
+    #[test]
+    fn body_parts() {
+        let parts: Vec<Part> = Part::iter_values().collect();
+        assert_eq!(parts, BODYPARTS_ALL.to_vec());
+        assert_eq!(parts.len(), 8);
+
+        let costs: Vec<u32> = BODYPARTS_ALL.iter().map(|part| part.cost()).collect();
+        assert_eq!(costs, vec![50, 100, 50, 80, 150, 10, 250, 600]);
+
+        let all_heal: u32 = (0..MAX_CREEP_SIZE).map(|_| Part::Heal.cost()).sum();
+        assert_eq!(all_heal, 12_500);
+    }
+
+    #[test]
+    fn direction_rotation() {
+        use Direction::*;
+
+        assert_eq!(Top.rotate_cw(1), TopRight);
+        assert_eq!(Top.rotate_cw(2), Right);
+        assert_eq!(TopRight.rotate_cw(2), BottomRight);
+        assert_eq!(TopLeft.rotate_cw(1), Top);
+        assert_eq!(Top.rotate_ccw(1), TopLeft);
+        assert_eq!(BottomLeft.rotate_ccw(3), Right);
+        assert_eq!(Left.rotate_cw(0), Left);
+        assert_eq!(Left.rotate_cw(8), Left);
+        assert_eq!(Left.rotate_ccw(17), BottomLeft);
+        assert_eq!(Left.rotate_cw(255), BottomLeft);
+
+        for &(direction, opposite) in &[
+            (Top, Bottom),
+            (TopRight, BottomLeft),
+            (Right, Left),
+            (TopLeft, BottomRight),
+        ] {
+            assert_eq!(direction.opposite(), opposite);
+            assert_eq!(opposite.opposite(), direction);
+            assert_eq!(-direction, opposite);
+            assert_eq!(direction.rotate_cw(4), opposite);
+        }
+    }
+
+    #[test]
+    fn direction_deltas() {
+        use Direction::*;
+
+        assert_eq!(TopRight.delta(), (1, -1));
+        assert_eq!(BottomLeft.delta(), (-1, 1));
+        assert_eq!(TopLeft.delta(), (-1, -1));
+        for &direction in &[
+            Top,
+            TopRight,
+            Right,
+            BottomRight,
+            Bottom,
+            BottomLeft,
+            Left,
+            TopLeft,
+        ] {
+            let (dx, dy) = direction.delta();
+            assert_eq!(Direction::from_delta(dx, dy), Some(direction));
+            assert_eq!(direction.opposite().delta(), (-dx, -dy));
+        }
+
+        assert_eq!(Direction::from_delta(0, 0), None);
+        assert_eq!(Direction::from_delta(2, 0), None);
+        assert_eq!(Direction::from_delta(-1, -2), None);
+    }
+
+    #[test]
+    fn colors() {
+        let colors: Vec<Color> = Color::iter_values().collect();
+        assert_eq!(colors, COLORS_ALL.to_vec());
+        assert_eq!(colors.first(), Some(&Color::Red));
+        assert_eq!(colors.last(), Some(&Color::White));
+
+        for (value, &color) in (1..=10).zip(COLORS_ALL.iter()) {
+            assert_eq!(color as u8, value);
+            assert_eq!(Color::from_u8(value), Some(color));
+            let parsed: Color = serde_json::from_str(&value.to_string()).unwrap();
+            assert_eq!(parsed, color);
+            assert_eq!(serde_json::to_string(&color).unwrap(), value.to_string());
+        }
+
+        assert_eq!(Color::from_u8(0), None);
+        assert!(serde_json::from_str::<Color>("0").is_err());
+        assert!(serde_json::from_str::<Color>("11").is_err());
+    }
+
+    #[test]
+    fn return_codes() {
+        assert_eq!(ReturnCode::NotInRange.to_string(), "ERR_NOT_IN_RANGE (-9)");
+        assert_eq!(
+            ReturnCode::NotEnough.to_string(),
+            "ERR_NOT_ENOUGH_RESOURCES (-6)"
+        );
+        assert_eq!(ReturnCode::Ok.to_string(), "OK (0)");
+
+        assert_eq!(ReturnCode::Ok.as_result(), Ok(()));
+        assert_eq!(ReturnCode::Busy.as_result(), Err(ReturnCode::Busy));
+
+        fn act(code: ReturnCode) -> Result<u32, Box<dyn std::error::Error>> {
+            code.as_result()?;
+            Ok(1)
+        }
+        assert_eq!(act(ReturnCode::Ok).unwrap(), 1);
+        assert_eq!(
+            act(ReturnCode::Busy).unwrap_err().to_string(),
+            "ERR_BUSY (-4)"
+        );
+    }
+
+    #[test]
+    fn terrain_masks() {
+        assert_eq!(Terrain::from_mask(0), Terrain::Plain);
+        assert_eq!(Terrain::from_mask(1), Terrain::Wall);
+        assert_eq!(Terrain::from_mask(2), Terrain::Swamp);
+        // walls win over swamps
+        assert_eq!(Terrain::from_mask(3), Terrain::Wall);
+        // unknown bits are ignored
+        assert_eq!(Terrain::from_mask(4), Terrain::Plain);
+        assert_eq!(Terrain::from_mask(6), Terrain::Swamp);
+    }
+}
@@ -1,6 +1,7 @@
 //! `*Type` constants.
-use std::{borrow::Cow, str::FromStr};
+use std::{borrow::Cow, convert::TryFrom, error::Error, fmt, str::FromStr};
 
+use enum_iterator::IntoEnumIterator;
 use num_derive::FromPrimitive;
 use parse_display::{Display, FromStr};
 use serde::{
@@ -9,6 +10,8 @@ use serde::{
 };
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use super::{Part, Terrain};
+
 /// Translates `STRUCTURE_*` constants.
 ///
 /// *Note:* This constant's `TryFrom<Value>`, `Serialize` and `Deserialize`
@@ -19,7 +22,18 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 ///
 /// See the [module-level documentation][crate::constants] for more details.
 #[derive(
-    Copy, Clone, Debug, Display, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr, FromStr,
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize_repr,
+    Deserialize_repr,
+    FromStr,
+    FromPrimitive,
+    IntoEnumIterator,
 )]
 #[repr(u8)]
 #[display(style = "camelCase")]
@@ -27,6 +41,7 @@ pub enum StructureType {
     Spawn = 0,
     Extension = 1,
     Road = 2,
+    #[display("constructedWall")]
     Wall = 3,
     Rampart = 4,
     KeeperLair = 5,
@@ -48,6 +63,18 @@ pub enum StructureType {
 }
 
 impl StructureType {
+    /// Converts from the made-up integer constant used by the `Serialize` and
+    /// `Deserialize` implementations, or returns `None` for unknown values.
+    ///
+    /// This is the same as [`FromPrimitive::from_u8`], without having to
+    /// import the trait.
+    ///
+    /// [`FromPrimitive::from_u8`]: num_traits::FromPrimitive::from_u8
+    #[inline]
+    pub fn from_u8(n: u8) -> Option<Self> {
+        num_traits::FromPrimitive::from_u8(n)
+    }
+
     /// Translates the `CONSTRUCTION_COST` constant.
     #[inline]
     pub fn construction_cost(self) -> Option<u32> {
@@ -75,6 +102,28 @@ impl StructureType {
         Some(cost)
     }
 
+    /// The energy needed to build structures of this type on the given
+    /// terrain: [`StructureType::construction_cost`], multiplied by
+    /// [`CONSTRUCTION_COST_ROAD_SWAMP_RATIO`] or
+    /// [`CONSTRUCTION_COST_ROAD_WALL_RATIO`] for roads on swamps or walls.
+    ///
+    /// [`CONSTRUCTION_COST_ROAD_SWAMP_RATIO`]: crate::constants::CONSTRUCTION_COST_ROAD_SWAMP_RATIO
+    /// [`CONSTRUCTION_COST_ROAD_WALL_RATIO`]: crate::constants::CONSTRUCTION_COST_ROAD_WALL_RATIO
+    #[inline]
+    pub fn construction_cost_on(self, terrain: Terrain) -> Option<u32> {
+        use super::numbers::{
+            CONSTRUCTION_COST_ROAD_SWAMP_RATIO, CONSTRUCTION_COST_ROAD_WALL_RATIO,
+        };
+
+        let cost = self.construction_cost()?;
+        let multiplier = match (self, terrain) {
+            (StructureType::Road, Terrain::Swamp) => CONSTRUCTION_COST_ROAD_SWAMP_RATIO,
+            (StructureType::Road, Terrain::Wall) => CONSTRUCTION_COST_ROAD_WALL_RATIO,
+            _ => 1,
+        };
+        Some(cost * multiplier)
+    }
+
     /// Translates the `CONTROLLER_STRUCTURES` constant
     #[inline]
     pub fn controller_structures(self, current_rcl: u32) -> u32 {
@@ -177,9 +226,9 @@ impl StructureType {
             Observer => OBSERVER_HITS,
             PowerBank => POWER_BANK_HITS,
             PowerSpawn => POWER_SPAWN_HITS,
-            Extractor => EXTENSION_HITS,
+            Extractor => EXTRACTOR_HITS,
             Lab => LAB_HITS,
-            Terminal => TOWER_HITS,
+            Terminal => TERMINAL_HITS,
             Container => CONTAINER_HITS,
             Nuker => NUKER_HITS,
             Factory => FACTORY_HITS,
@@ -189,6 +238,171 @@ impl StructureType {
         Some(hits)
     }
 
+    /// The maximum hits of structures of this type, where it doesn't depend on
+    /// the room's controller level.
+    ///
+    /// This is [`WALL_HITS_MAX`] for walls and the same as
+    /// [`StructureType::initial_hits`] for other structures. Ramparts return
+    /// `None`, as their maximum depends on the controller level: use
+    /// [`StructureType::max_hits_for_rcl`] for them.
+    ///
+    /// Roads built on swamps or walls have [`CONSTRUCTION_COST_ROAD_SWAMP_RATIO`]
+    /// or [`CONSTRUCTION_COST_ROAD_WALL_RATIO`] times the hits returned here,
+    /// as given by [`StructureType::max_hits_on`].
+    ///
+    /// [`WALL_HITS_MAX`]: crate::constants::WALL_HITS_MAX
+    /// [`CONSTRUCTION_COST_ROAD_SWAMP_RATIO`]: crate::constants::CONSTRUCTION_COST_ROAD_SWAMP_RATIO
+    /// [`CONSTRUCTION_COST_ROAD_WALL_RATIO`]: crate::constants::CONSTRUCTION_COST_ROAD_WALL_RATIO
+    #[inline]
+    pub fn max_hits(self) -> Option<u32> {
+        match self {
+            StructureType::Rampart => None,
+            StructureType::Wall => Some(super::numbers::WALL_HITS_MAX),
+            _ => self.initial_hits(),
+        }
+    }
+
+    /// The maximum hits of structures of this type built on the given
+    /// terrain: [`StructureType::max_hits`], multiplied by
+    /// [`CONSTRUCTION_COST_ROAD_SWAMP_RATIO`] or
+    /// [`CONSTRUCTION_COST_ROAD_WALL_RATIO`] for roads on swamps or walls.
+    ///
+    /// [`CONSTRUCTION_COST_ROAD_SWAMP_RATIO`]: crate::constants::CONSTRUCTION_COST_ROAD_SWAMP_RATIO
+    /// [`CONSTRUCTION_COST_ROAD_WALL_RATIO`]: crate::constants::CONSTRUCTION_COST_ROAD_WALL_RATIO
+    #[inline]
+    pub fn max_hits_on(self, terrain: Terrain) -> Option<u32> {
+        use super::numbers::{
+            CONSTRUCTION_COST_ROAD_SWAMP_RATIO, CONSTRUCTION_COST_ROAD_WALL_RATIO,
+        };
+
+        let hits = self.max_hits()?;
+        let multiplier = match (self, terrain) {
+            (StructureType::Road, Terrain::Swamp) => CONSTRUCTION_COST_ROAD_SWAMP_RATIO,
+            (StructureType::Road, Terrain::Wall) => CONSTRUCTION_COST_ROAD_WALL_RATIO,
+            _ => 1,
+        };
+        Some(hits * multiplier)
+    }
+
+    /// The maximum hits of structures of this type in a room with the given
+    /// controller level.
+    ///
+    /// This is [`rampart_hits_max`] for ramparts, [`WALL_HITS_MAX`] for walls
+    /// and the same as [`StructureType::initial_hits`] for other structures.
+    ///
+    /// [`rampart_hits_max`]: crate::constants::rampart_hits_max
+    /// [`WALL_HITS_MAX`]: crate::constants::WALL_HITS_MAX
+    #[inline]
+    pub fn max_hits_for_rcl(self, rcl: u32) -> Option<u32> {
+        use super::numbers::{rampart_hits_max, WALL_HITS_MAX};
+
+        match self {
+            StructureType::Rampart => Some(rampart_hits_max(rcl)),
+            StructureType::Wall => Some(WALL_HITS_MAX),
+            _ => self.initial_hits(),
+        }
+    }
+
+    /// Translates the `*_DECAY_*` constants, how structures of this type lose
+    /// hits over time.
+    ///
+    /// Returns `None` for structures which don't decay.
+    #[inline]
+    pub fn decay(self) -> Option<DecayInfo> {
+        use super::numbers::*;
+
+        let info = match self {
+            StructureType::Road => DecayInfo {
+                amount: ROAD_DECAY_AMOUNT,
+                period: ROAD_DECAY_TIME,
+                unowned_period: None,
+                swamp_multiplier: CONSTRUCTION_COST_ROAD_SWAMP_RATIO,
+                wall_multiplier: CONSTRUCTION_COST_ROAD_WALL_RATIO,
+            },
+            StructureType::Rampart => DecayInfo {
+                amount: RAMPART_DECAY_AMOUNT,
+                period: RAMPART_DECAY_TIME,
+                unowned_period: None,
+                swamp_multiplier: 1,
+                wall_multiplier: 1,
+            },
+            StructureType::Container => DecayInfo {
+                amount: CONTAINER_DECAY,
+                period: CONTAINER_DECAY_TIME_OWNED,
+                unowned_period: Some(CONTAINER_DECAY_TIME),
+                swamp_multiplier: 1,
+                wall_multiplier: 1,
+            },
+            _ => return None,
+        };
+        Some(info)
+    }
+
+    /// Translates the `*_CAPACITY` constants, how many resources structures of
+    /// this type can store.
+    ///
+    /// Extensions hold [`extension_energy_capacity`] energy, which depends on
+    /// the room's controller level; this returns their capacity at level 8.
+    /// Returns `None` for structures without a store, or whose store has no
+    /// fixed capacity.
+    ///
+    /// [`extension_energy_capacity`]: crate::constants::extension_energy_capacity
+    #[inline]
+    pub fn store_capacity(self) -> Option<StoreCapacity> {
+        use self::StructureType::*;
+        use super::numbers::*;
+
+        let capacity = match self {
+            Spawn => StoreCapacity::Energy(SPAWN_ENERGY_CAPACITY),
+            Extension => StoreCapacity::Energy(extension_energy_capacity(8)),
+            Link => StoreCapacity::Energy(LINK_CAPACITY),
+            Tower => StoreCapacity::Energy(TOWER_CAPACITY),
+            Storage => StoreCapacity::Shared(STORAGE_CAPACITY),
+            Terminal => StoreCapacity::Shared(TERMINAL_CAPACITY),
+            Container => StoreCapacity::Shared(CONTAINER_CAPACITY),
+            Factory => StoreCapacity::Shared(FACTORY_CAPACITY),
+            Lab => StoreCapacity::Split {
+                energy: LAB_ENERGY_CAPACITY,
+                other: LAB_MINERAL_CAPACITY,
+            },
+            Nuker => StoreCapacity::Split {
+                energy: NUKER_ENERGY_CAPACITY,
+                other: NUKER_GHODIUM_CAPACITY,
+            },
+            PowerSpawn => StoreCapacity::Split {
+                energy: POWER_SPAWN_ENERGY_CAPACITY,
+                other: POWER_SPAWN_POWER_CAPACITY,
+            },
+            Road | Wall | Rampart | KeeperLair | Portal | Controller | Observer | PowerBank
+            | Extractor | InvaderCore => return None,
+        };
+        Some(capacity)
+    }
+
+    /// Iterates over every structure type, in the order of their integer
+    /// representations.
+    pub fn iter_values() -> impl Iterator<Item = StructureType> {
+        <StructureType as IntoEnumIterator>::into_enum_iter()
+    }
+
+    /// Translates the `OBSTACLE_OBJECT_TYPES` constant, whether structures of
+    /// this type block creep movement.
+    ///
+    /// Ramparts aren't obstacles according to this constant, but a rampart
+    /// still blocks creeps which don't belong to its owner unless it is
+    /// public. Portals aren't obstacles either: moving onto one teleports the
+    /// creep.
+    #[inline]
+    pub fn is_obstacle(self) -> bool {
+        use self::StructureType::*;
+
+        match self {
+            Spawn | Extension | Wall | Link | Storage | Tower | Observer | PowerSpawn
+            | PowerBank | Lab | Terminal | Nuker | Factory | InvaderCore | Controller => true,
+            Road | Rampart | KeeperLair | Portal | Extractor | Container => false,
+        }
+    }
+
     /// Helper function for deserializing from a string rather than a fake
     /// integer value.
     pub fn deserialize_from_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
@@ -201,7 +415,94 @@ impl StructureType {
 
 js_deserializable!(StructureType);
 
-/// Translates `SUBSCRIPTION_TOKEN` and `INTERSHARD_RESOURCES` constants.
+/// How a structure loses hits over time, returned by
+/// [`StructureType::decay`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DecayInfo {
+    /// Hits lost each time the structure decays.
+    pub amount: u32,
+    /// Ticks between each decay, in rooms owned by a player.
+    pub period: u32,
+    /// Ticks between each decay in rooms not owned by anyone, if different
+    /// from `period`.
+    pub unowned_period: Option<u32>,
+    /// Multiplier applied to the hits and decay amount of structures built on
+    /// swamps. Only roads are affected.
+    pub swamp_multiplier: u32,
+    /// Multiplier applied to the hits and decay amount of structures built on
+    /// natural walls. Only roads, tunneling through them, are affected.
+    pub wall_multiplier: u32,
+}
+
+impl DecayInfo {
+    /// Ticks between each decay, depending on whether the room is owned.
+    #[inline]
+    pub fn period_in(&self, owned_room: bool) -> u32 {
+        match self.unowned_period {
+            Some(period) if !owned_room => period,
+            _ => self.period,
+        }
+    }
+
+    /// Hits lost each time the structure decays, for a structure built on the
+    /// given terrain.
+    #[inline]
+    pub fn amount_on(&self, terrain: Terrain) -> u32 {
+        match terrain {
+            Terrain::Plain => self.amount,
+            Terrain::Swamp => self.amount * self.swamp_multiplier,
+            Terrain::Wall => self.amount * self.wall_multiplier,
+        }
+    }
+}
+
+/// How many resources a structure can store, returned by
+/// [`StructureType::store_capacity`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StoreCapacity {
+    /// Only energy can be stored, up to the given amount.
+    Energy(u32),
+    /// Any resources can be stored, sharing the given total capacity.
+    Shared(u32),
+    /// Energy and a single other resource are stored separately, each up to
+    /// its own capacity: minerals and compounds for labs, ghodium for nukers
+    /// and power for power spawns.
+    Split { energy: u32, other: u32 },
+}
+
+impl StoreCapacity {
+    /// The capacity for energy.
+    #[inline]
+    pub fn energy(self) -> u32 {
+        match self {
+            StoreCapacity::Energy(capacity) | StoreCapacity::Shared(capacity) => capacity,
+            StoreCapacity::Split { energy, .. } => energy,
+        }
+    }
+
+    /// The capacity for resources other than energy, 0 if only energy can be
+    /// stored.
+    #[inline]
+    pub fn other(self) -> u32 {
+        match self {
+            StoreCapacity::Energy(_) => 0,
+            StoreCapacity::Shared(capacity) => capacity,
+            StoreCapacity::Split { other, .. } => other,
+        }
+    }
+
+    /// The total amount of resources which can be stored at once.
+    #[inline]
+    pub fn total(self) -> u32 {
+        match self {
+            StoreCapacity::Energy(capacity) | StoreCapacity::Shared(capacity) => capacity,
+            StoreCapacity::Split { energy, other } => energy + other,
+        }
+    }
+}
+
+/// Translates `SUBSCRIPTION_TOKEN`, `PIXEL`, `CPU_UNLOCK`, `ACCESS_KEY` and
+/// `INTERSHARD_RESOURCES` constants.
 ///
 /// *Note:* This constant's `TryFrom<Value>`, `Serialize` and `Deserialize`
 /// implementations only operate on made-up integer constants. If you're ever
@@ -212,13 +513,32 @@ js_deserializable!(StructureType);
 ///
 /// See the [module-level documentation][crate::constants] for more details.
 #[derive(
-    Copy, Clone, Debug, Display, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr, FromStr,
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize_repr,
+    Deserialize_repr,
+    FromStr,
+    FromPrimitive,
 )]
 #[repr(u16)]
 pub enum IntershardResourceType {
     /// `"token"`
     #[display("token")]
     SubscriptionToken = 1001,
+    /// `"pixel"`
+    #[display("pixel")]
+    Pixel = 1002,
+    /// `"cpuUnlock"`
+    #[display("cpuUnlock")]
+    CpuUnlock = 1003,
+    /// `"accessKey"`
+    #[display("accessKey")]
+    AccessKey = 1004,
 }
 
 impl IntershardResourceType {
@@ -247,7 +567,18 @@ js_deserializable!(IntershardResourceType);
 ///
 /// See the [module-level documentation][crate::constants] for more details.
 #[derive(
-    Copy, Clone, Debug, Display, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr, FromStr,
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize_repr,
+    Deserialize_repr,
+    FromStr,
+    FromPrimitive,
+    IntoEnumIterator,
 )]
 #[repr(u16)]
 pub enum ResourceType {
@@ -509,7 +840,178 @@ pub enum ResourceType {
     Essence = 84,
 }
 
-#[derive(Copy, Clone, Debug)]
+/// Every [`ResourceType`], in the order of their integer representations.
+///
+/// This is similar to the game's `RESOURCES_ALL` constant, but may not be in
+/// the same order: in particular, silicon, metal, biomass and mist come after
+/// ops here. The integer representation of resource types may also change
+/// between versions of this crate, so don't rely on the position of a
+/// resource in this list being stable.
+pub const RESOURCES_ALL: [ResourceType; 84] = [
+    ResourceType::Energy,
+    ResourceType::Power,
+    ResourceType::Hydrogen,
+    ResourceType::Oxygen,
+    ResourceType::Utrium,
+    ResourceType::Lemergium,
+    ResourceType::Keanium,
+    ResourceType::Zynthium,
+    ResourceType::Catalyst,
+    ResourceType::Ghodium,
+    ResourceType::Hydroxide,
+    ResourceType::ZynthiumKeanite,
+    ResourceType::UtriumLemergite,
+    ResourceType::UtriumHydride,
+    ResourceType::UtriumOxide,
+    ResourceType::KeaniumHydride,
+    ResourceType::KeaniumOxide,
+    ResourceType::LemergiumHydride,
+    ResourceType::LemergiumOxide,
+    ResourceType::ZynthiumHydride,
+    ResourceType::ZynthiumOxide,
+    ResourceType::GhodiumHydride,
+    ResourceType::GhodiumOxide,
+    ResourceType::UtriumAcid,
+    ResourceType::UtriumAlkalide,
+    ResourceType::KeaniumAcid,
+    ResourceType::KeaniumAlkalide,
+    ResourceType::LemergiumAcid,
+    ResourceType::LemergiumAlkalide,
+    ResourceType::ZynthiumAcid,
+    ResourceType::ZynthiumAlkalide,
+    ResourceType::GhodiumAcid,
+    ResourceType::GhodiumAlkalide,
+    ResourceType::CatalyzedUtriumAcid,
+    ResourceType::CatalyzedUtriumAlkalide,
+    ResourceType::CatalyzedKeaniumAcid,
+    ResourceType::CatalyzedKeaniumAlkalide,
+    ResourceType::CatalyzedLemergiumAcid,
+    ResourceType::CatalyzedLemergiumAlkalide,
+    ResourceType::CatalyzedZynthiumAcid,
+    ResourceType::CatalyzedZynthiumAlkalide,
+    ResourceType::CatalyzedGhodiumAcid,
+    ResourceType::CatalyzedGhodiumAlkalide,
+    ResourceType::Ops,
+    ResourceType::Silicon,
+    ResourceType::Metal,
+    ResourceType::Biomass,
+    ResourceType::Mist,
+    ResourceType::UtriumBar,
+    ResourceType::LemergiumBar,
+    ResourceType::ZynthiumBar,
+    ResourceType::KeaniumBar,
+    ResourceType::GhodiumMelt,
+    ResourceType::Oxidant,
+    ResourceType::Reductant,
+    ResourceType::Purifier,
+    ResourceType::Battery,
+    ResourceType::Composite,
+    ResourceType::Crystal,
+    ResourceType::Liquid,
+    ResourceType::Wire,
+    ResourceType::Switch,
+    ResourceType::Transistor,
+    ResourceType::Microchip,
+    ResourceType::Circuit,
+    ResourceType::Device,
+    ResourceType::Cell,
+    ResourceType::Phlegm,
+    ResourceType::Tissue,
+    ResourceType::Muscle,
+    ResourceType::Organoid,
+    ResourceType::Organism,
+    ResourceType::Alloy,
+    ResourceType::Tube,
+    ResourceType::Fixtures,
+    ResourceType::Frame,
+    ResourceType::Hydraulics,
+    ResourceType::Machine,
+    ResourceType::Condensate,
+    ResourceType::Concentrate,
+    ResourceType::Extract,
+    ResourceType::Spirit,
+    ResourceType::Emanation,
+    ResourceType::Essence,
+];
+
+/// The base minerals which can be extracted from a mineral deposit.
+pub const RESOURCES_BASE_MINERALS: [ResourceType; 7] = [
+    ResourceType::Hydrogen,
+    ResourceType::Oxygen,
+    ResourceType::Utrium,
+    ResourceType::Lemergium,
+    ResourceType::Keanium,
+    ResourceType::Zynthium,
+    ResourceType::Catalyst,
+];
+
+/// The commodities which can only be produced by factories, from bars to
+/// level 5 commodities.
+pub const RESOURCES_COMMODITIES: [ResourceType; 36] = [
+    ResourceType::UtriumBar,
+    ResourceType::LemergiumBar,
+    ResourceType::ZynthiumBar,
+    ResourceType::KeaniumBar,
+    ResourceType::GhodiumMelt,
+    ResourceType::Oxidant,
+    ResourceType::Reductant,
+    ResourceType::Purifier,
+    ResourceType::Battery,
+    ResourceType::Composite,
+    ResourceType::Crystal,
+    ResourceType::Liquid,
+    ResourceType::Wire,
+    ResourceType::Switch,
+    ResourceType::Transistor,
+    ResourceType::Microchip,
+    ResourceType::Circuit,
+    ResourceType::Device,
+    ResourceType::Cell,
+    ResourceType::Phlegm,
+    ResourceType::Tissue,
+    ResourceType::Muscle,
+    ResourceType::Organoid,
+    ResourceType::Organism,
+    ResourceType::Alloy,
+    ResourceType::Tube,
+    ResourceType::Fixtures,
+    ResourceType::Frame,
+    ResourceType::Hydraulics,
+    ResourceType::Machine,
+    ResourceType::Condensate,
+    ResourceType::Concentrate,
+    ResourceType::Extract,
+    ResourceType::Spirit,
+    ResourceType::Emanation,
+    ResourceType::Essence,
+];
+
+/// Broad categories of [`ResourceType`]s, returned by
+/// [`ResourceType::category`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceCategory {
+    Energy,
+    Power,
+    Ops,
+    /// Minerals extracted from mineral deposits, listed in
+    /// [`RESOURCES_BASE_MINERALS`].
+    BaseMineral,
+    /// Resources produced by lab reactions, from ghodium and hydroxide to
+    /// catalyzed boosts.
+    Compound,
+    /// Resources harvested from deposits in highway rooms.
+    Deposit,
+    /// Compressed forms of minerals and energy produced by factories: mineral
+    /// bars, ghodium melt, oxidant, reductant, purifier and battery.
+    CompressedCommodity,
+    /// Higher level commodities produced by factories.
+    Commodity,
+}
+
+/// The effect of a boost compound on a body part, along with its multiplier.
+///
+/// Returned by [`ResourceType::boost`].
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Boost {
     Harvest(f64),
     BuildAndRepair(f64),
@@ -523,7 +1025,124 @@ pub enum Boost {
     Tough(f64),
 }
 
+impl Boost {
+    /// The action this boost improves.
+    #[inline]
+    pub fn action(self) -> BoostAction {
+        match self {
+            Boost::Harvest(_) => BoostAction::Harvest,
+            Boost::BuildAndRepair(_) => BoostAction::BuildAndRepair,
+            Boost::Dismantle(_) => BoostAction::Dismantle,
+            Boost::UpgradeController(_) => BoostAction::UpgradeController,
+            Boost::Attack(_) => BoostAction::Attack,
+            Boost::RangedAttack(_) => BoostAction::RangedAttack,
+            Boost::Heal(_) => BoostAction::Heal,
+            Boost::Carry(_) => BoostAction::Carry,
+            Boost::Move(_) => BoostAction::Move,
+            Boost::Tough(_) => BoostAction::Tough,
+        }
+    }
+
+    /// The body part this boost applies to.
+    #[inline]
+    pub fn part(self) -> Part {
+        self.action().part()
+    }
+
+    /// The multiplier this boost applies to its action.
+    ///
+    /// For [`Boost::Tough`], this multiplies the damage taken, so lower
+    /// values are better.
+    #[inline]
+    pub fn multiplier(self) -> f64 {
+        match self {
+            Boost::Harvest(m)
+            | Boost::BuildAndRepair(m)
+            | Boost::Dismantle(m)
+            | Boost::UpgradeController(m)
+            | Boost::Attack(m)
+            | Boost::RangedAttack(m)
+            | Boost::Heal(m)
+            | Boost::Carry(m)
+            | Boost::Move(m)
+            | Boost::Tough(m) => m,
+        }
+    }
+}
+
+/// The action improved by a [`Boost`], without its multiplier.
+///
+/// `WORK` parts can be boosted for several different actions, which this
+/// distinguishes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BoostAction {
+    Harvest,
+    BuildAndRepair,
+    Dismantle,
+    UpgradeController,
+    Attack,
+    RangedAttack,
+    Heal,
+    Carry,
+    Move,
+    Tough,
+}
+
+impl BoostAction {
+    /// The body part boosts for this action apply to.
+    #[inline]
+    pub fn part(self) -> Part {
+        match self {
+            BoostAction::Harvest
+            | BoostAction::BuildAndRepair
+            | BoostAction::Dismantle
+            | BoostAction::UpgradeController => Part::Work,
+            BoostAction::Attack => Part::Attack,
+            BoostAction::RangedAttack => Part::RangedAttack,
+            BoostAction::Heal => Part::Heal,
+            BoostAction::Carry => Part::Carry,
+            BoostAction::Move => Part::Move,
+            BoostAction::Tough => Part::Tough,
+        }
+    }
+
+    /// The compounds boosting this action, ordered from tier 1 to tier 3.
+    pub fn compounds(self) -> [ResourceType; 3] {
+        use ResourceType::*;
+        match self {
+            BoostAction::Harvest => [UtriumOxide, UtriumAlkalide, CatalyzedUtriumAlkalide],
+            BoostAction::BuildAndRepair => {
+                [LemergiumHydride, LemergiumAcid, CatalyzedLemergiumAcid]
+            }
+            BoostAction::Dismantle => [ZynthiumHydride, ZynthiumAcid, CatalyzedZynthiumAcid],
+            BoostAction::UpgradeController => [GhodiumHydride, GhodiumAcid, CatalyzedGhodiumAcid],
+            BoostAction::Attack => [UtriumHydride, UtriumAcid, CatalyzedUtriumAcid],
+            BoostAction::RangedAttack => [KeaniumOxide, KeaniumAlkalide, CatalyzedKeaniumAlkalide],
+            BoostAction::Heal => [
+                LemergiumOxide,
+                LemergiumAlkalide,
+                CatalyzedLemergiumAlkalide,
+            ],
+            BoostAction::Carry => [KeaniumHydride, KeaniumAcid, CatalyzedKeaniumAcid],
+            BoostAction::Move => [ZynthiumOxide, ZynthiumAlkalide, CatalyzedZynthiumAlkalide],
+            BoostAction::Tough => [GhodiumOxide, GhodiumAlkalide, CatalyzedGhodiumAlkalide],
+        }
+    }
+}
+
 impl ResourceType {
+    /// Converts from the made-up integer constant used by the `Serialize` and
+    /// `Deserialize` implementations, or returns `None` for unknown values.
+    ///
+    /// This is the same as [`FromPrimitive::from_u16`], without having to
+    /// import the trait.
+    ///
+    /// [`FromPrimitive::from_u16`]: num_traits::FromPrimitive::from_u16
+    #[inline]
+    pub fn from_u16(n: u16) -> Option<Self> {
+        num_traits::FromPrimitive::from_u16(n)
+    }
+
     /// Translates the `BOOSTS` constant.
     #[inline]
     pub fn boost(self) -> Option<Boost> {
@@ -575,7 +1194,7 @@ impl ResourceType {
             //     rangedAttack: 3,
             //     rangedMassAttack: 3
             // },
-            KeaniumAlkalide => Boost::RangedAttack(4.0),
+            KeaniumAlkalide => Boost::RangedAttack(3.0),
             // XKHO2: {
             //     rangedAttack: 4,
             //     rangedMassAttack: 4
@@ -665,6 +1284,152 @@ impl ResourceType {
         Some(boost)
     }
 
+    /// Iterates over every resource type, in the order of their integer
+    /// representations.
+    ///
+    /// See [`RESOURCES_ALL`] for caveats about this order.
+    pub fn iter_values() -> impl Iterator<Item = ResourceType> {
+        <ResourceType as IntoEnumIterator>::into_enum_iter()
+    }
+
+    /// The tier of this boost compound: 1 for hydrides and oxides, 2 for acids
+    /// and alkalides, and 3 for their catalyzed variants.
+    ///
+    /// Returns `None` for resources which aren't boost compounds, exactly when
+    /// [`ResourceType::boost`] does.
+    #[inline]
+    pub fn boost_tier(self) -> Option<u8> {
+        let compounds = self.boost()?.action().compounds();
+        compounds
+            .iter()
+            .position(|&compound| compound == self)
+            .map(|index| index as u8 + 1)
+    }
+
+    /// The compound boosting `action` at the given tier, from 1 for hydrides
+    /// and oxides to 3 for catalyzed acids and alkalides.
+    ///
+    /// Returns `None` if `tier` isn't between 1 and 3.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::{BoostAction, ResourceType};
+    ///
+    /// assert_eq!(
+    ///     ResourceType::boost_for(BoostAction::Attack, 3),
+    ///     Some(ResourceType::CatalyzedUtriumAcid)
+    /// );
+    /// ```
+    #[inline]
+    pub fn boost_for(action: BoostAction, tier: u8) -> Option<ResourceType> {
+        match tier {
+            1..=3 => Some(action.compounds()[usize::from(tier) - 1]),
+            _ => None,
+        }
+    }
+
+    /// The broad category of this resource.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::{ResourceCategory, ResourceType};
+    ///
+    /// assert_eq!(ResourceType::Keanium.category(), ResourceCategory::BaseMineral);
+    /// assert_eq!(
+    ///     ResourceType::CatalyzedGhodiumAcid.category(),
+    ///     ResourceCategory::Compound
+    /// );
+    /// ```
+    #[inline]
+    pub fn category(self) -> ResourceCategory {
+        use ResourceType::*;
+
+        match self {
+            Energy => ResourceCategory::Energy,
+            Power => ResourceCategory::Power,
+            Ops => ResourceCategory::Ops,
+            Hydrogen | Oxygen | Utrium | Lemergium | Keanium | Zynthium | Catalyst => {
+                ResourceCategory::BaseMineral
+            }
+            Silicon | Metal | Biomass | Mist => ResourceCategory::Deposit,
+            UtriumBar | LemergiumBar | ZynthiumBar | KeaniumBar | GhodiumMelt | Oxidant
+            | Reductant | Purifier | Battery => ResourceCategory::CompressedCommodity,
+            Ghodium
+            | Hydroxide
+            | ZynthiumKeanite
+            | UtriumLemergite
+            | UtriumHydride
+            | UtriumOxide
+            | KeaniumHydride
+            | KeaniumOxide
+            | LemergiumHydride
+            | LemergiumOxide
+            | ZynthiumHydride
+            | ZynthiumOxide
+            | GhodiumHydride
+            | GhodiumOxide
+            | UtriumAcid
+            | UtriumAlkalide
+            | KeaniumAcid
+            | KeaniumAlkalide
+            | LemergiumAcid
+            | LemergiumAlkalide
+            | ZynthiumAcid
+            | ZynthiumAlkalide
+            | GhodiumAcid
+            | GhodiumAlkalide
+            | CatalyzedUtriumAcid
+            | CatalyzedUtriumAlkalide
+            | CatalyzedKeaniumAcid
+            | CatalyzedKeaniumAlkalide
+            | CatalyzedLemergiumAcid
+            | CatalyzedLemergiumAlkalide
+            | CatalyzedZynthiumAcid
+            | CatalyzedZynthiumAlkalide
+            | CatalyzedGhodiumAcid
+            | CatalyzedGhodiumAlkalide => ResourceCategory::Compound,
+            Composite | Crystal | Liquid | Wire | Switch | Transistor | Microchip | Circuit
+            | Device | Cell | Phlegm | Tissue | Muscle | Organoid | Organism | Alloy | Tube
+            | Fixtures | Frame | Hydraulics | Machine | Condensate | Concentrate | Extract
+            | Spirit | Emanation | Essence => ResourceCategory::Commodity,
+        }
+    }
+
+    /// Whether this resource is one of the [`RESOURCES_BASE_MINERALS`],
+    /// extracted from mineral deposits.
+    ///
+    /// Ghodium isn't a base mineral: it's only produced by lab reactions.
+    #[inline]
+    pub fn is_base_mineral(self) -> bool {
+        self.category() == ResourceCategory::BaseMineral
+    }
+
+    /// Whether this resource is produced by a lab reaction, including
+    /// ghodium and every boost compound.
+    #[inline]
+    pub fn is_compound(self) -> bool {
+        self.category() == ResourceCategory::Compound
+    }
+
+    /// Whether this resource is one of the [`RESOURCES_COMMODITIES`], only
+    /// produced by factories.
+    #[inline]
+    pub fn is_commodity(self) -> bool {
+        matches!(
+            self.category(),
+            ResourceCategory::CompressedCommodity | ResourceCategory::Commodity
+        )
+    }
+
+    /// Whether this resource is harvested from deposits in highway rooms:
+    /// silicon, metal, biomass or mist.
+    #[inline]
+    pub fn is_deposit_resource(self) -> bool {
+        self.category() == ResourceCategory::Deposit
+    }
+
     /// Helper function for deserializing from a string rather than a fake
     /// integer value.
     pub fn deserialize_from_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
@@ -694,121 +1459,95 @@ impl MarketResourceType {
     pub fn deserialize_from_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
         let s: Cow<'de, str> = Cow::deserialize(d)?;
 
-        ResourceType::from_str(&s)
-            .map(|ty| MarketResourceType::Resource(ty))
-            .or(IntershardResourceType::from_str(&s)
-                .map(|ty| MarketResourceType::IntershardResource(ty)))
-            .map_err(|_| {
-                D::Error::invalid_value(
-                    Unexpected::Str(&s),
-                    &"a known constant string in RESOURCES_ALL or INTERSHARD_RESOURCES",
-                )
+        Self::from_str(&s).map_err(|_| {
+            D::Error::invalid_value(
+                Unexpected::Str(&s),
+                &"a known constant string in RESOURCES_ALL or INTERSHARD_RESOURCES",
+            )
+        })
+    }
+}
+
+impl fmt::Display for MarketResourceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketResourceType::Resource(ty) => ty.fmt(f),
+            MarketResourceType::IntershardResource(ty) => ty.fmt(f),
+        }
+    }
+}
+
+impl FromStr for MarketResourceType {
+    type Err = parse_display::ParseError;
+
+    /// Parses either a `RESOURCE_*` or an `INTERSHARD_RESOURCES` constant
+    /// string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ResourceType::from_str(s)
+            .map(MarketResourceType::Resource)
+            .or_else(|_| {
+                IntershardResourceType::from_str(s).map(MarketResourceType::IntershardResource)
             })
     }
 }
 
+impl From<ResourceType> for MarketResourceType {
+    #[inline]
+    fn from(ty: ResourceType) -> Self {
+        MarketResourceType::Resource(ty)
+    }
+}
+
+impl From<IntershardResourceType> for MarketResourceType {
+    #[inline]
+    fn from(ty: IntershardResourceType) -> Self {
+        MarketResourceType::IntershardResource(ty)
+    }
+}
+
+/// Error returned when converting a [`MarketResourceType`] holding an
+/// intershard resource into a [`ResourceType`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntershardResourceError(pub IntershardResourceType);
+
+impl fmt::Display for IntershardResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is an intershard resource", self.0)
+    }
+}
+
+impl Error for IntershardResourceError {}
+
+impl TryFrom<MarketResourceType> for ResourceType {
+    type Error = IntershardResourceError;
+
+    #[inline]
+    fn try_from(ty: MarketResourceType) -> Result<Self, Self::Error> {
+        match ty {
+            MarketResourceType::Resource(ty) => Ok(ty),
+            MarketResourceType::IntershardResource(ty) => Err(IntershardResourceError(ty)),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for MarketResourceType {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let resource = u16::deserialize(deserializer)?;
-        let resource_type = match resource {
-            1 => MarketResourceType::Resource(ResourceType::Energy),
-            2 => MarketResourceType::Resource(ResourceType::Power),
-            3 => MarketResourceType::Resource(ResourceType::Hydrogen),
-            4 => MarketResourceType::Resource(ResourceType::Oxygen),
-            5 => MarketResourceType::Resource(ResourceType::Utrium),
-            6 => MarketResourceType::Resource(ResourceType::Lemergium),
-            7 => MarketResourceType::Resource(ResourceType::Keanium),
-            8 => MarketResourceType::Resource(ResourceType::Zynthium),
-            9 => MarketResourceType::Resource(ResourceType::Catalyst),
-            10 => MarketResourceType::Resource(ResourceType::Ghodium),
-            11 => MarketResourceType::Resource(ResourceType::Hydroxide),
-            12 => MarketResourceType::Resource(ResourceType::ZynthiumKeanite),
-            13 => MarketResourceType::Resource(ResourceType::UtriumLemergite),
-            14 => MarketResourceType::Resource(ResourceType::UtriumHydride),
-            15 => MarketResourceType::Resource(ResourceType::UtriumOxide),
-            16 => MarketResourceType::Resource(ResourceType::KeaniumHydride),
-            17 => MarketResourceType::Resource(ResourceType::KeaniumOxide),
-            18 => MarketResourceType::Resource(ResourceType::LemergiumHydride),
-            19 => MarketResourceType::Resource(ResourceType::LemergiumOxide),
-            20 => MarketResourceType::Resource(ResourceType::ZynthiumHydride),
-            21 => MarketResourceType::Resource(ResourceType::ZynthiumOxide),
-            22 => MarketResourceType::Resource(ResourceType::GhodiumHydride),
-            23 => MarketResourceType::Resource(ResourceType::GhodiumOxide),
-            24 => MarketResourceType::Resource(ResourceType::UtriumAcid),
-            25 => MarketResourceType::Resource(ResourceType::UtriumAlkalide),
-            26 => MarketResourceType::Resource(ResourceType::KeaniumAcid),
-            27 => MarketResourceType::Resource(ResourceType::KeaniumAlkalide),
-            28 => MarketResourceType::Resource(ResourceType::LemergiumAcid),
-            29 => MarketResourceType::Resource(ResourceType::LemergiumAlkalide),
-            30 => MarketResourceType::Resource(ResourceType::ZynthiumAcid),
-            31 => MarketResourceType::Resource(ResourceType::ZynthiumAlkalide),
-            32 => MarketResourceType::Resource(ResourceType::GhodiumAcid),
-            33 => MarketResourceType::Resource(ResourceType::GhodiumAlkalide),
-            34 => MarketResourceType::Resource(ResourceType::CatalyzedUtriumAcid),
-            35 => MarketResourceType::Resource(ResourceType::CatalyzedUtriumAlkalide),
-            36 => MarketResourceType::Resource(ResourceType::CatalyzedKeaniumAcid),
-            37 => MarketResourceType::Resource(ResourceType::CatalyzedKeaniumAlkalide),
-            38 => MarketResourceType::Resource(ResourceType::CatalyzedLemergiumAcid),
-            39 => MarketResourceType::Resource(ResourceType::CatalyzedLemergiumAlkalide),
-            40 => MarketResourceType::Resource(ResourceType::CatalyzedZynthiumAcid),
-            41 => MarketResourceType::Resource(ResourceType::CatalyzedZynthiumAlkalide),
-            42 => MarketResourceType::Resource(ResourceType::CatalyzedGhodiumAcid),
-            43 => MarketResourceType::Resource(ResourceType::CatalyzedGhodiumAlkalide),
-            44 => MarketResourceType::Resource(ResourceType::Ops),
-            45 => MarketResourceType::Resource(ResourceType::Silicon),
-            46 => MarketResourceType::Resource(ResourceType::Metal),
-            47 => MarketResourceType::Resource(ResourceType::Biomass),
-            48 => MarketResourceType::Resource(ResourceType::Mist),
-            49 => MarketResourceType::Resource(ResourceType::UtriumBar),
-            50 => MarketResourceType::Resource(ResourceType::LemergiumBar),
-            51 => MarketResourceType::Resource(ResourceType::ZynthiumBar),
-            52 => MarketResourceType::Resource(ResourceType::KeaniumBar),
-            53 => MarketResourceType::Resource(ResourceType::GhodiumMelt),
-            54 => MarketResourceType::Resource(ResourceType::Oxidant),
-            55 => MarketResourceType::Resource(ResourceType::Reductant),
-            56 => MarketResourceType::Resource(ResourceType::Purifier),
-            57 => MarketResourceType::Resource(ResourceType::Battery),
-            58 => MarketResourceType::Resource(ResourceType::Composite),
-            59 => MarketResourceType::Resource(ResourceType::Crystal),
-            60 => MarketResourceType::Resource(ResourceType::Liquid),
-            61 => MarketResourceType::Resource(ResourceType::Wire),
-            62 => MarketResourceType::Resource(ResourceType::Switch),
-            63 => MarketResourceType::Resource(ResourceType::Transistor),
-            64 => MarketResourceType::Resource(ResourceType::Microchip),
-            65 => MarketResourceType::Resource(ResourceType::Circuit),
-            66 => MarketResourceType::Resource(ResourceType::Device),
-            67 => MarketResourceType::Resource(ResourceType::Cell),
-            68 => MarketResourceType::Resource(ResourceType::Phlegm),
-            69 => MarketResourceType::Resource(ResourceType::Tissue),
-            70 => MarketResourceType::Resource(ResourceType::Muscle),
-            71 => MarketResourceType::Resource(ResourceType::Organoid),
-            72 => MarketResourceType::Resource(ResourceType::Organism),
-            73 => MarketResourceType::Resource(ResourceType::Alloy),
-            74 => MarketResourceType::Resource(ResourceType::Tube),
-            75 => MarketResourceType::Resource(ResourceType::Fixtures),
-            76 => MarketResourceType::Resource(ResourceType::Frame),
-            77 => MarketResourceType::Resource(ResourceType::Hydraulics),
-            78 => MarketResourceType::Resource(ResourceType::Machine),
-            79 => MarketResourceType::Resource(ResourceType::Condensate),
-            80 => MarketResourceType::Resource(ResourceType::Concentrate),
-            81 => MarketResourceType::Resource(ResourceType::Extract),
-            82 => MarketResourceType::Resource(ResourceType::Spirit),
-            83 => MarketResourceType::Resource(ResourceType::Emanation),
-            84 => MarketResourceType::Resource(ResourceType::Essence),
-            1001 => {
-                MarketResourceType::IntershardResource(IntershardResourceType::SubscriptionToken)
-            }
-            _ => {
-                return Err(D::Error::invalid_value(
-                    Unexpected::Unsigned(resource as u64),
+        ResourceType::from_u16(resource)
+            .map(MarketResourceType::Resource)
+            .or_else(|| {
+                num_traits::FromPrimitive::from_u16(resource)
+                    .map(MarketResourceType::IntershardResource)
+            })
+            .ok_or_else(|| {
+                D::Error::invalid_value(
+                    Unexpected::Unsigned(u64::from(resource)),
                     &"a valid RESOURCES_ALL or INTERSHARD_RESOURCES type integer",
-                ))
-            }
-        };
-        Ok(resource_type)
+                )
+            })
     }
 }
 
@@ -837,6 +1576,70 @@ pub enum PowerCreepClass {
 
 js_deserializable!(PowerCreepClass);
 
+impl PowerCreepClass {
+    /// The highest level power creeps of this class can reach,
+    /// [`POWER_CREEP_MAX_LEVEL`].
+    ///
+    /// [`POWER_CREEP_MAX_LEVEL`]: crate::constants::POWER_CREEP_MAX_LEVEL
+    #[inline]
+    pub fn max_level(self) -> u32 {
+        match self {
+            PowerCreepClass::Operator => super::numbers::POWER_CREEP_MAX_LEVEL,
+        }
+    }
+
+    /// Time, in milliseconds, that a power creep of this class must wait to
+    /// respawn after dying, [`POWER_CREEP_SPAWN_COOLDOWN`].
+    ///
+    /// [`POWER_CREEP_SPAWN_COOLDOWN`]: crate::constants::POWER_CREEP_SPAWN_COOLDOWN
+    #[inline]
+    pub fn spawn_cooldown(self) -> u32 {
+        match self {
+            PowerCreepClass::Operator => super::numbers::POWER_CREEP_SPAWN_COOLDOWN,
+        }
+    }
+
+    /// Maximum ticks to live of power creeps of this class,
+    /// [`POWER_CREEP_LIFE_TIME`].
+    ///
+    /// [`POWER_CREEP_LIFE_TIME`]: crate::constants::POWER_CREEP_LIFE_TIME
+    #[inline]
+    pub fn life_time(self) -> u32 {
+        match self {
+            PowerCreepClass::Operator => super::numbers::POWER_CREEP_LIFE_TIME,
+        }
+    }
+
+    /// The powers power creeps of this class can learn, in the order of their
+    /// `PWR_*` constants.
+    pub fn powers(self) -> &'static [PowerType] {
+        use PowerType::*;
+
+        match self {
+            PowerCreepClass::Operator => &[
+                GenerateOps,
+                OperateSpawn,
+                OperateTower,
+                OperateStorage,
+                OperateLab,
+                OperateExtension,
+                OperateObserver,
+                OperateTerminal,
+                DisruptSpawn,
+                DisruptTower,
+                Shield,
+                RegenSource,
+                RegenMineral,
+                DisruptTerminal,
+                OperatePower,
+                Fortify,
+                OperateController,
+                OperateFactory,
+            ],
+        }
+    }
+}
+
 /// Translates the `PWR_*` constants, which are types of powers used by power
 /// creeps
 #[derive(
@@ -866,6 +1669,229 @@ pub enum PowerType {
 
 js_deserializable!(PowerType);
 
+/// A value of [`PowerInfo`] which is either the same at every power level, or
+/// given for each of the 5 levels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PowerLevelValue {
+    Fixed(u32),
+    PerLevel([u32; 5]),
+}
+
+impl PowerLevelValue {
+    /// The value at the given power level, from 1 to 5.
+    #[inline]
+    pub fn at_level(self, level: u8) -> Option<u32> {
+        match (self, level) {
+            (PowerLevelValue::Fixed(value), 1..=5) => Some(value),
+            (PowerLevelValue::PerLevel(values), 1..=5) => Some(values[level as usize - 1]),
+            _ => None,
+        }
+    }
+}
+
+/// Information about a power, translated from the `POWER_INFO` constant.
+///
+/// Returned by [`PowerType::info`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PowerInfo {
+    /// The class of power creeps which can learn this power.
+    pub class: PowerCreepClass,
+    /// The power creep level required for each level of this power.
+    pub level: [u32; 5],
+    /// Ticks before the power can be used again.
+    pub cooldown: u32,
+    /// Ticks the effect lasts, for powers applying an effect.
+    pub duration: Option<PowerLevelValue>,
+    /// Range from which the power can be used, for powers with a target.
+    pub range: Option<u32>,
+    /// Ops consumed each time the power is used.
+    pub ops: Option<PowerLevelValue>,
+    /// Energy consumed each time the power is used.
+    pub energy: Option<u32>,
+    /// Ticks between each application of the effect, for powers applying their
+    /// effect periodically.
+    pub period: Option<u32>,
+    /// The strength of the effect at each level; its meaning depends on the
+    /// power.
+    pub effect: Option<[f64; 5]>,
+}
+
+impl PowerType {
+    /// The power creep level required to learn each of the 5 levels of this
+    /// power, from the `level` field of `POWER_INFO`.
+    ///
+    /// See [`PowerType::info`].
+    #[inline]
+    pub fn level_requirements(self) -> [u32; 5] {
+        self.info().level
+    }
+
+    /// Translates the `POWER_INFO` constant.
+    pub fn info(self) -> PowerInfo {
+        use PowerLevelValue::*;
+
+        const DEFAULT_LEVELS: [u32; 5] = [0, 2, 7, 14, 22];
+        let base = PowerInfo {
+            class: PowerCreepClass::Operator,
+            level: DEFAULT_LEVELS,
+            cooldown: 0,
+            duration: None,
+            range: None,
+            ops: None,
+            energy: None,
+            period: None,
+            effect: None,
+        };
+        match self {
+            PowerType::GenerateOps => PowerInfo {
+                cooldown: 50,
+                effect: Some([1.0, 2.0, 4.0, 6.0, 8.0]),
+                ..base
+            },
+            PowerType::OperateSpawn => PowerInfo {
+                cooldown: 300,
+                duration: Some(Fixed(1000)),
+                range: Some(3),
+                ops: Some(Fixed(100)),
+                effect: Some([0.9, 0.7, 0.5, 0.35, 0.2]),
+                ..base
+            },
+            PowerType::OperateTower => PowerInfo {
+                cooldown: 10,
+                duration: Some(Fixed(100)),
+                range: Some(3),
+                ops: Some(Fixed(10)),
+                effect: Some([1.1, 1.2, 1.3, 1.4, 1.5]),
+                ..base
+            },
+            PowerType::OperateStorage => PowerInfo {
+                cooldown: 800,
+                duration: Some(Fixed(1000)),
+                range: Some(3),
+                ops: Some(Fixed(100)),
+                effect: Some([
+                    500_000.0,
+                    1_000_000.0,
+                    2_000_000.0,
+                    4_000_000.0,
+                    7_000_000.0,
+                ]),
+                ..base
+            },
+            PowerType::OperateLab => PowerInfo {
+                cooldown: 50,
+                duration: Some(Fixed(1000)),
+                range: Some(3),
+                ops: Some(Fixed(10)),
+                effect: Some([2.0, 4.0, 6.0, 8.0, 10.0]),
+                ..base
+            },
+            PowerType::OperateExtension => PowerInfo {
+                cooldown: 50,
+                range: Some(3),
+                ops: Some(Fixed(2)),
+                effect: Some([0.2, 0.4, 0.6, 0.8, 1.0]),
+                ..base
+            },
+            PowerType::OperateObserver => PowerInfo {
+                cooldown: 400,
+                duration: Some(PerLevel([200, 400, 600, 800, 1000])),
+                range: Some(3),
+                ops: Some(Fixed(10)),
+                ..base
+            },
+            PowerType::OperateTerminal => PowerInfo {
+                cooldown: 500,
+                duration: Some(Fixed(1000)),
+                range: Some(3),
+                ops: Some(Fixed(100)),
+                effect: Some([0.9, 0.8, 0.7, 0.6, 0.5]),
+                ..base
+            },
+            PowerType::DisruptSpawn => PowerInfo {
+                cooldown: 5,
+                duration: Some(PerLevel([1, 2, 3, 4, 5])),
+                range: Some(20),
+                ops: Some(Fixed(10)),
+                ..base
+            },
+            PowerType::DisruptTower => PowerInfo {
+                cooldown: 0,
+                duration: Some(Fixed(5)),
+                range: Some(50),
+                ops: Some(Fixed(10)),
+                effect: Some([0.9, 0.8, 0.7, 0.6, 0.5]),
+                ..base
+            },
+            PowerType::Shield => PowerInfo {
+                cooldown: 20,
+                duration: Some(Fixed(50)),
+                energy: Some(100),
+                effect: Some([5000.0, 10000.0, 15000.0, 20000.0, 25000.0]),
+                ..base
+            },
+            PowerType::RegenSource => PowerInfo {
+                level: [10, 11, 12, 14, 22],
+                cooldown: 100,
+                duration: Some(Fixed(300)),
+                range: Some(3),
+                period: Some(15),
+                effect: Some([50.0, 100.0, 150.0, 200.0, 250.0]),
+                ..base
+            },
+            PowerType::RegenMineral => PowerInfo {
+                level: [10, 11, 12, 14, 22],
+                cooldown: 100,
+                duration: Some(Fixed(100)),
+                range: Some(3),
+                period: Some(10),
+                effect: Some([2.0, 4.0, 6.0, 8.0, 10.0]),
+                ..base
+            },
+            PowerType::DisruptTerminal => PowerInfo {
+                level: [20, 21, 22, 23, 24],
+                cooldown: 8,
+                duration: Some(Fixed(10)),
+                range: Some(50),
+                ops: Some(PerLevel([50, 40, 30, 20, 10])),
+                ..base
+            },
+            PowerType::OperatePower => PowerInfo {
+                level: [10, 11, 12, 14, 22],
+                cooldown: 800,
+                duration: Some(Fixed(1000)),
+                range: Some(3),
+                ops: Some(Fixed(200)),
+                effect: Some([1.0, 2.0, 3.0, 4.0, 5.0]),
+                ..base
+            },
+            PowerType::Fortify => PowerInfo {
+                cooldown: 5,
+                duration: Some(PerLevel([1, 2, 3, 4, 5])),
+                range: Some(3),
+                ops: Some(Fixed(5)),
+                ..base
+            },
+            PowerType::OperateController => PowerInfo {
+                level: [20, 21, 22, 23, 24],
+                cooldown: 800,
+                duration: Some(Fixed(1000)),
+                range: Some(3),
+                ops: Some(Fixed(200)),
+                effect: Some([10.0, 20.0, 30.0, 40.0, 50.0]),
+                ..base
+            },
+            PowerType::OperateFactory => PowerInfo {
+                cooldown: 800,
+                duration: Some(Fixed(1000)),
+                range: Some(3),
+                ops: Some(Fixed(100)),
+                ..base
+            },
+        }
+    }
+}
+
 /// Translates the `EFFECT_*` constants, which are natural effect types
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, Serialize_repr, Deserialize_repr,
@@ -880,19 +1906,23 @@ js_deserializable!(NaturalEffectType);
 
 /// Translates effect types which can include both `PWR_*` and `EFFECT_*`
 /// constants.
+///
+/// Effect ids unknown to this crate, such as those of powers added to the game
+/// after its release, deserialize to [`EffectType::Unknown`] rather than
+/// failing. Use [`EffectType::deserialize_strict`] to reject them instead.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum EffectType {
     PowerEffect(PowerType),
     NaturalEffect(NaturalEffectType),
+    /// An effect with an id unknown to this crate.
+    Unknown(u16),
 }
 
-impl<'de> Deserialize<'de> for EffectType {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let effect = u16::deserialize(deserializer)?;
-        let effect_type = match effect {
+impl EffectType {
+    /// The effect type with the given `PWR_*` or `EFFECT_*` id, or
+    /// [`EffectType::Unknown`] for ids unknown to this crate.
+    pub fn from_id(id: u16) -> Self {
+        match id {
             1 => EffectType::PowerEffect(PowerType::GenerateOps),
             2 => EffectType::PowerEffect(PowerType::OperateSpawn),
             3 => EffectType::PowerEffect(PowerType::OperateTower),
@@ -913,14 +1943,843 @@ impl<'de> Deserialize<'de> for EffectType {
             19 => EffectType::PowerEffect(PowerType::OperateFactory),
             1001 => EffectType::NaturalEffect(NaturalEffectType::Invulnerability),
             1002 => EffectType::NaturalEffect(NaturalEffectType::CollapseTimer),
-            _ => {
-                return Err(D::Error::invalid_value(
-                    Unexpected::Unsigned(effect as u64),
-                    &"a valid PWR_* or EFFECT_* type integer",
-                ))
+            _ => EffectType::Unknown(id),
+        }
+    }
+
+    /// The `PWR_*` or `EFFECT_*` id of this effect type.
+    pub fn id(self) -> u16 {
+        match self {
+            EffectType::PowerEffect(ty) => ty as u16,
+            EffectType::NaturalEffect(ty) => ty as u16,
+            EffectType::Unknown(id) => id,
+        }
+    }
+
+    /// Helper function for deserializing an effect type, failing on ids
+    /// unknown to this crate rather than returning [`EffectType::Unknown`].
+    ///
+    /// Use with `#[serde(deserialize_with = "EffectType::deserialize_strict")]`.
+    pub fn deserialize_strict<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let id = u16::deserialize(d)?;
+        match Self::from_id(id) {
+            EffectType::Unknown(_) => Err(D::Error::invalid_value(
+                Unexpected::Unsigned(u64::from(id)),
+                &"a valid PWR_* or EFFECT_* type integer",
+            )),
+            effect_type => Ok(effect_type),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EffectType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u16::deserialize(deserializer).map(Self::from_id)
+    }
+}
+
+impl Serialize for EffectType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.id().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+
+    use num_traits::FromPrimitive;
+
+    use super::{
+        Boost, BoostAction, EffectType, IntershardResourceError, IntershardResourceType,
+        MarketResourceType, NaturalEffectType, PowerInfo, PowerLevelValue, PowerType,
+        ResourceCategory, ResourceType, ResourceType::*, StoreCapacity, StructureType,
+        RESOURCES_ALL, RESOURCES_BASE_MINERALS, RESOURCES_COMMODITIES,
+    };
+    use crate::constants::{Part, Terrain};
+
+    #[test]
+    fn market_resource_type_strings() {
+        for &(s, ty) in [
+            ("energy", MarketResourceType::Resource(Energy)),
+            ("XGH2O", MarketResourceType::Resource(CatalyzedGhodiumAcid)),
+            (
+                "token",
+                MarketResourceType::IntershardResource(IntershardResourceType::SubscriptionToken),
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(s.parse::<MarketResourceType>(), Ok(ty));
+            assert_eq!(ty.to_string(), s);
+        }
+
+        assert!("garbage".parse::<MarketResourceType>().is_err());
+        assert!("".parse::<MarketResourceType>().is_err());
+        assert!("Energy".parse::<MarketResourceType>().is_err());
+    }
+
+    #[test]
+    fn market_resource_type_conversions() {
+        assert_eq!(
+            MarketResourceType::from(Energy),
+            MarketResourceType::Resource(Energy)
+        );
+        let pixel: MarketResourceType = IntershardResourceType::Pixel.into();
+        assert_eq!(
+            pixel,
+            MarketResourceType::IntershardResource(IntershardResourceType::Pixel)
+        );
+
+        assert_eq!(
+            ResourceType::try_from(MarketResourceType::from(Hydroxide)),
+            Ok(Hydroxide)
+        );
+        let err = ResourceType::try_from(pixel).unwrap_err();
+        assert_eq!(err, IntershardResourceError(IntershardResourceType::Pixel));
+        assert_eq!(err.to_string(), "pixel is an intershard resource");
+    }
+
+    #[test]
+    fn max_hits_for_rcl() {
+        let rampart: Vec<Option<u32>> = (1..=8)
+            .map(|rcl| StructureType::Rampart.max_hits_for_rcl(rcl))
+            .collect();
+        assert_eq!(
+            rampart,
+            vec![
+                Some(0),
+                Some(300_000),
+                Some(1_000_000),
+                Some(3_000_000),
+                Some(10_000_000),
+                Some(30_000_000),
+                Some(100_000_000),
+                Some(300_000_000),
+            ]
+        );
+
+        for rcl in 1..=8 {
+            assert_eq!(StructureType::Wall.max_hits_for_rcl(rcl), Some(300_000_000));
+            assert_eq!(StructureType::Road.max_hits_for_rcl(rcl), Some(5_000));
+            assert_eq!(StructureType::Controller.max_hits_for_rcl(rcl), None);
+        }
+    }
+
+    #[test]
+    fn max_hits() {
+        assert_eq!(StructureType::Wall.max_hits(), Some(300_000_000));
+        assert_eq!(StructureType::Road.max_hits(), Some(5_000));
+        assert_eq!(
+            StructureType::Road.max_hits(),
+            StructureType::Road.initial_hits()
+        );
+        assert_eq!(StructureType::Rampart.max_hits(), None);
+        assert_eq!(StructureType::Controller.max_hits(), None);
+
+        for ty in StructureType::iter_values().filter(|&ty| ty != StructureType::Rampart) {
+            assert_eq!(ty.max_hits(), ty.max_hits_for_rcl(8), "{:?}", ty);
+        }
+    }
+
+    #[test]
+    fn max_hits_on_terrain() {
+        use super::{StructureType::*, Terrain};
+
+        assert_eq!(Road.max_hits_on(Terrain::Plain), Some(5_000));
+        assert_eq!(Road.max_hits_on(Terrain::Swamp), Some(25_000));
+        assert_eq!(Road.max_hits_on(Terrain::Wall), Some(750_000));
+        assert_eq!(Container.max_hits_on(Terrain::Swamp), Some(250_000));
+        assert_eq!(Wall.max_hits_on(Terrain::Swamp), Some(300_000_000));
+        assert_eq!(Rampart.max_hits_on(Terrain::Plain), None);
+    }
+
+    #[test]
+    fn initial_hits() {
+        use StructureType::*;
+
+        // transcribed from constants.js
+        for &(ty, hits) in &[
+            (Spawn, Some(5000)),
+            (Extension, Some(1000)),
+            (Road, Some(5000)),
+            (Wall, Some(1)),
+            (Rampart, Some(1)),
+            (KeeperLair, None),
+            (Portal, None),
+            (Controller, None),
+            (Link, Some(1000)),
+            (Storage, Some(10_000)),
+            (Tower, Some(3000)),
+            (Observer, Some(500)),
+            (PowerBank, Some(2_000_000)),
+            (PowerSpawn, Some(5000)),
+            (Extractor, Some(500)),
+            (Lab, Some(500)),
+            (Terminal, Some(3000)),
+            (Container, Some(250_000)),
+            (Nuker, Some(1000)),
+            (Factory, Some(1000)),
+            (InvaderCore, Some(100_000)),
+        ] {
+            assert_eq!(ty.initial_hits(), hits, "{:?}", ty);
+        }
+        assert_eq!(StructureType::iter_values().count(), 21);
+    }
+
+    #[test]
+    fn structure_decay() {
+        let road = StructureType::Road.decay().unwrap();
+        assert_eq!((road.amount, road.period), (100, 1000));
+        assert_eq!(road.amount_on(Terrain::Plain), 100);
+        assert_eq!(road.amount_on(Terrain::Swamp), 500);
+        assert_eq!(road.amount_on(Terrain::Wall), 15_000);
+        assert_eq!(road.period_in(false), 1000);
+
+        let rampart = StructureType::Rampart.decay().unwrap();
+        assert_eq!(rampart.amount_on(Terrain::Swamp), 300);
+        assert_eq!(
+            (rampart.period_in(true), rampart.period_in(false)),
+            (100, 100)
+        );
+
+        let container = StructureType::Container.decay().unwrap();
+        assert_eq!(container.amount_on(Terrain::Plain), 5000);
+        assert_eq!(container.period_in(true), 500);
+        assert_eq!(container.period_in(false), 100);
+
+        let decaying: Vec<StructureType> = StructureType::iter_values()
+            .filter(|ty| ty.decay().is_some())
+            .collect();
+        assert_eq!(
+            decaying,
+            vec![
+                StructureType::Road,
+                StructureType::Rampart,
+                StructureType::Container
+            ]
+        );
+    }
+
+    #[test]
+    fn power_info() {
+        let generate_ops = PowerType::GenerateOps.info();
+        assert_eq!(generate_ops.cooldown, 50);
+        assert_eq!(generate_ops.ops, None);
+        assert_eq!(generate_ops.range, None);
+        assert_eq!(generate_ops.effect, Some([1.0, 2.0, 4.0, 6.0, 8.0]));
+
+        let operate_spawn = PowerType::OperateSpawn.info();
+        assert_eq!(operate_spawn.cooldown, 300);
+        assert_eq!(operate_spawn.ops, Some(PowerLevelValue::Fixed(100)));
+        assert_eq!(operate_spawn.duration.unwrap().at_level(3), Some(1000));
+        assert_eq!(operate_spawn.effect, Some([0.9, 0.7, 0.5, 0.35, 0.2]));
+
+        let fortify = PowerType::Fortify.info();
+        assert_eq!(fortify.cooldown, 5);
+        assert_eq!(fortify.effect, None);
+        let duration = fortify.duration.unwrap();
+        assert_eq!(duration.at_level(1), Some(1));
+        assert_eq!(duration.at_level(5), Some(5));
+        assert_eq!(duration.at_level(0), None);
+        assert_eq!(duration.at_level(6), None);
+
+        let PowerInfo {
+            level,
+            cooldown,
+            ops,
+            period,
+            effect,
+            ..
+        } = PowerType::RegenSource.info();
+        assert_eq!(level, [10, 11, 12, 14, 22]);
+        assert_eq!(cooldown, 100);
+        assert_eq!(ops, None);
+        assert_eq!(period, Some(15));
+        assert_eq!(effect, Some([50.0, 100.0, 150.0, 200.0, 250.0]));
+
+        assert_eq!(
+            PowerType::DisruptTerminal.info().ops.unwrap().at_level(2),
+            Some(40)
+        );
+        assert_eq!(PowerType::Shield.info().energy, Some(100));
+    }
+
+    #[test]
+    fn power_level_requirements() {
+        // most powers, including generating ops, follow the standard progression
+        assert_eq!(
+            PowerType::GenerateOps.level_requirements(),
+            [0, 2, 7, 14, 22]
+        );
+        assert_eq!(
+            PowerType::OperateFactory.level_requirements(),
+            [0, 2, 7, 14, 22]
+        );
+        assert_eq!(
+            PowerType::OperatePower.level_requirements(),
+            [10, 11, 12, 14, 22]
+        );
+        assert_eq!(
+            PowerType::OperateController.level_requirements(),
+            [20, 21, 22, 23, 24]
+        );
+    }
+
+    #[test]
+    fn effect_type_round_trip() {
+        let powers: Vec<EffectType> = (0..=1100u16)
+            .filter_map(PowerType::from_u16)
+            .map(EffectType::PowerEffect)
+            .collect();
+        let natural: Vec<EffectType> = (0..=1100u16)
+            .filter_map(NaturalEffectType::from_u16)
+            .map(EffectType::NaturalEffect)
+            .collect();
+        assert_eq!(powers.len(), 18);
+        assert_eq!(natural.len(), 2);
+
+        for effect in powers.iter().chain(natural.iter()) {
+            let value = serde_json::to_value(effect).unwrap();
+            let number = match effect {
+                EffectType::PowerEffect(ty) => *ty as u64,
+                EffectType::NaturalEffect(ty) => *ty as u64,
+                EffectType::Unknown(_) => unreachable!(),
+            };
+            assert_eq!(value, serde_json::json!(number));
+            assert_eq!(EffectType::from_id(effect.id()), *effect);
+            assert_eq!(
+                serde_json::from_value::<EffectType>(value).unwrap(),
+                *effect
+            );
+        }
+
+        // there is no PWR_* constant with the value 11
+        assert_eq!(
+            serde_json::to_value(EffectType::PowerEffect(PowerType::Shield)).unwrap(),
+            serde_json::json!(12)
+        );
+        assert_eq!(
+            serde_json::from_value::<EffectType>(serde_json::json!(11)).unwrap(),
+            EffectType::Unknown(11)
+        );
+    }
+
+    #[test]
+    fn unknown_effect_types() {
+        use serde::Deserialize;
+
+        #[derive(Debug, Deserialize)]
+        struct Strict {
+            #[serde(deserialize_with = "EffectType::deserialize_strict")]
+            effect: EffectType,
+        }
+
+        // an effect added to the game after this crate, next to known ones
+        let effects: Vec<EffectType> = serde_json::from_str("[13, 1001, 42, 1003]").unwrap();
+        assert_eq!(
+            effects,
+            vec![
+                EffectType::PowerEffect(PowerType::RegenSource),
+                EffectType::NaturalEffect(NaturalEffectType::Invulnerability),
+                EffectType::Unknown(42),
+                EffectType::Unknown(1003),
+            ]
+        );
+        assert_eq!(EffectType::Unknown(42).id(), 42);
+        assert_eq!(
+            serde_json::to_value(EffectType::Unknown(42)).unwrap(),
+            serde_json::json!(42)
+        );
+        assert!(serde_json::from_str::<EffectType>("-1").is_err());
+        assert!(serde_json::from_str::<EffectType>("70000").is_err());
+
+        let strict: Strict = serde_json::from_str(r#"{"effect":13}"#).unwrap();
+        assert_eq!(
+            strict.effect,
+            EffectType::PowerEffect(PowerType::RegenSource)
+        );
+        assert!(serde_json::from_str::<Strict>(r#"{"effect":42}"#).is_err());
+    }
+
+    #[test]
+    fn resource_type_iteration() {
+        let all: Vec<ResourceType> = ResourceType::iter_values().collect();
+        assert_eq!(&all[..], &RESOURCES_ALL[..]);
+        for (index, resource) in RESOURCES_ALL.iter().enumerate() {
+            assert_eq!(*resource as usize, index + 1);
+        }
+
+        // every integer the enum deserializes from is covered exactly once
+        let deserialized: Vec<ResourceType> = (0..=1100u16)
+            .filter_map(|value| serde_json::from_str(&value.to_string()).ok())
+            .collect();
+        assert_eq!(deserialized, all);
+
+        for mineral in RESOURCES_BASE_MINERALS.iter() {
+            assert!(RESOURCES_ALL.contains(mineral));
+            assert!(mineral.reaction_components().is_none());
+        }
+        assert_eq!(RESOURCES_COMMODITIES[0], UtriumBar);
+        assert_eq!(
+            RESOURCES_COMMODITIES[RESOURCES_COMMODITIES.len() - 1],
+            Essence
+        );
+        assert!(!RESOURCES_COMMODITIES.contains(&Silicon));
+    }
+
+    #[test]
+    fn structure_type_iteration() {
+        let all: Vec<StructureType> = StructureType::iter_values().collect();
+        assert_eq!(all.len(), 21);
+        for (index, ty) in all.iter().enumerate() {
+            assert_eq!(*ty as usize, index);
+        }
+        assert_eq!(all.first(), Some(&StructureType::Spawn));
+        assert_eq!(all.last(), Some(&StructureType::InvaderCore));
+    }
+
+    #[test]
+    fn obstacle_structures() {
+        assert!(!StructureType::Road.is_obstacle());
+        assert!(!StructureType::Container.is_obstacle());
+        assert!(!StructureType::Rampart.is_obstacle());
+        assert!(!StructureType::Portal.is_obstacle());
+        assert!(StructureType::Spawn.is_obstacle());
+        assert!(StructureType::Wall.is_obstacle());
+        assert!(StructureType::Controller.is_obstacle());
+        assert!(StructureType::InvaderCore.is_obstacle());
+    }
+
+    #[test]
+    fn boost_parts() {
+        let expected = [
+            (UtriumHydride, Part::Attack, BoostAction::Attack),
+            (CatalyzedUtriumAlkalide, Part::Work, BoostAction::Harvest),
+            (KeaniumAcid, Part::Carry, BoostAction::Carry),
+            (KeaniumOxide, Part::RangedAttack, BoostAction::RangedAttack),
+            (LemergiumHydride, Part::Work, BoostAction::BuildAndRepair),
+            (CatalyzedLemergiumAlkalide, Part::Heal, BoostAction::Heal),
+            (ZynthiumAcid, Part::Work, BoostAction::Dismantle),
+            (ZynthiumOxide, Part::Move, BoostAction::Move),
+            (GhodiumAcid, Part::Work, BoostAction::UpgradeController),
+            (CatalyzedGhodiumAlkalide, Part::Tough, BoostAction::Tough),
+        ];
+        for &(resource, part, action) in expected.iter() {
+            let boost = resource.boost().unwrap();
+            assert_eq!(boost.part(), part, "{:?}", resource);
+            assert_eq!(boost.action(), action, "{:?}", resource);
+        }
+    }
+
+    #[test]
+    fn boost_multiplier() {
+        assert_eq!(Boost::Attack(4.0).multiplier(), 4.0);
+        assert_eq!(
+            CatalyzedGhodiumAlkalide.boost().map(Boost::multiplier),
+            Some(0.3)
+        );
+        assert_eq!(Energy.boost(), None);
+    }
+
+    #[test]
+    fn boost_table() {
+        // transcribed from the BOOSTS constant in constants.js
+        let expected = [
+            (UtriumHydride, Boost::Attack(2.0)),
+            (UtriumAcid, Boost::Attack(3.0)),
+            (CatalyzedUtriumAcid, Boost::Attack(4.0)),
+            (UtriumOxide, Boost::Harvest(3.0)),
+            (UtriumAlkalide, Boost::Harvest(5.0)),
+            (CatalyzedUtriumAlkalide, Boost::Harvest(7.0)),
+            (KeaniumHydride, Boost::Carry(2.0)),
+            (KeaniumAcid, Boost::Carry(3.0)),
+            (CatalyzedKeaniumAcid, Boost::Carry(4.0)),
+            (KeaniumOxide, Boost::RangedAttack(2.0)),
+            (KeaniumAlkalide, Boost::RangedAttack(3.0)),
+            (CatalyzedKeaniumAlkalide, Boost::RangedAttack(4.0)),
+            (LemergiumHydride, Boost::BuildAndRepair(1.5)),
+            (LemergiumAcid, Boost::BuildAndRepair(1.8)),
+            (CatalyzedLemergiumAcid, Boost::BuildAndRepair(2.0)),
+            (LemergiumOxide, Boost::Heal(2.0)),
+            (LemergiumAlkalide, Boost::Heal(3.0)),
+            (CatalyzedLemergiumAlkalide, Boost::Heal(4.0)),
+            (ZynthiumHydride, Boost::Dismantle(2.0)),
+            (ZynthiumAcid, Boost::Dismantle(3.0)),
+            (CatalyzedZynthiumAcid, Boost::Dismantle(4.0)),
+            (ZynthiumOxide, Boost::Move(2.0)),
+            (ZynthiumAlkalide, Boost::Move(3.0)),
+            (CatalyzedZynthiumAlkalide, Boost::Move(4.0)),
+            (GhodiumHydride, Boost::UpgradeController(1.5)),
+            (GhodiumAcid, Boost::UpgradeController(1.8)),
+            (CatalyzedGhodiumAcid, Boost::UpgradeController(2.0)),
+            (GhodiumOxide, Boost::Tough(0.7)),
+            (GhodiumAlkalide, Boost::Tough(0.5)),
+            (CatalyzedGhodiumAlkalide, Boost::Tough(0.3)),
+        ];
+        for &(resource, boost) in expected.iter() {
+            assert_eq!(resource.boost(), Some(boost), "{:?}", resource);
+        }
+
+        // nothing else boosts
+        let boosting = ResourceType::iter_values()
+            .filter(|resource| resource.boost().is_some())
+            .count();
+        assert_eq!(boosting, expected.len());
+    }
+
+    #[test]
+    fn boost_for_action_and_tier() {
+        let actions = [
+            (BoostAction::Harvest, 3.0, 7.0),
+            (BoostAction::BuildAndRepair, 1.5, 2.0),
+            (BoostAction::Dismantle, 2.0, 4.0),
+            (BoostAction::UpgradeController, 1.5, 2.0),
+            (BoostAction::Attack, 2.0, 4.0),
+            (BoostAction::RangedAttack, 2.0, 4.0),
+            (BoostAction::Heal, 2.0, 4.0),
+            (BoostAction::Carry, 2.0, 4.0),
+            (BoostAction::Move, 2.0, 4.0),
+            (BoostAction::Tough, 0.7, 0.3),
+        ];
+        for &(action, tier_1, tier_3) in actions.iter() {
+            for tier in 1..=3 {
+                let boost = ResourceType::boost_for(action, tier)
+                    .and_then(ResourceType::boost)
+                    .unwrap();
+                assert_eq!(boost.action(), action, "{:?} tier {}", action, tier);
+            }
+            let multiplier = |tier| {
+                ResourceType::boost_for(action, tier)
+                    .and_then(ResourceType::boost)
+                    .map(Boost::multiplier)
+            };
+            assert_eq!(multiplier(1), Some(tier_1), "{:?}", action);
+            assert_eq!(multiplier(3), Some(tier_3), "{:?}", action);
+            assert_eq!(ResourceType::boost_for(action, 0), None);
+            assert_eq!(ResourceType::boost_for(action, 4), None);
+        }
+
+        assert_eq!(
+            ResourceType::boost_for(BoostAction::Harvest, 2),
+            Some(UtriumAlkalide)
+        );
+        assert_eq!(
+            ResourceType::boost_for(BoostAction::UpgradeController, 2)
+                .and_then(ResourceType::boost),
+            Some(Boost::UpgradeController(1.8))
+        );
+    }
+
+    #[test]
+    fn resource_categories() {
+        assert_eq!(Energy.category(), ResourceCategory::Energy);
+        assert_eq!(Power.category(), ResourceCategory::Power);
+        assert_eq!(Ops.category(), ResourceCategory::Ops);
+
+        // ghodium is produced by a reaction rather than extracted
+        assert!(!Ghodium.is_base_mineral());
+        assert!(Ghodium.is_compound());
+        assert!(Catalyst.is_base_mineral());
+        assert!(Hydroxide.is_compound());
+
+        assert_eq!(Battery.category(), ResourceCategory::CompressedCommodity);
+        assert!(Battery.is_commodity());
+        assert_eq!(Composite.category(), ResourceCategory::Commodity);
+        assert!(Mist.is_deposit_resource());
+        assert!(!Mist.is_commodity());
+
+        for resource in ResourceType::iter_values() {
+            assert_eq!(
+                resource.is_base_mineral(),
+                RESOURCES_BASE_MINERALS.contains(&resource),
+                "{:?}",
+                resource
+            );
+            assert_eq!(
+                resource.is_commodity(),
+                RESOURCES_COMMODITIES.contains(&resource),
+                "{:?}",
+                resource
+            );
+            assert_eq!(
+                resource.is_compound(),
+                resource.reaction_components().is_some(),
+                "{:?}",
+                resource
+            );
+        }
+    }
+
+    #[test]
+    fn boost_tier() {
+        assert_eq!(GhodiumOxide.boost_tier(), Some(1));
+        assert_eq!(KeaniumAcid.boost_tier(), Some(2));
+        assert_eq!(CatalyzedZynthiumAlkalide.boost_tier(), Some(3));
+        assert_eq!(Hydroxide.boost_tier(), None);
+        assert_eq!(Ghodium.boost_tier(), None);
+
+        let mut compounds = 0;
+        for value in 0..=1000u16 {
+            let resource: ResourceType = match serde_json::from_str(&value.to_string()) {
+                Ok(resource) => resource,
+                Err(_) => continue,
+            };
+            assert_eq!(
+                resource.boost_tier().is_some(),
+                resource.boost().is_some(),
+                "{:?}",
+                resource
+            );
+            if let Some(tier) = resource.boost_tier() {
+                compounds += 1;
+                assert_eq!(
+                    ResourceType::boost_for(resource.boost().unwrap().action(), tier),
+                    Some(resource)
+                );
+            }
+        }
+        assert_eq!(compounds, 30);
+    }
+
+    #[test]
+    fn store_capacities() {
+        let nuker = StructureType::Nuker.store_capacity().unwrap();
+        assert_eq!(
+            nuker,
+            StoreCapacity::Split {
+                energy: 300_000,
+                other: 5000
             }
+        );
+        assert_eq!(nuker.total(), 305_000);
+
+        let lab = StructureType::Lab.store_capacity().unwrap();
+        assert_eq!((lab.energy(), lab.other(), lab.total()), (2000, 3000, 5000));
+
+        let tower = StructureType::Tower.store_capacity().unwrap();
+        assert_eq!((tower.energy(), tower.other()), (1000, 0));
+        let container = StructureType::Container.store_capacity().unwrap();
+        assert_eq!((container.energy(), container.other()), (2000, 2000));
+        assert_eq!(
+            StructureType::Extension.store_capacity(),
+            Some(StoreCapacity::Energy(200))
+        );
+
+        assert_eq!(StructureType::Road.store_capacity(), None);
+        assert_eq!(StructureType::Controller.store_capacity(), None);
+    }
+
+    #[test]
+    fn extension_capacity() {
+        use crate::constants::{extension_energy_capacity, total_extension_capacity};
+
+        let expected = [
+            (1, 50, 0),
+            (2, 50, 250),
+            (3, 50, 500),
+            (4, 50, 1000),
+            (5, 50, 1500),
+            (6, 50, 2000),
+            (7, 100, 5000),
+            (8, 200, 12_000),
+        ];
+        for &(rcl, each, total) in &expected {
+            assert_eq!(extension_energy_capacity(rcl), each, "rcl {}", rcl);
+            assert_eq!(total_extension_capacity(rcl), total, "rcl {}", rcl);
+        }
+    }
+
+    #[test]
+    fn tower_falloff() {
+        use crate::constants::{
+            tower_attack_power_at_range, tower_heal_power_at_range, tower_repair_power_at_range,
+        };
+
+        let expected = [
+            (0, 600, 400, 800),
+            (5, 600, 400, 800),
+            (6, 570, 380, 760),
+            (10, 450, 300, 600),
+            (19, 180, 120, 240),
+            (20, 150, 100, 200),
+            (25, 150, 100, 200),
+        ];
+        for &(range, attack, heal, repair) in &expected {
+            let powers = (
+                tower_attack_power_at_range(range),
+                tower_heal_power_at_range(range),
+                tower_repair_power_at_range(range),
+            );
+            assert_eq!(powers, (attack, heal, repair), "range {}", range);
+        }
+    }
+
+    #[test]
+    fn global_levels() {
+        use crate::constants::{
+            gcl_level_for_progress, gcl_progress_required, gpl_level_for_progress,
+            gpl_progress_required,
         };
 
-        Ok(effect_type)
+        let gcl_breakpoints = [
+            (2, 1_000_000),
+            (3, 5_278_032),
+            (4, 13_966_611),
+            (5, 27_857_619),
+            (8, 106_717_415),
+            (10, 195_066_200),
+        ];
+        assert_eq!(gcl_progress_required(1), 0);
+        assert_eq!(gcl_level_for_progress(0), 1);
+        for &(level, progress) in &gcl_breakpoints {
+            assert_eq!(gcl_progress_required(level), progress);
+            assert_eq!(gcl_level_for_progress(progress), level);
+            assert_eq!(gcl_level_for_progress(progress - 1), level - 1);
+        }
+
+        assert_eq!(gpl_level_for_progress(0), 0);
+        for &level in &[1, 2, 10, 100] {
+            let progress = gpl_progress_required(level);
+            assert_eq!(progress, u64::from(level * level) * 1000);
+            assert_eq!(gpl_level_for_progress(progress), level);
+            assert_eq!(gpl_level_for_progress(progress - 1), level - 1);
+        }
+    }
+
+    #[test]
+    fn power_creep_classes() {
+        use super::{PowerCreepClass, PowerType};
+
+        let operator = PowerCreepClass::Operator;
+        assert_eq!(operator.max_level(), 25);
+        assert_eq!(operator.life_time(), 5000);
+        assert_eq!(operator.spawn_cooldown(), 8 * 3600 * 1000);
+
+        let all_powers: Vec<PowerType> = (0..=u8::MAX).filter_map(PowerType::from_u8).collect();
+        assert_eq!(all_powers.len(), 18);
+        assert_eq!(operator.powers(), &all_powers[..]);
+        for power in operator.powers() {
+            assert_eq!(power.info().class, operator);
+        }
+    }
+
+    #[test]
+    fn spawning_time() {
+        use crate::constants::{spawning_ticks_remaining, CREEP_SPAWN_TIME};
+
+        assert_eq!(CREEP_SPAWN_TIME, 3);
+        assert_eq!(spawning_ticks_remaining(10, 1000, 1000), 30);
+        assert_eq!(spawning_ticks_remaining(10, 1000, 1012), 18);
+        assert_eq!(spawning_ticks_remaining(10, 1000, 1030), 0);
+        // long finished, or started in the future
+        assert_eq!(spawning_ticks_remaining(10, 1000, 2000), 0);
+        assert_eq!(spawning_ticks_remaining(1, 1010, 1000), 3);
+    }
+
+    #[test]
+    fn deposit_cooldown() {
+        use crate::constants::deposit_cooldown_for_harvested;
+
+        assert_eq!(deposit_cooldown_for_harvested(0), 0);
+        assert_eq!(deposit_cooldown_for_harvested(1), 1);
+        assert_eq!(deposit_cooldown_for_harvested(1000), 4);
+        assert_eq!(deposit_cooldown_for_harvested(20_000), 145);
+        assert_eq!(deposit_cooldown_for_harvested(50_000), 436);
+        // 999.9999999999995 in double precision
+        assert_eq!(deposit_cooldown_for_harvested(100_000), 1000);
+    }
+
+    #[test]
+    fn source_capacity() {
+        use crate::constants::{
+            expected_energy_per_tick, source_energy_capacity, ENERGY_REGEN_TIME, MINERAL_REGEN_TIME,
+        };
+
+        assert_eq!(source_energy_capacity(true, false), 3000);
+        assert_eq!(source_energy_capacity(false, false), 1500);
+        assert_eq!(source_energy_capacity(false, true), 4000);
+        assert_eq!(source_energy_capacity(true, true), 4000);
+
+        assert_eq!(expected_energy_per_tick(3000), 10.0);
+        assert_eq!(expected_energy_per_tick(1500), 5.0);
+        assert!((expected_energy_per_tick(4000) - 13.333_333).abs() < 1e-5);
+        assert_eq!(ENERGY_REGEN_TIME, 300);
+        assert_eq!(MINERAL_REGEN_TIME, 50_000);
+    }
+
+    #[test]
+    fn construction_cost_on_terrain() {
+        use super::{StructureType::*, Terrain};
+
+        assert_eq!(Road.construction_cost_on(Terrain::Plain), Some(300));
+        assert_eq!(Road.construction_cost_on(Terrain::Swamp), Some(1500));
+        assert_eq!(Road.construction_cost_on(Terrain::Wall), Some(45_000));
+        assert_eq!(Extension.construction_cost_on(Terrain::Swamp), Some(3000));
+        assert_eq!(Container.construction_cost_on(Terrain::Swamp), Some(5000));
+        assert_eq!(Controller.construction_cost_on(Terrain::Plain), None);
+    }
+
+    #[test]
+    fn invader_core_levels() {
+        use crate::constants::{
+            invader_core_creep_spawn_time, invader_core_expand_time, stronghold_rampart_hits,
+            INVADER_CORE_CONTROLLER_DOWNGRADE, INVADER_CORE_CONTROLLER_POWER,
+        };
+
+        let expand: Vec<_> = (1..=5).map(invader_core_expand_time).collect();
+        assert_eq!(
+            expand,
+            vec![Some(4000), Some(3500), Some(3000), Some(2500), Some(2000)]
+        );
+        let spawn: Vec<_> = (0..=5).map(invader_core_creep_spawn_time).collect();
+        assert_eq!(
+            spawn,
+            vec![Some(0), Some(0), Some(6), Some(3), Some(2), Some(1)]
+        );
+        assert_eq!(stronghold_rampart_hits(0), Some(0));
+        assert_eq!(stronghold_rampart_hits(5), Some(2_000_000));
+        assert_eq!(stronghold_rampart_hits(6), None);
+        for &level in &[0, 6] {
+            assert_eq!(invader_core_expand_time(level), None);
+        }
+        assert_eq!(invader_core_creep_spawn_time(6), None);
+        assert_eq!(INVADER_CORE_CONTROLLER_POWER, 2);
+        assert_eq!(INVADER_CORE_CONTROLLER_DOWNGRADE, 5000);
+    }
+
+    #[test]
+    fn primitive_conversions() {
+        assert_eq!(StructureType::from_u8(0), Some(StructureType::Spawn));
+        assert_eq!(StructureType::from_u8(20), Some(StructureType::InvaderCore));
+        assert_eq!(StructureType::from_u8(21), None);
+        assert_eq!(StructureType::from_u64(u64::from(u8::MAX) + 1), None);
+
+        assert_eq!(ResourceType::from_u16(0), None);
+        assert_eq!(ResourceType::from_u16(1), Some(Energy));
+        assert_eq!(ResourceType::from_u16(84), Some(Essence));
+        assert_eq!(ResourceType::from_u16(85), None);
+        assert_eq!(ResourceType::from_u16(1001), None);
+        for resource in ResourceType::iter_values() {
+            assert_eq!(ResourceType::from_u16(resource as u16), Some(resource));
+        }
+
+        let market = |n: u16| serde_json::from_value::<MarketResourceType>(n.into()).ok();
+        assert_eq!(market(0), None);
+        assert_eq!(market(84), Some(MarketResourceType::Resource(Essence)));
+        assert_eq!(market(85), None);
+        assert_eq!(
+            market(1001),
+            Some(MarketResourceType::IntershardResource(
+                IntershardResourceType::SubscriptionToken
+            ))
+        );
+        assert_eq!(market(1005), None);
     }
 }
@@ -0,0 +1,212 @@
+//! Diagnostics for the JavaScript references held by rust code.
+//!
+//! Every wrapper around a Screeps object (`Creep`, `Room`, `StructureSpawn`,
+//! ...) holds a [`stdweb::Reference`], which is an index into a table of
+//! JavaScript objects kept alive on behalf of rust.
+//!
+//! - Cloning a wrapper is cheap: it increments the refcount of the table entry
+//!   and does not copy the JavaScript object.
+//! - Dropping a wrapper decrements the refcount, and the table entry is
+//!   released once the last clone is dropped.
+//!
+//! Game objects are re-created by the server every tick, so a wrapper stored
+//! in a long-lived cache keeps a stale object alive and its table entry
+//! allocated for as long as the cache lives. [`reference_report`] can be used
+//! to find such leaks by looking at which types of objects accumulate over
+//! ticks, and with the `count-references` feature, `live_wrappers` counts
+//! the live wrappers of each type on the rust side.
+//!
+//! Dropping the cache holding the wrappers is the way to fix a leak. As a
+//! last resort, [`purge_references`] releases the stale objects themselves,
+//! leaving the wrappers still pointing at them unusable.
+use std::collections::HashMap;
+#[cfg(feature = "count-references")]
+use std::{
+    any::type_name, cell::RefCell, collections::BTreeMap, fmt,
+    marker::PhantomData as StdPhantomData,
+};
+
+use serde::Deserialize;
+
+use crate::traits::TryInto;
+
+/// Summary of the JavaScript references currently held by rust code.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceReport {
+    /// Number of distinct JavaScript objects referenced from rust.
+    pub distinct_objects: u32,
+    /// Number of live rust handles, counting each clone separately.
+    pub handles: u32,
+    /// Number of distinct JavaScript objects referenced from rust, keyed by
+    /// the name of their JavaScript constructor (`"Creep"`, `"Room"`,
+    /// `"Object"`, ...).
+    pub by_type: HashMap<String, u32>,
+}
+
+js_deserializable!(ReferenceReport);
+
+/// Counts the JavaScript references currently held by rust code.
+///
+/// This walks the whole reference table, so it's best used occasionally, for
+/// instance once every few hundred ticks, and compared between calls.
+pub fn reference_report() -> ReferenceReport {
+    (js! {
+        const runtime = Module.STDWEB_PRIVATE;
+        const report = { distinctObjects: 0, handles: 0, byType: {} };
+        for (const id in runtime.id_to_ref_map) {
+            const reference = runtime.id_to_ref_map[id];
+            const name = (reference !== null && reference !== undefined && reference.constructor)
+                ? reference.constructor.name
+                : String(reference);
+            report.distinctObjects += 1;
+            report.handles += runtime.id_to_refcount_map[id];
+            report.byType[name] = (report.byType[name] || 0) + 1;
+        }
+        return report;
+    })
+    .try_into()
+    .expect("expected reference report to be a known format")
+}
+
+/// Releases the stale game objects referenced from rust, those from previous
+/// ticks which the game has since replaced, returning how many were released.
+///
+/// The table entries of the wrappers referencing them are kept, so the
+/// wrappers can still be dropped normally, but they point at an empty object
+/// from then on: any use of them other than dropping will panic. This frees
+/// the memory held by leaked objects, and should be followed by dropping the
+/// cache which leaked them.
+pub fn purge_references() -> u32 {
+    js_unwrap! {{
+        const runtime = Module.STDWEB_PRIVATE;
+        let purged = 0;
+        for (const id in runtime.id_to_ref_map) {
+            const object = runtime.id_to_ref_map[id];
+            let current = object;
+            if (object instanceof Room) {
+                current = Game.rooms[object.name];
+            } else if (object instanceof RoomObject) {
+                // flags are the only room objects without an id
+                current = object.id ? Game.getObjectById(object.id) : Game.flags[object.name];
+            }
+            if (current !== object) {
+                runtime.ref_to_id_map.delete(object);
+                runtime.ref_to_id_map_fallback.delete(object);
+                runtime.id_to_ref_map[id] = {};
+                purged += 1;
+            }
+        }
+        return purged;
+    }}
+}
+
+/// Counts the live instances of the wrapper `T`, with the `count-references`
+/// feature.
+///
+/// Every wrapper around a Screeps object holds one of these, counting up when
+/// it's created or cloned and down when it's dropped, so that
+/// [`live_wrappers`] can report them. It's named `PhantomData` as the
+/// `ReferenceType` derive only allows a second field by that name.
+#[cfg(feature = "count-references")]
+pub struct PhantomData<T: 'static>(StdPhantomData<fn() -> T>);
+
+#[cfg(feature = "count-references")]
+thread_local! {
+    static LIVE_WRAPPERS: RefCell<HashMap<&'static str, usize>> = RefCell::new(HashMap::new());
+}
+
+#[cfg(feature = "count-references")]
+impl<T: 'static> PhantomData<T> {
+    fn name() -> &'static str {
+        let name = type_name::<T>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    fn count(change: fn(&mut usize)) {
+        // ignore wrappers dropped after the counts during thread shutdown
+        let _ = LIVE_WRAPPERS
+            .try_with(|live| change(live.borrow_mut().entry(Self::name()).or_insert(0)));
+    }
+}
+
+#[cfg(feature = "count-references")]
+impl<T: 'static> Default for PhantomData<T> {
+    fn default() -> Self {
+        Self::count(|count| *count += 1);
+        PhantomData(StdPhantomData)
+    }
+}
+
+#[cfg(feature = "count-references")]
+impl<T: 'static> Clone for PhantomData<T> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "count-references")]
+impl<T: 'static> Drop for PhantomData<T> {
+    fn drop(&mut self) {
+        Self::count(|count| *count -= 1);
+    }
+}
+
+#[cfg(feature = "count-references")]
+impl<T: 'static> fmt::Debug for PhantomData<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PhantomData<{}>", Self::name())
+    }
+}
+
+/// The number of live wrappers of each type, such as `"Creep"`, counting each
+/// clone separately, with the `count-references` feature.
+///
+/// Types whose wrappers were all dropped are counted as 0.
+#[cfg(feature = "count-references")]
+pub fn live_wrappers() -> BTreeMap<&'static str, usize> {
+    LIVE_WRAPPERS.with(|live| {
+        live.borrow()
+            .iter()
+            .map(|(&name, &count)| (name, count))
+            .collect()
+    })
+}
+
+#[cfg(all(test, feature = "count-references"))]
+mod test {
+    use super::{live_wrappers, PhantomData};
+    use crate::objects::{Creep, StructureSpawn};
+
+    fn live(name: &str) -> usize {
+        live_wrappers().get(name).copied().unwrap_or(0)
+    }
+
+    #[test]
+    fn counts_live_wrappers() {
+        // wrappers can't be created outside of JavaScript, so this uses the
+        // counters they hold, created and cloned along with them
+        assert_eq!(live("Creep"), 0);
+
+        let creep = PhantomData::<Creep>::default();
+        let spawns: Vec<PhantomData<StructureSpawn>> =
+            (0..3).map(|_| PhantomData::default()).collect();
+        assert_eq!(live("Creep"), 1);
+        assert_eq!(live("StructureSpawn"), 3);
+
+        let clone = creep.clone();
+        assert_eq!(live("Creep"), 2);
+        drop(creep);
+        assert_eq!(live("Creep"), 1);
+        drop(clone);
+        assert_eq!(live("Creep"), 0);
+        assert_eq!(live_wrappers().get("Creep"), Some(&0));
+
+        drop(spawns);
+        assert_eq!(live("StructureSpawn"), 0);
+        assert_eq!(
+            format!("{:?}", PhantomData::<Creep>::default()),
+            "PhantomData<Creep>"
+        );
+    }
+}
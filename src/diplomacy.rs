@@ -0,0 +1,455 @@
+//! Telling allies apart from hostile players.
+//!
+//! [`set_allies`] stores a list of allied usernames in the wasm heap, where
+//! it stays until changed or until the next global reset. The hostile
+//! helpers in this module, and other helpers taking an [`IgnoreAllies`] flag,
+//! consult the list at the time they are called, so changes take effect
+//! immediately.
+//!
+//! Note that the game's `HOSTILE_*` find constants know nothing about allies,
+//! and return every object not owned by the current player.
+use std::{cell::RefCell, collections::HashSet};
+
+use crate::{
+    constants::{find, Part, PowerType},
+    game,
+    objects::{
+        Creep, OwnedStructure, OwnedStructureProperties, PowerCreep, Room, SharedCreepProperties,
+    },
+};
+
+thread_local! {
+    static ALLIES: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Replaces the list of allied usernames.
+pub fn set_allies(usernames: &[&str]) {
+    ALLIES.with(|allies| {
+        let mut allies = allies.borrow_mut();
+        allies.clear();
+        allies.extend(usernames.iter().map(|&username| username.to_owned()));
+    });
+}
+
+/// The allied usernames, in no particular order.
+pub fn allies() -> Vec<String> {
+    ALLIES.with(|allies| allies.borrow().iter().cloned().collect())
+}
+
+/// Whether `username` is in the list of allies.
+pub fn is_ally(username: &str) -> bool {
+    ALLIES.with(|allies| allies.borrow().contains(username))
+}
+
+/// Whether objects owned by `username`, other than the current player,
+/// should be treated as hostile: true unless `username` is an ally.
+pub fn is_hostile_username(username: &str) -> bool {
+    !is_ally(username)
+}
+
+/// Whether `username` is the current player's, as found by
+/// [`game::my_username`].
+///
+/// Always false if the current player's username can't be determined.
+pub fn is_mine(username: &str) -> bool {
+    matches!(game::my_username(), Ok(me) if me == username)
+}
+
+/// A player owning or reserving a room, as seen by the current player.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RoomOwner {
+    /// The current player.
+    Me,
+    /// A player in the list of allies.
+    Ally(String),
+    /// Any other player, including the invaders.
+    Hostile(String),
+}
+
+impl RoomOwner {
+    /// Classifies `username`, given the current player's username if known.
+    pub fn classify(username: &str, my_username: Option<&str>) -> RoomOwner {
+        if my_username == Some(username) {
+            RoomOwner::Me
+        } else if is_ally(username) {
+            RoomOwner::Ally(username.to_owned())
+        } else {
+            RoomOwner::Hostile(username.to_owned())
+        }
+    }
+}
+
+/// Who controls a room, as returned by [`Room::ownership`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RoomOwnership {
+    /// The room has no controller, or its controller is neither owned nor
+    /// reserved.
+    Unowned,
+    /// The room's controller is owned.
+    Owned(RoomOwner),
+    /// The room's controller is reserved.
+    Reserved(RoomOwner),
+}
+
+impl RoomOwnership {
+    /// Classifies a room whose controller is owned by `owner` or reserved by
+    /// `reserved_by`, given the current player's username if known.
+    pub fn classify(
+        owner: Option<&str>,
+        reserved_by: Option<&str>,
+        my_username: Option<&str>,
+    ) -> RoomOwnership {
+        match (owner, reserved_by) {
+            (Some(owner), _) => RoomOwnership::Owned(RoomOwner::classify(owner, my_username)),
+            (None, Some(reserved_by)) => {
+                RoomOwnership::Reserved(RoomOwner::classify(reserved_by, my_username))
+            }
+            (None, None) => RoomOwnership::Unowned,
+        }
+    }
+
+    /// Whether the room is owned or reserved by the current player.
+    pub fn is_mine(&self) -> bool {
+        matches!(
+            self,
+            RoomOwnership::Owned(RoomOwner::Me) | RoomOwnership::Reserved(RoomOwner::Me)
+        )
+    }
+
+    /// Whether the room is owned or reserved by a hostile player.
+    pub fn is_hostile(&self) -> bool {
+        matches!(
+            self,
+            RoomOwnership::Owned(RoomOwner::Hostile(_))
+                | RoomOwnership::Reserved(RoomOwner::Hostile(_))
+        )
+    }
+}
+
+/// Whether helpers looking for hostile objects should leave out the objects
+/// of allies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IgnoreAllies {
+    /// Objects owned by allies are not hostile.
+    Yes,
+    /// Every object not owned by the current player is hostile.
+    No,
+}
+
+impl IgnoreAllies {
+    /// Whether an object owned by `username`, other than the current player,
+    /// counts as hostile.
+    pub fn is_hostile(self, username: &str) -> bool {
+        match self {
+            IgnoreAllies::Yes => is_hostile_username(username),
+            IgnoreAllies::No => true,
+        }
+    }
+}
+
+/// Creeps in `room` owned by other players, leaving out allies' creeps if
+/// `ignore_allies` is set.
+pub fn hostile_creeps(room: &Room, ignore_allies: IgnoreAllies) -> Vec<Creep> {
+    room.find(find::HOSTILE_CREEPS)
+        .into_iter()
+        .filter(|creep| ignore_allies.is_hostile(&creep.owner_name()))
+        .collect()
+}
+
+/// Power creeps in `room` owned by other players, leaving out allies' power
+/// creeps if `ignore_allies` is set.
+pub fn hostile_power_creeps(room: &Room, ignore_allies: IgnoreAllies) -> Vec<PowerCreep> {
+    room.find(find::HOSTILE_POWER_CREEPS)
+        .into_iter()
+        .filter(|creep| ignore_allies.is_hostile(&creep.owner_name()))
+        .collect()
+}
+
+/// Structures in `room` owned by other players, leaving out allies'
+/// structures if `ignore_allies` is set.
+pub fn hostile_structures(room: &Room, ignore_allies: IgnoreAllies) -> Vec<OwnedStructure> {
+    room.find(find::HOSTILE_STRUCTURES)
+        .into_iter()
+        .filter(|structure| match structure.owner_name() {
+            Some(owner) => ignore_allies.is_hostile(&owner),
+            None => false,
+        })
+        .collect()
+}
+
+/// How dangerous a power is when used against the current player by a
+/// hostile power creep.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PowerThreat {
+    /// The power doesn't affect the current player's rooms.
+    None,
+    /// The power hampers the current player's economy or makes hostile
+    /// creeps harder to kill.
+    Low,
+    /// The power disables defenses or spawning, and should be dealt with
+    /// first.
+    High,
+}
+
+impl Default for PowerThreat {
+    fn default() -> Self {
+        PowerThreat::None
+    }
+}
+
+impl PowerThreat {
+    /// The threat posed by a hostile power creep having `power`.
+    pub fn of(power: PowerType) -> PowerThreat {
+        match power {
+            PowerType::DisruptSpawn | PowerType::DisruptTower => PowerThreat::High,
+            PowerType::DisruptTerminal | PowerType::Shield => PowerThreat::Low,
+            _ => PowerThreat::None,
+        }
+    }
+}
+
+/// Combat parts of the hostile creeps in a room, and powers of its hostile
+/// power creeps, as counted by [`HostileSummary::of_room`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostileSummary {
+    /// Number of hostile creeps.
+    pub creeps: u32,
+    /// Active attack parts across all hostile creeps.
+    pub attack: u32,
+    /// Active ranged attack parts across all hostile creeps.
+    pub ranged_attack: u32,
+    /// Active heal parts across all hostile creeps.
+    pub heal: u32,
+    /// Active work parts across all hostile creeps, able to dismantle.
+    pub work: u32,
+    /// Number of hostile power creeps.
+    pub power_creeps: u32,
+    /// The highest threat among the powers of all hostile power creeps.
+    pub power_threat: PowerThreat,
+    /// Owners of the hostile creeps and power creeps, without duplicates.
+    pub owners: Vec<String>,
+}
+
+impl HostileSummary {
+    /// Counts the active parts of the creeps, and the powers of the power
+    /// creeps, in `room` owned by other players.
+    pub fn of_room(room: &Room, ignore_allies: IgnoreAllies) -> Self {
+        let mut summary = HostileSummary::default();
+        for creep in room.find(find::HOSTILE_CREEPS) {
+            let body = creep.body();
+            let active = body
+                .iter()
+                .filter(|part| part.hits > 0)
+                .map(|part| part.part);
+            summary.add(&creep.owner_name(), active, ignore_allies);
+        }
+        for power_creep in room.find(find::HOSTILE_POWER_CREEPS) {
+            summary.add_power_creep(
+                &power_creep.owner_name(),
+                power_creep.power_keys(),
+                ignore_allies,
+            );
+        }
+        summary
+    }
+
+    /// Adds a creep owned by `owner` with the given active parts, unless it
+    /// belongs to an ally and `ignore_allies` is set.
+    pub fn add<I>(&mut self, owner: &str, active_parts: I, ignore_allies: IgnoreAllies)
+    where
+        I: IntoIterator<Item = Part>,
+    {
+        if !ignore_allies.is_hostile(owner) {
+            return;
+        }
+        self.creeps += 1;
+        for part in active_parts {
+            match part {
+                Part::Attack => self.attack += 1,
+                Part::RangedAttack => self.ranged_attack += 1,
+                Part::Heal => self.heal += 1,
+                Part::Work => self.work += 1,
+                _ => (),
+            }
+        }
+        self.add_owner(owner);
+    }
+
+    /// Adds a power creep owned by `owner` with the given powers, unless it
+    /// belongs to an ally and `ignore_allies` is set.
+    pub fn add_power_creep<I>(&mut self, owner: &str, powers: I, ignore_allies: IgnoreAllies)
+    where
+        I: IntoIterator<Item = PowerType>,
+    {
+        if !ignore_allies.is_hostile(owner) {
+            return;
+        }
+        self.power_creeps += 1;
+        for power in powers {
+            self.power_threat = self.power_threat.max(PowerThreat::of(power));
+        }
+        self.add_owner(owner);
+    }
+
+    fn add_owner(&mut self, owner: &str) {
+        if !self.owners.iter().any(|known| known == owner) {
+            self.owners.push(owner.to_owned());
+        }
+    }
+
+    /// Whether any hostile creep has parts able to attack, heal or dismantle,
+    /// or any hostile power creep has a power threatening the room.
+    pub fn is_threat(&self) -> bool {
+        self.attack + self.ranged_attack + self.heal + self.work > 0
+            || self.power_threat > PowerThreat::None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        allies, is_ally, is_hostile_username, set_allies, HostileSummary, IgnoreAllies,
+        PowerThreat, RoomOwner, RoomOwnership,
+    };
+    use crate::{
+        constants::{find, FindConstant, Part::*, PowerType},
+        objects::PowerCreep,
+    };
+
+    #[test]
+    fn ally_list() {
+        assert!(!is_ally("Alice"));
+        assert!(is_hostile_username("Alice"));
+
+        set_allies(&["Alice", "Bob", "Alice"]);
+        assert!(is_ally("Alice"));
+        assert!(is_ally("Bob"));
+        assert!(!is_ally("alice"));
+        assert!(!is_hostile_username("Bob"));
+        assert!(is_hostile_username("Mallory"));
+        let mut names = allies();
+        names.sort();
+        assert_eq!(names, vec!["Alice", "Bob"]);
+
+        assert!(IgnoreAllies::No.is_hostile("Bob"));
+        assert!(!IgnoreAllies::Yes.is_hostile("Bob"));
+
+        // replacing the list takes effect right away
+        set_allies(&["Mallory"]);
+        assert!(!is_ally("Alice"));
+        assert!(is_ally("Mallory"));
+        set_allies(&[]);
+        assert!(allies().is_empty());
+    }
+
+    #[test]
+    fn hostile_summary() {
+        set_allies(&["Bob"]);
+        let creeps = [
+            ("Mallory", vec![Attack, Attack, Move, Move]),
+            ("Bob", vec![RangedAttack, Heal, Move]),
+            ("Mallory", vec![Work, Carry, Move]),
+            ("Eve", vec![Move]),
+        ];
+        let summarize = |ignore_allies| {
+            let mut summary = HostileSummary::default();
+            for (owner, parts) in &creeps {
+                summary.add(owner, parts.iter().copied(), ignore_allies);
+            }
+            summary
+        };
+
+        let without_allies = summarize(IgnoreAllies::Yes);
+        assert_eq!(
+            without_allies,
+            HostileSummary {
+                creeps: 3,
+                attack: 2,
+                ranged_attack: 0,
+                heal: 0,
+                work: 1,
+                power_creeps: 0,
+                power_threat: PowerThreat::None,
+                owners: vec!["Mallory".to_owned(), "Eve".to_owned()],
+            }
+        );
+        assert!(without_allies.is_threat());
+
+        let everyone = summarize(IgnoreAllies::No);
+        assert_eq!(everyone.creeps, 4);
+        assert_eq!(everyone.ranged_attack, 1);
+        assert_eq!(everyone.heal, 1);
+        assert_eq!(everyone.owners.len(), 3);
+
+        // only unarmed creeps are left once Mallory is an ally too
+        set_allies(&["Bob", "Mallory"]);
+        let summary = summarize(IgnoreAllies::Yes);
+        assert_eq!(summary.creeps, 1);
+        assert!(!summary.is_threat());
+    }
+
+    #[test]
+    fn power_creep_threats() {
+        fn finds_power_creeps<T: FindConstant<Item = PowerCreep>>(_: T) {}
+        finds_power_creeps(find::MY_POWER_CREEPS);
+        finds_power_creeps(find::HOSTILE_POWER_CREEPS);
+
+        set_allies(&["Bob"]);
+        let mut summary = HostileSummary::default();
+        summary.add_power_creep(
+            "Mallory",
+            vec![PowerType::GenerateOps, PowerType::OperateSpawn],
+            IgnoreAllies::Yes,
+        );
+        assert_eq!(summary.power_creeps, 1);
+        assert_eq!(summary.power_threat, PowerThreat::None);
+        assert!(!summary.is_threat());
+
+        summary.add_power_creep("Mallory", vec![PowerType::Shield], IgnoreAllies::Yes);
+        assert_eq!(summary.power_threat, PowerThreat::Low);
+        assert!(summary.is_threat());
+
+        // an ally's disruptive power creep is left out
+        summary.add_power_creep("Bob", vec![PowerType::DisruptTower], IgnoreAllies::Yes);
+        assert_eq!(summary.power_threat, PowerThreat::Low);
+        summary.add_power_creep(
+            "Eve",
+            vec![PowerType::DisruptSpawn, PowerType::Shield],
+            IgnoreAllies::Yes,
+        );
+        assert_eq!(summary.power_creeps, 3);
+        assert_eq!(summary.power_threat, PowerThreat::High);
+        assert_eq!(summary.owners, vec!["Mallory", "Eve"]);
+        assert_eq!(summary.creeps, 0);
+        set_allies(&[]);
+    }
+
+    #[test]
+    fn room_ownership() {
+        set_allies(&["Bob"]);
+        let me = Some("Alice");
+        assert_eq!(
+            RoomOwnership::classify(Some("Alice"), None, me),
+            RoomOwnership::Owned(RoomOwner::Me)
+        );
+        assert_eq!(
+            RoomOwnership::classify(None, Some("Bob"), me),
+            RoomOwnership::Reserved(RoomOwner::Ally("Bob".to_owned()))
+        );
+        let invaders = RoomOwnership::classify(None, Some("Invader"), me);
+        assert!(invaders.is_hostile());
+        assert!(!invaders.is_mine());
+        assert!(RoomOwnership::classify(None, Some("Alice"), me).is_mine());
+        assert_eq!(
+            RoomOwnership::classify(None, None, me),
+            RoomOwnership::Unowned
+        );
+
+        // without a known username, nothing is ours
+        assert_eq!(
+            RoomOwnership::classify(Some("Alice"), None, None),
+            RoomOwnership::Owned(RoomOwner::Hostile("Alice".to_owned()))
+        );
+        set_allies(&[]);
+    }
+}
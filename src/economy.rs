@@ -0,0 +1,330 @@
+//! Accounting for the income and expenses of remote mining rooms.
+//!
+//! A [`RemoteLedger`] records the energy brought home from each remote room,
+//! and the energy spent on it: spawning its creeps, reserving its controller
+//! and repairing its roads and containers. It reports each remote's profit
+//! per tick over a rolling window, answering whether a remote is worth
+//! keeping.
+//!
+//! Transactions are summed into buckets of a fixed number of ticks, so the
+//! ledger stays small enough to persist in a segment with a
+//! [`SegmentBackend`][crate::memory::backend::SegmentBackend]:
+//!
+//! ```no_run
+//! use screeps::{
+//!     economy::RemoteLedger,
+//!     memory::backend::{self, MemoryBackend, SegmentBackend},
+//! };
+//!
+//! let mut segment = SegmentBackend::new(10);
+//! // an inactive segment reads as nothing stored, and storing to it would
+//! // replace the real ledger with a fresh one
+//! if segment.is_available() {
+//!     let mut ledger: RemoteLedger = backend::load(&mut segment)
+//!         .ok()
+//!         .flatten()
+//!         .unwrap_or_default();
+//!     // record this tick's transactions...
+//!     backend::store(&mut segment, &ledger).unwrap();
+//! }
+//! ```
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{constants::Part, local::RoomName};
+
+/// Energy moved in and out of a remote during one bucket of ticks.
+///
+/// Serialized as an array of the bucket's index followed by each amount, to
+/// keep persisted ledgers small.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Bucket(u32, u32, u32, u32, u32);
+
+/// Transactions recorded for a single remote.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct RemoteAccount {
+    /// The tick of the first transaction recorded for the remote.
+    #[serde(rename = "f")]
+    first: u32,
+    /// Buckets within the window, oldest first.
+    #[serde(rename = "b")]
+    buckets: Vec<Bucket>,
+}
+
+/// Energy income and expenses of a remote over a window of ticks, as
+/// reported by [`RemoteLedger::summary`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemoteSummary {
+    /// Energy brought home from the remote.
+    pub income: u32,
+    /// Energy spent spawning the remote's creeps, other than claim parts.
+    pub spawn: u32,
+    /// Energy spent on claim parts reserving the remote's controller.
+    pub reservation: u32,
+    /// Energy spent repairing the remote's roads and containers.
+    pub repair: u32,
+    /// Number of ticks covered by the summary.
+    pub ticks: u32,
+}
+
+impl RemoteSummary {
+    /// Total energy spent on the remote.
+    pub fn expenses(&self) -> u32 {
+        self.spawn + self.reservation + self.repair
+    }
+
+    /// Energy brought home minus energy spent.
+    pub fn profit(&self) -> i64 {
+        i64::from(self.income) - i64::from(self.expenses())
+    }
+
+    /// Average profit per tick over the covered ticks.
+    pub fn profit_per_tick(&self) -> f64 {
+        if self.ticks == 0 {
+            0.0
+        } else {
+            self.profit() as f64 / f64::from(self.ticks)
+        }
+    }
+}
+
+/// Rolling energy accounts for remote mining rooms.
+///
+/// Each transaction is recorded with the game tick it happened on, typically
+/// [`game::time`][crate::game::time]. Transactions older than the window,
+/// 1500 ticks by default, are dropped as new ones are recorded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteLedger {
+    #[serde(rename = "t")]
+    bucket_ticks: u32,
+    #[serde(rename = "n")]
+    bucket_count: u32,
+    #[serde(rename = "r")]
+    remotes: HashMap<RoomName, RemoteAccount>,
+}
+
+impl Default for RemoteLedger {
+    fn default() -> Self {
+        RemoteLedger {
+            bucket_ticks: 100,
+            bucket_count: 15,
+            remotes: HashMap::new(),
+        }
+    }
+}
+
+impl RemoteLedger {
+    /// Creates an empty ledger with a window of 1500 ticks, the lifetime of
+    /// a creep, in buckets of 100 ticks.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the window to `bucket_count` buckets of `bucket_ticks` ticks
+    /// each - default 15 buckets of 100 ticks.
+    ///
+    /// Smaller buckets make the window roll more smoothly, at the cost of a
+    /// larger ledger. Should be set before recording any transactions.
+    pub fn window(mut self, bucket_ticks: u32, bucket_count: u32) -> Self {
+        self.bucket_ticks = bucket_ticks.max(1);
+        self.bucket_count = bucket_count.max(1);
+        self
+    }
+
+    /// Records energy brought home from `remote`.
+    pub fn record_income(&mut self, remote: RoomName, energy: u32, time: u32) {
+        self.bucket(remote, time).1 += energy;
+    }
+
+    /// Records a creep spawned for `remote`, meant to be called when
+    /// spawning it.
+    ///
+    /// The cost of claim parts counts as reservation upkeep, and the rest of
+    /// the body as spawn energy.
+    pub fn record_spawn(&mut self, remote: RoomName, body: &[Part], time: u32) {
+        let (reservation, spawn) = body.iter().fold((0, 0), |(claim, other), &part| {
+            if part == Part::Claim {
+                (claim + part.cost(), other)
+            } else {
+                (claim, other + part.cost())
+            }
+        });
+        let bucket = self.bucket(remote, time);
+        bucket.2 += spawn;
+        bucket.3 += reservation;
+    }
+
+    /// Records energy spent repairing roads and containers in `remote`.
+    pub fn record_repair(&mut self, remote: RoomName, energy: u32, time: u32) {
+        self.bucket(remote, time).4 += energy;
+    }
+
+    /// Sums the transactions of `remote` within the window ending at `time`,
+    /// or returns `None` if nothing was recorded for it.
+    ///
+    /// The window starts at the first recorded transaction if that is more
+    /// recent, so new remotes aren't judged on ticks before they were
+    /// started.
+    pub fn summary(&self, remote: RoomName, time: u32) -> Option<RemoteSummary> {
+        let account = self.remotes.get(&remote)?;
+        let oldest = self.oldest_bucket(time);
+        let start = (oldest * self.bucket_ticks).max(account.first);
+
+        let mut summary = RemoteSummary {
+            ticks: (time + 1).saturating_sub(start),
+            ..RemoteSummary::default()
+        };
+        for bucket in &account.buckets {
+            if bucket.0 >= oldest && bucket.0 * self.bucket_ticks <= time {
+                summary.income += bucket.1;
+                summary.spawn += bucket.2;
+                summary.reservation += bucket.3;
+                summary.repair += bucket.4;
+            }
+        }
+        Some(summary)
+    }
+
+    /// Average profit per tick of `remote` within the window ending at
+    /// `time`, or `None` if nothing was recorded for it.
+    pub fn profit_per_tick(&self, remote: RoomName, time: u32) -> Option<f64> {
+        self.summary(remote, time)
+            .map(|summary| summary.profit_per_tick())
+    }
+
+    /// Iterates over the remotes with recorded transactions.
+    pub fn remotes(&self) -> impl Iterator<Item = RoomName> + '_ {
+        self.remotes.keys().copied()
+    }
+
+    /// Forgets the transactions of `remote`, such as when it's abandoned.
+    pub fn remove(&mut self, remote: RoomName) {
+        self.remotes.remove(&remote);
+    }
+
+    /// Index of the oldest bucket still within the window ending at `time`.
+    fn oldest_bucket(&self, time: u32) -> u32 {
+        (time / self.bucket_ticks).saturating_sub(self.bucket_count - 1)
+    }
+
+    /// Finds or creates the bucket of `remote` holding `time`, dropping
+    /// buckets which fell out of the window.
+    fn bucket(&mut self, remote: RoomName, time: u32) -> &mut Bucket {
+        let oldest = self.oldest_bucket(time);
+        let index = time / self.bucket_ticks;
+        let account = self.remotes.entry(remote).or_insert_with(|| RemoteAccount {
+            first: time,
+            buckets: Vec::new(),
+        });
+        account.first = account.first.min(time);
+        account.buckets.retain(|bucket| bucket.0 >= oldest);
+
+        let position = match account.buckets.iter().position(|bucket| bucket.0 >= index) {
+            Some(position) if account.buckets[position].0 == index => position,
+            Some(position) => {
+                account.buckets.insert(position, Bucket(index, 0, 0, 0, 0));
+                position
+            }
+            None => {
+                account.buckets.push(Bucket(index, 0, 0, 0, 0));
+                account.buckets.len() - 1
+            }
+        };
+        &mut account.buckets[position]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RemoteLedger, RemoteSummary};
+    use crate::{constants::Part::*, local::RoomName};
+
+    fn room(name: &str) -> RoomName {
+        RoomName::new(name).unwrap()
+    }
+
+    #[test]
+    fn remote_accounting() {
+        let remote = room("W2N1");
+        let mut ledger = RemoteLedger::new();
+        assert_eq!(ledger.summary(remote, 1000), None);
+
+        // a miner, a hauler and a reserver spawned as the remote starts
+        ledger.record_spawn(
+            remote,
+            &[Work, Work, Work, Work, Work, Move, Move, Move],
+            1000,
+        );
+        ledger.record_spawn(remote, &[Carry, Carry, Carry, Carry, Move, Move], 1000);
+        ledger.record_spawn(remote, &[Claim, Claim, Move, Move], 1010);
+        ledger.record_repair(remote, 150, 1150);
+        for tick in (1100..1200).step_by(20) {
+            ledger.record_income(remote, 200, tick);
+        }
+
+        let summary = ledger.summary(remote, 1199).unwrap();
+        assert_eq!(
+            summary,
+            RemoteSummary {
+                income: 1000,
+                spawn: 650 + 300 + 100,
+                reservation: 1200,
+                repair: 150,
+                ticks: 200,
+            }
+        );
+        assert_eq!(summary.expenses(), 2400);
+        assert_eq!(summary.profit(), -1400);
+        assert_eq!(ledger.profit_per_tick(remote, 1199), Some(-7.0));
+        assert_eq!(ledger.remotes().collect::<Vec<_>>(), vec![remote]);
+
+        ledger.remove(remote);
+        assert_eq!(ledger.profit_per_tick(remote, 1199), None);
+    }
+
+    #[test]
+    fn rolling_window() {
+        let remote = room("E5S5");
+        let mut ledger = RemoteLedger::new().window(10, 3);
+
+        ledger.record_spawn(remote, &[Work, Move], 0);
+        for tick in 0..30 {
+            ledger.record_income(remote, 1, tick);
+        }
+        // the spawn cost is within the window covering ticks 0 to 29
+        assert_eq!(ledger.summary(remote, 29).unwrap().profit(), 30 - 150);
+
+        for tick in 30..60 {
+            ledger.record_income(remote, 1, tick);
+        }
+        // once it's out, the window covers 30 ticks of income only
+        let summary = ledger.summary(remote, 59).unwrap();
+        assert_eq!(summary.spawn, 0);
+        assert_eq!(summary.ticks, 30);
+        assert_eq!(summary.profit_per_tick(), 1.0);
+        // in the middle of a bucket, only the elapsed part of it counts
+        assert_eq!(ledger.summary(remote, 44).unwrap().ticks, 25);
+
+        // transactions reported late still count towards the bucket of their tick
+        ledger.record_repair(remote, 10, 45);
+        assert_eq!(ledger.summary(remote, 59).unwrap().repair, 10);
+    }
+
+    #[test]
+    fn compact_persistence() {
+        let remote = room("W2N1");
+        let mut ledger = RemoteLedger::new();
+        ledger.record_income(remote, 500, 1234);
+        ledger.record_spawn(remote, &[Claim, Move], 1300);
+
+        let json = serde_json::to_string(&ledger).unwrap();
+        assert_eq!(
+            json,
+            r#"{"t":100,"n":15,"r":{"W2N1":{"f":1234,"b":[[12,500,0,0,0],[13,0,50,600,0]]}}}"#
+        );
+        let restored: RemoteLedger = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, ledger);
+    }
+}
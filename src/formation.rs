@@ -0,0 +1,598 @@
+//! Moving four creeps together as a 2x2 quad.
+//!
+//! A quad is tracked by its anchor, the top left tile of the 2x2 square it
+//! occupies. [`QuadPath::search`] finds a path for the anchor over a cost
+//! matrix where each tile costs as much as the most expensive tile of the
+//! square anchored there, so the whole quad fits along the path. [`Quad`]
+//! then moves the four creeps along that path, keeping them in formation and
+//! turning them to face the direction of travel.
+//!
+//! Paths are limited to a single room, and never come within one tile of the
+//! room's exits.
+use crate::{
+    constants::{Direction, Terrain},
+    game,
+    local::{Position, RoomName},
+    objects::{Creep, HasPosition, SharedCreepProperties},
+    pathfinder::{self, LocalCostMatrix, MultiRoomCostResult, SearchOptions},
+};
+
+/// Cost of an impassable tile in a cost matrix.
+const IMPASSABLE: u8 = 255;
+
+/// Builds a cost matrix from a room's terrain, with walls impassable.
+///
+/// `terrain` returns the terrain at the given `(x, y)` coordinates.
+pub fn terrain_matrix<F>(terrain: F, plain_cost: u8, swamp_cost: u8) -> LocalCostMatrix
+where
+    F: Fn(u8, u8) -> Terrain,
+{
+    let mut matrix = LocalCostMatrix::new();
+    for x in 0..50 {
+        for y in 0..50 {
+            let cost = match terrain(x, y) {
+                Terrain::Plain => plain_cost,
+                Terrain::Swamp => swamp_cost,
+                Terrain::Wall => IMPASSABLE,
+            };
+            matrix.set(x, y, cost);
+        }
+    }
+    matrix
+}
+
+/// Turns a cost matrix for single creeps into a cost matrix for quad anchors.
+///
+/// Each anchor tile costs as much as the most expensive tile of the 2x2
+/// square it anchors, and is impassable if any of them is. Anchors next to or
+/// on the room's edges are impassable, so the quad never steps onto an exit.
+pub fn dilate(matrix: &LocalCostMatrix) -> LocalCostMatrix {
+    let mut dilated = LocalCostMatrix::new();
+    for x in 0..50 {
+        for y in 0..50 {
+            let cost = if x == 0 || y == 0 || x >= 48 || y >= 48 {
+                IMPASSABLE
+            } else {
+                matrix
+                    .get(x, y)
+                    .max(matrix.get(x + 1, y))
+                    .max(matrix.get(x, y + 1))
+                    .max(matrix.get(x + 1, y + 1))
+            };
+            dilated.set(x, y, cost);
+        }
+    }
+    dilated
+}
+
+/// Options for [`QuadPath::search`].
+pub struct QuadSearchOptions<F>
+where
+    F: FnMut(RoomName, &mut LocalCostMatrix),
+{
+    range: u32,
+    plain_cost: u8,
+    swamp_cost: u8,
+    max_ops: u32,
+    obstacles: F,
+}
+
+impl Default for QuadSearchOptions<fn(RoomName, &mut LocalCostMatrix)> {
+    fn default() -> Self {
+        fn no_obstacles(_: RoomName, _: &mut LocalCostMatrix) {}
+
+        QuadSearchOptions {
+            range: 0,
+            plain_cost: 1,
+            swamp_cost: 5,
+            max_ops: 2000,
+            obstacles: no_obstacles,
+        }
+    }
+}
+
+impl QuadSearchOptions<fn(RoomName, &mut LocalCostMatrix)> {
+    /// Creates default options: reaching the goal exactly, with plains costing
+    /// 1 and swamps costing 5.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F> QuadSearchOptions<F>
+where
+    F: FnMut(RoomName, &mut LocalCostMatrix),
+{
+    /// Sets a callback marking obstacles such as structures and creeps, called
+    /// with the terrain cost matrix before it's dilated.
+    pub fn obstacles<F2>(self, obstacles: F2) -> QuadSearchOptions<F2>
+    where
+        F2: FnMut(RoomName, &mut LocalCostMatrix),
+    {
+        QuadSearchOptions {
+            range: self.range,
+            plain_cost: self.plain_cost,
+            swamp_cost: self.swamp_cost,
+            max_ops: self.max_ops,
+            obstacles,
+        }
+    }
+
+    /// Sets the range from the goal at which the anchor stops - default 0.
+    #[inline]
+    pub fn range(mut self, range: u32) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Sets plain cost - default 1.
+    #[inline]
+    pub fn plain_cost(mut self, cost: u8) -> Self {
+        self.plain_cost = cost;
+        self
+    }
+
+    /// Sets swamp cost - default 5.
+    #[inline]
+    pub fn swamp_cost(mut self, cost: u8) -> Self {
+        self.swamp_cost = cost;
+        self
+    }
+
+    /// Sets maximum ops - default 2000.
+    #[inline]
+    pub fn max_ops(mut self, ops: u32) -> Self {
+        self.max_ops = ops;
+        self
+    }
+}
+
+/// A path for a quad's anchor, followed by [`Quad::step`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuadPath {
+    anchors: Vec<Position>,
+    index: usize,
+    incomplete: bool,
+}
+
+impl QuadPath {
+    /// Creates a path from a list of consecutive anchor positions, starting
+    /// with the quad's current anchor.
+    pub fn new(anchors: Vec<Position>) -> Self {
+        QuadPath {
+            anchors,
+            index: 0,
+            incomplete: false,
+        }
+    }
+
+    /// Searches a path for a quad anchored at `origin` to `goal`, both in the
+    /// same room.
+    ///
+    /// If no full path is found, the path leads as close to the goal as
+    /// possible and [`QuadPath::incomplete`] returns true.
+    pub fn search<F>(origin: Position, goal: Position, opts: QuadSearchOptions<F>) -> Self
+    where
+        F: FnMut(RoomName, &mut LocalCostMatrix),
+    {
+        let QuadSearchOptions {
+            range,
+            plain_cost,
+            swamp_cost,
+            max_ops,
+            mut obstacles,
+        } = opts;
+        let room = origin.room_name();
+
+        let terrain = game::map::get_room_terrain(room).get_local();
+        let mut matrix = terrain_matrix(
+            |x, y| terrain.get(u32::from(x), u32::from(y)),
+            plain_cost,
+            swamp_cost,
+        );
+        obstacles(room, &mut matrix);
+        let dilated = dilate(&matrix);
+
+        let search_opts = SearchOptions::new()
+            .room_callback(move |name| {
+                if name == room {
+                    dilated.upload().into()
+                } else {
+                    MultiRoomCostResult::Impassable
+                }
+            })
+            .plain_cost(plain_cost)
+            .swamp_cost(swamp_cost)
+            .max_ops(max_ops)
+            .max_rooms(1);
+        let result = pathfinder::search(&origin, &goal, range, search_opts);
+
+        let mut anchors = vec![origin];
+        anchors.extend(result.load_local_path());
+        QuadPath {
+            anchors,
+            index: 0,
+            incomplete: result.incomplete,
+        }
+    }
+
+    /// All anchor positions of the path, including the starting one.
+    pub fn anchors(&self) -> &[Position] {
+        &self.anchors
+    }
+
+    /// The position the quad's anchor should currently be at, or `None` if
+    /// the path is empty.
+    pub fn current(&self) -> Option<Position> {
+        self.anchors.get(self.index).copied()
+    }
+
+    /// The direction from the current anchor position to the next one, or
+    /// `None` if the end of the path has been reached.
+    pub fn next_direction(&self) -> Option<Direction> {
+        let current = self.anchors.get(self.index)?;
+        let next = self.anchors.get(self.index + 1)?;
+        current.get_direction_to(next)
+    }
+
+    /// Moves on to the next anchor position.
+    pub fn advance(&mut self) {
+        if self.index + 1 < self.anchors.len() {
+            self.index += 1;
+        }
+    }
+
+    /// Whether the end of the path has been reached.
+    pub fn is_finished(&self) -> bool {
+        self.index + 1 >= self.anchors.len()
+    }
+
+    /// Whether the search ran out of operations or couldn't reach the goal.
+    pub fn incomplete(&self) -> bool {
+        self.incomplete
+    }
+}
+
+/// The moves making up a single tick of quad movement, as planned by
+/// [`Quad::plan`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QuadStep {
+    /// The quad's facing after the moves.
+    pub facing: Direction,
+    /// The direction each creep moves in, in the same order as the creeps
+    /// passed to [`Quad::step`]. `None` for creeps staying put.
+    pub moves: [Option<Direction>; 4],
+    /// Whether the quad's anchor moves along the path. False when the quad is
+    /// rotating in place.
+    pub advances: bool,
+}
+
+/// Outcome of [`Quad::step`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QuadStatus {
+    /// All creeps moved along the path.
+    Moved,
+    /// The creeps swapped places to face the direction of travel.
+    Rotated,
+    /// At least one creep is fatigued; nobody moved so the quad stays
+    /// together.
+    Waiting,
+    /// At least one creep was out of formation and is moving back into it.
+    Regrouping,
+    /// The end of the path has been reached.
+    Arrived,
+}
+
+/// Formation of four creeps moving as a 2x2 square.
+///
+/// The creeps passed to [`Quad::step`] are, in order, the front left, front
+/// right, back left and back right members of the quad, relative to the
+/// direction it faces. When the path turns, the quad first rotates in place
+/// so its front creeps lead again, which keeps attackers or healers
+/// positioned at the front.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Quad {
+    facing: Direction,
+    rotate: bool,
+}
+
+impl Quad {
+    /// Creates a quad facing the given direction, rotating when the path
+    /// turns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `facing` isn't one of `Top`, `Right`, `Bottom` or `Left`.
+    pub fn new(facing: Direction) -> Self {
+        assert!(
+            is_cardinal(facing),
+            "quads can only face cardinal directions"
+        );
+        Quad {
+            facing,
+            rotate: true,
+        }
+    }
+
+    /// Sets whether the quad rotates to face the direction of travel - default
+    /// true. Quads which don't rotate keep their initial facing.
+    #[inline]
+    pub fn rotate(mut self, rotate: bool) -> Self {
+        self.rotate = rotate;
+        self
+    }
+
+    /// The direction the quad faces.
+    #[inline]
+    pub fn facing(&self) -> Direction {
+        self.facing
+    }
+
+    /// The offset of each creep's tile from the anchor, in the same order as
+    /// the creeps passed to [`Quad::step`].
+    pub fn slot_offsets(&self) -> [(i32, i32); 4] {
+        slot_offsets(self.facing)
+    }
+
+    /// Plans the moves taking the quad one step in `direction`.
+    ///
+    /// If `direction` is a cardinal direction other than the one the quad
+    /// faces, the quad rotates in place instead. Diagonal steps move the quad
+    /// without changing its facing.
+    pub fn plan(&self, direction: Direction) -> QuadStep {
+        if self.rotate && is_cardinal(direction) && direction != self.facing {
+            let from = slot_offsets(self.facing);
+            let to = slot_offsets(direction);
+            let mut moves = [None; 4];
+            for (i, slot) in moves.iter_mut().enumerate() {
+                *slot = direction_of(to[i].0 - from[i].0, to[i].1 - from[i].1);
+            }
+            QuadStep {
+                facing: direction,
+                moves,
+                advances: false,
+            }
+        } else {
+            QuadStep {
+                facing: self.facing,
+                moves: [Some(direction); 4],
+                advances: true,
+            }
+        }
+    }
+
+    /// Moves the creeps one step along `path`.
+    ///
+    /// Creeps out of formation are first moved back to their tile around the
+    /// current anchor, and nobody moves while any creep is fatigued. The path
+    /// only advances once the step is issued, so moves blocked by other
+    /// creeps show up as regrouping on the next tick.
+    pub fn step(&mut self, creeps: [&Creep; 4], path: &mut QuadPath) -> QuadStatus {
+        let anchor = match path.current() {
+            Some(anchor) => anchor,
+            None => return QuadStatus::Arrived,
+        };
+
+        let mut regrouping = false;
+        for (creep, offset) in creeps.iter().zip(&self.slot_offsets()) {
+            let slot = anchor + *offset;
+            if creep.pos() != slot {
+                creep.move_to(&slot);
+                regrouping = true;
+            }
+        }
+        if regrouping {
+            return QuadStatus::Regrouping;
+        }
+
+        let direction = match path.next_direction() {
+            Some(direction) => direction,
+            None => return QuadStatus::Arrived,
+        };
+        if creeps.iter().any(|creep| creep.fatigue() > 0) {
+            return QuadStatus::Waiting;
+        }
+
+        let step = self.plan(direction);
+        for (creep, direction) in creeps.iter().zip(&step.moves) {
+            if let Some(direction) = direction {
+                creep.move_direction(*direction);
+            }
+        }
+        self.facing = step.facing;
+        if step.advances {
+            path.advance();
+            QuadStatus::Moved
+        } else {
+            QuadStatus::Rotated
+        }
+    }
+}
+
+fn is_cardinal(direction: Direction) -> bool {
+    matches!(
+        direction,
+        Direction::Top | Direction::Right | Direction::Bottom | Direction::Left
+    )
+}
+
+/// Tile offsets from the anchor of the front left, front right, back left and
+/// back right creeps of a quad facing `facing`.
+fn slot_offsets(facing: Direction) -> [(i32, i32); 4] {
+    match facing {
+        Direction::Right => [(1, 0), (1, 1), (0, 0), (0, 1)],
+        Direction::Bottom => [(1, 1), (0, 1), (1, 0), (0, 0)],
+        Direction::Left => [(0, 1), (0, 0), (1, 1), (1, 0)],
+        _ => [(0, 0), (1, 0), (0, 1), (1, 1)],
+    }
+}
+
+/// The direction of a single step by `(dx, dy)`, or `None` for no movement.
+fn direction_of(dx: i32, dy: i32) -> Option<Direction> {
+    Direction::from_delta(dx.signum() as i8, dy.signum() as i8)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{dilate, direction_of, terrain_matrix, Quad, IMPASSABLE};
+    use crate::constants::{Direction, Terrain};
+
+    /// Terrain of walls except for the given open rectangles, given as
+    /// inclusive `(x1, y1, x2, y2)` corners.
+    fn corridors(open: &'static [(u8, u8, u8, u8)]) -> impl Fn(u8, u8) -> Terrain {
+        move |x, y| {
+            if open
+                .iter()
+                .any(|&(x1, y1, x2, y2)| x >= x1 && x <= x2 && y >= y1 && y <= y2)
+            {
+                Terrain::Plain
+            } else {
+                Terrain::Wall
+            }
+        }
+    }
+
+    #[test]
+    fn dilation() {
+        let mut matrix = terrain_matrix(|_, _| Terrain::Plain, 1, 5);
+        matrix.set(10, 10, IMPASSABLE);
+        matrix.set(20, 20, 5);
+        let dilated = dilate(&matrix);
+
+        // every square covering the wall is blocked
+        for &(x, y) in &[(9, 9), (10, 9), (9, 10), (10, 10)] {
+            assert_eq!(dilated.get(x, y), IMPASSABLE, "({}, {})", x, y);
+        }
+        assert_eq!(dilated.get(11, 10), 1);
+        assert_eq!(dilated.get(8, 9), 1);
+
+        // every square covering the swamp costs as much as it
+        for &(x, y) in &[(19, 19), (20, 19), (19, 20), (20, 20)] {
+            assert_eq!(dilated.get(x, y), 5, "({}, {})", x, y);
+        }
+        assert_eq!(dilated.get(21, 20), 1);
+
+        // squares touching the room's edges are blocked
+        for i in 0..50 {
+            for &edge in &[0, 48, 49] {
+                assert_eq!(dilated.get(edge, i), IMPASSABLE);
+                assert_eq!(dilated.get(i, edge), IMPASSABLE);
+            }
+        }
+        assert_eq!(dilated.get(1, 1), 1);
+        assert_eq!(dilated.get(47, 47), 1);
+    }
+
+    #[test]
+    fn narrow_corridor() {
+        let narrow = terrain_matrix(corridors(&[(5, 10, 30, 10)]), 1, 5);
+        let dilated = dilate(&narrow);
+        assert!((0..50).all(|x| (0..50).all(|y| dilated.get(x, y) == IMPASSABLE)));
+    }
+
+    #[test]
+    fn corridor_with_corner() {
+        // a 2 wide corridor going right, then down
+        let matrix = terrain_matrix(corridors(&[(5, 10, 30, 11), (29, 10, 30, 40)]), 1, 5);
+        let dilated = dilate(&matrix);
+        let passable = |x: i32, y: i32| dilated.get(x as u8, y as u8) != IMPASSABLE;
+        let open = |x: i32, y: i32| matrix.get(x as u8, y as u8) != IMPASSABLE;
+
+        // anchors can only follow the middle of the corridor
+        assert!((5..=29).all(|x| passable(x, 10)));
+        assert!((10..=39).all(|y| passable(29, y)));
+        assert!(!passable(30, 10) && !passable(28, 11) && !passable(29, 40));
+
+        let path = std::iter::repeat(Direction::Right)
+            .take(24)
+            .chain(std::iter::repeat(Direction::Bottom).take(29));
+        let mut quad = Quad::new(Direction::Right);
+        let mut anchor = (5, 10);
+        let mut creeps: Vec<(i32, i32)> = quad
+            .slot_offsets()
+            .iter()
+            .map(|(dx, dy)| (anchor.0 + dx, anchor.1 + dy))
+            .collect();
+        let mut rotations = 0;
+
+        for direction in path {
+            loop {
+                let step = quad.plan(direction);
+                for (creep, direction) in creeps.iter_mut().zip(&step.moves) {
+                    if let Some(direction) = direction {
+                        let (dx, dy) = direction.offset();
+                        *creep = (creep.0 + dx, creep.1 + dy);
+                    }
+                }
+                quad = Quad::new(step.facing);
+                if step.advances {
+                    let (dx, dy) = direction.offset();
+                    anchor = (anchor.0 + dx, anchor.1 + dy);
+                } else {
+                    rotations += 1;
+                }
+
+                assert!(creeps.iter().all(|&(x, y)| open(x, y)));
+                let slots: Vec<(i32, i32)> = quad
+                    .slot_offsets()
+                    .iter()
+                    .map(|(dx, dy)| (anchor.0 + dx, anchor.1 + dy))
+                    .collect();
+                assert_eq!(creeps, slots);
+                if step.advances {
+                    break;
+                }
+            }
+        }
+
+        assert_eq!(rotations, 1);
+        assert_eq!(anchor, (29, 39));
+        assert_eq!(quad.facing(), Direction::Bottom);
+        // the front creeps lead down the corridor
+        assert_eq!(&creeps[..2], &[(30, 40), (29, 40)]);
+    }
+
+    #[test]
+    fn rotation_moves() {
+        let quad = Quad::new(Direction::Top);
+
+        let right = quad.plan(Direction::Right);
+        assert!(!right.advances);
+        assert_eq!(right.facing, Direction::Right);
+        assert_eq!(
+            right.moves,
+            [
+                Some(Direction::Right),
+                Some(Direction::Bottom),
+                Some(Direction::Top),
+                Some(Direction::Left),
+            ]
+        );
+
+        // turning around swaps diagonally opposite creeps
+        let back = quad.plan(Direction::Bottom);
+        assert_eq!(
+            back.moves,
+            [
+                Some(Direction::BottomRight),
+                Some(Direction::BottomLeft),
+                Some(Direction::TopRight),
+                Some(Direction::TopLeft),
+            ]
+        );
+
+        let diagonal = quad.plan(Direction::TopRight);
+        assert!(diagonal.advances);
+        assert_eq!(diagonal.facing, Direction::Top);
+        assert_eq!(diagonal.moves, [Some(Direction::TopRight); 4]);
+
+        let fixed = quad.rotate(false).plan(Direction::Left);
+        assert!(fixed.advances);
+        assert_eq!(fixed.facing, Direction::Top);
+        assert_eq!(fixed.moves, [Some(Direction::Left); 4]);
+
+        assert_eq!(direction_of(0, 0), None);
+        assert_eq!(direction_of(-1, 1), Some(Direction::BottomLeft));
+    }
+}
@@ -4,8 +4,11 @@
 //! generally means all state which is true this tick throughout the world.
 //!
 //! [`Game`]: http://docs.screeps.com/api/#Game
+use std::{cell::RefCell, error::Error, fmt};
+
 use crate::{
-    local::{ObjectId, RawObjectId},
+    constants::MAX_CONSTRUCTION_SITES,
+    local::{GameTime, ObjectId, RawObjectId},
     objects::{HasId, RoomObject, SizedRoomObject},
     traits::TryInto,
     ConversionError,
@@ -15,6 +18,7 @@ pub mod cpu;
 pub mod gcl;
 pub mod gpl;
 pub mod map;
+pub mod map_visual;
 pub mod market;
 pub mod shards;
 
@@ -25,6 +29,25 @@ pub mod construction_sites {
     game_map_access!(objects::ConstructionSite, Game.constructionSites);
 }
 
+/// The number of construction sites the current player has, read without
+/// converting [`construction_sites`] to a `HashMap`.
+pub fn construction_site_count() -> u32 {
+    js_unwrap!(Object.keys(Game.constructionSites).length)
+}
+
+/// The number of construction sites which can still be placed before reaching
+/// [`MAX_CONSTRUCTION_SITES`], after which the game rejects new sites with
+/// [`ReturnCode::Full`].
+///
+/// [`ReturnCode::Full`]: crate::constants::ReturnCode::Full
+pub fn construction_site_budget_remaining() -> u32 {
+    site_budget_remaining(construction_site_count())
+}
+
+fn site_budget_remaining(site_count: u32) -> u32 {
+    MAX_CONSTRUCTION_SITES.saturating_sub(site_count)
+}
+
 /// See [http://docs.screeps.com/api/#Game.creeps]
 ///
 /// [http://docs.screeps.com/api/#Game.creeps]: http://docs.screeps.com/api/#Game.creeps
@@ -140,8 +163,82 @@ pub mod structures {
 /// See [http://docs.screeps.com/api/#Game.time]
 ///
 /// [http://docs.screeps.com/api/#Game.time]: http://docs.screeps.com/api/#Game.time
-pub fn time() -> u32 {
-    js_unwrap!(Game.time)
+pub fn time() -> GameTime {
+    GameTime(js_unwrap!(Game.time))
+}
+
+thread_local! {
+    static MY_USERNAME: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Sources of the current player's username, in the order they're tried by
+/// [`my_username`].
+const USERNAME_SOURCES: [fn() -> Option<String>; 4] = [
+    spawn_owner,
+    controller_owner,
+    creep_owner,
+    construction_site_owner,
+];
+
+fn spawn_owner() -> Option<String> {
+    js_unwrap!(Object.values(Game.spawns).map((s) => s.owner.username)[0] || null)
+}
+
+fn controller_owner() -> Option<String> {
+    js_unwrap!(
+        Object.values(Game.rooms)
+            .map((r) => r.controller)
+            .filter((c) => c && c.my)
+            .map((c) => c.owner.username)[0]
+            || null
+    )
+}
+
+fn creep_owner() -> Option<String> {
+    js_unwrap!(Object.values(Game.creeps).map((c) => c.owner.username)[0] || null)
+}
+
+fn construction_site_owner() -> Option<String> {
+    js_unwrap!(Object.values(Game.constructionSites).map((s) => s.owner.username)[0] || null)
+}
+
+/// Error returned by [`my_username`] when the current player owns nothing
+/// their username could be read from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnknownUsername;
+
+impl fmt::Display for UnknownUsername {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(
+            "couldn't determine the current player's username: no owned spawn, \
+             controller, creep or construction site is visible",
+        )
+    }
+}
+
+impl Error for UnknownUsername {}
+
+/// The current player's username.
+///
+/// The game doesn't expose it directly, so it's read from the owner of the
+/// first owned object found, trying in order spawns, controllers of owned
+/// rooms, creeps and construction sites. Once found, the username is cached
+/// until the next global reset.
+///
+/// Returns an error if the player owns none of these, such as right after
+/// respawning.
+pub fn my_username() -> Result<String, UnknownUsername> {
+    cached_username(&USERNAME_SOURCES)
+}
+
+fn cached_username(sources: &[fn() -> Option<String>]) -> Result<String, UnknownUsername> {
+    MY_USERNAME.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.is_none() {
+            *cache = sources.iter().find_map(|source| source());
+        }
+        cache.clone().ok_or(UnknownUsername)
+    })
 }
 
 /// See [http://docs.screeps.com/api/#Game.getObjectById]
@@ -217,3 +314,72 @@ pub fn notify(message: &str, group_interval: Option<u32>) {
         Game.notify(@{message}, @{group_interval.unwrap_or(0)});
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{cached_username, site_budget_remaining, UnknownUsername, MY_USERNAME};
+
+    fn nothing() -> Option<String> {
+        None
+    }
+
+    fn alice() -> Option<String> {
+        Some("Alice".to_owned())
+    }
+
+    fn bob() -> Option<String> {
+        Some("Bob".to_owned())
+    }
+
+    fn unreachable() -> Option<String> {
+        panic!("source consulted after the username was found")
+    }
+
+    fn reset() {
+        MY_USERNAME.with(|cache| *cache.borrow_mut() = None);
+    }
+
+    #[test]
+    fn username_fallback_order() {
+        // no spawns or owned rooms, but a creep and a construction site
+        reset();
+        assert_eq!(
+            cached_username(&[nothing, nothing, alice, bob]),
+            Ok("Alice".to_owned())
+        );
+
+        // later sources aren't consulted once one has the username
+        reset();
+        assert_eq!(
+            cached_username(&[bob, unreachable, unreachable, unreachable]),
+            Ok("Bob".to_owned())
+        );
+
+        reset();
+        assert_eq!(
+            cached_username(&[nothing, nothing, nothing, nothing]),
+            Err(UnknownUsername)
+        );
+    }
+
+    #[test]
+    fn username_cache() {
+        reset();
+        assert_eq!(
+            cached_username(&[nothing, nothing, nothing, nothing]),
+            Err(UnknownUsername)
+        );
+        // failures aren't cached
+        assert_eq!(cached_username(&[nothing, alice]), Ok("Alice".to_owned()));
+        assert_eq!(cached_username(&[unreachable]), Ok("Alice".to_owned()));
+    }
+
+    #[test]
+    fn construction_site_budget() {
+        assert_eq!(site_budget_remaining(0), 100);
+        assert_eq!(site_budget_remaining(97), 3);
+        assert_eq!(site_budget_remaining(100), 0);
+        // never below 0, even with more sites than the cap
+        assert_eq!(site_budget_remaining(105), 0);
+    }
+}
@@ -95,6 +95,139 @@ impl<'de> Deserialize<'de> for RoomStatus {
     }
 }
 
+/// Source of room statuses used by a [`RoomAccessCache`].
+///
+/// This is implemented by [`GameRoomStatus`], which queries the game, and by
+/// any `FnMut(RoomName) -> RoomStatus` closure.
+pub trait RoomStatusSource {
+    /// Looks up the status of a single room.
+    fn room_status(&mut self, room_name: RoomName) -> RoomStatus;
+
+    /// Looks up the statuses of several rooms at once, in the same order as
+    /// `room_names`.
+    fn room_statuses(&mut self, room_names: &[RoomName]) -> Vec<RoomStatus> {
+        room_names
+            .iter()
+            .map(|room_name| self.room_status(*room_name))
+            .collect()
+    }
+}
+
+impl<F> RoomStatusSource for F
+where
+    F: FnMut(RoomName) -> RoomStatus,
+{
+    fn room_status(&mut self, room_name: RoomName) -> RoomStatus {
+        self(room_name)
+    }
+}
+
+/// Looks up room statuses with `Game.map.getRoomStatus`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct GameRoomStatus;
+
+impl RoomStatusSource for GameRoomStatus {
+    fn room_status(&mut self, room_name: RoomName) -> RoomStatus {
+        get_room_status(room_name).status
+    }
+
+    fn room_statuses(&mut self, room_names: &[RoomName]) -> Vec<RoomStatus> {
+        let names: Vec<String> = room_names.iter().map(ToString::to_string).collect();
+        let statuses: Vec<MapRoomStatus> =
+            js_unwrap!(@{names}.map((name) => Game.map.getRoomStatus(name)));
+        statuses.into_iter().map(|s| s.status).collect()
+    }
+}
+
+/// Caches room statuses to cheaply check whether rooms can be entered, for
+/// instance from route or pathfinder callbacks.
+///
+/// A room is considered accessible when it isn't closed and has the same
+/// status as the home room given when creating the cache: creeps in normal
+/// rooms can't enter novice or respawn areas, and creeps in those areas can't
+/// leave them. Separate novice or respawn areas aren't told apart.
+///
+/// Statuses are kept for `ttl` ticks, and at least for the tick they were
+/// looked up in. Room statuses rarely change, so a long TTL is usually fine.
+pub struct RoomAccessCache<S = GameRoomStatus> {
+    source: S,
+    home: RoomName,
+    ttl: u32,
+    entries: collections::HashMap<RoomName, (RoomStatus, u32)>,
+}
+
+impl RoomAccessCache<GameRoomStatus> {
+    /// Creates a cache looking up statuses with `Game.map.getRoomStatus`.
+    pub fn new(home: RoomName, ttl: u32) -> Self {
+        RoomAccessCache::with_source(home, ttl, GameRoomStatus)
+    }
+}
+
+impl<S> RoomAccessCache<S>
+where
+    S: RoomStatusSource,
+{
+    /// Creates a cache looking up statuses from `source`.
+    pub fn with_source(home: RoomName, ttl: u32, source: S) -> Self {
+        RoomAccessCache {
+            source,
+            home,
+            ttl: ttl.max(1),
+            entries: collections::HashMap::new(),
+        }
+    }
+
+    fn is_fresh(&self, room_name: RoomName, current_tick: u32) -> bool {
+        match self.entries.get(&room_name) {
+            Some((_, fetched)) => current_tick.wrapping_sub(*fetched) < self.ttl,
+            None => false,
+        }
+    }
+
+    /// The status of a room, looked up if it isn't cached or has expired.
+    pub fn status(&mut self, room_name: RoomName, current_tick: u32) -> RoomStatus {
+        if !self.is_fresh(room_name, current_tick) {
+            let status = self.source.room_status(room_name);
+            self.entries.insert(room_name, (status, current_tick));
+        }
+        self.entries[&room_name].0
+    }
+
+    /// Whether creeps from the home room can enter the given room.
+    pub fn is_room_accessible(&mut self, room_name: RoomName, current_tick: u32) -> bool {
+        let home_status = self.status(self.home, current_tick);
+        let status = self.status(room_name, current_tick);
+        status != RoomStatus::Closed && status == home_status
+    }
+
+    /// Looks up the statuses of all given rooms which aren't cached, along
+    /// with the home room's, in a single batch.
+    ///
+    /// Calling this before a pathfinding burst avoids one lookup per room
+    /// from within callbacks.
+    pub fn prefetch(&mut self, room_names: &[RoomName], current_tick: u32) {
+        let mut missing: Vec<RoomName> = Vec::new();
+        for room_name in Some(&self.home).into_iter().chain(room_names) {
+            if !self.is_fresh(*room_name, current_tick) && !missing.contains(room_name) {
+                missing.push(*room_name);
+            }
+        }
+        if missing.is_empty() {
+            return;
+        }
+
+        let statuses = self.source.room_statuses(&missing);
+        for (room_name, status) in missing.into_iter().zip(statuses) {
+            self.entries.insert(room_name, (status, current_tick));
+        }
+    }
+
+    /// Forgets all cached statuses.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
 /// Implements `Game.map.findExit`.
 pub fn find_exit(from_room: RoomName, to_room: RoomName) -> Result<ExitDirection, ReturnCode> {
     let code: i32 = js_unwrap! {Game.map.findExit(@{from_room}, @{to_room})};
@@ -208,3 +341,158 @@ pub struct RoomRouteStep {
     pub room: RoomName,
 }
 js_deserializable!(RoomRouteStep);
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{RoomAccessCache, RoomStatus, RoomStatusSource};
+    use crate::local::RoomName;
+
+    fn room(name: &str) -> RoomName {
+        RoomName::new(name).unwrap()
+    }
+
+    /// Map with a novice area west of W10 and a closed room at W5N5.
+    fn status(room_name: RoomName) -> RoomStatus {
+        let name = room_name.to_string();
+        if name == "W5N5" {
+            RoomStatus::Closed
+        } else if name.starts_with('W') && name[1..name.len() - 2].parse::<u32>().unwrap() >= 11 {
+            RoomStatus::Novice
+        } else {
+            RoomStatus::Normal
+        }
+    }
+
+    #[test]
+    fn accessibility() {
+        let mut cache = RoomAccessCache::with_source(room("W1N1"), 100, status);
+
+        assert!(cache.is_room_accessible(room("W2N1"), 1));
+        assert!(cache.is_room_accessible(room("E3S3"), 1));
+        assert!(!cache.is_room_accessible(room("W5N5"), 1));
+        assert!(!cache.is_room_accessible(room("W11N1"), 1));
+
+        let mut novice = RoomAccessCache::with_source(room("W12N1"), 100, status);
+        assert!(novice.is_room_accessible(room("W11N1"), 1));
+        assert!(!novice.is_room_accessible(room("W10N1"), 1));
+    }
+
+    #[test]
+    fn ttl_expiry() {
+        let lookups = Rc::new(RefCell::new(Vec::new()));
+        let source = {
+            let lookups = lookups.clone();
+            move |room_name: RoomName| {
+                lookups.borrow_mut().push(room_name);
+                status(room_name)
+            }
+        };
+        let mut cache = RoomAccessCache::with_source(room("W1N1"), 10, source);
+
+        assert!(cache.is_room_accessible(room("W2N1"), 100));
+        assert!(cache.is_room_accessible(room("W2N1"), 100));
+        assert!(cache.is_room_accessible(room("W2N1"), 109));
+        assert_eq!(lookups.borrow().len(), 2);
+
+        // expired on the tick `ttl` ticks after the lookup
+        assert!(cache.is_room_accessible(room("W2N1"), 110));
+        assert_eq!(lookups.borrow().len(), 4);
+
+        cache.clear();
+        assert_eq!(cache.status(room("W5N5"), 110), RoomStatus::Closed);
+        assert_eq!(lookups.borrow().len(), 5);
+    }
+
+    #[test]
+    fn zero_ttl_caches_for_current_tick() {
+        let lookups = Rc::new(RefCell::new(0));
+        let source = {
+            let lookups = lookups.clone();
+            move |room_name: RoomName| {
+                *lookups.borrow_mut() += 1;
+                status(room_name)
+            }
+        };
+        let mut cache = RoomAccessCache::with_source(room("W1N1"), 0, source);
+
+        cache.status(room("W2N1"), 5);
+        cache.status(room("W2N1"), 5);
+        assert_eq!(*lookups.borrow(), 1);
+        cache.status(room("W2N1"), 6);
+        assert_eq!(*lookups.borrow(), 2);
+    }
+
+    #[test]
+    fn prefetch_batches_lookups() {
+        let lookups = Rc::new(RefCell::new(0));
+        let source = {
+            let lookups = lookups.clone();
+            move |room_name: RoomName| {
+                *lookups.borrow_mut() += 1;
+                status(room_name)
+            }
+        };
+        let mut cache = RoomAccessCache::with_source(room("W1N1"), 50, source);
+
+        let rooms = [room("W2N1"), room("W3N1"), room("W2N1"), room("W5N5")];
+        cache.prefetch(&rooms, 1);
+        // home room plus three distinct rooms
+        assert_eq!(*lookups.borrow(), 4);
+
+        for room_name in rooms.iter() {
+            cache.is_room_accessible(*room_name, 20);
+        }
+        cache.prefetch(&rooms, 20);
+        assert_eq!(*lookups.borrow(), 4);
+
+        cache.prefetch(&rooms, 51);
+        assert_eq!(*lookups.borrow(), 8);
+    }
+
+    /// Source recording single and batched lookups separately.
+    #[derive(Default)]
+    struct BatchingSource {
+        single: Vec<RoomName>,
+        batches: Vec<Vec<RoomName>>,
+    }
+
+    impl RoomStatusSource for BatchingSource {
+        fn room_status(&mut self, room_name: RoomName) -> RoomStatus {
+            self.single.push(room_name);
+            status(room_name)
+        }
+
+        fn room_statuses(&mut self, room_names: &[RoomName]) -> Vec<RoomStatus> {
+            self.batches.push(room_names.to_vec());
+            room_names
+                .iter()
+                .map(|room_name| status(*room_name))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn prefetch_uses_batched_lookups() {
+        let mut cache = RoomAccessCache::with_source(room("W1N1"), 50, BatchingSource::default());
+
+        let rooms = [room("W2N1"), room("W12N1"), room("W2N1")];
+        cache.prefetch(&rooms, 1);
+        assert_eq!(
+            cache.source.batches,
+            vec![vec![room("W1N1"), room("W2N1"), room("W12N1")]]
+        );
+
+        // prefetched statuses are used without further lookups
+        assert!(cache.is_room_accessible(room("W2N1"), 10));
+        assert!(!cache.is_room_accessible(room("W12N1"), 10));
+        assert!(cache.source.single.is_empty());
+
+        // only rooms which aren't cached are batched
+        cache.prefetch(&[room("W2N1"), room("W5N5")], 10);
+        assert_eq!(cache.source.batches.len(), 2);
+        assert_eq!(cache.source.batches[1], vec![room("W5N5")]);
+        assert!(cache.source.single.is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+//! See [http://docs.screeps.com/api/#Game-map-visual]
+//!
+//! Map visuals are limited to [`MAP_VISUAL_SIZE_LIMIT`] bytes per tick, and
+//! the game silently drops visuals past that limit. The drawing functions of
+//! this module keep track of the size of the visuals drawn this tick, and
+//! skip visuals which would go over the limit, returning `false`.
+//!
+//! [http://docs.screeps.com/api/#Game-map-visual]: http://docs.screeps.com/api/#Game-map-visual
+use std::cell::Cell;
+
+use serde::Serialize;
+
+use crate::{
+    game,
+    local::{Position, RoomName},
+    objects::{CircleStyle, LineStyle, PolyStyle, RectStyle, TextStyle},
+};
+
+/// Maximum size of the map visuals drawn each tick, in bytes.
+pub const MAP_VISUAL_SIZE_LIMIT: u32 = 1000 * 1024;
+
+/// A position on the world map, serialized as map visuals expect it.
+#[derive(Clone, Serialize)]
+pub struct MapPoint {
+    x: u32,
+    y: u32,
+    #[serde(rename = "n")]
+    room_name: RoomName,
+}
+
+impl From<Position> for MapPoint {
+    fn from(pos: Position) -> Self {
+        MapPoint {
+            x: pos.x(),
+            y: pos.y(),
+            room_name: pos.room_name(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct MapCircleData {
+    #[serde(flatten)]
+    pos: MapPoint,
+    #[serde(rename = "s", skip_serializing_if = "Option::is_none")]
+    style: Option<CircleStyle>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct MapLineData {
+    x1: u32,
+    y1: u32,
+    n1: RoomName,
+    x2: u32,
+    y2: u32,
+    n2: RoomName,
+    #[serde(rename = "s", skip_serializing_if = "Option::is_none")]
+    style: Option<LineStyle>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct MapRectData {
+    #[serde(flatten)]
+    pos: MapPoint,
+    #[serde(rename = "w")]
+    width: u32,
+    #[serde(rename = "h")]
+    height: u32,
+    #[serde(rename = "s", skip_serializing_if = "Option::is_none")]
+    style: Option<RectStyle>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct MapPolyData {
+    points: Vec<MapPoint>,
+    #[serde(rename = "s", skip_serializing_if = "Option::is_none")]
+    style: Option<PolyStyle>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct MapTextData {
+    text: String,
+    #[serde(flatten)]
+    pos: MapPoint,
+    #[serde(rename = "s", skip_serializing_if = "Option::is_none")]
+    style: Option<TextStyle>,
+}
+
+/// A single map visual, the map equivalent of a
+/// [`Visual`][crate::objects::Visual].
+///
+/// Map visuals are drawn at world positions, so they may span several rooms.
+#[derive(Clone, Serialize)]
+#[serde(tag = "t")]
+pub enum MapVisual {
+    #[serde(rename = "c")]
+    Circle(MapCircleData),
+    #[serde(rename = "l")]
+    Line(MapLineData),
+    #[serde(rename = "r")]
+    Rect(MapRectData),
+    #[serde(rename = "p")]
+    Poly(MapPolyData),
+    #[serde(rename = "t")]
+    Text(MapTextData),
+}
+js_serializable!(MapVisual);
+
+impl MapVisual {
+    pub fn circle(pos: Position, style: Option<CircleStyle>) -> MapVisual {
+        MapVisual::Circle(MapCircleData {
+            pos: pos.into(),
+            style,
+        })
+    }
+
+    pub fn line(from: Position, to: Position, style: Option<LineStyle>) -> MapVisual {
+        MapVisual::Line(MapLineData {
+            x1: from.x(),
+            y1: from.y(),
+            n1: from.room_name(),
+            x2: to.x(),
+            y2: to.y(),
+            n2: to.room_name(),
+            style,
+        })
+    }
+
+    /// A rectangle with its top left corner at `top_left`, with a size in
+    /// tiles.
+    pub fn rect(
+        top_left: Position,
+        width: u32,
+        height: u32,
+        style: Option<RectStyle>,
+    ) -> MapVisual {
+        MapVisual::Rect(MapRectData {
+            pos: top_left.into(),
+            width,
+            height,
+            style,
+        })
+    }
+
+    pub fn poly(points: Vec<Position>, style: Option<PolyStyle>) -> MapVisual {
+        MapVisual::Poly(MapPolyData {
+            points: points.into_iter().map(Into::into).collect(),
+            style,
+        })
+    }
+
+    /// Text at `pos`. The text style's `font` is ignored by map visuals.
+    pub fn text(pos: Position, text: String, style: Option<TextStyle>) -> MapVisual {
+        MapVisual::Text(MapTextData {
+            text,
+            pos: pos.into(),
+            style,
+        })
+    }
+
+    /// The estimated number of bytes this visual adds to the size of the
+    /// tick's map visuals: the length of its JSON serialization, plus a
+    /// separator.
+    pub fn estimated_size(&self) -> u32 {
+        serde_json::to_string(self).map_or(0, |json| json.len() as u32 + 1)
+    }
+}
+
+/// Tracks the size of the map visuals drawn during a tick.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MapVisualBudget {
+    limit: u32,
+    used: u32,
+}
+
+impl MapVisualBudget {
+    /// Creates a budget of `limit` bytes, of which `used` bytes are already
+    /// used.
+    pub fn new(limit: u32, used: u32) -> Self {
+        MapVisualBudget { limit, used }
+    }
+
+    /// Bytes of map visuals which can still be drawn.
+    pub fn remaining(&self) -> u32 {
+        self.limit.saturating_sub(self.used)
+    }
+
+    /// Reserves space for `visual`, returning `false` without reserving
+    /// anything if it doesn't fit in the remaining budget.
+    pub fn try_reserve(&mut self, visual: &MapVisual) -> bool {
+        self.try_reserve_bytes(visual.estimated_size())
+    }
+
+    /// Reserves `size` bytes, returning `false` without reserving anything if
+    /// they don't fit in the remaining budget.
+    pub fn try_reserve_bytes(&mut self, size: u32) -> bool {
+        if size > self.remaining() {
+            return false;
+        }
+        self.used += size;
+        true
+    }
+}
+
+thread_local! {
+    /// The budget of the current tick, along with the tick it belongs to.
+    static BUDGET: Cell<Option<(u32, MapVisualBudget)>> = Cell::new(None);
+}
+
+/// Runs `f` with the budget of the current tick, starting from the size
+/// reported by the game on the first call of each tick so that visuals drawn
+/// from JavaScript are accounted for.
+fn with_budget<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut MapVisualBudget) -> R,
+{
+    let tick = game::time().ticks();
+    BUDGET.with(|cell| {
+        let mut budget = match cell.get() {
+            Some((budget_tick, budget)) if budget_tick == tick => budget,
+            _ => MapVisualBudget::new(MAP_VISUAL_SIZE_LIMIT, get_size()),
+        };
+        let result = f(&mut budget);
+        cell.set(Some((tick, budget)));
+        result
+    })
+}
+
+/// Draws a map visual, returning `false` if it was skipped because it would
+/// go over this tick's size limit.
+pub fn draw(visual: &MapVisual) -> bool {
+    if !with_budget(|budget| budget.try_reserve(visual)) {
+        return false;
+    }
+    js! { @(no_return)
+        console.addVisual("map", @{visual});
+    }
+    true
+}
+
+/// Draws map visuals in order until one would go over this tick's size limit,
+/// returning the number of visuals drawn.
+pub fn draw_multi(visuals: &[MapVisual]) -> usize {
+    let count = with_budget(|budget| {
+        visuals
+            .iter()
+            .take_while(|visual| budget.try_reserve(visual))
+            .count()
+    });
+    let drawn = &visuals[..count];
+    if !drawn.is_empty() {
+        js! { @(no_return)
+            (@{drawn}).forEach(function(v) { console.addVisual("map", v); });
+        }
+    }
+    count
+}
+
+pub fn circle(pos: Position, style: Option<CircleStyle>) -> bool {
+    draw(&MapVisual::circle(pos, style))
+}
+
+pub fn line(from: Position, to: Position, style: Option<LineStyle>) -> bool {
+    draw(&MapVisual::line(from, to, style))
+}
+
+pub fn rect(top_left: Position, width: u32, height: u32, style: Option<RectStyle>) -> bool {
+    draw(&MapVisual::rect(top_left, width, height, style))
+}
+
+pub fn poly(points: Vec<Position>, style: Option<PolyStyle>) -> bool {
+    draw(&MapVisual::poly(points, style))
+}
+
+pub fn text(pos: Position, text: String, style: Option<TextStyle>) -> bool {
+    draw(&MapVisual::text(pos, text, style))
+}
+
+/// Bytes of map visuals which can still be drawn this tick.
+pub fn remaining_budget() -> u32 {
+    with_budget(|budget| budget.remaining())
+}
+
+/// The size of the map visuals drawn this tick, in bytes, as reported by the
+/// game.
+pub fn get_size() -> u32 {
+    js_unwrap!(Game.map.visual.getSize())
+}
+
+/// Removes all map visuals drawn this tick.
+pub fn clear() {
+    js! { @(no_return)
+        Game.map.visual.clear();
+    }
+    with_budget(|budget| *budget = MapVisualBudget::new(MAP_VISUAL_SIZE_LIMIT, 0));
+}
+
+/// Exports the map visuals drawn this tick, to be drawn again on later ticks
+/// with [`import`]. Returns an empty string if nothing was drawn.
+pub fn export() -> String {
+    js_unwrap!(Game.map.visual.export() || "")
+}
+
+/// Draws map visuals previously returned by [`export`].
+///
+/// Returns `false` if they were skipped because they would go over this
+/// tick's size limit.
+pub fn import(data: &str) -> bool {
+    let fits = with_budget(|budget| budget.try_reserve_bytes(data.len() as u32));
+    if fits {
+        js! { @(no_return)
+            Game.map.visual.import(@{data});
+        }
+    }
+    fits
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MapVisual, MapVisualBudget, MAP_VISUAL_SIZE_LIMIT};
+    use crate::{
+        local::{Position, RoomName},
+        objects::{CircleStyle, LineStyle, TextStyle},
+    };
+
+    fn pos(x: u32, y: u32, room: &str) -> Position {
+        Position::new(x, y, RoomName::new(room).unwrap())
+    }
+
+    #[test]
+    fn map_visual_size() {
+        let circle = MapVisual::circle(pos(25, 25, "W1N1"), None);
+        assert_eq!(
+            serde_json::to_string(&circle).unwrap(),
+            r#"{"t":"c","x":25,"y":25,"n":"W1N1"}"#
+        );
+        assert_eq!(circle.estimated_size(), 35);
+
+        let line = MapVisual::line(
+            pos(10, 10, "W1N1"),
+            pos(40, 40, "E2S3"),
+            Some(LineStyle::default().color("#ff0000")),
+        );
+        assert_eq!(
+            serde_json::to_string(&line).unwrap(),
+            r##"{"t":"l","x1":10,"y1":10,"n1":"W1N1","x2":40,"y2":40,"n2":"E2S3","s":{"color":"#ff0000"}}"##
+        );
+
+        let text = MapVisual::text(pos(1, 2, "W1N1"), "hi".to_owned(), None);
+        assert_eq!(
+            serde_json::to_string(&text).unwrap(),
+            r#"{"t":"t","text":"hi","x":1,"y":2,"n":"W1N1"}"#
+        );
+
+        let poly = MapVisual::poly(vec![pos(1, 2, "W1N1"), pos(3, 4, "W1N2")], None);
+        assert_eq!(
+            serde_json::to_string(&poly).unwrap(),
+            r#"{"t":"p","points":[{"x":1,"y":2,"n":"W1N1"},{"x":3,"y":4,"n":"W1N2"}]}"#
+        );
+
+        let rect = MapVisual::rect(pos(0, 0, "W1N1"), 50, 50, None);
+        assert_eq!(
+            serde_json::to_string(&rect).unwrap(),
+            r#"{"t":"r","x":0,"y":0,"n":"W1N1","w":50,"h":50}"#
+        );
+    }
+
+    #[test]
+    fn budget_cutoff() {
+        let circle = MapVisual::circle(
+            pos(25, 25, "W1N1"),
+            Some(CircleStyle::default().radius(5.0)),
+        );
+        let size = circle.estimated_size();
+
+        let mut budget = MapVisualBudget::new(size * 3 + 10, size);
+        assert_eq!(budget.remaining(), size * 2 + 10);
+        assert!(budget.try_reserve(&circle));
+        assert!(budget.try_reserve(&circle));
+        assert_eq!(budget.remaining(), 10);
+        assert!(!budget.try_reserve(&circle));
+        // skipped visuals don't use any budget
+        assert_eq!(budget.remaining(), 10);
+
+        let small = MapVisual::text(pos(1, 1, "W1N1"), String::new(), None);
+        assert!(small.estimated_size() > 10);
+        assert!(!budget.try_reserve(&small));
+
+        let full = MapVisualBudget::new(MAP_VISUAL_SIZE_LIMIT, MAP_VISUAL_SIZE_LIMIT + 5);
+        assert_eq!(full.remaining(), 0);
+
+        let styled = MapVisual::text(
+            pos(1, 1, "W1N1"),
+            "long label".to_owned(),
+            Some(TextStyle::default().color("#ffffff")),
+        );
+        assert!(styled.estimated_size() > small.estimated_size());
+    }
+}
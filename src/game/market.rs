@@ -1,9 +1,9 @@
 //! See [https://docs.screeps.com/api/#Game-market]
 //!
 //! [https://docs.screeps.com/api/#Game-market]: https://docs.screeps.com/api/#Game-market
-use std::{borrow::Cow, collections::HashMap, str::FromStr};
+use std::{borrow::Cow, cell::Cell, cmp::Ordering, collections::HashMap, str::FromStr};
 
-use parse_display::FromStr;
+use parse_display::{Display, FromStr};
 use serde::{
     de::{Deserializer, Error as _, Unexpected},
     Deserialize,
@@ -11,7 +11,8 @@ use serde::{
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::{
-    constants::{MarketResourceType, ResourceType, ReturnCode},
+    constants::{MarketResourceType, ResourceType, ReturnCode, MARKET_FEE, TERMINAL_COOLDOWN},
+    game,
     local::RoomName,
     traits::TryInto,
 };
@@ -29,7 +30,9 @@ use crate::{
 ///
 /// See the [constants module's documentation][crate::constants] for more
 /// details.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr, FromStr)]
+#[derive(
+    Copy, Clone, Debug, Display, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr, FromStr,
+)]
 #[repr(u8)]
 pub enum OrderType {
     #[display("sell")]
@@ -48,15 +51,6 @@ impl OrderType {
     }
 }
 
-// impl OrderType {
-//     fn as_string(&self) -> String {
-//         match self {
-//             OrderType::Sell => String::from("sell"),
-//             OrderType::Buy => String::from("buy")
-//         }
-//     }
-// }
-
 #[derive(Deserialize, Debug)]
 pub struct Player {
     pub username: String,
@@ -141,6 +135,25 @@ pub struct MyOrder {
 }
 js_deserializable!(MyOrder);
 
+impl MyOrder {
+    /// Whether this order is a candidate for cleanup with [`cancel_order`].
+    ///
+    /// An order is stale if it's inactive (the terminal lacks the resources or
+    /// energy to fulfill it), has less than `min_remaining` units left to
+    /// trade, or was created more than `max_age_ticks` ticks before
+    /// `current_tick`. Intershard orders have no creation tick, so they're
+    /// never considered stale because of their age.
+    pub fn is_stale(&self, current_tick: u32, min_remaining: u32, max_age_ticks: u32) -> bool {
+        if !self.active || self.remaining_amount < min_remaining {
+            return true;
+        }
+        match self.created {
+            Some(created) => current_tick.saturating_sub(created) > max_age_ticks,
+            None => false,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderHistoryRecord {
@@ -174,8 +187,62 @@ pub fn orders() -> HashMap<String, MyOrder> {
     js_unwrap!(Game.market.orders)
 }
 
-pub fn calc_transaction_cost(amount: u32, room1: RoomName, room2: RoomName) -> f64 {
-    js_unwrap!(Game.market.calcTransactionCost(@{amount}, @{room1.to_string()}, @{room2.to_string()}))
+/// Get the ids of the player's orders which are stale according to
+/// [`MyOrder::is_stale`], sorted by id.
+///
+/// Cancelling these frees up order slots (see [`MARKET_MAX_ORDERS`]); note
+/// that the fee paid when creating an order isn't refunded.
+///
+/// [`MARKET_MAX_ORDERS`]: crate::constants::MARKET_MAX_ORDERS
+pub fn stale_orders(min_remaining: u32, max_age_ticks: u32) -> Vec<String> {
+    let current_tick = game::time().ticks();
+    let mut ids: Vec<String> = orders()
+        .into_iter()
+        .filter(|(_, order)| order.is_stale(current_tick, min_remaining, max_age_ticks))
+        .map(|(id, _)| id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// The credits charged when creating an order for `total_amount` units at
+/// `price` credits each, which is [`MARKET_FEE`] of the order's value
+/// rounded up to a whole credit.
+///
+/// Orders with any value cost at least one credit, however cheap they are.
+pub fn order_fee(price: f64, total_amount: u32) -> u32 {
+    // same order of operations as the server, so floating point rounding
+    // lands on the same side
+    (price * f64::from(total_amount) * MARKET_FEE).ceil() as u32
+}
+
+/// The energy cost of sending `amount` units of resources with a terminal to
+/// a room `distance` rooms away, as computed by the server.
+pub fn terminal_energy_cost(amount: u32, distance: u32) -> u32 {
+    (f64::from(amount) * (1.0 - (-f64::from(distance) / 30.0).exp())).ceil() as u32
+}
+
+thread_local! {
+    static WORLD_SIZE: Cell<Option<u32>> = Cell::new(None);
+}
+
+/// The energy cost of sending `amount` units of resources with a terminal
+/// from `room1` to `room2`.
+///
+/// This gives the same result as `Game.market.calcTransactionCost`, but is
+/// computed in Rust with [`terminal_energy_cost`] and the distance between
+/// the rooms, wrapping around the edges of the world as the server does. The
+/// world size is only fetched from the game on the first call.
+pub fn calc_transaction_cost(amount: u32, room1: RoomName, room2: RoomName) -> u32 {
+    let world_size = WORLD_SIZE.with(|size| match size.get() {
+        Some(world_size) => world_size,
+        None => {
+            let world_size = game::map::get_world_size();
+            size.set(Some(world_size));
+            world_size
+        }
+    });
+    terminal_energy_cost(amount, room1.wrapped_linear_distance_to(room2, world_size))
 }
 
 pub fn cancel_order(order_id: &str) -> ReturnCode {
@@ -186,14 +253,21 @@ pub fn change_order_price(order_id: &str, new_price: f64) -> ReturnCode {
     js_unwrap!(Game.market.changeOrderPrice(@{order_id}, @{new_price}))
 }
 
-pub fn create_order(
+/// Create a market order in your terminal
+///
+/// `resource_type` may be either a [`ResourceType`] or an
+/// [`IntershardResourceType`], or a [`MarketResourceType`] wrapping either.
+/// `room` is required for orders of non-intershard resources.
+///
+/// [`IntershardResourceType`]: crate::constants::IntershardResourceType
+pub fn create_order<T: Into<MarketResourceType>>(
     order_type: OrderType,
-    resource_type: MarketResourceType,
+    resource_type: T,
     price: f64,
     total_amount: u32,
     room: Option<RoomName>,
 ) -> ReturnCode {
-    let resource_num = match resource_type {
+    let resource_num = match resource_type.into() {
         MarketResourceType::Resource(ty) => ty as u32,
         MarketResourceType::IntershardResource(ty) => ty as u32,
     };
@@ -301,3 +375,422 @@ pub fn get_order(id: &str) -> Option<Order> {
     };
     order.try_into().ok()
 }
+
+/// A pair of orders for the same resource which can be traded at a profit, as
+/// found by [`arbitrage_opportunities`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arbitrage {
+    /// Id of the buy order the resource is sold to.
+    pub buy_order: String,
+    /// Id of the sell order the resource is bought from.
+    pub sell_order: String,
+    pub resource_type: ResourceType,
+    /// Owned room whose terminal receives the resource bought.
+    pub receiving_room: RoomName,
+    /// Owned room whose terminal sends the resource sold.
+    pub sending_room: RoomName,
+    /// Amount available to trade on both orders.
+    pub amount: u32,
+    /// Credits gained by trading `amount`, after paying for the energy of
+    /// both transfers.
+    pub profit_after_energy: f64,
+    /// Whether both deals go through the same terminal, which can't make the
+    /// second deal until its cooldown from the first ends.
+    pub same_terminal: bool,
+}
+
+impl Arbitrage {
+    /// Ticks to wait after buying the resource before selling it:
+    /// [`TERMINAL_COOLDOWN`] when both deals go through the same terminal, or
+    /// 0 when both can be made on the same tick.
+    pub fn sell_delay(&self) -> u32 {
+        if self.same_terminal {
+            TERMINAL_COOLDOWN
+        } else {
+            0
+        }
+    }
+}
+
+/// Finds pairs of orders where buying from a sell order and selling to a buy
+/// order is profitable, using [`calc_transaction_cost`] for transfer costs.
+///
+/// See [`arbitrage_opportunities_with`].
+pub fn arbitrage_opportunities(
+    orders: &[Order],
+    my_rooms: &[RoomName],
+    min_margin: f64,
+    energy_price: f64,
+) -> Vec<Arbitrage> {
+    arbitrage_opportunities_with(
+        orders,
+        my_rooms,
+        min_margin,
+        energy_price,
+        |amount, from, to| f64::from(calc_transaction_cost(amount, from, to)),
+    )
+}
+
+/// Finds pairs of orders where buying from a sell order and selling to a buy
+/// order is profitable, using `transaction_cost` to compute the energy cost
+/// of sending an amount from one room to another.
+///
+/// Each pair is routed through whichever of `my_rooms` has the cheapest
+/// transfer for each deal, with energy valued at `energy_price` credits per
+/// unit. When the rooms differ, the resource sold comes from the sending
+/// room's existing stock while the resource bought restocks the receiving
+/// room. Pairs are returned if their profit per unit traded is at least
+/// `min_margin`, most profitable first. Orders may be part of several pairs,
+/// and intershard orders are ignored.
+pub fn arbitrage_opportunities_with<F>(
+    orders: &[Order],
+    my_rooms: &[RoomName],
+    min_margin: f64,
+    energy_price: f64,
+    mut transaction_cost: F,
+) -> Vec<Arbitrage>
+where
+    F: FnMut(u32, RoomName, RoomName) -> f64,
+{
+    let room_orders = |order_type| {
+        orders.iter().filter_map(move |order| match order {
+            Order {
+                resource_type: MarketResourceType::Resource(resource_type),
+                room_name: Some(room_name),
+                ..
+            } if order.order_type == order_type && order.amount > 0 => {
+                Some((order, *resource_type, *room_name))
+            }
+            _ => None,
+        })
+    };
+
+    let mut result = Vec::new();
+    for (sell, resource_type, sell_room) in room_orders(OrderType::Sell) {
+        for (buy, _, buy_room) in room_orders(OrderType::Buy)
+            .filter(|(buy, ty, _)| *ty == resource_type && buy.price > sell.price)
+        {
+            let amount = sell.amount.min(buy.amount);
+            let receiving =
+                cheapest_room(my_rooms, |room| transaction_cost(amount, sell_room, room));
+            let sending = cheapest_room(my_rooms, |room| transaction_cost(amount, room, buy_room));
+            let ((receiving_room, receive_cost), (sending_room, send_cost)) =
+                match (receiving, sending) {
+                    (Some(receiving), Some(sending)) => (receiving, sending),
+                    _ => continue,
+                };
+
+            let profit_after_energy = f64::from(amount) * (buy.price - sell.price)
+                - (receive_cost + send_cost) * energy_price;
+            if profit_after_energy / f64::from(amount) < min_margin {
+                continue;
+            }
+            result.push(Arbitrage {
+                buy_order: buy.id.clone(),
+                sell_order: sell.id.clone(),
+                resource_type,
+                receiving_room,
+                sending_room,
+                amount,
+                profit_after_energy,
+                // each room has a single terminal
+                same_terminal: receiving_room == sending_room,
+            });
+        }
+    }
+
+    result.sort_by(|a, b| {
+        b.profit_after_energy
+            .partial_cmp(&a.profit_after_energy)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.sell_order.cmp(&b.sell_order))
+            .then_with(|| a.buy_order.cmp(&b.buy_order))
+    });
+    result
+}
+
+/// The room with the lowest cost according to `cost`, along with that cost.
+fn cheapest_room<F>(rooms: &[RoomName], mut cost: F) -> Option<(RoomName, f64)>
+where
+    F: FnMut(RoomName) -> f64,
+{
+    rooms
+        .iter()
+        .map(|&room| (room, cost(room)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{
+        arbitrage_opportunities_with, order_fee, terminal_energy_cost, Arbitrage, MyOrder, Order,
+        OrderType,
+    };
+    use crate::{
+        constants::{IntershardResourceType, MarketResourceType, ResourceType, TERMINAL_COOLDOWN},
+        local::RoomName,
+    };
+
+    fn parse(json: &str) -> MyOrder {
+        serde_json::from_str(json).expect("expected order to deserialize")
+    }
+
+    #[test]
+    fn deserialize_my_order() {
+        let order = parse(
+            r#"{
+                "id": "55c34a6b5be41a0a6e80c68b",
+                "created": 13131117,
+                "createdTimestamp": 1543253147522,
+                "active": true,
+                "type": "sell",
+                "resourceType": "OH",
+                "roomName": "W1N1",
+                "amount": 15821,
+                "remainingAmount": 30000,
+                "totalAmount": 50000,
+                "price": 2.95
+            }"#,
+        );
+
+        assert_eq!(order.created, Some(13131117));
+        assert!(order.active);
+        assert_eq!(order.order_type, OrderType::Sell);
+        assert_eq!(
+            order.resource_type,
+            MarketResourceType::Resource(ResourceType::Hydroxide)
+        );
+        assert_eq!(order.room_name, Some(RoomName::new("W1N1").unwrap()));
+        assert_eq!(order.remaining_amount, 30000);
+
+        assert!(!order.is_stale(13131117 + 1000, 1000, 1000));
+        assert!(order.is_stale(13131117 + 1001, 1000, 1000));
+        assert!(order.is_stale(13131117, 30001, 1000));
+    }
+
+    #[test]
+    fn deserialize_inactive_intershard_order() {
+        let order = parse(
+            r#"{
+                "id": "5d0e7b1f1a0bd36e3e4b9c1f",
+                "createdTimestamp": 1543253147522,
+                "active": false,
+                "type": "buy",
+                "resourceType": "token",
+                "amount": 0,
+                "remainingAmount": 1,
+                "totalAmount": 1,
+                "price": 3500000
+            }"#,
+        );
+
+        assert_eq!(order.created, None);
+        assert!(!order.active);
+        assert_eq!(order.order_type, OrderType::Buy);
+        assert_eq!(
+            order.resource_type,
+            MarketResourceType::IntershardResource(IntershardResourceType::SubscriptionToken)
+        );
+        assert_eq!(order.room_name, None);
+
+        // inactive orders are always stale
+        assert!(order.is_stale(0, 0, u32::MAX));
+    }
+
+    #[test]
+    fn deserialize_pixel_order() {
+        let order: Order = serde_json::from_str(
+            r#"{
+                "id": "5f2cbc1a6d2c2b2b1a6cbd4e",
+                "createdTimestamp": 1596832794313,
+                "type": "sell",
+                "resourceType": "pixel",
+                "amount": 100,
+                "remainingAmount": 100,
+                "price": 3250
+            }"#,
+        )
+        .expect("expected order to deserialize");
+
+        assert_eq!(order.created, None);
+        assert_eq!(order.order_type, OrderType::Sell);
+        assert_eq!(
+            order.resource_type,
+            MarketResourceType::IntershardResource(IntershardResourceType::Pixel)
+        );
+        assert_eq!(order.room_name, None);
+        assert_eq!(order.price, 3250.0);
+    }
+
+    #[test]
+    fn intershard_resource_strings() {
+        for &(s, ty) in [
+            ("token", IntershardResourceType::SubscriptionToken),
+            ("pixel", IntershardResourceType::Pixel),
+            ("cpuUnlock", IntershardResourceType::CpuUnlock),
+            ("accessKey", IntershardResourceType::AccessKey),
+        ]
+        .iter()
+        {
+            assert_eq!(s.parse::<IntershardResourceType>(), Ok(ty));
+            assert_eq!(ty.to_string(), s);
+            assert_eq!(
+                serde_json::from_value::<MarketResourceType>(serde_json::json!(ty as u16)).unwrap(),
+                MarketResourceType::IntershardResource(ty)
+            );
+        }
+    }
+
+    fn order(
+        id: &str,
+        order_type: &str,
+        resource: &str,
+        room: &str,
+        amount: u32,
+        price: f64,
+    ) -> Order {
+        serde_json::from_value(json!({
+            "id": id,
+            "created": 1000,
+            "createdTimestamp": 1543253147522u64,
+            "type": order_type,
+            "resourceType": resource,
+            "roomName": room,
+            "amount": amount,
+            "remainingAmount": amount,
+            "price": price,
+        }))
+        .unwrap()
+    }
+
+    /// The server's transaction cost, without wrapping around the world.
+    fn transaction_cost(amount: u32, from: RoomName, to: RoomName) -> f64 {
+        f64::from(terminal_energy_cost(amount, from.linear_distance_to(to)))
+    }
+
+    fn fixture() -> Vec<Order> {
+        vec![
+            order("s1", "sell", "OH", "W2N1", 1000, 1.0),
+            order("b1", "buy", "OH", "W3N1", 500, 1.5),
+            // nominally profitable, but far away
+            order("b2", "buy", "OH", "W30N30", 2000, 1.2),
+            order("b3", "buy", "OH", "W2N2", 2000, 0.9),
+            // closer to the second room
+            order("b4", "buy", "OH", "W6N1", 300, 1.625),
+            order("b5", "buy", "H", "W2N1", 1000, 5.0),
+            order("b6", "buy", "OH", "W2N1", 0, 10.0),
+        ]
+    }
+
+    #[test]
+    fn arbitrage() {
+        let orders = fixture();
+        let rooms = [
+            RoomName::new("W1N1").unwrap(),
+            RoomName::new("W5N1").unwrap(),
+        ];
+
+        let found = arbitrage_opportunities_with(&orders, &rooms, 0.0, 1.0, transaction_cost);
+        assert_eq!(
+            found,
+            vec![
+                // 250 credits of margin, 17 + 33 energy
+                Arbitrage {
+                    buy_order: "b1".to_owned(),
+                    sell_order: "s1".to_owned(),
+                    resource_type: ResourceType::Hydroxide,
+                    receiving_room: rooms[0],
+                    sending_room: rooms[0],
+                    amount: 500,
+                    profit_after_energy: 200.0,
+                    same_terminal: true,
+                },
+                // 187.5 credits of margin, 10 + 10 energy
+                Arbitrage {
+                    buy_order: "b4".to_owned(),
+                    sell_order: "s1".to_owned(),
+                    resource_type: ResourceType::Hydroxide,
+                    receiving_room: rooms[0],
+                    sending_room: rooms[1],
+                    amount: 300,
+                    profit_after_energy: 167.5,
+                    same_terminal: false,
+                },
+            ]
+        );
+
+        assert_eq!(found[0].sell_delay(), TERMINAL_COOLDOWN);
+        assert_eq!(found[1].sell_delay(), 0);
+
+        let margin = arbitrage_opportunities_with(&orders, &rooms, 0.5, 1.0, transaction_cost);
+        assert_eq!(margin.len(), 1);
+        assert_eq!(margin[0].buy_order, "b4");
+
+        assert!(arbitrage_opportunities_with(&orders, &[], 0.0, 1.0, transaction_cost).is_empty());
+    }
+
+    #[test]
+    fn arbitrage_energy_price() {
+        let orders = fixture();
+        let rooms = [
+            RoomName::new("W1N1").unwrap(),
+            RoomName::new("W5N1").unwrap(),
+        ];
+
+        // 200 credits of margin on b2 cost 33 + 620 energy to transfer
+        let found = arbitrage_opportunities_with(&orders, &rooms, 0.0, 0.1, transaction_cost);
+        let pairs: Vec<(&str, f64)> = found
+            .iter()
+            .map(|a| (a.buy_order.as_str(), a.profit_after_energy))
+            .collect();
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs[0].0, "b1");
+        assert_eq!(pairs[1].0, "b4");
+        assert_eq!(pairs[2].0, "b2");
+        assert!((pairs[2].1 - 134.7).abs() < 1e-9);
+        assert_eq!(found[2].amount, 1000);
+
+        let found = arbitrage_opportunities_with(&orders, &rooms, 0.0, 0.4, transaction_cost);
+        assert!(found.iter().all(|a| a.buy_order != "b2"));
+    }
+
+    #[test]
+    fn order_types() {
+        assert_eq!(OrderType::Buy.to_string(), "buy");
+        assert_eq!("sell".parse::<OrderType>(), Ok(OrderType::Sell));
+        assert!("Sell".parse::<OrderType>().is_err());
+    }
+
+    #[test]
+    fn order_fees() {
+        // any order with value costs at least a credit
+        assert_eq!(order_fee(0.001, 1), 1);
+        assert_eq!(order_fee(0.001, 1_000_000), 50);
+        assert_eq!(order_fee(0.0, 1000), 0);
+        assert_eq!(order_fee(1.0, 0), 0);
+        // exact multiples aren't rounded up
+        assert_eq!(order_fee(1.0, 20), 1);
+        assert_eq!(order_fee(0.1, 200), 1);
+        assert_eq!(order_fee(1.0, 21), 2);
+        assert_eq!(order_fee(2.0, 10_000), 1000);
+    }
+
+    #[test]
+    fn terminal_energy_costs() {
+        // values returned by Game.market.calcTransactionCost
+        assert_eq!(terminal_energy_cost(1000, 0), 0);
+        assert_eq!(terminal_energy_cost(1000, 1), 33);
+        assert_eq!(terminal_energy_cost(1, 1), 1);
+        assert_eq!(terminal_energy_cost(1000, 10), 284);
+        assert_eq!(terminal_energy_cost(1000, 30), 633);
+        assert_eq!(terminal_energy_cost(5000, 15), 1968);
+        assert_eq!(terminal_energy_cost(100_000, 100), 96_433);
+
+        // across the sector corner from W1N1 to E1S1
+        let from = RoomName::new("W1N1").unwrap();
+        let to = RoomName::new("E1S1").unwrap();
+        assert_eq!(terminal_energy_cost(1000, from.linear_distance_to(to)), 96);
+    }
+}
@@ -0,0 +1,295 @@
+//! Planning lab production across an empire.
+//!
+//! [`boost_audit`] compares the compounds stored across all rooms with target
+//! amounts, and reports which compounds are short and whether the minerals on
+//! hand are enough to react the missing amount right away.
+//!
+//! [`lab_throughput`] and [`labs_needed_for`] relate the number of labs in a
+//! room to the amount of a compound it can produce.
+use std::collections::HashMap;
+
+use crate::{
+    constants::{ResourceType, LAB_REACTION_AMOUNT},
+    local::RoomName,
+};
+
+/// Number of ticks in a day used by [`lab_throughput`] and
+/// [`labs_needed_for`], assuming ticks of 3 seconds.
+///
+/// Actual tick durations differ between shards and servers.
+pub const ESTIMATED_TICKS_PER_DAY: u32 = 28_800;
+
+/// The amount of a compound a room's labs can produce, as estimated by
+/// [`lab_throughput`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ThroughputEstimate {
+    pub per_tick: f64,
+    /// Amount produced over [`ESTIMATED_TICKS_PER_DAY`] ticks, rounded down.
+    pub per_day: u32,
+}
+
+/// Estimates how much of `product` can be produced by `labs_available` labs
+/// running the same reaction continuously.
+///
+/// Two of the labs hold the reagents, and each of the others produces
+/// [`LAB_REACTION_AMOUNT`] units of the compound every
+/// [`ResourceType::reaction_time`] ticks. Power effects and the time spent
+/// moving resources in and out of the labs aren't counted.
+///
+/// Returns `None` if `product` isn't produced in labs.
+pub fn lab_throughput(labs_available: u32, product: ResourceType) -> Option<ThroughputEstimate> {
+    let reaction_time = product.reaction_time()?;
+    let producing = labs_available.saturating_sub(2);
+    let per_tick = f64::from(producing * LAB_REACTION_AMOUNT) / f64::from(reaction_time);
+    let per_day = u64::from(producing * LAB_REACTION_AMOUNT) * u64::from(ESTIMATED_TICKS_PER_DAY)
+        / u64::from(reaction_time);
+    Some(ThroughputEstimate {
+        per_tick,
+        per_day: per_day as u32,
+    })
+}
+
+/// The number of labs, reagent labs included, needed to produce
+/// `amount_per_day` of `product` over [`ESTIMATED_TICKS_PER_DAY`] ticks.
+///
+/// This is the inverse of [`lab_throughput`]; the result may be more than
+/// the 10 labs a room can have. Returns 0 if nothing needs producing, and
+/// `None` if `product` isn't produced in labs.
+pub fn labs_needed_for(product: ResourceType, amount_per_day: u32) -> Option<u32> {
+    let reaction_time = product.reaction_time()?;
+    if amount_per_day == 0 {
+        return Some(0);
+    }
+    let per_lab = u64::from(LAB_REACTION_AMOUNT) * u64::from(ESTIMATED_TICKS_PER_DAY);
+    let needed = u64::from(amount_per_day) * u64::from(reaction_time);
+    let producing = (needed + per_lab - 1) / per_lab;
+    Some(producing as u32 + 2)
+}
+
+/// A compound whose empire-wide stock is below its target, as reported by
+/// [`boost_audit`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BoostShortfall {
+    pub resource: ResourceType,
+    /// The amount stored across all rooms.
+    pub have: u32,
+    /// The target amount.
+    pub want: u32,
+    /// Whether the missing amount can be produced by lab reactions from the
+    /// minerals and intermediate compounds currently stored across all rooms.
+    pub producible_now: bool,
+}
+
+/// Compares the resources stored in each room with target amounts, returning
+/// the resources below their target sorted by resource type.
+///
+/// `inventories` holds the resources stored in each room, typically the sum of
+/// its labs, terminal and storage. Stock is pooled across rooms, assuming
+/// resources can be moved between them with terminals.
+///
+/// Each shortfall is checked for being producible on its own, so two
+/// shortfalls may both be reported as producible while competing for the same
+/// minerals.
+pub fn boost_audit(
+    inventories: &HashMap<RoomName, HashMap<ResourceType, u32>>,
+    targets: &HashMap<ResourceType, u32>,
+) -> Vec<BoostShortfall> {
+    let mut totals: HashMap<ResourceType, u32> = HashMap::new();
+    for inventory in inventories.values() {
+        for (&resource, &amount) in inventory {
+            *totals.entry(resource).or_insert(0) += amount;
+        }
+    }
+
+    let mut shortfalls: Vec<BoostShortfall> = targets
+        .iter()
+        .filter_map(|(&resource, &want)| {
+            let have = totals.get(&resource).copied().unwrap_or(0);
+            if have >= want {
+                return None;
+            }
+            let producible_now = match resource.reaction_components() {
+                Some(components) => {
+                    let mut stock = totals.clone();
+                    components
+                        .iter()
+                        .all(|&component| consume(component, want - have, &mut stock))
+                }
+                None => false,
+            };
+            Some(BoostShortfall {
+                resource,
+                have,
+                want,
+                producible_now,
+            })
+        })
+        .collect();
+    shortfalls.sort_by_key(|shortfall| shortfall.resource as u16);
+    shortfalls
+}
+
+/// Takes `amount` of `resource` out of `stock`, reacting whatever is missing
+/// from its components. Each reaction consumes one unit of each component per
+/// unit produced.
+///
+/// Returns false if base minerals run out.
+fn consume(resource: ResourceType, amount: u32, stock: &mut HashMap<ResourceType, u32>) -> bool {
+    let stored = stock.entry(resource).or_insert(0);
+    let taken = amount.min(*stored);
+    *stored -= taken;
+    let missing = amount - taken;
+    if missing == 0 {
+        return true;
+    }
+
+    match resource.reaction_components() {
+        Some(components) => components
+            .iter()
+            .all(|&component| consume(component, missing, stock)),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{boost_audit, lab_throughput, labs_needed_for, BoostShortfall};
+    use crate::{constants::ResourceType::*, local::RoomName};
+
+    #[test]
+    fn tier_3_targets() {
+        let mut inventories = HashMap::new();
+        inventories.insert(
+            RoomName::new("W1N1").unwrap(),
+            vec![
+                (CatalyzedGhodiumAcid, 2000),
+                (CatalyzedUtriumAcid, 1000),
+                (Catalyst, 3000),
+                (Hydrogen, 1500),
+                (Oxygen, 1000),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        inventories.insert(
+            RoomName::new("W2N1").unwrap(),
+            vec![
+                (CatalyzedGhodiumAcid, 1000),
+                (UtriumAcid, 500),
+                (Hydrogen, 500),
+                (Utrium, 200),
+                (Keanium, 100),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        inventories.insert(RoomName::new("W3N1").unwrap(), HashMap::new());
+
+        let targets = vec![
+            // enough stored across both rooms
+            (CatalyzedGhodiumAcid, 3000),
+            // 500 UH2O on hand plus 200 more from U + H + OH
+            (CatalyzedUtriumAcid, 1700),
+            // also producible on its own, competing for the same utrium
+            (CatalyzedUtriumAlkalide, 100),
+            // only enough keanium for 100
+            (CatalyzedKeaniumAlkalide, 200),
+            // base minerals aren't produced by reactions
+            (Zynthium, 1000),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            boost_audit(&inventories, &targets),
+            vec![
+                BoostShortfall {
+                    resource: Zynthium,
+                    have: 0,
+                    want: 1000,
+                    producible_now: false,
+                },
+                BoostShortfall {
+                    resource: CatalyzedUtriumAcid,
+                    have: 1000,
+                    want: 1700,
+                    producible_now: true,
+                },
+                BoostShortfall {
+                    resource: CatalyzedUtriumAlkalide,
+                    have: 0,
+                    want: 100,
+                    producible_now: true,
+                },
+                BoostShortfall {
+                    resource: CatalyzedKeaniumAlkalide,
+                    have: 0,
+                    want: 200,
+                    producible_now: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn intermediate_shortage() {
+        let mut inventories = HashMap::new();
+        inventories.insert(
+            RoomName::new("E1S1").unwrap(),
+            vec![
+                (UtriumAcid, 100),
+                (Catalyst, 300),
+                (Utrium, 100),
+                (Hydrogen, 200),
+                (Oxygen, 99),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let mut targets = HashMap::new();
+
+        // 100 from UH2O, then 100 more from 100 U, 200 H and 100 O
+        targets.insert(CatalyzedUtriumAcid, 200);
+        assert!(!boost_audit(&inventories, &targets)[0].producible_now);
+
+        inventories
+            .get_mut(&RoomName::new("E1S1").unwrap())
+            .unwrap()
+            .insert(Oxygen, 100);
+        assert!(boost_audit(&inventories, &targets)[0].producible_now);
+
+        targets.insert(CatalyzedUtriumAcid, 0);
+        assert!(boost_audit(&inventories, &targets).is_empty());
+    }
+
+    #[test]
+    fn lab_throughput_table() {
+        let expected = [
+            (Hydroxide, 3, 0.25, 7200),
+            (Hydroxide, 6, 1.0, 28_800),
+            (Hydroxide, 10, 2.0, 57_600),
+            (CatalyzedGhodiumAcid, 3, 0.0625, 1800),
+            (CatalyzedGhodiumAcid, 6, 0.25, 7200),
+            (CatalyzedGhodiumAcid, 10, 0.5, 14_400),
+        ];
+        for &(product, labs, per_tick, per_day) in &expected {
+            let estimate = lab_throughput(labs, product).unwrap();
+            assert_eq!(estimate.per_tick, per_tick);
+            assert_eq!(estimate.per_day, per_day);
+            assert_eq!(labs_needed_for(product, per_day), Some(labs));
+        }
+
+        // the reagent labs alone produce nothing
+        assert_eq!(lab_throughput(2, Hydroxide).unwrap().per_day, 0);
+        assert_eq!(lab_throughput(0, Hydroxide).unwrap().per_tick, 0.0);
+        assert_eq!(lab_throughput(10, Energy), None);
+
+        // partial labs round up
+        assert_eq!(labs_needed_for(CatalyzedGhodiumAcid, 1801), Some(4));
+        assert_eq!(labs_needed_for(CatalyzedGhodiumAcid, 100_000), Some(58));
+        assert_eq!(labs_needed_for(Hydroxide, 0), Some(0));
+        assert_eq!(labs_needed_for(Utrium, 1000), None);
+    }
+}
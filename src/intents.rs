@@ -0,0 +1,438 @@
+//! Detecting conflicting intents issued on the same object in one tick.
+//!
+//! Issuing two intents which can't both happen, like two moves, or an attack
+//! and a heal, on the same creep is a common bug: the game silently keeps
+//! only one of them. With the `debug-intents` feature enabled, the action
+//! methods of creeps, power creeps and towers record each intent they issue,
+//! along with the location of the call and the tag set with [`with_tag`], and
+//! [`check`] reports the conflicts among them:
+//!
+//! ```no_run
+//! use screeps::{intents, prelude::*};
+//!
+//! for creep in screeps::game::creeps::values() {
+//!     intents::with_tag(&creep.name(), || {
+//!         // run the creep's role
+//!     });
+//! }
+//!
+//! for conflict in intents::check() {
+//!     log::warn!("{}", conflict);
+//! }
+//! ```
+//!
+//! Without the feature, nothing is recorded by the action methods, but
+//! intents issued by other means can still be recorded with [`record_for`].
+//!
+//! Two intents conflict when they are of the same [`IntentKind`], when both
+//! are in one of the game's action pipelines, where only the highest priority
+//! action takes effect (see [`IntentKind::is_exclusive_with`]), or when both
+//! transfer, withdraw or drop the same resource.
+//!
+//! To avoid such conflicts rather than detect them, queue a creep's actions
+//! in an [`ActionPipeline`], which only issues those that can take effect
+//! together.
+use std::{cell::RefCell, collections::HashMap, fmt, panic::Location};
+
+use parse_display::Display;
+
+use crate::constants::ResourceType;
+
+mod pipeline;
+
+pub use self::pipeline::{ActionPipeline, DropReason, DroppedAction, PipelineReport};
+
+/// Kinds of intents, named after the methods of the game's API issuing them.
+#[derive(Copy, Clone, Debug, Display, PartialEq, Eq, Hash)]
+#[display(style = "camelCase")]
+pub enum IntentKind {
+    Attack,
+    AttackController,
+    Build,
+    ClaimController,
+    Dismantle,
+    Drop,
+    EnableRoom,
+    GenerateSafeMode,
+    Harvest,
+    Heal,
+    /// Any movement, including `moveTo`, `moveByPath` and being pulled.
+    Move,
+    Pickup,
+    Pull,
+    RangedAttack,
+    RangedHeal,
+    RangedMassAttack,
+    Renew,
+    Repair,
+    ReserveController,
+    Say,
+    SignController,
+    Suicide,
+    Transfer,
+    UpgradeController,
+    UsePower,
+    Withdraw,
+}
+
+/// Actions of creeps of which only one takes effect each tick, the last one
+/// listed: attacking and healing a creep, for instance, only heals it.
+const MELEE_PIPELINE: &[IntentKind] = &[
+    IntentKind::Harvest,
+    IntentKind::Attack,
+    IntentKind::Build,
+    IntentKind::Repair,
+    IntentKind::Dismantle,
+    IntentKind::AttackController,
+    IntentKind::RangedHeal,
+    IntentKind::Heal,
+];
+
+/// Ranged actions of creeps of which only one takes effect each tick.
+const RANGED_PIPELINE: &[IntentKind] = &[
+    IntentKind::RangedAttack,
+    IntentKind::RangedMassAttack,
+    IntentKind::Build,
+    IntentKind::Repair,
+    IntentKind::RangedHeal,
+];
+
+impl IntentKind {
+    /// Whether this and a different kind of intent can't both take effect on
+    /// the same tick, being in the same action pipeline.
+    ///
+    /// The pipelines are those of creeps: `harvest`, `attack`, `build`,
+    /// `repair`, `dismantle`, `attackController`, `rangedHeal` and `heal` in
+    /// one, `rangedAttack`, `rangedMassAttack`, `build`, `repair` and
+    /// `rangedHeal` in the other. Towers, only able to do one of `attack`,
+    /// `heal` and `repair` each tick, fit the first pipeline.
+    pub fn is_exclusive_with(self, other: IntentKind) -> bool {
+        self != other
+            && [MELEE_PIPELINE, RANGED_PIPELINE]
+                .iter()
+                .any(|pipeline| pipeline.contains(&self) && pipeline.contains(&other))
+    }
+
+    /// Whether intents of this kind conflict when they move the same
+    /// resource.
+    fn moves_resource(self) -> bool {
+        matches!(
+            self,
+            IntentKind::Transfer | IntentKind::Withdraw | IntentKind::Drop
+        )
+    }
+}
+
+/// Where an intent was issued from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallSite {
+    /// The location of the call to the action method.
+    pub location: &'static Location<'static>,
+    /// The tag set with [`with_tag`] when the intent was issued.
+    pub tag: Option<String>,
+}
+
+impl CallSite {
+    /// The location of the caller, with the current tag.
+    #[track_caller]
+    pub fn here() -> Self {
+        CallSite {
+            location: Location::caller(),
+            tag: TAG.with(|tag| tag.borrow().clone()),
+        }
+    }
+}
+
+impl fmt::Display for CallSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.tag {
+            Some(tag) => write!(f, "{} [{}]", self.location, tag),
+            None => self.location.fmt(f),
+        }
+    }
+}
+
+/// An intent recorded in an [`IntentLog`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntentRecord {
+    pub kind: IntentKind,
+    /// The resource transferred, withdrawn or dropped, if any.
+    pub resource: Option<ResourceType>,
+    pub site: CallSite,
+}
+
+impl fmt::Display for IntentRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.resource {
+            Some(resource) => write!(f, "{} of {} at {}", self.kind, resource, self.site),
+            None => write!(f, "{} at {}", self.kind, self.site),
+        }
+    }
+}
+
+/// Why two intents conflict.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    /// Both intents are of the same kind, and only the last one is kept.
+    Duplicate,
+    /// The intents are in the same action pipeline, and only the highest
+    /// priority one takes effect.
+    Exclusive,
+    /// Both intents transfer, withdraw or drop the same resource.
+    SameResource,
+}
+
+/// Two intents on the same object which can't both take effect.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntentConflict {
+    /// The id of the object the intents were issued on.
+    pub object: String,
+    pub kind: ConflictKind,
+    /// The intent issued first.
+    pub first: IntentRecord,
+    /// The intent issued later.
+    pub second: IntentRecord,
+}
+
+impl fmt::Display for IntentConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.kind {
+            ConflictKind::Duplicate => "duplicates",
+            ConflictKind::Exclusive => "is exclusive with",
+            ConflictKind::SameResource => "moves the same resource as",
+        };
+        write!(
+            f,
+            "conflicting intents on {}: {} {} {}",
+            self.object, self.second, reason, self.first
+        )
+    }
+}
+
+/// The intents issued on each object, in the order they were issued.
+#[derive(Clone, Debug, Default)]
+pub struct IntentLog {
+    objects: Vec<(String, Vec<IntentRecord>)>,
+    /// Index of each object's entry in `objects`.
+    index: HashMap<String, usize>,
+}
+
+impl IntentLog {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an intent on the object with the given id.
+    pub fn record(&mut self, object: &str, record: IntentRecord) {
+        match self.index.get(object) {
+            Some(&i) => self.objects[i].1.push(record),
+            None => {
+                self.index.insert(object.to_owned(), self.objects.len());
+                self.objects.push((object.to_owned(), vec![record]));
+            }
+        }
+    }
+
+    /// Whether no intents have been recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Forgets all recorded intents.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.objects.clear();
+        self.index.clear();
+    }
+
+    /// Finds the conflicting pairs of intents on each object, in the order
+    /// the objects were first given an intent and the later intent of each
+    /// pair was issued.
+    pub fn conflicts(&self) -> Vec<IntentConflict> {
+        let mut conflicts = Vec::new();
+        for (object, records) in &self.objects {
+            for (i, second) in records.iter().enumerate() {
+                for first in &records[..i] {
+                    let kind = if first.kind == second.kind {
+                        ConflictKind::Duplicate
+                    } else if first.kind.is_exclusive_with(second.kind) {
+                        ConflictKind::Exclusive
+                    } else if first.kind.moves_resource()
+                        && second.kind.moves_resource()
+                        && first.resource.is_some()
+                        && first.resource == second.resource
+                    {
+                        ConflictKind::SameResource
+                    } else {
+                        continue;
+                    };
+                    conflicts.push(IntentConflict {
+                        object: object.clone(),
+                        kind,
+                        first: first.clone(),
+                        second: second.clone(),
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+thread_local! {
+    static LOG: RefCell<IntentLog> = RefCell::new(IntentLog::new());
+    static TAG: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Runs `f`, tagging the intents it issues with `tag`, such as the name of
+/// the creep or role issuing them.
+///
+/// The previous tag is restored afterwards, so tags can be nested.
+pub fn with_tag<F, R>(tag: &str, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let previous = TAG.with(|current| current.replace(Some(tag.to_owned())));
+    let result = f();
+    TAG.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// Records an intent on the object with the given id, issued from the
+/// caller's location.
+///
+/// The crate's action methods do this themselves when the `debug-intents`
+/// feature is enabled; this is for intents issued in other ways.
+#[track_caller]
+pub fn record_for(object_id: &str, kind: IntentKind, resource: Option<ResourceType>) {
+    let record = IntentRecord {
+        kind,
+        resource,
+        site: CallSite::here(),
+    };
+    LOG.with(|log| log.borrow_mut().record(object_id, record));
+}
+
+/// Records an intent issued by one of the crate's action methods on a game
+/// object.
+#[cfg(feature = "debug-intents")]
+#[track_caller]
+pub(crate) fn record(object: &stdweb::Reference, kind: IntentKind, resource: Option<ResourceType>) {
+    let id: String = js_unwrap!(@{object}.id);
+    record_for(&id, kind, resource);
+}
+
+/// Reports the conflicts among the intents recorded since the last check,
+/// and forgets them.
+///
+/// Call this once at the end of each tick.
+pub fn check() -> Vec<IntentConflict> {
+    LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        let conflicts = log.conflicts();
+        log.clear();
+        conflicts
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::Location;
+
+    use super::{check, record_for, with_tag, ConflictKind, IntentKind};
+    use crate::constants::ResourceType;
+
+    #[test]
+    fn exclusive_kinds() {
+        use IntentKind::*;
+
+        assert!(Attack.is_exclusive_with(Heal));
+        assert!(Heal.is_exclusive_with(Harvest));
+        assert!(RangedAttack.is_exclusive_with(RangedHeal));
+        assert!(RangedMassAttack.is_exclusive_with(Build));
+        assert!(!Attack.is_exclusive_with(RangedAttack));
+        assert!(!Harvest.is_exclusive_with(Move));
+        assert!(!Transfer.is_exclusive_with(Drop));
+        assert!(!Move.is_exclusive_with(Move));
+        assert_eq!(RangedMassAttack.to_string(), "rangedMassAttack");
+    }
+
+    #[test]
+    fn conflicting_intents() {
+        let first_move = Location::caller();
+        record_for("creep1", IntentKind::Move, None);
+        with_tag("harvester", || {
+            record_for("creep1", IntentKind::Harvest, None);
+            record_for("creep2", IntentKind::Move, None);
+            with_tag("inner", || {
+                record_for("creep1", IntentKind::Move, None);
+            });
+            record_for("creep1", IntentKind::Transfer, Some(ResourceType::Energy));
+        });
+        record_for("creep1", IntentKind::Drop, Some(ResourceType::Energy));
+        record_for("creep1", IntentKind::Drop, Some(ResourceType::Hydrogen));
+        record_for("creep2", IntentKind::Attack, None);
+        record_for("creep2", IntentKind::Heal, None);
+
+        let conflicts = check();
+        let summary: Vec<_> = conflicts
+            .iter()
+            .map(|c| (c.object.as_str(), c.kind, c.first.kind, c.second.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "creep1",
+                    ConflictKind::Duplicate,
+                    IntentKind::Move,
+                    IntentKind::Move
+                ),
+                (
+                    "creep1",
+                    ConflictKind::SameResource,
+                    IntentKind::Transfer,
+                    IntentKind::Drop
+                ),
+                (
+                    "creep1",
+                    ConflictKind::Duplicate,
+                    IntentKind::Drop,
+                    IntentKind::Drop
+                ),
+                (
+                    "creep2",
+                    ConflictKind::Exclusive,
+                    IntentKind::Attack,
+                    IntentKind::Heal
+                ),
+            ]
+        );
+
+        let moves = &conflicts[0];
+        assert_eq!(moves.first.site.location.file(), file!());
+        assert_eq!(moves.first.site.location.line(), first_move.line() + 1);
+        assert_eq!(moves.first.site.tag, None);
+        assert_eq!(moves.second.site.location.line(), first_move.line() + 6);
+        assert_eq!(moves.second.site.tag.as_deref(), Some("inner"));
+        assert_eq!(conflicts[1].first.site.tag.as_deref(), Some("harvester"));
+        assert_eq!(conflicts[1].second.site.tag, None);
+        let message = conflicts[1].to_string();
+        assert!(
+            message.starts_with("conflicting intents on creep1: drop of energy at "),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains(" moves the same resource as transfer of energy at "),
+            "{}",
+            message
+        );
+        assert!(message.ends_with(" [harvester]"), "{}", message);
+
+        // checking forgets the intents
+        assert_eq!(check(), vec![]);
+    }
+}
@@ -0,0 +1,331 @@
+//! Issuing only the actions of a creep which can all take effect in one tick.
+use std::fmt;
+
+use crate::constants::ReturnCode;
+
+use super::IntentKind;
+
+/// Why an action queued in an [`ActionPipeline`] wasn't issued.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DropReason {
+    /// An action of the same kind was issued successfully, and the game keeps
+    /// only one.
+    Duplicate,
+    /// An action in the same action pipeline was issued successfully, and the
+    /// game only performs one of them (see [`IntentKind::is_exclusive_with`]).
+    Exclusive,
+}
+
+/// An action queued in an [`ActionPipeline`] which wasn't issued.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DroppedAction {
+    pub kind: IntentKind,
+    pub priority: u32,
+    pub reason: DropReason,
+    /// The successfully issued action it was dropped in favor of.
+    pub kept: IntentKind,
+    /// The priority of the successfully issued action it was dropped in
+    /// favor of.
+    pub kept_priority: u32,
+}
+
+impl fmt::Display for DroppedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.reason {
+            DropReason::Duplicate => "is a duplicate of",
+            DropReason::Exclusive => "is exclusive with",
+        };
+        write!(
+            f,
+            "dropped {} (priority {}): {} {} (priority {})",
+            self.kind, self.priority, reason, self.kept, self.kept_priority
+        )
+    }
+}
+
+/// The outcome of [`ActionPipeline::commit`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PipelineReport {
+    /// The actions issued, in the order they were issued, with the return
+    /// codes of the calls issuing them, including those which failed.
+    pub issued: Vec<(IntentKind, ReturnCode)>,
+    /// The actions not issued, in the order they were queued.
+    pub dropped: Vec<DroppedAction>,
+}
+
+impl PipelineReport {
+    /// Whether an action of this kind was issued successfully.
+    pub fn was_issued(&self, kind: IntentKind) -> bool {
+        self.issued
+            .iter()
+            .any(|&(issued, code)| issued == kind && code == ReturnCode::Ok)
+    }
+
+    /// The actions issued whose calls returned an error, in the order they
+    /// were issued.
+    pub fn failed(&self) -> impl Iterator<Item = (IntentKind, ReturnCode)> + '_ {
+        self.issued
+            .iter()
+            .copied()
+            .filter(|&(_, code)| code != ReturnCode::Ok)
+    }
+}
+
+struct QueuedAction<'a> {
+    kind: IntentKind,
+    priority: u32,
+    issue: Box<dyn FnOnce() -> ReturnCode + 'a>,
+}
+
+/// Actions wanted from a creep this tick, of which only a subset that can
+/// all take effect together is issued.
+///
+/// The game silently ignores all but one action of a creep in each of its
+/// action pipelines, such as a `repair` issued along with a `heal`. Queuing
+/// the actions instead of issuing them directly issues them by priority,
+/// falling back to the next action of a pipeline when one fails, and reports
+/// the others:
+///
+/// ```no_run
+/// use screeps::{intents::{ActionPipeline, IntentKind}, prelude::*, Creep, StructureRoad};
+///
+/// # fn run(creep: &Creep, road: &StructureRoad, hurt: &Creep) {
+/// let report = ActionPipeline::new()
+///     .queue(IntentKind::Repair, 1, || creep.repair(road))
+///     .queue(IntentKind::Heal, 10, || creep.heal(hurt))
+///     .queue(IntentKind::Move, 5, || creep.move_to(hurt))
+///     .commit();
+/// // `heal` and `move` are issued, and `repair` is dropped unless `heal`
+/// // fails, such as when `hurt` is out of range
+/// for dropped in &report.dropped {
+///     log::debug!("{}: {}", creep.name(), dropped);
+/// }
+/// # }
+/// ```
+#[derive(Default)]
+pub struct ActionPipeline<'a> {
+    actions: Vec<QueuedAction<'a>>,
+}
+
+impl<'a> ActionPipeline<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        ActionPipeline {
+            actions: Vec::new(),
+        }
+    }
+
+    /// Queues an action of the given kind, issued by calling `issue`.
+    ///
+    /// Actions with a higher priority are preferred, and among actions of
+    /// the same priority, those queued first.
+    pub fn queue<F>(mut self, kind: IntentKind, priority: u32, issue: F) -> Self
+    where
+        F: FnOnce() -> ReturnCode + 'a,
+    {
+        self.actions.push(QueuedAction {
+            kind,
+            priority,
+            issue: Box::new(issue),
+        });
+        self
+    }
+
+    /// Issues the queued actions which can take effect together, in order
+    /// of priority.
+    ///
+    /// Each action is issued unless an action of the same kind, or one it's
+    /// exclusive with, was already issued successfully. An action whose call
+    /// returns an error doesn't take effect, so it doesn't prevent the
+    /// actions after it from being issued.
+    pub fn commit(self) -> PipelineReport {
+        let mut actions: Vec<(usize, QueuedAction<'a>)> =
+            self.actions.into_iter().enumerate().collect();
+        // stable, so ties stay in the order they were queued
+        actions.sort_by_key(|(_, action)| std::cmp::Reverse(action.priority));
+
+        let mut kept: Vec<(IntentKind, u32)> = Vec::new();
+        let mut issued = Vec::new();
+        let mut dropped = Vec::new();
+        for (i, action) in actions {
+            match conflict(&kept, action.kind) {
+                Some((kept_kind, kept_priority, reason)) => dropped.push((
+                    i,
+                    DroppedAction {
+                        kind: action.kind,
+                        priority: action.priority,
+                        reason,
+                        kept: kept_kind,
+                        kept_priority,
+                    },
+                )),
+                None => {
+                    let code = (action.issue)();
+                    if code == ReturnCode::Ok {
+                        kept.push((action.kind, action.priority));
+                    }
+                    issued.push((action.kind, code));
+                }
+            }
+        }
+
+        dropped.sort_by_key(|&(i, _)| i);
+        PipelineReport {
+            issued,
+            dropped: dropped.into_iter().map(|(_, action)| action).collect(),
+        }
+    }
+}
+
+/// The first of the `kept` actions, of the given kinds and priorities, which
+/// an action of kind `kind` conflicts with, along with the reason.
+fn conflict(kept: &[(IntentKind, u32)], kind: IntentKind) -> Option<(IntentKind, u32, DropReason)> {
+    kept.iter().find_map(|&(kept, priority)| {
+        if kept == kind {
+            Some((kept, priority, DropReason::Duplicate))
+        } else if kept.is_exclusive_with(kind) {
+            Some((kept, priority, DropReason::Exclusive))
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+
+    use super::{ActionPipeline, DropReason, DroppedAction, IntentKind, IntentKind::*};
+    use crate::constants::ReturnCode;
+
+    /// The kinds issued and dropped when queuing `actions` in order, with
+    /// the given priorities.
+    fn outcome(actions: &[(IntentKind, u32)]) -> (Vec<IntentKind>, Vec<IntentKind>) {
+        let calls = RefCell::new(Vec::new());
+        let mut pipeline = ActionPipeline::new();
+        for &(kind, priority) in actions {
+            let calls = &calls;
+            pipeline = pipeline.queue(kind, priority, move || {
+                calls.borrow_mut().push(kind);
+                ReturnCode::Ok
+            });
+        }
+        let report = pipeline.commit();
+
+        let issued: Vec<IntentKind> = report.issued.iter().map(|&(kind, _)| kind).collect();
+        assert_eq!(issued, calls.into_inner());
+        let dropped = report.dropped.iter().map(|action| action.kind).collect();
+        (issued, dropped)
+    }
+
+    #[test]
+    fn allowed_combinations() {
+        // actions outside of the pipelines go with anything
+        assert_eq!(
+            outcome(&[(Move, 1), (Harvest, 1), (Transfer, 1), (Say, 1)]),
+            (vec![Move, Harvest, Transfer, Say], vec![])
+        );
+        // one action of each pipeline
+        assert_eq!(
+            outcome(&[(Heal, 1), (RangedAttack, 1), (Move, 1)]),
+            (vec![Heal, RangedAttack, Move], vec![])
+        );
+        assert_eq!(
+            outcome(&[(Attack, 1), (RangedMassAttack, 1)]),
+            (vec![Attack, RangedMassAttack], vec![])
+        );
+        assert_eq!(
+            outcome(&[(Harvest, 1), (RangedAttack, 1), (Pickup, 1), (Drop, 1)]),
+            (vec![Harvest, RangedAttack, Pickup, Drop], vec![])
+        );
+        assert_eq!(
+            outcome(&[(Dismantle, 1), (RangedAttack, 1), (Withdraw, 1)]),
+            (vec![Dismantle, RangedAttack, Withdraw], vec![])
+        );
+    }
+
+    #[test]
+    fn disallowed_combinations() {
+        // the same pipeline: build, repair and rangedHeal are in both
+        assert_eq!(
+            outcome(&[(Move, 1), (Build, 1), (Heal, 2)]),
+            (vec![Heal, Move], vec![Build])
+        );
+        assert_eq!(
+            outcome(&[(Attack, 2), (Heal, 1)]),
+            (vec![Attack], vec![Heal])
+        );
+        assert_eq!(
+            outcome(&[(Harvest, 1), (Repair, 1)]),
+            (vec![Harvest], vec![Repair])
+        );
+        assert_eq!(
+            outcome(&[(RangedAttack, 1), (RangedHeal, 3), (Build, 2)]),
+            (vec![RangedHeal], vec![RangedAttack, Build])
+        );
+        assert_eq!(
+            outcome(&[(RangedMassAttack, 1), (Repair, 1), (Attack, 1)]),
+            (vec![RangedMassAttack, Attack], vec![Repair])
+        );
+        assert_eq!(
+            outcome(&[(AttackController, 1), (Dismantle, 1)]),
+            (vec![AttackController], vec![Dismantle])
+        );
+        // duplicates, even outside the pipelines
+        assert_eq!(
+            outcome(&[(Move, 1), (Move, 2), (Transfer, 1), (Transfer, 1)]),
+            (vec![Move, Transfer], vec![Move, Transfer])
+        );
+    }
+
+    #[test]
+    fn drop_reasons() {
+        let report = ActionPipeline::new()
+            .queue(Repair, 1, || ReturnCode::Ok)
+            .queue(Heal, 10, || ReturnCode::NotInRange)
+            .queue(Heal, 3, || ReturnCode::Ok)
+            .queue(Move, 5, || ReturnCode::Tired)
+            .commit();
+
+        // the failed heal and move don't count, so the second heal is issued
+        assert_eq!(
+            report.issued,
+            vec![
+                (Heal, ReturnCode::NotInRange),
+                (Move, ReturnCode::Tired),
+                (Heal, ReturnCode::Ok)
+            ]
+        );
+        assert_eq!(
+            report.failed().collect::<Vec<_>>(),
+            vec![(Heal, ReturnCode::NotInRange), (Move, ReturnCode::Tired)]
+        );
+        assert!(report.was_issued(Heal));
+        assert!(!report.was_issued(Move));
+        assert!(!report.was_issued(Repair));
+        assert_eq!(
+            report.dropped,
+            vec![DroppedAction {
+                kind: Repair,
+                priority: 1,
+                reason: DropReason::Exclusive,
+                kept: Heal,
+                kept_priority: 3,
+            }]
+        );
+        assert_eq!(
+            report.dropped[0].to_string(),
+            "dropped repair (priority 1): is exclusive with heal (priority 3)"
+        );
+
+        let report = ActionPipeline::new()
+            .queue(Repair, 1, || ReturnCode::Ok)
+            .queue(Heal, 10, || ReturnCode::NotInRange)
+            .commit();
+        assert_eq!(
+            report.issued,
+            vec![(Heal, ReturnCode::NotInRange), (Repair, ReturnCode::Ok)]
+        );
+        assert!(report.dropped.is_empty());
+    }
+}
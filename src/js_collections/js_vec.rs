@@ -3,7 +3,7 @@ use crate::{
     traits::{FromExpectedType, IntoExpectedType, TryFrom, TryInto},
     ConversionError,
 };
-use std::marker::PhantomData;
+use std::{error::Error, fmt, marker::PhantomData};
 use stdweb::{Array, InstanceOf, JsSerialize, Reference, ReferenceType, Value};
 
 //
@@ -92,10 +92,21 @@ where
         TryIntoIter::new(self)
     }
 
-    /// Turns this remote JS array into a local `Vec`, returning an error if any
-    /// elements are not the expected type and `check-all-casts` is enabled.
-    pub fn try_local(&self) -> Result<Vec<T>, ConversionError> {
-        self.try_iter().collect()
+    /// Turns this remote JS array into a local `Vec`, returning an error
+    /// naming the first element which is not the expected type if
+    /// `check-all-casts` is enabled.
+    pub fn try_local(&self) -> Result<Vec<T>, ElementConversionError> {
+        collect_strict(self.try_iter())
+    }
+
+    /// Turns this remote JS array into a local `Vec` of the elements which
+    /// are the expected type, along with an error for each element which
+    /// isn't if `check-all-casts` is enabled.
+    ///
+    /// Useful when a single unexpected element, such as a corrupt entry,
+    /// shouldn't prevent processing the rest.
+    pub fn try_collect_partial(&self) -> (Vec<T>, Vec<ElementConversionError>) {
+        collect_partial(self.try_iter())
     }
 
     /// Turns this remote JS array into a local `Vec`, panicking if any elements
@@ -105,6 +116,58 @@ where
     }
 }
 
+/// Error returned by bulk conversions of [`JsVec`] elements, naming the
+/// element which couldn't be converted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElementConversionError {
+    /// The index of the element in the array.
+    pub index: usize,
+    /// Why the element couldn't be converted, such as the expected and found
+    /// types.
+    pub kind: ConversionError,
+}
+
+impl fmt::Display for ElementConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "couldn't convert array element {}: {}",
+            self.index, self.kind
+        )
+    }
+}
+
+impl Error for ElementConversionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.kind)
+    }
+}
+
+fn collect_strict<T, I>(items: I) -> Result<Vec<T>, ElementConversionError>
+where
+    I: Iterator<Item = Result<T, ConversionError>>,
+{
+    items
+        .enumerate()
+        .map(|(index, item)| item.map_err(|kind| ElementConversionError { index, kind }))
+        .collect()
+}
+
+fn collect_partial<T, I>(items: I) -> (Vec<T>, Vec<ElementConversionError>)
+where
+    I: Iterator<Item = Result<T, ConversionError>>,
+{
+    let mut converted = Vec::new();
+    let mut errors = Vec::new();
+    for (index, item) in items.enumerate() {
+        match item {
+            Ok(item) => converted.push(item),
+            Err(kind) => errors.push(ElementConversionError { index, kind }),
+        }
+    }
+    (converted, errors)
+}
+
 pub struct IntoIter<T> {
     index: u32,
     inner: JsVec<T>,
@@ -297,10 +360,11 @@ impl<T> TryInto<Vec<T>> for JsVec<T>
 where
     T: TryFrom<Value, Error = ConversionError>,
 {
-    type Error = ConversionError;
+    type Error = ElementConversionError;
 
     fn try_into(self) -> Result<Vec<T>, Self::Error> {
-        self.inner.try_into()
+        let values: Vec<Value> = self.inner.into();
+        collect_strict(values.into_iter().map(T::try_from))
     }
 }
 
@@ -404,3 +468,73 @@ where
         (&*v).into()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{collect_partial, collect_strict, ElementConversionError};
+    use crate::ConversionError;
+
+    /// An element of a fixture array, standing in for a JavaScript value.
+    #[derive(Copy, Clone)]
+    enum Element {
+        Number(u32),
+        Text,
+        Bool,
+    }
+
+    use Element::*;
+
+    /// Converts elements as an array expected to contain numbers would.
+    fn convert(elements: &[Element]) -> impl Iterator<Item = Result<u32, ConversionError>> + '_ {
+        elements.iter().map(|element| {
+            let actual = match *element {
+                Number(number) => return Ok(number),
+                Text => "String",
+                Bool => "Boolean",
+            };
+            Err(ConversionError::TypeMismatch {
+                expected: "Number".into(),
+                actual: actual.into(),
+            })
+        })
+    }
+
+    /// An array of numbers with a string and a boolean mixed in.
+    const MIXED: [Element; 5] = [Number(1), Text, Number(3), Bool, Number(5)];
+
+    #[test]
+    fn strict_conversion() {
+        assert_eq!(
+            collect_strict(convert(&[Number(1), Number(2)])),
+            Ok(vec![1, 2])
+        );
+
+        let error = collect_strict(convert(&MIXED)).unwrap_err();
+        assert_eq!(error.index, 1);
+        match &error.kind {
+            ConversionError::TypeMismatch { expected, actual } => {
+                assert_eq!(expected, "Number");
+                assert_eq!(actual, "String");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(error
+            .to_string()
+            .starts_with("couldn't convert array element 1: "));
+    }
+
+    #[test]
+    fn partial_conversion() {
+        let (numbers, errors) = collect_partial(convert(&MIXED));
+        assert_eq!(numbers, vec![1, 3, 5]);
+        let indices: Vec<usize> = errors
+            .iter()
+            .map(|&ElementConversionError { index, .. }| index)
+            .collect();
+        assert_eq!(indices, vec![1, 3]);
+
+        let (numbers, errors) = collect_partial(convert(&[]));
+        assert!(numbers.is_empty());
+        assert!(errors.is_empty());
+    }
+}
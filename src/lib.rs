@@ -17,6 +17,35 @@
 //! # ...
 //! screeps-game-api = { version = "0.3", features = ["check-all-casts"] }
 //! ```
+//!
+//! ## `debug-intents`
+//!
+//! Records the intents issued through the action methods of creeps, power
+//! creeps and towers, with the location of each call, so that conflicting
+//! intents on the same object can be reported by [`intents::check`]. This has
+//! a cost on every action, and is meant for debugging.
+//!
+//! ## `count-references`
+//!
+//! Counts the live wrappers of each type of Screeps object, such as
+//! [`Creep`], reported by [`debug::live_wrappers`]. This adds a counter to
+//! every wrapper, and is meant for finding wrappers leaking across ticks.
+//!
+//! ## `quickcheck`
+//!
+//! Implements `quickcheck::Arbitrary` for [`RoomName`], [`Position`],
+//! [`Direction`], [`StructureType`] and [`ResourceType`], for property
+//! testing code built on them. Room names are drawn from the whole world and
+//! shrink towards `E0N0`, and positions shrink towards their room's origin.
+//! quickcheck doesn't build for `wasm32-unknown-unknown`, so this is only
+//! meant for tests run natively, such as with `cargo test`.
+//!
+//! [`RoomName`]: crate::local::RoomName
+//! [`Position`]: crate::local::Position
+//! [`Direction`]: crate::constants::Direction
+//! [`StructureType`]: crate::constants::StructureType
+//! [`ResourceType`]: crate::constants::ResourceType
+//! [`Creep`]: crate::objects::Creep
 #![recursion_limit = "128"]
 
 #[macro_use]
@@ -25,23 +54,47 @@ extern crate stdweb;
 #[macro_use]
 pub mod macros;
 
+#[cfg(feature = "quickcheck")]
+mod arbitrary;
+
+pub mod body;
+pub mod combat;
 pub mod constants;
+pub mod debug;
+pub mod diplomacy;
+pub mod economy;
+pub mod formation;
 pub mod game;
+pub mod industry;
+pub mod intents;
 pub mod inter_shard_memory;
 pub mod js_collections;
 pub mod local;
+pub mod logistics;
 pub mod memory;
 pub mod objects;
 pub mod pathfinder;
+pub mod planning;
+pub mod profiling;
 pub mod raw_memory;
+pub mod salvage;
+pub mod scheduling;
+pub mod spawning;
+pub mod static_info;
+pub mod toggles;
 pub mod traits;
+pub mod valuation;
+pub mod visuals;
 
 pub use stdweb::private::ConversionError;
 
 pub use crate::{
     constants::*,
     js_collections::JsVec,
-    local::{ObjectId, Position, RawObjectId, RawObjectIdParseError, RoomName, RoomNameParseError},
+    local::{
+        Axis, CoordinateError, GameTime, ObjectId, Position, RawObjectId, RawObjectIdParseError,
+        RoomName, RoomNameParseError, TickDuration,
+    },
     objects::*,
     traits::{FromExpectedType, IntoExpectedType},
 };
@@ -1,9 +1,14 @@
 //! Pure-data structures relating to Screeps.
 use std::ops::Range;
 
+pub mod assignment;
+
+mod creep_path;
+mod game_time;
 mod object_id;
 mod room_name;
 mod room_position;
+mod terrain;
 
 /// Represents two constants related to room names.
 ///
@@ -18,4 +23,6 @@ const HALF_WORLD_SIZE: i32 = 128;
 /// Valid room name coordinates.
 const VALID_ROOM_NAME_COORDINATES: Range<i32> = -HALF_WORLD_SIZE..HALF_WORLD_SIZE;
 
-pub use self::{object_id::*, room_name::*, room_position::*};
+pub use self::{
+    creep_path::*, game_time::*, object_id::*, room_name::*, room_position::*, terrain::*,
+};
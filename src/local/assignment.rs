@@ -0,0 +1,257 @@
+//! Utilities for matching a set of positions to another set of positions.
+//!
+//! The main entry point is [`nearest_assignment`], which pairs each creep
+//! with at most one target such that the sum of ranges between paired
+//! positions is minimal.
+use std::cmp::Ordering;
+
+use super::Position;
+
+/// Assigns each creep to at most one target, minimizing the total range
+/// between creeps and their assigned targets.
+///
+/// Range is calculated with [`Position::get_range_to`]. See
+/// [`nearest_assignment_by`] for using a different travel estimate.
+///
+/// The returned vector has one entry per creep, containing the index of the
+/// target assigned to that creep. When there are more creeps than targets,
+/// the creeps left without a target are assigned `None`; when there are more
+/// targets than creeps, some targets will be left unassigned.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{local::assignment::nearest_assignment, Position, RoomName};
+///
+/// let room = RoomName::new("W1N1").unwrap();
+/// let creeps = [Position::new(10, 10, room), Position::new(40, 40, room)];
+/// let targets = [Position::new(41, 41, room)];
+///
+/// assert_eq!(nearest_assignment(&creeps, &targets), vec![None, Some(0)]);
+/// ```
+pub fn nearest_assignment(creeps: &[Position], targets: &[Position]) -> Vec<Option<usize>> {
+    nearest_assignment_by(creeps, targets, |creep, target| creep.get_range_to(target))
+}
+
+/// Assigns each creep to at most one target, minimizing the total cost given
+/// by `cost` between creeps and their assigned targets.
+///
+/// `cost(creep, target)` is called exactly once for each creep/target pair,
+/// so it may be used with a more expensive travel estimate than the range
+/// used by [`nearest_assignment`].
+///
+/// When several assignments have the same total cost, the one chosen is
+/// deterministic: it depends only on the costs and on the order of `creeps`
+/// and `targets`.
+pub fn nearest_assignment_by<F>(
+    creeps: &[Position],
+    targets: &[Position],
+    mut cost: F,
+) -> Vec<Option<usize>>
+where
+    F: FnMut(&Position, &Position) -> u32,
+{
+    let costs: Vec<Vec<u32>> = creeps
+        .iter()
+        .map(|creep| targets.iter().map(|target| cost(creep, target)).collect())
+        .collect();
+
+    let mut result = vec![None; creeps.len()];
+
+    match creeps.len().cmp(&targets.len()) {
+        Ordering::Less | Ordering::Equal => {
+            let row_to_column = hungarian(creeps.len(), targets.len(), |r, c| costs[r][c]);
+            for (creep, target) in row_to_column.into_iter().enumerate() {
+                result[creep] = Some(target);
+            }
+        }
+        Ordering::Greater => {
+            let row_to_column = hungarian(targets.len(), creeps.len(), |r, c| costs[c][r]);
+            for (target, creep) in row_to_column.into_iter().enumerate() {
+                result[creep] = Some(target);
+            }
+        }
+    }
+
+    result
+}
+
+/// Solves the rectangular assignment problem for a `rows x columns` cost
+/// matrix, where `rows <= columns`, using the Hungarian algorithm.
+///
+/// Returns the column assigned to each row.
+fn hungarian<F>(rows: usize, columns: usize, cost: F) -> Vec<usize>
+where
+    F: Fn(usize, usize) -> u32,
+{
+    debug_assert!(rows <= columns);
+
+    if rows == 0 {
+        return Vec::new();
+    }
+
+    // potentials and matching use 1-based indices, with index 0 acting as a
+    // virtual starting column for each augmenting path.
+    let mut row_potential = vec![0i64; rows + 1];
+    let mut column_potential = vec![0i64; columns + 1];
+    let mut column_match = vec![0usize; columns + 1];
+    let mut way = vec![0usize; columns + 1];
+
+    for row in 1..=rows {
+        column_match[0] = row;
+        let mut current_column = 0;
+        let mut min_slack = vec![i64::MAX; columns + 1];
+        let mut used = vec![false; columns + 1];
+
+        loop {
+            used[current_column] = true;
+            let current_row = column_match[current_column];
+            let mut delta = i64::MAX;
+            let mut next_column = 0;
+
+            for column in 1..=columns {
+                if used[column] {
+                    continue;
+                }
+                let slack = i64::from(cost(current_row - 1, column - 1))
+                    - row_potential[current_row]
+                    - column_potential[column];
+                if slack < min_slack[column] {
+                    min_slack[column] = slack;
+                    way[column] = current_column;
+                }
+                // strict comparison keeps the lowest column index on ties
+                if min_slack[column] < delta {
+                    delta = min_slack[column];
+                    next_column = column;
+                }
+            }
+
+            for column in 0..=columns {
+                if used[column] {
+                    row_potential[column_match[column]] += delta;
+                    column_potential[column] -= delta;
+                } else {
+                    min_slack[column] -= delta;
+                }
+            }
+
+            current_column = next_column;
+            if column_match[current_column] == 0 {
+                break;
+            }
+        }
+
+        // walk the augmenting path back to the virtual column
+        loop {
+            let previous_column = way[current_column];
+            column_match[current_column] = column_match[previous_column];
+            current_column = previous_column;
+            if current_column == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut row_to_column = vec![0; rows];
+    for column in 1..=columns {
+        if column_match[column] != 0 {
+            row_to_column[column_match[column] - 1] = column - 1;
+        }
+    }
+    row_to_column
+}
+
+#[cfg(test)]
+mod test {
+    use super::{nearest_assignment, nearest_assignment_by};
+    use crate::local::{Position, RoomName};
+
+    fn pos(x: u32, y: u32) -> Position {
+        Position::new(x, y, RoomName::new("E5N5").unwrap())
+    }
+
+    fn total_range(creeps: &[Position], targets: &[Position], result: &[Option<usize>]) -> u32 {
+        result
+            .iter()
+            .enumerate()
+            .filter_map(|(creep, target)| target.map(|t| creeps[creep].get_range_to(&targets[t])))
+            .sum()
+    }
+
+    #[test]
+    fn empty_inputs() {
+        assert_eq!(nearest_assignment(&[], &[]), vec![]);
+        assert_eq!(nearest_assignment(&[pos(1, 1)], &[]), vec![None]);
+        assert_eq!(
+            nearest_assignment(&[], &[pos(1, 1)]),
+            Vec::<Option<usize>>::new()
+        );
+    }
+
+    #[test]
+    fn beats_greedy_matching() {
+        // greedily taking the closest pair (creep 1, target 0) first would
+        // force creep 0 to walk across the room.
+        let creeps = [pos(10, 10), pos(20, 10)];
+        let targets = [pos(19, 10), pos(45, 10)];
+        let result = nearest_assignment(&creeps, &targets);
+
+        assert_eq!(result, vec![Some(0), Some(1)]);
+        assert_eq!(total_range(&creeps, &targets, &result), 9 + 25);
+
+        let creeps = [pos(10, 10), pos(11, 10)];
+        let targets = [pos(12, 10), pos(1, 10)];
+        let result = nearest_assignment(&creeps, &targets);
+
+        assert_eq!(result, vec![Some(1), Some(0)]);
+        assert_eq!(total_range(&creeps, &targets, &result), 9 + 1);
+    }
+
+    #[test]
+    fn more_creeps_than_targets() {
+        let creeps = [pos(1, 1), pos(25, 25), pos(48, 48), pos(26, 26)];
+        let targets = [pos(47, 47), pos(25, 24)];
+        let result = nearest_assignment(&creeps, &targets);
+
+        assert_eq!(result, vec![None, Some(1), Some(0), None]);
+        assert_eq!(total_range(&creeps, &targets, &result), 1 + 1);
+    }
+
+    #[test]
+    fn more_targets_than_creeps() {
+        let creeps = [pos(5, 5), pos(40, 5)];
+        let targets = [pos(45, 45), pos(6, 6), pos(40, 7), pos(20, 20)];
+        let result = nearest_assignment(&creeps, &targets);
+
+        assert_eq!(result, vec![Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn ties_are_deterministic() {
+        let creeps = [pos(10, 10), pos(10, 10)];
+        let targets = [pos(12, 10), pos(8, 10)];
+
+        let first = nearest_assignment(&creeps, &targets);
+        assert_eq!(first, vec![Some(0), Some(1)]);
+        for _ in 0..10 {
+            assert_eq!(nearest_assignment(&creeps, &targets), first);
+        }
+    }
+
+    #[test]
+    fn custom_cost() {
+        let creeps = [pos(10, 10), pos(30, 10)];
+        let targets = [pos(20, 10), pos(40, 10)];
+
+        // penalize target 0 heavily for creep 0, forcing the other pairing
+        let result = nearest_assignment_by(&creeps, &targets, |creep, target| {
+            if *creep == creeps[0] && *target == targets[0] {
+                1000
+            } else {
+                creep.get_range_to(target)
+            }
+        });
+        assert_eq!(result, vec![Some(1), Some(0)]);
+    }
+}
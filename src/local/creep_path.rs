@@ -0,0 +1,439 @@
+//! Compact storage of a path being followed by a creep.
+use std::{error::Error, fmt, str::FromStr};
+
+use num_traits::FromPrimitive;
+use serde::{
+    de::{Deserializer, Error as _},
+    Deserialize, Serialize, Serializer,
+};
+
+use super::{Position, HALF_WORLD_SIZE};
+use crate::constants::Direction;
+
+/// A path being followed by a creep, compact enough to be kept in creep
+/// memory.
+///
+/// The path is stored as a start position and a list of directions, along
+/// with the index of the next step to take. Its string form, used by its
+/// `Display`, `FromStr`, `Serialize` and `Deserialize` implementations, packs
+/// directions two per byte and encodes everything as base64, using around 3
+/// characters per 4 steps.
+///
+/// Parsing a corrupted string fails with a [`CreepPathParseError`] rather than
+/// panicking, which callers will usually want to treat as a reason to find a
+/// new path.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{local::CreepPathMemory, Direction, Position, RoomName};
+///
+/// let start = Position::new(10, 10, RoomName::new("W1N1").unwrap());
+/// let mut path = CreepPathMemory::new(start, vec![Direction::Right, Direction::Bottom]);
+///
+/// assert_eq!(path.current_target(), Some(Position::new(11, 10, start.room_name())));
+/// path.advance();
+///
+/// let stored = path.to_string();
+/// let path: CreepPathMemory = stored.parse().unwrap();
+/// assert_eq!(path.remaining(), 1);
+/// assert_eq!(path.current_target(), Some(Position::new(11, 11, start.room_name())));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CreepPathMemory {
+    start: Position,
+    directions: Vec<Direction>,
+    index: usize,
+    current: Position,
+}
+
+impl CreepPathMemory {
+    /// Creates a path starting at `start` and following `directions`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if following the path would leave the world, or if it's longer
+    /// than 65535 steps.
+    pub fn new(start: Position, directions: Vec<Direction>) -> Self {
+        assert!(
+            directions.len() <= usize::from(u16::MAX),
+            "path too long: {} steps",
+            directions.len()
+        );
+        assert!(
+            fits_in_world(start, &directions),
+            "path leaves the world bounds"
+        );
+
+        CreepPathMemory {
+            start,
+            directions,
+            index: 0,
+            current: start,
+        }
+    }
+
+    /// The position the path starts at.
+    #[inline]
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    /// The position the creep should be at, having taken all steps before
+    /// the current one.
+    #[inline]
+    pub fn current_position(&self) -> Position {
+        self.current
+    }
+
+    /// The direction of the next step to take, or `None` once the path is
+    /// complete.
+    #[inline]
+    pub fn next_direction(&self) -> Option<Direction> {
+        self.directions.get(self.index).copied()
+    }
+
+    /// The position reached by taking the next step, or `None` once the path
+    /// is complete.
+    #[inline]
+    pub fn current_target(&self) -> Option<Position> {
+        self.next_direction().map(|dir| self.current + dir.offset())
+    }
+
+    /// Marks the next step as taken, returning the new current position, or
+    /// `None` if the path was already complete.
+    pub fn advance(&mut self) -> Option<Position> {
+        let target = self.current_target()?;
+        self.index += 1;
+        self.current = target;
+        Some(target)
+    }
+
+    /// The number of steps left to take.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.directions.len() - self.index
+    }
+
+    /// Whether all steps of the path have been taken.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// The directions of the steps left to take.
+    #[inline]
+    pub fn remaining_directions(&self) -> &[Direction] {
+        &self.directions[self.index..]
+    }
+}
+
+impl fmt::Display for CreepPathMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut bytes = Vec::with_capacity(6 + (self.directions.len() + 1) / 2);
+        bytes.extend_from_slice(&(self.start.packed_repr() as u32).to_be_bytes());
+        bytes.extend_from_slice(&(self.index as u16).to_be_bytes());
+        for pair in self.directions.chunks(2) {
+            let high = pair[0] as u8;
+            let low = pair.get(1).map(|d| *d as u8).unwrap_or(0);
+            bytes.push(high << 4 | low);
+        }
+        f.write_str(&base64::encode(&bytes))
+    }
+}
+
+/// Error parsing a [`CreepPathMemory`] from its string representation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CreepPathParseError {
+    /// The string isn't valid base64.
+    InvalidEncoding,
+    /// The string is too short to hold a start position and an index.
+    TooShort,
+    /// The start position's coordinates are out of bounds.
+    InvalidStart,
+    /// A packed direction isn't a valid direction.
+    InvalidDirection { value: u8 },
+    /// The index of the next step is past the end of the path.
+    IndexOutOfBounds { index: usize, length: usize },
+    /// Following the path would leave the world.
+    LeavesWorld,
+}
+
+impl fmt::Display for CreepPathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreepPathParseError::InvalidEncoding => write!(f, "creep path isn't valid base64"),
+            CreepPathParseError::TooShort => write!(f, "creep path is too short"),
+            CreepPathParseError::InvalidStart => write!(f, "creep path start is out of bounds"),
+            CreepPathParseError::InvalidDirection { value } => {
+                write!(f, "creep path contains invalid direction {}", value)
+            }
+            CreepPathParseError::IndexOutOfBounds { index, length } => write!(
+                f,
+                "creep path index {} is out of bounds for length {}",
+                index, length
+            ),
+            CreepPathParseError::LeavesWorld => write!(f, "creep path leaves the world bounds"),
+        }
+    }
+}
+
+impl Error for CreepPathParseError {}
+
+impl FromStr for CreepPathMemory {
+    type Err = CreepPathParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = base64::decode(s).ok_or(CreepPathParseError::InvalidEncoding)?;
+        if bytes.len() < 6 {
+            return Err(CreepPathParseError::TooShort);
+        }
+
+        let packed = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let start = Position::from_packed(packed as i32);
+        if start.x() >= 50 || start.y() >= 50 {
+            return Err(CreepPathParseError::InvalidStart);
+        }
+
+        let index = usize::from(u16::from_be_bytes([bytes[4], bytes[5]]));
+
+        let mut directions = Vec::with_capacity((bytes.len() - 6) * 2);
+        let last = bytes.len() - 1;
+        for (i, byte) in bytes.iter().enumerate().skip(6) {
+            for &value in &[byte >> 4, byte & 0x0F] {
+                // a zero low nibble in the last byte pads odd-length paths
+                if value == 0 && i == last && directions.len() % 2 == 1 {
+                    continue;
+                }
+                let dir = Direction::from_u8(value)
+                    .ok_or(CreepPathParseError::InvalidDirection { value })?;
+                directions.push(dir);
+            }
+        }
+
+        if index > directions.len() {
+            return Err(CreepPathParseError::IndexOutOfBounds {
+                index,
+                length: directions.len(),
+            });
+        }
+        if !fits_in_world(start, &directions) {
+            return Err(CreepPathParseError::LeavesWorld);
+        }
+
+        let current = directions[..index]
+            .iter()
+            .fold(start, |pos, dir| pos + dir.offset());
+
+        Ok(CreepPathMemory {
+            start,
+            directions,
+            index,
+            current,
+        })
+    }
+}
+
+impl Serialize for CreepPathMemory {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CreepPathMemory {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// Whether every position along the path is within the world bounds.
+fn fits_in_world(start: Position, directions: &[Direction]) -> bool {
+    let bounds = -HALF_WORLD_SIZE * 50..HALF_WORLD_SIZE * 50;
+    let (mut x, mut y) = start.world_coords();
+    directions.iter().all(|dir| {
+        let (dx, dy) = dir.offset();
+        x += dx;
+        y += dy;
+        bounds.contains(&x) && bounds.contains(&y)
+    })
+}
+
+/// Minimal unpadded base64 implementation using the standard alphabet.
+mod base64 {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    pub fn encode(bytes: &[u8]) -> String {
+        let mut out = String::with_capacity((bytes.len() * 4 + 2) / 3);
+        for chunk in bytes.chunks(3) {
+            let b = [
+                chunk[0],
+                chunk.get(1).copied().unwrap_or(0),
+                chunk.get(2).copied().unwrap_or(0),
+            ];
+            let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+            for i in 0..=chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            }
+        }
+        out
+    }
+
+    pub fn decode(s: &str) -> Option<Vec<u8>> {
+        let values = s
+            .bytes()
+            .map(|c| ALPHABET.iter().position(|&a| a == c).map(|v| v as u32))
+            .collect::<Option<Vec<u32>>>()?;
+
+        let mut out = Vec::with_capacity(values.len() * 3 / 4);
+        for chunk in values.chunks(4) {
+            if chunk.len() == 1 {
+                return None;
+            }
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |n, (i, v)| n | v << (18 - 6 * i));
+            for i in 0..chunk.len() - 1 {
+                out.push((n >> (16 - 8 * i)) as u8);
+            }
+        }
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_traits::FromPrimitive;
+
+    use super::{base64, CreepPathMemory, CreepPathParseError};
+    use crate::{
+        constants::Direction,
+        local::{Position, RoomName},
+    };
+
+    #[test]
+    fn base64_round_trip() {
+        for len in 0..10u8 {
+            let bytes: Vec<u8> = (0..len).map(|i| i.wrapping_mul(97) ^ 0xA5).collect();
+            assert_eq!(base64::decode(&base64::encode(&bytes)), Some(bytes));
+        }
+        assert_eq!(base64::encode(b"Man"), "TWFu");
+        assert_eq!(base64::encode(b"Ma"), "TWE");
+        assert_eq!(base64::decode("TWE"), Some(b"Ma".to_vec()));
+    }
+
+    #[test]
+    fn follow_path_across_rooms() {
+        let room = RoomName::new("E0N0").unwrap();
+        let start = Position::new(48, 25, room);
+        let mut path = CreepPathMemory::new(
+            start,
+            vec![Direction::Right, Direction::Right, Direction::BottomRight],
+        );
+
+        assert_eq!(path.remaining(), 3);
+        assert_eq!(path.advance(), Some(Position::new(49, 25, room)));
+        assert_eq!(
+            path.advance(),
+            Some(Position::new(0, 25, RoomName::new("E1N0").unwrap()))
+        );
+        assert_eq!(path.next_direction(), Some(Direction::BottomRight));
+        assert_eq!(
+            path.advance(),
+            Some(Position::new(1, 26, RoomName::new("E1N0").unwrap()))
+        );
+        assert!(path.is_complete());
+        assert_eq!(path.current_target(), None);
+        assert_eq!(path.advance(), None);
+    }
+
+    #[test]
+    fn string_round_trip() {
+        let start = Position::new(3, 47, RoomName::new("W12S34").unwrap());
+        let directions = vec![
+            Direction::Top,
+            Direction::TopLeft,
+            Direction::Left,
+            Direction::Bottom,
+            Direction::BottomRight,
+        ];
+        let mut path = CreepPathMemory::new(start, directions);
+        path.advance();
+        path.advance();
+
+        let serialized = serde_json::to_string(&path).unwrap();
+        let deserialized: CreepPathMemory = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, path);
+        assert_eq!(deserialized.remaining(), 3);
+        assert_eq!(deserialized.current_position(), path.current_position());
+    }
+
+    #[test]
+    fn random_round_trips() {
+        // simple xorshift, to get reproducible pseudo-random sequences
+        let mut state: u32 = 0x2545_F491;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+
+        let start = Position::new(25, 25, RoomName::new("E5S5").unwrap());
+        for _ in 0..200 {
+            let len = (next() % 64) as usize;
+            let directions: Vec<Direction> = (0..len)
+                .map(|_| Direction::from_u32(next() % 8 + 1).unwrap())
+                .collect();
+            let mut path = CreepPathMemory::new(start, directions.clone());
+            for _ in 0..(next() as usize % (len + 1)) {
+                path.advance();
+            }
+
+            let parsed: CreepPathMemory = path.to_string().parse().unwrap();
+            assert_eq!(parsed, path);
+            assert_eq!(parsed.remaining_directions(), &directions[path.index..]);
+        }
+    }
+
+    #[test]
+    fn corrupted_strings() {
+        let start = Position::new(25, 25, RoomName::new("E5S5").unwrap());
+        let path = CreepPathMemory::new(start, vec![Direction::Top; 3]).to_string();
+
+        assert_eq!(
+            "not base64!".parse::<CreepPathMemory>(),
+            Err(CreepPathParseError::InvalidEncoding)
+        );
+        assert_eq!(
+            path[..4].parse::<CreepPathMemory>(),
+            Err(CreepPathParseError::TooShort)
+        );
+        // start position with x = 255
+        assert_eq!(
+            "AAD/AAAA".parse::<CreepPathMemory>(),
+            Err(CreepPathParseError::InvalidStart)
+        );
+        // direction 9
+        let mut bad_direction = path[..8].to_owned();
+        bad_direction.push_str("kQ");
+        assert_eq!(
+            bad_direction.parse::<CreepPathMemory>(),
+            Err(CreepPathParseError::InvalidDirection { value: 9 })
+        );
+        // index 5 into a path of length 3
+        let bytes = {
+            let mut b = base64::decode(&path).unwrap();
+            b[5] = 5;
+            b
+        };
+        assert_eq!(
+            base64::encode(&bytes).parse::<CreepPathMemory>(),
+            Err(CreepPathParseError::IndexOutOfBounds {
+                index: 5,
+                length: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+    ops,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A game tick, as returned by [`game::time`].
+///
+/// Subtracting two ticks gives the [`TickDuration`] between them, and adding
+/// a duration to a tick gives a later tick. Arithmetic saturates rather than
+/// overflowing: the duration since a later tick is 0.
+///
+/// Serializes as a plain number, and the number of ticks is available as the
+/// public field or with [`GameTime::ticks`].
+///
+/// [`game::time`]: crate::game::time
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct GameTime(pub u32);
+
+/// A number of ticks between two [`GameTime`]s.
+///
+/// Serializes as a plain number.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct TickDuration(pub u32);
+
+impl GameTime {
+    #[inline]
+    pub const fn new(ticks: u32) -> Self {
+        GameTime(ticks)
+    }
+
+    /// The number of ticks since the start of the server.
+    #[inline]
+    pub const fn ticks(self) -> u32 {
+        self.0
+    }
+
+    /// The ticks elapsed since `earlier`, or 0 if it's later than this tick.
+    #[inline]
+    pub fn since(self, earlier: GameTime) -> TickDuration {
+        TickDuration(self.0.saturating_sub(earlier.0))
+    }
+
+    /// The tick `duration` ticks after this one, or `None` on overflow.
+    #[inline]
+    pub fn checked_add(self, duration: TickDuration) -> Option<GameTime> {
+        self.0.checked_add(duration.0).map(GameTime)
+    }
+
+    /// Whether something run every `interval` ticks, `offset` ticks after the
+    /// multiples of `interval`, should run on this tick.
+    ///
+    /// Every tick is a match for intervals of 0 or 1. Use
+    /// [`GameTime::stagger_offset`] to spread tasks over the ticks of the
+    /// interval rather than running them all on the same tick.
+    #[inline]
+    pub fn every(self, interval: u32, offset: u32) -> bool {
+        interval <= 1 || self.0 % interval == offset % interval
+    }
+
+    /// An offset in `0..interval` derived from hashing `key`, such as a room
+    /// name, for use with [`GameTime::every`].
+    ///
+    /// Keys are spread evenly over the interval, and each key always gets the
+    /// same offset from a given build of the code, so tasks keep their tick
+    /// across global resets.
+    pub fn stagger_offset<K: ?Sized + Hash>(key: &K, interval: u32) -> u32 {
+        if interval <= 1 {
+            return 0;
+        }
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() % u64::from(interval)) as u32
+    }
+}
+
+impl TickDuration {
+    #[inline]
+    pub const fn new(ticks: u32) -> Self {
+        TickDuration(ticks)
+    }
+
+    #[inline]
+    pub const fn ticks(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for GameTime {
+    #[inline]
+    fn from(ticks: u32) -> Self {
+        GameTime(ticks)
+    }
+}
+
+impl From<GameTime> for u32 {
+    #[inline]
+    fn from(time: GameTime) -> Self {
+        time.0
+    }
+}
+
+impl From<u32> for TickDuration {
+    #[inline]
+    fn from(ticks: u32) -> Self {
+        TickDuration(ticks)
+    }
+}
+
+impl From<TickDuration> for u32 {
+    #[inline]
+    fn from(duration: TickDuration) -> Self {
+        duration.0
+    }
+}
+
+impl fmt::Display for GameTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for TickDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl ops::Add<TickDuration> for GameTime {
+    type Output = GameTime;
+
+    #[inline]
+    fn add(self, rhs: TickDuration) -> GameTime {
+        GameTime(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl ops::AddAssign<TickDuration> for GameTime {
+    #[inline]
+    fn add_assign(&mut self, rhs: TickDuration) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::Sub<TickDuration> for GameTime {
+    type Output = GameTime;
+
+    #[inline]
+    fn sub(self, rhs: TickDuration) -> GameTime {
+        GameTime(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl ops::SubAssign<TickDuration> for GameTime {
+    #[inline]
+    fn sub_assign(&mut self, rhs: TickDuration) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::Sub<GameTime> for GameTime {
+    type Output = TickDuration;
+
+    #[inline]
+    fn sub(self, rhs: GameTime) -> TickDuration {
+        self.since(rhs)
+    }
+}
+
+impl ops::Add<TickDuration> for TickDuration {
+    type Output = TickDuration;
+
+    #[inline]
+    fn add(self, rhs: TickDuration) -> TickDuration {
+        TickDuration(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl ops::Sub<TickDuration> for TickDuration {
+    type Output = TickDuration;
+
+    #[inline]
+    fn sub(self, rhs: TickDuration) -> TickDuration {
+        TickDuration(self.0.saturating_sub(rhs.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GameTime, TickDuration};
+    use crate::local::RoomName;
+
+    #[test]
+    fn tick_arithmetic() {
+        let spawned = GameTime(1000);
+        let now = spawned + TickDuration(250);
+        assert_eq!(now, GameTime(1250));
+        assert_eq!(now - spawned, TickDuration(250));
+        assert_eq!(now - TickDuration(1250), GameTime(0));
+
+        // saturating rather than overflowing
+        assert_eq!(spawned - now, TickDuration(0));
+        assert_eq!(spawned - TickDuration(5000), GameTime(0));
+        assert_eq!(
+            GameTime(u32::MAX - 1) + TickDuration(10),
+            GameTime(u32::MAX)
+        );
+        assert_eq!(GameTime(u32::MAX - 1).checked_add(TickDuration(10)), None);
+        assert_eq!(
+            GameTime(5).checked_add(TickDuration(10)),
+            Some(GameTime(15))
+        );
+        assert_eq!(TickDuration(5) - TickDuration(10), TickDuration(0));
+
+        let mut time = GameTime(10);
+        time += TickDuration(5) + TickDuration(5);
+        time -= TickDuration(3);
+        assert_eq!(time.ticks(), 17);
+        assert_eq!(u32::from(time), 17);
+
+        assert_eq!(serde_json::to_string(&time).unwrap(), "17");
+        assert_eq!(
+            serde_json::from_str::<TickDuration>("1500").unwrap(),
+            TickDuration(1500)
+        );
+        assert_eq!(time.to_string(), "17");
+    }
+
+    #[test]
+    fn staggering() {
+        let matching: Vec<u32> = (100..130)
+            .filter(|&tick| GameTime(tick).every(10, 3))
+            .collect();
+        assert_eq!(matching, vec![103, 113, 123]);
+        assert!(GameTime(7).every(10, 27));
+        assert!(GameTime(7).every(0, 5));
+        assert!(GameTime(7).every(1, 5));
+
+        let room = RoomName::new("W1N1").unwrap();
+        let offset = GameTime::stagger_offset(&room, 10);
+        assert_eq!(offset, GameTime::stagger_offset(&room, 10));
+        assert_eq!(GameTime::stagger_offset(&room, 0), 0);
+
+        // 1024 rooms spread over 16 ticks, each running exactly once
+        let mut counts = [0; 16];
+        for x in 0..32 {
+            for y in 0..32 {
+                let room = RoomName::new(&format!("W{}N{}", x, y)).unwrap();
+                let offset = GameTime::stagger_offset(&room, 16);
+                let runs: Vec<u32> = (0..16)
+                    .filter(|&tick| GameTime(tick).every(16, offset))
+                    .collect();
+                assert_eq!(runs, vec![offset]);
+                counts[offset as usize] += 1;
+            }
+        }
+        assert!(
+            counts.iter().all(|&count| (32..=96).contains(&count)),
+            "{:?}",
+            counts
+        );
+    }
+}
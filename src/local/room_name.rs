@@ -106,10 +106,10 @@ impl RoomName {
     /// Returns an error if the coordinates are outside of the valid room name
     /// bounds.
     pub(super) fn from_coords(x_coord: i32, y_coord: i32) -> Result<Self, RoomNameParseError> {
-        if !VALID_ROOM_NAME_COORDINATES.contains(&x_coord)
-            || !VALID_ROOM_NAME_COORDINATES.contains(&y_coord)
-        {
-            return Err(RoomNameParseError::PositionOutOfBounds { x_coord, y_coord });
+        for &(axis, value) in &[(Axis::X, x_coord), (Axis::Y, y_coord)] {
+            if !VALID_ROOM_NAME_COORDINATES.contains(&value) {
+                return Err(RoomNameParseError::OutOfRange { axis, value });
+            }
         }
 
         let room_x = (x_coord + HALF_WORLD_SIZE) as u16;
@@ -148,6 +148,40 @@ impl RoomName {
         write!(res, "{}", self).expect("expected ArrayString write to be infallible");
         res
     }
+
+    /// The linear distance to `other`, in rooms: the larger of the horizontal
+    /// and vertical distances.
+    ///
+    /// This is what [`game::map::get_room_linear_distance`] returns when not
+    /// `continuous`.
+    ///
+    /// [`game::map::get_room_linear_distance`]:
+    /// crate::game::map::get_room_linear_distance
+    pub fn linear_distance_to(self, other: RoomName) -> u32 {
+        let dx = (self.x_coord() - other.x_coord()).unsigned_abs();
+        let dy = (self.y_coord() - other.y_coord()).unsigned_abs();
+        dx.max(dy)
+    }
+
+    /// The linear distance to `other`, in rooms, in a world `world_size`
+    /// rooms across whose edges wrap around to the opposite side.
+    ///
+    /// This is what [`game::map::get_room_linear_distance`] returns when
+    /// `continuous`, with `world_size` as returned by
+    /// [`game::map::get_world_size`].
+    ///
+    /// [`game::map::get_room_linear_distance`]:
+    /// crate::game::map::get_room_linear_distance
+    /// [`game::map::get_world_size`]: crate::game::map::get_world_size
+    pub fn wrapped_linear_distance_to(self, other: RoomName, world_size: u32) -> u32 {
+        let wrap = |d: i32| {
+            let d = d.unsigned_abs();
+            d.min(world_size.saturating_sub(d))
+        };
+        let dx = wrap(self.x_coord() - other.x_coord());
+        let dy = wrap(self.y_coord() - other.y_coord());
+        dx.max(dy)
+    }
 }
 
 impl ops::Add<(i32, i32)> for RoomName {
@@ -212,8 +246,14 @@ impl FromStr for RoomName {
     type Err = RoomNameParseError;
 
     fn from_str(s: &str) -> Result<Self, RoomNameParseError> {
+        if s.is_empty() {
+            return Err(RoomNameParseError::Empty);
+        }
+
         parse_to_coords(s)
-            .map_err(|()| RoomNameParseError::new(s))
+            .map_err(|()| RoomNameParseError::BadFormat {
+                string: s.to_owned(),
+            })
             .and_then(|(x, y)| RoomName::from_coords(x, y))
     }
 }
@@ -270,50 +310,70 @@ fn parse_to_coords(s: &str) -> Result<(i32, i32), ()> {
     Ok((room_x, room_y))
 }
 
-/// An error representing when a string can't be parsed into a
-/// [`RoomName`].
-///
-/// [`RoomName`]: struct.RoomName.html
-#[derive(Clone, Debug)]
-pub enum RoomNameParseError {
-    TooLarge { length: usize },
-    InvalidString { string: ArrayString<[u8; 8]> },
-    PositionOutOfBounds { x_coord: i32, y_coord: i32 },
+/// One of the two axes of room names and room positions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// The west-east axis.
+    X,
+    /// The north-south axis.
+    Y,
 }
 
-impl RoomNameParseError {
-    /// Private method to construct a `RoomNameParseError`.
-    fn new(failed_room_name: &str) -> Self {
-        match ArrayString::from(failed_room_name) {
-            Ok(string) => RoomNameParseError::InvalidString { string },
-            Err(_) => RoomNameParseError::TooLarge {
-                length: failed_room_name.len(),
-            },
+impl fmt::Display for Axis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Axis::X => f.write_str("x"),
+            Axis::Y => f.write_str("y"),
         }
     }
 }
 
+/// An error representing when a string or coordinates can't be turned into a
+/// [`RoomName`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoomNameParseError {
+    /// The string was empty.
+    Empty,
+    /// The string wasn't formatted like a room name.
+    BadFormat { string: String },
+    /// A coordinate was outside of the world, which spans `-128..128` on
+    /// each axis: `W127` to `E127`, and `N127` to `S127`.
+    ///
+    /// For `Wxx` and `Nyy` rooms, `value` is `-xx - 1`.
+    OutOfRange { axis: Axis, value: i32 },
+}
+
 impl error::Error for RoomNameParseError {}
 
 impl fmt::Display for RoomNameParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            RoomNameParseError::TooLarge { length } => write!(
+            RoomNameParseError::Empty => write!(
                 f,
-                "got invalid room name, too large to stick in error. \
-                 expected length 8 or less, got length {}",
-                length
+                "expected room name formatted `[ewEW][0-9]+[nsNS][0-9]+`, found an empty string"
             ),
-            RoomNameParseError::InvalidString { string } => write!(
+            RoomNameParseError::BadFormat { string } => write!(
                 f,
                 "expected room name formatted `[ewEW][0-9]+[nsNS][0-9]+`, found `{}`",
                 string
             ),
-            RoomNameParseError::PositionOutOfBounds { x_coord, y_coord } => write!(
-                f,
-                "expected room name with coords within -128..+128, found {}, {}",
-                x_coord, y_coord,
-            ),
+            RoomNameParseError::OutOfRange { axis, value } => {
+                let (negative, positive) = match axis {
+                    Axis::X => ('W', 'E'),
+                    Axis::Y => ('N', 'S'),
+                };
+                let (direction, number) = if *value >= 0 {
+                    (positive, i64::from(*value))
+                } else {
+                    (negative, -i64::from(*value) - 1)
+                };
+                write!(
+                    f,
+                    "expected room name with coords within -128..+128, found {} coordinate {} \
+                     (`{}{}`)",
+                    axis, value, direction, number
+                )
+            }
         }
     }
 }
@@ -399,7 +459,7 @@ mod serde {
     use std::fmt;
 
     use serde::{
-        de::{Error, Unexpected, Visitor},
+        de::{Error, Visitor},
         Deserialize, Deserializer, Serialize, Serializer,
     };
 
@@ -429,8 +489,7 @@ mod serde {
         where
             E: Error,
         {
-            v.parse()
-                .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+            v.parse().map_err(E::custom)
         }
     }
 
@@ -460,4 +519,85 @@ mod test {
             assert_eq!(&room_name.to_string(), RoomName::new(room_name).unwrap());
         }
     }
+
+    #[test]
+    fn linear_distances() {
+        use super::RoomName;
+        let room = |name: &str| RoomName::new(name).unwrap();
+
+        assert_eq!(room("W1N1").linear_distance_to(room("W1N1")), 0);
+        assert_eq!(room("W1N1").linear_distance_to(room("W5N3")), 4);
+        // W0 and E0 are next to each other
+        assert_eq!(room("W0N5").linear_distance_to(room("E0S5")), 11);
+        assert_eq!(room("W1N1").linear_distance_to(room("E1S1")), 3);
+
+        // a world of W100 to E100 is 202 rooms across
+        assert_eq!(room("W100N1").linear_distance_to(room("E100N1")), 201);
+        assert_eq!(
+            room("W100N1").wrapped_linear_distance_to(room("E100N1"), 202),
+            1
+        );
+        assert_eq!(
+            room("W1N1").wrapped_linear_distance_to(room("E1S1"), 202),
+            3
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        use super::{Axis, RoomName, RoomNameParseError};
+
+        let err = RoomName::new("").unwrap_err();
+        assert_eq!(err, RoomNameParseError::Empty);
+        assert!(err.to_string().contains("empty string"));
+
+        for bad in &["E5", "N5E5", "E5N", "Ex5N5", "W5N5W", "W99999999999N1"] {
+            let err = RoomName::new(bad).unwrap_err();
+            assert_eq!(
+                err,
+                RoomNameParseError::BadFormat {
+                    string: bad.to_string()
+                }
+            );
+            assert!(err.to_string().contains(&format!("`{}`", bad)));
+        }
+
+        let err = RoomName::new("W200N5").unwrap_err();
+        assert_eq!(
+            err,
+            RoomNameParseError::OutOfRange {
+                axis: Axis::X,
+                value: -201
+            }
+        );
+        assert!(err.to_string().contains("x coordinate -201 (`W200`)"));
+
+        let err = RoomName::new("E5S128").unwrap_err();
+        assert_eq!(
+            err,
+            RoomNameParseError::OutOfRange {
+                axis: Axis::Y,
+                value: 128
+            }
+        );
+        assert!(err.to_string().contains("y coordinate 128 (`S128`)"));
+
+        assert_eq!(
+            RoomName::from_coords(0, -129),
+            Err(RoomNameParseError::OutOfRange {
+                axis: Axis::Y,
+                value: -129
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_errors() {
+        use super::RoomName;
+
+        let err = serde_json::from_str::<RoomName>("\"E5S128\"").unwrap_err();
+        assert!(err.to_string().contains("y coordinate 128 (`S128`)"));
+        let err = serde_json::from_str::<RoomName>("\"hello\"").unwrap_err();
+        assert!(err.to_string().contains("found `hello`"));
+    }
 }
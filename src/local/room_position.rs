@@ -5,10 +5,10 @@
 //! file stay within Rust.
 use std::{
     cmp::{Ord, Ordering, PartialOrd},
-    fmt,
+    error, fmt,
 };
 
-use super::{RoomName, HALF_WORLD_SIZE};
+use super::{Axis, RoomName, HALF_WORLD_SIZE};
 
 mod extra_math;
 mod game_math;
@@ -237,14 +237,31 @@ impl Position {
     ///
     /// # Panics
     ///
-    /// Will panic if either `x` or `y` is larger than 49, or if `room_name` is
-    /// outside of the range `E127N127 - W127S127`.
+    /// Will panic if either `x` or `y` is larger than 49. See
+    /// [`Position::checked_new`] for a non-panicking version.
     #[inline]
     pub fn new(x: u32, y: u32, room_name: RoomName) -> Self {
-        assert!(x < 50, "out of bounds x: {}", x);
-        assert!(y < 50, "out of bounds y: {}", y);
+        match Self::checked_new(x, y, room_name) {
+            Ok(pos) => pos,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Create a new Position, or an error if either `x` or `y` is larger than
+    /// 49.
+    #[inline]
+    pub fn checked_new(x: u32, y: u32, room_name: RoomName) -> Result<Self, CoordinateError> {
+        for &(axis, value) in &[(Axis::X, x), (Axis::Y, y)] {
+            if value >= 50 {
+                return Err(CoordinateError { axis, value });
+            }
+        }
 
-        Self::from_coords_adjusted_and_room_packed(x, y, room_name.packed_repr())
+        Ok(Self::from_coords_adjusted_and_room_packed(
+            x,
+            y,
+            room_name.packed_repr(),
+        ))
     }
 
     /// Creates a `Position` from x,y coordinates and room coordinates
@@ -310,15 +327,43 @@ impl Position {
         RoomName::from_packed(((self.packed >> 16) & 0xFFFF) as u16)
     }
 
+    /// Sets this position's in-room x coordinate.
+    ///
+    /// # Panics
+    ///
+    /// Will panic with a [`CoordinateError`] message if `x` is larger than
+    /// 49.
     #[inline]
     pub fn set_x(&mut self, x: u32) {
-        assert!(x < 50, "out of bounds x: {}", x);
+        if x >= 50 {
+            panic!(
+                "{}",
+                CoordinateError {
+                    axis: Axis::X,
+                    value: x
+                }
+            );
+        }
         self.packed = (self.packed & !(0xFF << 8)) | (x << 8);
     }
 
+    /// Sets this position's in-room y coordinate.
+    ///
+    /// # Panics
+    ///
+    /// Will panic with a [`CoordinateError`] message if `y` is larger than
+    /// 49.
     #[inline]
     pub fn set_y(&mut self, y: u32) {
-        assert!(y < 50, "out of bounds y: {}", y);
+        if y >= 50 {
+            panic!(
+                "{}",
+                CoordinateError {
+                    axis: Axis::Y,
+                    value: y
+                }
+            );
+        }
         self.packed = (self.packed & !0xFF) | y;
     }
 
@@ -328,12 +373,22 @@ impl Position {
         self.packed = (self.packed & 0xFFFF) | (room_repr_packed << 16);
     }
 
+    /// Returns this position with its in-room x coordinate replaced.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `x` is larger than 49, like [`Position::set_x`].
     #[inline]
     pub fn with_x(mut self, x: u32) -> Self {
         self.set_x(x);
         self
     }
 
+    /// Returns this position with its in-room y coordinate replaced.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `y` is larger than 49, like [`Position::set_y`].
     #[inline]
     pub fn with_y(mut self, y: u32) -> Self {
         self.set_y(y);
@@ -362,6 +417,27 @@ impl Ord for Position {
     }
 }
 
+/// An error representing an in-room coordinate outside of `0..50`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CoordinateError {
+    /// The axis of the coordinate.
+    pub axis: Axis,
+    /// The coordinate.
+    pub value: u32,
+}
+
+impl error::Error for CoordinateError {}
+
+impl fmt::Display for CoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected room position {} coordinate within 0..50, found {}",
+            self.axis, self.value
+        )
+    }
+}
+
 mod stdweb {
     use stdweb::{Reference, Value};
 
@@ -410,7 +486,7 @@ mod stdweb {
 }
 
 mod serde {
-    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
     use super::{Position, RoomName};
 
@@ -422,12 +498,6 @@ mod serde {
         y: u32,
     }
 
-    impl From<ReadableFormat> for Position {
-        fn from(ReadableFormat { room_name, x, y }: ReadableFormat) -> Self {
-            Position::new(x, y, room_name)
-        }
-    }
-
     impl From<Position> for ReadableFormat {
         fn from(pos: Position) -> Self {
             ReadableFormat {
@@ -457,7 +527,8 @@ mod serde {
             D: Deserializer<'de>,
         {
             if deserializer.is_human_readable() {
-                ReadableFormat::deserialize(deserializer).map(Into::into)
+                let ReadableFormat { room_name, x, y } = ReadableFormat::deserialize(deserializer)?;
+                Position::checked_new(x, y, room_name).map_err(D::Error::custom)
             } else {
                 i32::deserialize(deserializer).map(Position::from_packed)
             }
@@ -467,7 +538,7 @@ mod serde {
 
 #[cfg(test)]
 mod test {
-    use super::Position;
+    use super::{Axis, CoordinateError, Position};
 
     const TEST_POSITIONS: &[(i32, (u32, u32, &str))] = &[
         (-2122440404i32, (33, 44, "E1N1")),
@@ -495,4 +566,47 @@ mod test {
             assert_eq!(pos.packed_repr(), packed);
         }
     }
+
+    #[test]
+    #[should_panic(expected = "expected room position y coordinate within 0..50, found 50")]
+    fn with_y_out_of_bounds() {
+        Position::new(1, 1, "E1N1".parse().unwrap()).with_y(50);
+    }
+
+    #[test]
+    fn coordinate_errors() {
+        let room = "E1N1".parse().unwrap();
+        assert_eq!(
+            Position::checked_new(49, 0, room),
+            Ok(Position::new(49, 0, room))
+        );
+
+        let err = Position::checked_new(50, 3, room).unwrap_err();
+        assert_eq!(
+            err,
+            CoordinateError {
+                axis: Axis::X,
+                value: 50
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "expected room position x coordinate within 0..50, found 50"
+        );
+
+        let err = Position::checked_new(3, 70, room).unwrap_err();
+        assert_eq!((err.axis, err.value), (Axis::Y, 70));
+        assert!(err
+            .to_string()
+            .contains("y coordinate within 0..50, found 70"));
+
+        let err = serde_json::from_str::<Position>(r#"{"roomName": "E1N1", "x": 4, "y": 50}"#)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("y coordinate within 0..50, found 50"));
+        let err =
+            serde_json::from_str::<Position>(r#"{"roomName": "", "x": 4, "y": 5}"#).unwrap_err();
+        assert!(err.to_string().contains("empty string"));
+    }
 }
@@ -1,4 +1,5 @@
 use super::{Position, HALF_WORLD_SIZE};
+use crate::constants::Direction;
 
 impl Position {
     /// Returns this position's horizontal "world coordinate".
@@ -69,11 +70,58 @@ impl Position {
 
         Self::from_coords_and_world_coords_adjusted(x, y, room_x, room_y)
     }
+
+    /// Returns the position a creep on this room edge tile appears at in the
+    /// adjacent room, or `None` if this position isn't on an edge.
+    ///
+    /// A creep stepping onto `x = 0` is moved to `x = 49` of the room to the
+    /// west at the start of the next tick, and likewise for the other edges,
+    /// keeping the other coordinate.
+    ///
+    /// Corner tiles are on two edges, so `direction`, the direction the creep
+    /// was moving in, decides which room it goes to: the edges it points
+    /// across are crossed, so moving diagonally out of a corner crosses both.
+    /// For corner tiles, `None` is returned without a direction or when the
+    /// direction doesn't point out of the room. Other edge tiles ignore
+    /// `direction`.
+    ///
+    /// Also returns `None` at the edge of the world.
+    pub fn room_transition(self, direction: Option<Direction>) -> Option<Position> {
+        let crossing = |coord: u32, delta: i8| match coord {
+            0 if delta < 0 => -1,
+            49 if delta > 0 => 1,
+            _ => 0,
+        };
+        let on_x_edge = self.x() == 0 || self.x() == 49;
+        let on_y_edge = self.y() == 0 || self.y() == 49;
+
+        let (dx, dy) = match (on_x_edge, on_y_edge) {
+            (false, false) => return None,
+            (true, false) => (if self.x() == 0 { -1 } else { 1 }, 0),
+            (false, true) => (0, if self.y() == 0 { -1 } else { 1 }),
+            (true, true) => {
+                let (delta_x, delta_y) = direction?.delta();
+                (crossing(self.x(), delta_x), crossing(self.y(), delta_y))
+            }
+        };
+        if (dx, dy) == (0, 0) {
+            return None;
+        }
+
+        let bounds = -HALF_WORLD_SIZE * 50..HALF_WORLD_SIZE * 50;
+        let (x, y) = (self.world_x() + dx, self.world_y() + dy);
+        if bounds.contains(&x) && bounds.contains(&y) {
+            Some(Position::from_world_coords(x, y))
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::Position;
+    use crate::constants::Direction;
 
     const TEST_ROOM_NAMES: &[&str] = &[
         "E1N1", "E20N0", "W0N0", "E0N0", "W0S0", "E0S0", "W0N0", "E0N0", "W0S0", "E0S0", "W50S20",
@@ -94,4 +142,71 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn room_transitions() {
+        let pos = |x, y, room: &str| Position::new(x, y, room.parse().unwrap());
+
+        // each edge, including across the W0 / E0 and N0 / S0 boundaries
+        assert_eq!(
+            pos(0, 20, "E0N5").room_transition(None),
+            Some(pos(49, 20, "W0N5"))
+        );
+        assert_eq!(
+            pos(49, 20, "W0N5").room_transition(None),
+            Some(pos(0, 20, "E0N5"))
+        );
+        assert_eq!(
+            pos(30, 0, "W3S0").room_transition(None),
+            Some(pos(30, 49, "W3N0"))
+        );
+        assert_eq!(
+            pos(30, 49, "W3N0").room_transition(Some(Direction::Top)),
+            Some(pos(30, 0, "W3S0"))
+        );
+        assert_eq!(
+            pos(49, 1, "E10N10").room_transition(None),
+            Some(pos(0, 1, "E11N10"))
+        );
+        assert_eq!(
+            pos(12, 0, "E10N10").room_transition(None),
+            Some(pos(12, 49, "E10N11"))
+        );
+
+        assert_eq!(pos(1, 1, "E10N10").room_transition(None), None);
+        assert_eq!(pos(25, 48, "E10N10").room_transition(None), None);
+
+        // corners need a direction pointing out of the room
+        let corner = pos(0, 0, "E10N10");
+        assert_eq!(corner.room_transition(None), None);
+        assert_eq!(
+            corner.room_transition(Some(Direction::Left)),
+            Some(pos(49, 0, "E9N10"))
+        );
+        assert_eq!(
+            corner.room_transition(Some(Direction::Top)),
+            Some(pos(0, 49, "E10N11"))
+        );
+        assert_eq!(
+            corner.room_transition(Some(Direction::TopLeft)),
+            Some(pos(49, 49, "E9N11"))
+        );
+        assert_eq!(
+            corner.room_transition(Some(Direction::BottomLeft)),
+            Some(pos(49, 0, "E9N10"))
+        );
+        assert_eq!(corner.room_transition(Some(Direction::BottomRight)), None);
+        assert_eq!(
+            pos(49, 49, "W1S1").room_transition(Some(Direction::BottomRight)),
+            Some(pos(0, 0, "W0S2"))
+        );
+        assert_eq!(
+            pos(49, 0, "W1S1").room_transition(Some(Direction::Right)),
+            Some(pos(0, 0, "W0S1"))
+        );
+
+        // there's nothing past the edge of the world
+        assert_eq!(pos(0, 10, "W127N0").room_transition(None), None);
+        assert_eq!(pos(10, 49, "E0S127").room_transition(None), None);
+    }
 }
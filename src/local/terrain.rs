@@ -0,0 +1,101 @@
+use std::ops::Index;
+
+use crate::constants::Terrain;
+
+/// Number of tiles in a room, and bytes in a raw terrain buffer.
+const ROOM_AREA: usize = 2500;
+
+/// A copy of a room's terrain, kept in Rust memory.
+///
+/// Created from the raw buffer of a [`RoomTerrain`] with
+/// [`RoomTerrain::get_local`], this answers terrain queries without calling
+/// into JavaScript. Tiles can be looked up with [`LocalRoomTerrain::get`] or
+/// by indexing with `(x, y)` coordinates:
+///
+/// ```no_run
+/// use screeps::{constants::Terrain, game};
+///
+/// let terrain = game::map::get_room_terrain("W1N1".parse().unwrap()).get_local();
+/// assert_eq!(terrain[(25, 25)], Terrain::Plain);
+/// ```
+///
+/// [`RoomTerrain`]: crate::objects::RoomTerrain
+/// [`RoomTerrain::get_local`]: crate::objects::RoomTerrain::get_local
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LocalRoomTerrain {
+    bits: Box<[u8; ROOM_AREA]>,
+}
+
+impl LocalRoomTerrain {
+    /// Creates terrain from the bytes of a raw terrain buffer, in row-major
+    /// order.
+    pub fn new_from_bits(bits: Box<[u8; ROOM_AREA]>) -> Self {
+        LocalRoomTerrain { bits }
+    }
+
+    /// Gets the terrain at the given coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is 50 or more.
+    pub fn get(&self, x: u32, y: u32) -> Terrain {
+        Terrain::from_mask(self.bits[tile(x, y)])
+    }
+
+    /// The raw terrain bytes, in row-major order.
+    pub fn get_bits(&self) -> &[u8; ROOM_AREA] {
+        &self.bits
+    }
+}
+
+impl Index<(u32, u32)> for LocalRoomTerrain {
+    type Output = Terrain;
+
+    fn index(&self, (x, y): (u32, u32)) -> &Terrain {
+        match self.get(x, y) {
+            Terrain::Plain => &Terrain::Plain,
+            Terrain::Wall => &Terrain::Wall,
+            Terrain::Swamp => &Terrain::Swamp,
+        }
+    }
+}
+
+fn tile(x: u32, y: u32) -> usize {
+    assert!(
+        x < 50 && y < 50,
+        "out of bounds room coordinates ({}, {})",
+        x,
+        y
+    );
+    y as usize * 50 + x as usize
+}
+
+#[cfg(test)]
+mod test {
+    use super::LocalRoomTerrain;
+    use crate::constants::Terrain;
+
+    #[test]
+    fn raw_terrain_lookup() {
+        let mut bits = Box::new([0; 2500]);
+        bits[0] = 1;
+        bits[1] = 2;
+        bits[50] = 3;
+        bits[2499] = 1;
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        assert_eq!(terrain.get(0, 0), Terrain::Wall);
+        assert_eq!(terrain.get(1, 0), Terrain::Swamp);
+        assert_eq!(terrain[(0, 1)], Terrain::Wall);
+        assert_eq!(terrain[(2, 0)], Terrain::Plain);
+        assert_eq!(terrain[(49, 49)], Terrain::Wall);
+        assert_eq!(terrain.get_bits()[50], 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds() {
+        let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; 2500]));
+        terrain.get(50, 0);
+    }
+}
@@ -0,0 +1,446 @@
+//! Estimating hauler travel times and throughput.
+//!
+//! [`round_trip_estimate`] combines the movement rules of the game with a
+//! hauler's carry capacity, giving the amount of resources a single hauler
+//! can move per tick between two positions. This is mostly useful for sizing
+//! the number of haulers needed by remote mining operations.
+//!
+//! [`fill_route`] orders the spawns and extensions a filler creep should
+//! visit, so it doesn't zigzag across the base.
+use crate::{
+    constants::{Boost, Part, ResourceType, CARRY_CAPACITY},
+    local::Position,
+};
+
+/// The surface a hauler travels over.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Surface {
+    Road,
+    Plain,
+    Swamp,
+}
+
+impl Surface {
+    /// Fatigue generated by each body part weighing on a creep when it moves
+    /// onto a tile of this surface.
+    #[inline]
+    pub fn fatigue_per_part(self) -> u32 {
+        match self {
+            Surface::Road => 1,
+            Surface::Plain => 2,
+            Surface::Swamp => 10,
+        }
+    }
+}
+
+/// Options for [`round_trip_estimate`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RoundTripOptions {
+    surface: Surface,
+    path_length: Option<u32>,
+    carried: Option<u32>,
+}
+
+impl Default for RoundTripOptions {
+    fn default() -> Self {
+        RoundTripOptions {
+            surface: Surface::Plain,
+            path_length: None,
+            carried: None,
+        }
+    }
+}
+
+impl RoundTripOptions {
+    /// Creates default options: traveling over plains, using the range between
+    /// both positions as the path length, with a full load.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the surface of every tile on the path.
+    #[inline]
+    pub fn surface(mut self, surface: Surface) -> Self {
+        self.surface = surface;
+        self
+    }
+
+    /// Sets the length of the path, for example as found by the pathfinder,
+    /// instead of using the range between both positions.
+    #[inline]
+    pub fn path_length(mut self, length: u32) -> Self {
+        self.path_length = Some(length);
+        self
+    }
+
+    /// Sets the amount of resources carried on the loaded trip, instead of
+    /// the full carry capacity of the hauler. Amounts above the carry capacity
+    /// are capped to it.
+    #[inline]
+    pub fn carried(mut self, amount: u32) -> Self {
+        self.carried = Some(amount);
+        self
+    }
+}
+
+/// Travel times and throughput of a hauler, as estimated by
+/// [`round_trip_estimate`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RoundTrip {
+    /// Ticks to travel from the source to the destination while loaded.
+    pub ticks_loaded: u32,
+    /// Ticks to travel back from the destination to the source while empty.
+    pub ticks_empty: u32,
+    /// Resources moved per tick over a full round trip.
+    pub throughput_per_tick: f64,
+}
+
+/// Estimates the travel time of a hauler carrying resources from `from` to
+/// `to` and returning empty, along with its throughput.
+///
+/// `hauler_body` lists each body part along with the compound boosting it,
+/// if any. Parts are assumed to be undamaged.
+///
+/// Every part other than `MOVE` weighs on the creep, except `CARRY` parts
+/// which only weigh while holding resources. Carried resources fill `CARRY`
+/// parts in body order. Each tile generates fatigue for each weighing part
+/// depending on its [`Surface`], and each `MOVE` part removes 2 fatigue per
+/// tick, multiplied by its boost.
+///
+/// The time spent transferring resources at either end isn't counted.
+///
+/// Returns `None` if the hauler has no `MOVE` parts, or can't carry
+/// anything.
+pub fn round_trip_estimate(
+    hauler_body: &[(Part, Option<ResourceType>)],
+    from: &Position,
+    to: &Position,
+    opts: RoundTripOptions,
+) -> Option<RoundTrip> {
+    let carry_capacities: Vec<u32> = hauler_body
+        .iter()
+        .filter(|(part, _)| *part == Part::Carry)
+        .map(|(_, boost)| match boost.and_then(ResourceType::boost) {
+            Some(Boost::Carry(multiplier)) => (f64::from(CARRY_CAPACITY) * multiplier) as u32,
+            _ => CARRY_CAPACITY,
+        })
+        .collect();
+    let capacity: u32 = carry_capacities.iter().sum();
+    let carried = opts.carried.map_or(capacity, |amount| amount.min(capacity));
+    if carried == 0 {
+        return None;
+    }
+
+    let move_power: f64 = hauler_body
+        .iter()
+        .filter(|(part, _)| *part == Part::Move)
+        .map(|(_, boost)| match boost.and_then(ResourceType::boost) {
+            Some(Boost::Move(multiplier)) => 2.0 * multiplier,
+            _ => 2.0,
+        })
+        .sum();
+    if move_power == 0.0 {
+        return None;
+    }
+
+    let base_weight = hauler_body
+        .iter()
+        .filter(|(part, _)| *part != Part::Move && *part != Part::Carry)
+        .count() as u32;
+    let mut remaining = carried;
+    let loaded_carry_parts = carry_capacities
+        .iter()
+        .take_while(|&&part_capacity| {
+            let holds_resources = remaining > 0;
+            remaining = remaining.saturating_sub(part_capacity);
+            holds_resources
+        })
+        .count() as u32;
+
+    let path_length = opts.path_length.unwrap_or_else(|| from.get_range_to(to));
+    let travel_ticks = |weight: u32| -> u32 {
+        let fatigue = f64::from(weight * opts.surface.fatigue_per_part());
+        let ticks_per_tile = ((fatigue / move_power).ceil() as u32).max(1);
+        ticks_per_tile * path_length
+    };
+
+    let ticks_loaded = travel_ticks(base_weight + loaded_carry_parts);
+    let ticks_empty = travel_ticks(base_weight);
+    let total_ticks = ticks_loaded + ticks_empty;
+    let throughput_per_tick = if total_ticks == 0 {
+        0.0
+    } else {
+        f64::from(carried) / f64::from(total_ticks)
+    };
+
+    Some(RoundTrip {
+        ticks_loaded,
+        ticks_empty,
+        throughput_per_tick,
+    })
+}
+
+/// Maximum number of stops in a route found by [`fill_route`].
+///
+/// This bounds the time spent improving the route, and is more than the
+/// number of spawns and extensions a room can have.
+pub const FILL_ROUTE_MAX_STOPS: usize = 70;
+
+/// Orders the structures a filler creep carrying `capacity` energy should
+/// visit from `start`, returning indices into `room_structures`.
+///
+/// Each entry of `room_structures` is a structure's position along with the
+/// energy it needs. Stops are picked by repeatedly visiting the nearest
+/// structure still needing energy, until the energy carried runs out or
+/// [`FILL_ROUTE_MAX_STOPS`] are planned; structures needing no energy are
+/// skipped. The order is then shortened by reversing parts of it wherever
+/// that reduces the total range travelled (2-opt).
+///
+/// Distances are linear ranges, ignoring obstacles, which suits the tightly
+/// packed extensions of most bases. Ties are broken by index, so the same
+/// input always gives the same route.
+pub fn fill_route(
+    room_structures: &[(Position, u32)],
+    start: Position,
+    capacity: u32,
+) -> Vec<usize> {
+    let mut route = Vec::new();
+    let mut visited = vec![false; room_structures.len()];
+    let mut remaining = capacity;
+    let mut current = start;
+    while remaining > 0 && route.len() < FILL_ROUTE_MAX_STOPS {
+        let nearest = room_structures
+            .iter()
+            .enumerate()
+            .filter(|&(i, &(_, deficit))| !visited[i] && deficit > 0)
+            .min_by_key(|&(i, (pos, _))| (current.get_range_to(pos), i));
+        let (index, &(pos, deficit)) = match nearest {
+            Some(stop) => stop,
+            None => break,
+        };
+        visited[index] = true;
+        route.push(index);
+        remaining = remaining.saturating_sub(deficit);
+        current = pos;
+    }
+
+    // 2-opt over the open route, with the start fixed and the end free
+    let point = |route: &[usize], stop: Option<usize>| match stop {
+        Some(i) => room_structures[route[i]].0,
+        None => start,
+    };
+    let range = |a: Position, b: Position| a.get_range_to(&b);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..route.len() {
+            for j in i + 1..route.len() {
+                // reversing route[i..=j] replaces the edges before i and
+                // after j
+                let before = point(&route, i.checked_sub(1));
+                let (first, last) = (point(&route, Some(i)), point(&route, Some(j)));
+                let mut delta = i64::from(range(before, last)) - i64::from(range(before, first));
+                if j + 1 < route.len() {
+                    let after = point(&route, Some(j + 1));
+                    delta += i64::from(range(first, after)) - i64::from(range(last, after));
+                }
+                if delta < 0 {
+                    route[i..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    route
+}
+
+#[cfg(test)]
+mod test {
+    use std::iter;
+
+    use super::{
+        fill_route, round_trip_estimate, RoundTrip, RoundTripOptions, Surface, FILL_ROUTE_MAX_STOPS,
+    };
+    use crate::{
+        constants::{Part, ResourceType},
+        local::{Position, RoomName},
+    };
+
+    fn body(parts: &[(Part, usize)]) -> Vec<(Part, Option<ResourceType>)> {
+        parts
+            .iter()
+            .flat_map(|&(part, count)| iter::repeat((part, None)).take(count))
+            .collect()
+    }
+
+    fn positions(range: u32) -> (Position, Position) {
+        let room = RoomName::new("E5S5").unwrap();
+        (
+            Position::new(10, 25, room),
+            Position::new(10 + range, 25, room),
+        )
+    }
+
+    #[test]
+    fn off_road_hauler() {
+        // 10 CARRY and 5 MOVE: moves every other tick on plains when full
+        let hauler = body(&[(Part::Carry, 10), (Part::Move, 5)]);
+        let (from, to) = positions(20);
+
+        let plains = round_trip_estimate(&hauler, &from, &to, RoundTripOptions::new()).unwrap();
+        assert_eq!(plains.ticks_loaded, 40);
+        assert_eq!(plains.ticks_empty, 20);
+        assert!((plains.throughput_per_tick - 500.0 / 60.0).abs() < 1e-9);
+
+        // a full swamp path: 100 fatigue per tile loaded, 10 fatigue removed
+        // per tick
+        let swamp = RoundTripOptions::new().surface(Surface::Swamp);
+        let swamp = round_trip_estimate(&hauler, &from, &to, swamp).unwrap();
+        assert_eq!(swamp.ticks_loaded, 200);
+        assert_eq!(swamp.ticks_empty, 20);
+    }
+
+    #[test]
+    fn road_hauler() {
+        let hauler = body(&[(Part::Carry, 10), (Part::Move, 5)]);
+        let (from, to) = positions(20);
+        let opts = RoundTripOptions::new().surface(Surface::Road);
+
+        assert_eq!(
+            round_trip_estimate(&hauler, &from, &to, opts),
+            Some(RoundTrip {
+                ticks_loaded: 20,
+                ticks_empty: 20,
+                throughput_per_tick: 12.5,
+            })
+        );
+
+        // a longer path found by the pathfinder
+        let detour = round_trip_estimate(&hauler, &from, &to, opts.path_length(25)).unwrap();
+        assert_eq!(detour.ticks_loaded, 25);
+        assert!((detour.throughput_per_tick - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn partial_load_and_work_parts() {
+        // 6 CARRY, 1 WORK and 2 MOVE, carrying 120 in 3 of its CARRY parts
+        let hauler = body(&[(Part::Work, 1), (Part::Carry, 6), (Part::Move, 2)]);
+        let (from, to) = positions(10);
+        let opts = RoundTripOptions::new().carried(120);
+
+        let trip = round_trip_estimate(&hauler, &from, &to, opts).unwrap();
+        // (1 + 3) * 2 fatigue per tile, 4 removed per tick
+        assert_eq!(trip.ticks_loaded, 20);
+        // the WORK part still weighs on the empty trip: 2 fatigue per tile
+        assert_eq!(trip.ticks_empty, 10);
+        assert!((trip.throughput_per_tick - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn boosted_hauler() {
+        let mut hauler = body(&[(Part::Carry, 10)]);
+        hauler.push((Part::Move, Some(ResourceType::CatalyzedZynthiumAlkalide)));
+        hauler.push((Part::Move, Some(ResourceType::CatalyzedZynthiumAlkalide)));
+        hauler[0].1 = Some(ResourceType::KeaniumHydride);
+        let (from, to) = positions(10);
+
+        // 16 fatigue removed per tick, capacity of 550
+        let road = RoundTripOptions::new().surface(Surface::Road);
+        let trip = round_trip_estimate(&hauler, &from, &to, road).unwrap();
+        assert_eq!((trip.ticks_loaded, trip.ticks_empty), (10, 10));
+        assert!((trip.throughput_per_tick - 27.5).abs() < 1e-9);
+
+        let trip = round_trip_estimate(&hauler, &from, &to, RoundTripOptions::new()).unwrap();
+        assert_eq!((trip.ticks_loaded, trip.ticks_empty), (20, 10));
+    }
+
+    #[test]
+    fn immobile_or_empty() {
+        let (from, to) = positions(10);
+        let opts = RoundTripOptions::new();
+
+        let no_move = body(&[(Part::Carry, 4)]);
+        assert_eq!(round_trip_estimate(&no_move, &from, &to, opts), None);
+
+        let no_carry = body(&[(Part::Work, 4), (Part::Move, 4)]);
+        assert_eq!(round_trip_estimate(&no_carry, &from, &to, opts), None);
+
+        let hauler = body(&[(Part::Carry, 4), (Part::Move, 4)]);
+        assert_eq!(
+            round_trip_estimate(&hauler, &from, &to, opts.carried(0)),
+            None
+        );
+    }
+
+    fn route_length(stops: &[(Position, u32)], start: Position, order: &[usize]) -> u32 {
+        let mut current = start;
+        let mut length = 0;
+        for &i in order {
+            length += current.get_range_to(&stops[i].0);
+            current = stops[i].0;
+        }
+        length
+    }
+
+    /// Extensions in the diagonal rows of a bunker around (25, 25), each
+    /// needing 50 energy, listed alternating between opposite sides of the
+    /// bunker.
+    fn bunker() -> Vec<(Position, u32)> {
+        let room = RoomName::new("W1N1").unwrap();
+        let mut stops = Vec::new();
+        for offset in 1..=4 {
+            for step in 0..=offset {
+                for &(dx, dy) in &[(1, 1), (-1, -1), (1, -1), (-1, 1)] {
+                    let x = 25 + dx * (offset - step + 1);
+                    let y = 25 + dy * (step + 1);
+                    stops.push((Position::new(x as u32, y as u32, room), 50));
+                }
+            }
+        }
+        stops
+    }
+
+    #[test]
+    fn fill_route_bunker() {
+        let stops = bunker();
+        let room = RoomName::new("W1N1").unwrap();
+        let start = Position::new(25, 25, room);
+        let naive: Vec<usize> = (0..stops.len()).collect();
+
+        let route = fill_route(&stops, start, 100_000);
+        let mut sorted = route.clone();
+        sorted.sort();
+        assert_eq!(sorted, naive);
+        let optimized = route_length(&stops, start, &route);
+        let naive = route_length(&stops, start, &naive);
+        assert!(optimized * 2 < naive, "{} vs {}", optimized, naive);
+
+        // the same input always gives the same route
+        assert_eq!(fill_route(&stops, start, 100_000), route);
+    }
+
+    #[test]
+    fn fill_route_capacity() {
+        let room = RoomName::new("W1N1").unwrap();
+        let stops = vec![
+            (Position::new(30, 25, room), 50),
+            (Position::new(10, 25, room), 50),
+            (Position::new(26, 25, room), 0),
+            (Position::new(27, 25, room), 200),
+            (Position::new(28, 25, room), 100),
+        ];
+        let start = Position::new(25, 25, room);
+
+        // already full structures are skipped
+        assert_eq!(fill_route(&stops, start, 1000), vec![3, 4, 0, 1]);
+        // the last stop may only be partially filled
+        assert_eq!(fill_route(&stops, start, 250), vec![3, 4]);
+        assert_eq!(fill_route(&stops, start, 0), Vec::<usize>::new());
+        assert_eq!(fill_route(&[], start, 1000), Vec::<usize>::new());
+
+        let many = vec![(start, 1); 100];
+        assert_eq!(fill_route(&many, start, 1000).len(), FILL_ROUTE_MAX_STOPS);
+    }
+}
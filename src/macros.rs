@@ -112,6 +112,11 @@ macro_rules! js_unwrap_ref {
 ///   - `TryFrom<Value>`
 ///   - `TryFrom<&Value>`
 /// - Implements `FromExpectedType<Reference>` for `objJ`
+///
+/// With the `count-references` feature, the struct also holds a
+/// [`debug::PhantomData`] counting its live instances.
+///
+/// [`debug::PhantomData`]: crate::debug::PhantomData
 macro_rules! reference_wrappers {
     (
         $(
@@ -120,12 +125,20 @@ macro_rules! reference_wrappers {
         )+
     ) => {
         $(
+            #[cfg(not(feature = "count-references"))]
             #[derive(Clone, ReferenceType)]
             $(
                 #[$attr]
             )*
             $vis struct $name(Reference);
 
+            #[cfg(feature = "count-references")]
+            #[derive(Clone, ReferenceType)]
+            $(
+                #[$attr]
+            )*
+            $vis struct $name(Reference, $crate::debug::PhantomData<$name>);
+
             impl crate::traits::FromExpectedType<Reference> for $name {
                 fn from_expected_type(reference: Reference) -> Result<Self, ConversionError> {
                     #[cfg(feature = "check-all-casts")]
@@ -240,7 +253,7 @@ macro_rules! impl_has_id {
 /// ```ignore
 /// creep_simple_generic_action! {
 ///     impl Creep {
-///         pub fn $rust_method_name1($action_target_trait1) = js_method_name1();
+///         pub fn $rust_method_name1($action_target_trait1) = js_method_name1() => $intent_kind;
 ///         pub fn $rust_method_name2($action_target_trait2) = js_method_name2();
 ///         ...
 ///     }
@@ -251,20 +264,27 @@ macro_rules! impl_has_id {
 ///
 /// The generic comes from the fact that this implements the method to be able
 /// to target any object that conforms to the `action_target_trait` trait.
+///
+/// The optional intent kind is the [`IntentKind`] recorded by the method; see
+/// [`record_intent!`].
+///
+/// [`IntentKind`]: crate::intents::IntentKind
 macro_rules! creep_simple_generic_action {
     (
         impl $struct_name:ident {
             $(
-                $vis:vis fn $method:ident($trait:ident) = $js_name:ident ();
+                $vis:vis fn $method:ident($trait:ident) = $js_name:ident () $(=> $kind:ident)?;
             )+
         }
     ) => (
         impl $struct_name {
             $(
+                #[cfg_attr(feature = "debug-intents", track_caller)]
                 $vis fn $method<T>(&self, target: &T) -> ReturnCode
                 where
                     T: ?Sized + $trait,
                 {
+                    $(record_intent!(self, $kind);)?
                     js_unwrap!(@{self.as_ref()}.$js_name(@{target.as_ref()}))
                 }
             )*
@@ -279,9 +299,9 @@ macro_rules! creep_simple_generic_action {
 ///
 /// Macro Syntax:
 /// ```ignore
-/// creep_simple_generic_action! {
+/// creep_simple_concrete_action! {
 ///     impl Creep {
-///         pub fn $rust_method_name1($target_type1) = js_method_name1();
+///         pub fn $rust_method_name1($target_type1) = js_method_name1() => $intent_kind;
 ///         pub fn $rust_method_name2($target_type2) = js_method_name2();
 ///         ...
 ///     }
@@ -291,18 +311,21 @@ macro_rules! creep_simple_generic_action {
 /// For this macro, the last comma is facultative.
 ///
 /// The concrete comes from the fact that this implements the method to be able
-/// to target only the `type` given.
+/// to target only the `type` given. The optional intent kind is as for
+/// [`creep_simple_generic_action!`].
 macro_rules! creep_simple_concrete_action {
     (
         impl $struct_name:ident {
             $(
-                $vis:vis fn $method:ident($type:ty) = $js_name:ident ();
+                $vis:vis fn $method:ident($type:ty) = $js_name:ident () $(=> $kind:ident)?;
             )+
         }
     ) => (
         impl $struct_name {
             $(
+                #[cfg_attr(feature = "debug-intents", track_caller)]
                 $vis fn $method(&self, target: &$type) -> ReturnCode {
+                    $(record_intent!(self, $kind);)?
                     js_unwrap!(@{self.as_ref()}.$js_name(@{target.as_ref()}))
                 }
             )*
@@ -310,6 +333,37 @@ macro_rules! creep_simple_concrete_action {
     )
 }
 
+/// Records an intent issued by an action method for [`intents::check`], when
+/// the `debug-intents` feature is enabled, and does nothing otherwise.
+///
+/// Macro Syntax:
+/// ```ignore
+/// record_intent!(self, $intent_kind);
+/// record_intent!(self, $intent_kind, $resource_type);
+/// ```
+///
+/// `self` must be a wrapper around a game object with an id, and the intent
+/// kind a variant of [`IntentKind`]. Action methods recording intents should
+/// be annotated with `#[cfg_attr(feature = "debug-intents", track_caller)]`
+/// so the intent is recorded with the location of their caller.
+///
+/// [`intents::check`]: crate::intents::check
+/// [`IntentKind`]: crate::intents::IntentKind
+macro_rules! record_intent {
+    ($object:expr, $kind:ident) => {
+        #[cfg(feature = "debug-intents")]
+        crate::intents::record($object.as_ref(), crate::intents::IntentKind::$kind, None);
+    };
+    ($object:expr, $kind:ident, $resource:expr) => {
+        #[cfg(feature = "debug-intents")]
+        crate::intents::record(
+            $object.as_ref(),
+            crate::intents::IntentKind::$kind,
+            Some($resource),
+        );
+    };
+}
+
 /// Declares an item with a doc attribute computed by some macro expression.
 /// This allows documentation to be dynamically generated based on input.
 /// Necessary to work around https://github.com/rust-lang/rust/issues/52607.
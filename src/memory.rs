@@ -46,21 +46,46 @@
 //!
 //! # Other methods that provide `MemoryReference`s
 //! In addition to accessing the memory from the root, it is possible to
-//! access the memory via creeps, spawns, rooms and flags. Accessing the memory
-//! from those objects will also result in a `MemoryReference` which instead
-//! points at the root of this object's memory.
+//! access the memory via creeps, power creeps, spawns and rooms. Accessing the
+//! memory from those objects will also result in a `MemoryReference` which
+//! instead points at the root of this object's memory, such as
+//! `Memory.creeps[name]`.
+//!
+//! # Avoiding the `Memory` global
+//! The game parses `RawMemory` into the `Memory` global the first time it is
+//! accessed each tick, which can take several milliseconds for large memory.
+//! There are two ways to reduce this cost:
+//!  - calling [`disable_js_memory_parse`] at the start of every tick, which
+//!    keeps the parsed `Memory` object cached in the javascript heap, only
+//!    parsing it again after a global reset;
+//!  - storing memory in a [`MemoryBackend`] such as [`RawMemoryBackend`],
+//!    either selected with [`set_backend`] for [`root`] and the memory of
+//!    game objects to use it, or used directly to store serialized rust
+//!    types. `Memory` is then never parsed as long as javascript code doesn't
+//!    touch it.
 //!
 //! [`root`]: crate::memory::root
+//! [`set_backend`]: crate::memory::set_backend
+//! [`disable_js_memory_parse`]: crate::memory::disable_js_memory_parse
+//! [`MemoryBackend`]: crate::memory::backend::MemoryBackend
+//! [`RawMemoryBackend`]: crate::memory::backend::RawMemoryBackend
 
-use std::fmt;
+use std::{cell::RefCell, fmt};
 
 use stdweb::{JsSerialize, Reference, Value};
 
 use crate::{
+    game,
+    local::GameTime,
     traits::{TryFrom, TryInto},
     ConversionError,
 };
 
+use self::backend::MemoryBackend;
+
+pub mod backend;
+pub mod migrations;
+
 #[derive(Clone, Debug)]
 pub struct UnexpectedTypeError;
 
@@ -328,7 +353,142 @@ impl TryFrom<Value> for MemoryReference {
     }
 }
 
-/// Get a reference to the `Memory` global object
+/// Get a reference to the root memory object.
+///
+/// This is the `Memory` global object, unless a backend was selected with
+/// [`set_backend`], in which case it's the memory read from that backend.
 pub fn root() -> MemoryReference {
-    js_unwrap!(Memory)
+    BACKEND.with(|state| match *state.borrow_mut() {
+        Some(ref mut state) => state.root(),
+        None => js_unwrap!(Memory),
+    })
+}
+
+/// The memory of the game object named `name`, such as `Memory.creeps[name]`
+/// for the `"creeps"` collection, created if it doesn't exist.
+pub(crate) fn object_memory(collection: &str, name: &str) -> MemoryReference {
+    let root = root();
+    js_unwrap! {{
+        const objects = @{root.as_ref()}[@{collection}] = @{root.as_ref()}[@{collection}] || {};
+        return objects[@{name}] = objects[@{name}] || {};
+    }}
+}
+
+/// Replaces the memory of the game object named `name`, such as
+/// `Memory.creeps[name]` for the `"creeps"` collection.
+pub(crate) fn set_object_memory(collection: &str, name: &str, memory: &MemoryReference) {
+    let root = root();
+    js! { @(no_return)
+        const objects = @{root.as_ref()}[@{collection}] = @{root.as_ref()}[@{collection}] || {};
+        objects[@{name}] = @{memory.as_ref()};
+    }
+}
+
+thread_local! {
+    static BACKEND: RefCell<Option<BackendState>> = RefCell::new(None);
+}
+
+struct BackendState {
+    backend: Box<dyn MemoryBackend>,
+    /// The memory read from the backend, and the tick it was read on.
+    root: Option<(GameTime, MemoryReference)>,
+}
+
+impl BackendState {
+    fn root(&mut self) -> MemoryReference {
+        let time = game::time();
+        if let Some((read_at, ref root)) = self.root {
+            if read_at == time {
+                return MemoryReference(root.0.clone());
+            }
+        }
+        let root: MemoryReference = match self.backend.read() {
+            Some(data) => (js! { return JSON.parse(@{data}); })
+                .try_into()
+                .expect("expected memory backend to hold a JSON object"),
+            None if self.backend.is_available() => MemoryReference::new(),
+            // not cached, so that `flush` doesn't overwrite the stored memory
+            None => return MemoryReference::new(),
+        };
+        self.root = Some((time, MemoryReference(root.0.clone())));
+        root
+    }
+}
+
+/// Stores the memory returned by [`root`] and the `memory` methods of game
+/// objects in `backend` instead of the `Memory` global.
+///
+/// The stored string must be a JSON object. It's read the first time memory
+/// is accessed each tick, and changes are only written back to the backend by
+/// [`flush`], which must be called at the end of every tick.
+///
+/// On ticks where the backend isn't available, memory accessors return empty
+/// objects which are never written back, leaving the stored memory untouched.
+///
+/// The game itself still uses `Memory` for the paths cached by creeps moving
+/// with `move_to`, unless `reuse_path` is 0, and in the `memory` property of
+/// game objects in javascript.
+pub fn set_backend<B: MemoryBackend + 'static>(backend: B) {
+    BACKEND.with(|state| {
+        *state.borrow_mut() = Some(BackendState {
+            backend: Box::new(backend),
+            root: None,
+        })
+    });
+}
+
+/// Stores memory in the `Memory` global again, after [`set_backend`].
+///
+/// Changes not yet written with [`flush`] are lost.
+pub fn clear_backend() {
+    BACKEND.with(|state| *state.borrow_mut() = None);
+}
+
+/// Writes the memory read this tick back to the backend selected with
+/// [`set_backend`].
+///
+/// This does nothing if memory wasn't accessed this tick, or when memory is
+/// stored in the `Memory` global, which the game writes back itself.
+pub fn flush() {
+    BACKEND.with(|state| {
+        if let Some(ref mut state) = *state.borrow_mut() {
+            if let Some((read_at, ref root)) = state.root {
+                if read_at == game::time() {
+                    let data: String = js_unwrap!(JSON.stringify(@{root.as_ref()}));
+                    state.backend.write(&data);
+                }
+            }
+        }
+    });
+}
+
+/// Replaces the `Memory` global with a copy parsed once and cached in the
+/// javascript heap.
+///
+/// This must be called at the start of every tick, before anything accesses
+/// `Memory`. The cached object is serialized back to `RawMemory` at the end of
+/// each tick, so changes made to it are persisted, and it's reused on the
+/// next tick instead of parsing `RawMemory` again. After a global reset, or
+/// if a tick was skipped, `RawMemory` is parsed again.
+///
+/// Changes made with `RawMemory.set` are not seen by the cached object, and
+/// are overwritten at the end of the tick. Don't combine this with a
+/// [`RawMemoryBackend`].
+///
+/// [`RawMemoryBackend`]: crate::memory::backend::RawMemoryBackend
+pub fn disable_js_memory_parse() {
+    js! { @(no_return)
+        const time = Game.time;
+        if (global.__rust_parsed_memory_time !== time) {
+            // the cache is only up to date if it was written back last tick
+            if (global.__rust_parsed_memory_time !== time - 1) {
+                const raw = RawMemory.get();
+                global.__rust_parsed_memory = raw ? JSON.parse(raw) : {};
+            }
+            global.__rust_parsed_memory_time = time;
+        }
+        delete global.Memory;
+        global.Memory = global.__rust_parsed_memory;
+        RawMemory._parsed = global.__rust_parsed_memory;
+    }
 }
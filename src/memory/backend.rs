@@ -0,0 +1,262 @@
+//! Pluggable storage for memory serialized by rust code.
+//!
+//! Bots which keep their state in rust types can serialize it as a whole
+//! instead of going through [`MemoryReference`] accessors. A
+//! [`MemoryBackend`] decides where that serialized string lives:
+//!
+//! - [`RawMemoryBackend`] stores it directly in `RawMemory`, so the `Memory`
+//!   global is never parsed. This is the cheapest option, but memory is then
+//!   no longer usable from JavaScript or the game's memory viewer.
+//! - [`JsMemoryBackend`] stores it as the contents of the `Memory` global,
+//!   keeping it visible from JavaScript at the cost of the game's parsing.
+//! - [`HeapBackend`] keeps it in the wasm heap only. It survives between
+//!   ticks, but is lost on global resets.
+//! - [`SegmentBackend`] stores it in a `RawMemory` segment, keeping it out of
+//!   the main memory string, for data which isn't needed every tick.
+//!
+//! [`load`] and [`store`] (de)serialize any serde type through a backend.
+//!
+//! [`MemoryReference`]: crate::memory::MemoryReference
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::raw_memory;
+
+/// Storage for a single serialized memory string.
+pub trait MemoryBackend {
+    /// Reads the stored string, or `None` if nothing is stored.
+    fn read(&mut self) -> Option<String>;
+
+    /// Replaces the stored string.
+    fn write(&mut self, data: &str);
+
+    /// Whether [`read`] can currently tell what's stored, which is always the
+    /// case unless overridden.
+    ///
+    /// Backends which can't always be read, such as segments which must be
+    /// made active a tick in advance, return `false` while reading would
+    /// wrongly report that nothing is stored.
+    ///
+    /// [`read`]: MemoryBackend::read
+    fn is_available(&mut self) -> bool {
+        true
+    }
+}
+
+/// Stores memory in `RawMemory`, using `RawMemory.get` and `RawMemory.set`.
+///
+/// When using this backend, avoid touching the `Memory` global: the game
+/// would then parse `RawMemory` as JSON and serialize `Memory` back over
+/// it at the end of the tick.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RawMemoryBackend;
+
+impl MemoryBackend for RawMemoryBackend {
+    fn read(&mut self) -> Option<String> {
+        let data = raw_memory::get();
+        if data.is_empty() {
+            None
+        } else {
+            Some(data)
+        }
+    }
+
+    fn write(&mut self, data: &str) {
+        raw_memory::set(data);
+    }
+}
+
+/// Stores memory as the contents of the `Memory` global object.
+///
+/// The stored string must be a JSON object.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct JsMemoryBackend;
+
+impl MemoryBackend for JsMemoryBackend {
+    fn read(&mut self) -> Option<String> {
+        js_unwrap!(Object.keys(Memory).length === 0 ? null : JSON.stringify(Memory))
+    }
+
+    fn write(&mut self, data: &str) {
+        js! { @(no_return)
+            const data = JSON.parse(@{data});
+            for (const key of Object.keys(Memory)) {
+                delete Memory[key];
+            }
+            Object.assign(Memory, data);
+        }
+    }
+}
+
+/// Stores memory in a `RawMemory` segment.
+///
+/// The segment must have been made active with
+/// [`raw_memory::set_active_segments`] on the previous tick to be read, which
+/// [`is_available`] reports. Segments hold at most 100 KB.
+///
+/// [`is_available`]: MemoryBackend::is_available
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SegmentBackend {
+    id: u32,
+}
+
+impl SegmentBackend {
+    /// Creates a backend for the segment with the given id, from 0 to 99.
+    pub fn new(id: u32) -> Self {
+        SegmentBackend { id }
+    }
+
+    /// The id of the segment.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl MemoryBackend for SegmentBackend {
+    /// Reads the segment, or `None` if it's empty or not active this tick.
+    ///
+    /// `None` doesn't mean that nothing is stored unless [`is_available`]
+    /// returns `true`.
+    ///
+    /// [`is_available`]: MemoryBackend::is_available
+    fn read(&mut self) -> Option<String> {
+        raw_memory::get_segment(self.id).filter(|data| !data.is_empty())
+    }
+
+    fn write(&mut self, data: &str) {
+        raw_memory::set_segment(self.id, data);
+    }
+
+    /// Whether the segment is active this tick.
+    fn is_available(&mut self) -> bool {
+        raw_memory::get_segment(self.id).is_some()
+    }
+}
+
+/// Keeps memory in the wasm heap, without ever persisting it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeapBackend {
+    data: Option<String>,
+}
+
+impl HeapBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MemoryBackend for HeapBackend {
+    fn read(&mut self) -> Option<String> {
+        self.data.clone()
+    }
+
+    fn write(&mut self, data: &str) {
+        self.data = Some(data.to_owned());
+    }
+}
+
+/// Deserializes a value from the memory stored in `backend`, or returns
+/// `None` if nothing is stored.
+pub fn load<T, B>(backend: &mut B) -> Result<Option<T>, serde_json::Error>
+where
+    T: DeserializeOwned,
+    B: ?Sized + MemoryBackend,
+{
+    backend
+        .read()
+        .map(|data| serde_json::from_str(&data))
+        .transpose()
+}
+
+/// Serializes a value and stores it in `backend`.
+pub fn store<T, B>(backend: &mut B, value: &T) -> Result<(), serde_json::Error>
+where
+    T: ?Sized + Serialize,
+    B: ?Sized + MemoryBackend,
+{
+    let data = serde_json::to_string(value)?;
+    backend.write(&data);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{load, store, HeapBackend, MemoryBackend};
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct BotMemory {
+        tick: u32,
+        rooms: HashMap<String, Vec<u32>>,
+    }
+
+    /// Backend recording the strings written to it, standing in for
+    /// `RawMemory`.
+    #[derive(Default)]
+    struct RecordingBackend {
+        writes: Vec<String>,
+    }
+
+    impl MemoryBackend for RecordingBackend {
+        fn read(&mut self) -> Option<String> {
+            self.writes.last().cloned()
+        }
+
+        fn write(&mut self, data: &str) {
+            self.writes.push(data.to_owned());
+        }
+    }
+
+    fn sample() -> BotMemory {
+        let mut rooms = HashMap::new();
+        rooms.insert("W1N1".to_owned(), vec![1, 2, 3]);
+        BotMemory { tick: 42, rooms }
+    }
+
+    #[test]
+    fn raw_string_round_trip() {
+        let mut backend = RecordingBackend::default();
+        assert_eq!(load::<BotMemory, _>(&mut backend).unwrap(), None);
+
+        store(&mut backend, &sample()).unwrap();
+        assert_eq!(backend.writes.len(), 1);
+        assert_eq!(backend.writes[0], r#"{"tick":42,"rooms":{"W1N1":[1,2,3]}}"#);
+        assert_eq!(load(&mut backend).unwrap(), Some(sample()));
+    }
+
+    #[test]
+    fn backend_selection() {
+        let mut heap = HeapBackend::new();
+        let mut recording = RecordingBackend::default();
+
+        for (i, backend) in [
+            &mut heap as &mut dyn MemoryBackend,
+            &mut recording as &mut dyn MemoryBackend,
+        ]
+        .iter_mut()
+        .enumerate()
+        {
+            let memory = BotMemory {
+                tick: i as u32,
+                ..BotMemory::default()
+            };
+            store(*backend, &memory).unwrap();
+            assert_eq!(load(*backend).unwrap(), Some(memory));
+        }
+
+        assert_eq!(heap.read(), Some(r#"{"tick":0,"rooms":{}}"#.to_owned()));
+        assert_eq!(
+            recording.writes,
+            vec![r#"{"tick":1,"rooms":{}}"#.to_owned()]
+        );
+    }
+
+    #[test]
+    fn corrupted_memory() {
+        let mut backend = HeapBackend::new();
+        backend.write("{\"tick\":");
+        assert!(load::<BotMemory, _>(&mut backend).is_err());
+    }
+}
@@ -0,0 +1,356 @@
+//! Versioned changes to the layout of memory.
+//!
+//! As a bot evolves, the layout of its memory changes, and memory written by
+//! the previous version of the code has to be converted. [`Migrations`] holds
+//! an ordered list of conversions, each bringing memory from one schema
+//! version to the next. The current version is stored in memory itself,
+//! under [`SCHEMA_VERSION_KEY`], so only the migrations which haven't run yet
+//! are applied:
+//!
+//! ```no_run
+//! use screeps::memory::{
+//!     backend::JsMemoryBackend,
+//!     migrations::{MigrationError, Migrations},
+//! };
+//! use serde_json::Value;
+//!
+//! fn rename_rooms(memory: &mut Value) -> Result<(), MigrationError> {
+//!     let memory = memory.as_object_mut().unwrap();
+//!     if let Some(rooms) = memory.remove("rooms") {
+//!         memory.insert("colonies".to_owned(), rooms);
+//!     }
+//!     Ok(())
+//! }
+//!
+//! let migrations = Migrations::new().add("rename rooms to colonies", rename_rooms);
+//! // at startup, before reading memory
+//! migrations.run_pending(&mut JsMemoryBackend).unwrap();
+//! ```
+//!
+//! Pending migrations are applied all at once: if any of them fails, none of
+//! their changes are written back.
+use std::{error::Error, fmt};
+
+use serde_json::{Map, Value};
+
+use super::backend::MemoryBackend;
+
+/// Key of the top level memory object under which the schema version is
+/// stored.
+pub const SCHEMA_VERSION_KEY: &str = "__schema_version";
+
+/// A conversion of memory from one schema version to the next.
+///
+/// Migrations receive the whole top level memory object, and should leave it
+/// an object.
+pub type Migration = fn(&mut Value) -> Result<(), MigrationError>;
+
+/// Error returned by a [`Migration`] unable to convert memory.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MigrationError {
+    message: String,
+}
+
+impl MigrationError {
+    /// Creates an error describing why memory couldn't be converted.
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        MigrationError {
+            message: message.into(),
+        }
+    }
+
+    /// The description the error was created with.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for MigrationError {}
+
+/// Error returned by [`Migrations::apply`] and [`Migrations::run_pending`].
+///
+/// Memory is left untouched whenever an error is returned.
+#[derive(Debug)]
+pub enum RunError {
+    /// The backend can't currently be read, such as a segment which isn't
+    /// active this tick.
+    Unavailable,
+    /// The stored memory isn't valid JSON.
+    Parse(serde_json::Error),
+    /// The stored memory isn't a JSON object.
+    NotAnObject,
+    /// The stored schema version isn't a number, or is newer than the last
+    /// known migration, such as after rolling back to older code.
+    UnknownVersion(Value),
+    /// A migration returned an error.
+    Failed {
+        /// The schema version the migration would have brought memory to.
+        version: u32,
+        /// The name the migration was added with.
+        name: &'static str,
+        error: MigrationError,
+    },
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Unavailable => write!(f, "memory backend isn't available"),
+            RunError::Parse(e) => write!(f, "couldn't parse memory: {}", e),
+            RunError::NotAnObject => write!(f, "memory isn't an object"),
+            RunError::UnknownVersion(version) => {
+                write!(f, "unknown memory schema version {}", version)
+            }
+            RunError::Failed {
+                version,
+                name,
+                error,
+            } => write!(f, "migration {} ({}) failed: {}", version, name, error),
+        }
+    }
+}
+
+impl Error for RunError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RunError::Parse(e) => Some(e),
+            RunError::Failed { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// An ordered list of migrations.
+///
+/// The migration added first brings memory from version 0, memory without a
+/// stored version, to version 1; the second from version 1 to 2, and so on.
+/// Migrations should never be removed or reordered once deployed.
+#[derive(Clone, Debug, Default)]
+pub struct Migrations {
+    steps: Vec<(&'static str, Migration)>,
+}
+
+impl Migrations {
+    /// Creates an empty list, leaving memory at version 0.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a migration to the next schema version, with a name used when
+    /// logging it.
+    pub fn add(mut self, name: &'static str, migration: Migration) -> Self {
+        self.steps.push((name, migration));
+        self
+    }
+
+    /// The schema version memory is at once every migration has run.
+    pub fn latest_version(&self) -> u32 {
+        self.steps.len() as u32
+    }
+
+    /// Applies the migrations `memory` hasn't seen yet, updating its stored
+    /// schema version, and returns the names of the migrations which ran.
+    ///
+    /// `memory` is only modified if every pending migration succeeds.
+    pub fn apply(&self, memory: &mut Value) -> Result<Vec<&'static str>, RunError> {
+        let version = match memory
+            .as_object()
+            .ok_or(RunError::NotAnObject)?
+            .get(SCHEMA_VERSION_KEY)
+        {
+            None => 0,
+            Some(version) => match version.as_u64() {
+                Some(version) if version <= u64::from(self.latest_version()) => version as u32,
+                _ => return Err(RunError::UnknownVersion(version.clone())),
+            },
+        };
+        let pending = &self.steps[version as usize..];
+        if pending.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut migrated = memory.clone();
+        for (i, &(name, migration)) in pending.iter().enumerate() {
+            let version = version + i as u32 + 1;
+            migration(&mut migrated).map_err(|error| RunError::Failed {
+                version,
+                name,
+                error,
+            })?;
+            migrated
+                .as_object_mut()
+                .ok_or(RunError::NotAnObject)?
+                .insert(SCHEMA_VERSION_KEY.to_owned(), version.into());
+        }
+        *memory = migrated;
+
+        Ok(pending.iter().map(|&(name, _)| name).collect())
+    }
+
+    /// Applies the pending migrations to the memory stored in `backend`,
+    /// writing it back only if they all succeed, and returns the names of the
+    /// migrations which ran.
+    ///
+    /// Empty memory is treated as an empty object at version 0, so every
+    /// migration runs on it. Each migration which ran is logged at the info
+    /// level.
+    ///
+    /// Returns [`RunError::Unavailable`] without writing anything if the
+    /// backend can't be read this tick, as its memory would otherwise be
+    /// mistaken for empty and overwritten.
+    pub fn run_pending<B>(&self, backend: &mut B) -> Result<Vec<&'static str>, RunError>
+    where
+        B: ?Sized + MemoryBackend,
+    {
+        if !backend.is_available() {
+            return Err(RunError::Unavailable);
+        }
+        let mut memory = match backend.read() {
+            Some(data) => serde_json::from_str(&data).map_err(RunError::Parse)?,
+            None => Value::Object(Map::new()),
+        };
+        let ran = self.apply(&mut memory)?;
+        if !ran.is_empty() {
+            backend.write(&memory.to_string());
+            for name in &ran {
+                log::info!("applied memory migration: {}", name);
+            }
+        }
+        Ok(ran)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::{MigrationError, Migrations, RunError};
+    use crate::memory::backend::{HeapBackend, MemoryBackend};
+
+    fn add_creeps(memory: &mut Value) -> Result<(), MigrationError> {
+        memory["creeps"] = json!({});
+        Ok(())
+    }
+
+    fn rename_rooms(memory: &mut Value) -> Result<(), MigrationError> {
+        let memory = memory.as_object_mut().unwrap();
+        let rooms = memory
+            .remove("rooms")
+            .ok_or_else(|| MigrationError::new("no rooms"))?;
+        memory.insert("colonies".to_owned(), rooms);
+        Ok(())
+    }
+
+    fn count_colonies(memory: &mut Value) -> Result<(), MigrationError> {
+        let count = memory["colonies"].as_array().map_or(0, Vec::len);
+        memory["colony_count"] = count.into();
+        Ok(())
+    }
+
+    fn migrations() -> Migrations {
+        Migrations::new()
+            .add("add creeps", add_creeps)
+            .add("rename rooms", rename_rooms)
+            .add("count colonies", count_colonies)
+    }
+
+    #[test]
+    fn migration_chain() {
+        let mut backend = HeapBackend::new();
+        backend.write(r#"{"rooms":["W1N1","W2N1"]}"#);
+
+        let migrations = migrations();
+        assert_eq!(migrations.latest_version(), 3);
+        assert_eq!(
+            migrations.run_pending(&mut backend).unwrap(),
+            vec!["add creeps", "rename rooms", "count colonies"]
+        );
+        let memory: Value = serde_json::from_str(&backend.read().unwrap()).unwrap();
+        assert_eq!(
+            memory,
+            json!({
+                "__schema_version": 3,
+                "creeps": {},
+                "colonies": ["W1N1", "W2N1"],
+                "colony_count": 2,
+            })
+        );
+
+        // nothing left to run
+        assert!(migrations.run_pending(&mut backend).unwrap().is_empty());
+
+        // only the new migration runs once one is added
+        let mut memory = json!({"__schema_version": 2, "colonies": []});
+        assert_eq!(
+            migrations.apply(&mut memory).unwrap(),
+            vec!["count colonies"]
+        );
+        assert_eq!(memory["colony_count"], 0);
+    }
+
+    /// Backend standing in for a segment which isn't active this tick.
+    #[derive(Default)]
+    struct InactiveSegment {
+        writes: Vec<String>,
+    }
+
+    impl MemoryBackend for InactiveSegment {
+        fn read(&mut self) -> Option<String> {
+            None
+        }
+
+        fn write(&mut self, data: &str) {
+            self.writes.push(data.to_owned());
+        }
+
+        fn is_available(&mut self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn unavailable_backend() {
+        let mut backend = InactiveSegment::default();
+        assert!(matches!(
+            migrations().run_pending(&mut backend),
+            Err(RunError::Unavailable)
+        ));
+        assert!(backend.writes.is_empty());
+    }
+
+    #[test]
+    fn failed_migration() {
+        let original = r#"{"__schema_version":0,"spawns":{}}"#;
+        let mut backend = HeapBackend::new();
+        backend.write(original);
+
+        // the first migration succeeds, but the second finds no rooms
+        match migrations().run_pending(&mut backend) {
+            Err(RunError::Failed {
+                version: 2,
+                name: "rename rooms",
+                error,
+            }) => assert_eq!(error.message(), "no rooms"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(backend.read().unwrap(), original);
+
+        let mut newer = json!({"__schema_version": 4});
+        assert!(matches!(
+            migrations().apply(&mut newer),
+            Err(RunError::UnknownVersion(_))
+        ));
+        backend.write("[]");
+        assert!(matches!(
+            migrations().run_pending(&mut backend),
+            Err(RunError::NotAnObject)
+        ));
+    }
+}
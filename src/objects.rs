@@ -30,11 +30,13 @@ mod structure;
 pub use self::{
     creep_shared::{MoveToOptions, SharedCreepProperties},
     impls::{
-        AttackEvent, AttackType, Bodypart, BuildEvent, CircleStyle, Effect, Event, EventType,
-        ExitEvent, FindOptions, FontStyle, HarvestEvent, HealEvent, HealType, LineDrawStyle,
-        LineStyle, LookResult, ObjectDestroyedEvent, Path, PolyStyle, PortalDestination,
-        PositionedLookResult, RectStyle, RepairEvent, Reservation, ReserveControllerEvent,
-        RoomVisual, Sign, SpawnOptions, Step, TextAlign, TextStyle, UpgradeControllerEvent, Visual,
+        AttackEvent, AttackType, Bodypart, BuildEvent, CircleStyle, DowngradeUrgency, Effect,
+        Event, EventType, ExitEvent, FindOptions, FlagPlacement, FontStyle, HarvestEvent,
+        HealEvent, HealType, LaunchNukeError, LineDrawStyle, LineStyle, LookResult,
+        ObjectDestroyedEvent, Path, PolyStyle, PortalDestination, PositionedLookResult,
+        PowerCreepPlacement, RectStyle, RefillPriority, RepairEvent, Reservation,
+        ReserveControllerEvent, RoomVisual, SerializedPathError, Sign, SpawnOptions, Step,
+        TextAlign, TextStyle, TowerRefillPolicy, UpgradeControllerEvent, Visual,
     },
     structure::Structure,
 };
@@ -227,8 +229,19 @@ pub unsafe trait RoomObjectProperties: AsRef<Reference> + HasPosition {
         js_unwrap_ref!(@{self.as_ref()}.room)
     }
 
+    /// The effects applied to this object.
+    ///
+    /// Effects added to the game after this crate was released have an
+    /// [`EffectType::Unknown`] type, and effects which can't be parsed are
+    /// skipped.
+    ///
+    /// [`EffectType::Unknown`]: crate::constants::EffectType::Unknown
     fn effects(&self) -> Vec<Effect> {
-        js_unwrap!(@{self.as_ref()}.effects || [])
+        let effects: Vec<Value> = js_unwrap!(@{self.as_ref()}.effects || []);
+        effects
+            .into_iter()
+            .filter_map(|effect| effect.try_into().ok())
+            .collect()
     }
 }
 
@@ -326,7 +339,9 @@ pub unsafe trait OwnedStructureProperties: StructureProperties {
     where
         Self: SizedRoomObject,
     {
-        OwnedStructure(self.into())
+        // this is an owned structure, as `OwnedStructureProperties` is only
+        // implemented for them
+        unsafe { OwnedStructure::from_reference_unchecked(self.into()) }
     }
 }
 
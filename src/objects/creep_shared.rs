@@ -5,7 +5,7 @@ use stdweb::{Value, Reference};
 use crate::{
     constants::{Direction, ResourceType, ReturnCode},
     local::{Position, RoomName},
-    memory::MemoryReference,
+    memory::{self, MemoryReference},
     objects::{
         Creep, FindOptions, HasPosition, PolyStyle, PowerCreep, Resource, RoomObjectProperties,
         Step, Transferable, Withdrawable,
@@ -27,7 +27,9 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         js_unwrap!(@{self.as_ref()}.cancelOrder(@{name}))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn drop(&self, ty: ResourceType, amount: Option<u32>) -> ReturnCode {
+        record_intent!(self, Drop, ty);
         match amount {
             Some(v) => {
                 js_unwrap!(@{self.as_ref()}.drop(__resource_type_num_to_str(@{ty as u32}), @{v}))
@@ -36,14 +38,19 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         }
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn move_direction(&self, dir: Direction) -> ReturnCode {
+        record_intent!(self, Move);
         js_unwrap!(@{self.as_ref()}.move(@{dir as u32}))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn move_to_xy(&self, x: u32, y: u32) -> ReturnCode {
+        record_intent!(self, Move);
         js_unwrap!(@{self.as_ref()}.moveTo(@{x}, @{y}))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn move_to_xy_with_options<'a, F>(
         &self,
         x: u32,
@@ -57,11 +64,14 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         self.move_to_with_options(&pos, move_options)
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn move_to<T: ?Sized + HasPosition>(&self, target: &T) -> ReturnCode {
+        record_intent!(self, Move);
         let p = target.pos();
         js_unwrap!(@{self.as_ref()}.moveTo(pos_from_packed(@{p.packed_repr()})))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn move_to_with_options<'a, F, T>(
         &self,
         target: &T,
@@ -71,6 +81,7 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         T: ?Sized + HasPosition,
         F: FnMut(RoomName, CostMatrix<'a>) -> SingleRoomCostResult<'a> + 'a,
     {
+        record_intent!(self, Move);
         let MoveToOptions {
             reuse_path,
             serialize_memory,
@@ -145,22 +156,39 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         .expect("expected return code from moveTo")
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn move_by_path_serialized(&self, path: &str) -> ReturnCode {
+        record_intent!(self, Move);
         js_unwrap!(@{self.as_ref()}.moveByPath(@{path}))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn move_by_path_steps(&self, path: &[Step]) -> ReturnCode {
+        record_intent!(self, Move);
         js_unwrap!(@{self.as_ref()}.moveByPath(@{path}))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn move_by_path_search_result(&self, path: &SearchResults) -> ReturnCode {
+        record_intent!(self, Move);
         js_unwrap!(@{self.as_ref()}.moveByPath(@{path.opaque_path()}))
     }
 
+    /// The memory of this creep, or an empty object not stored anywhere if
+    /// the creep isn't owned by the player.
     fn memory(&self) -> MemoryReference {
-        js_unwrap!(@{self.as_ref()}.memory)
+        if self.my() {
+            memory::object_memory(self.memory_collection(), &self.name())
+        } else {
+            MemoryReference::new()
+        }
     }
 
+    /// The key of the top level memory object holding the memory of this kind
+    /// of creep.
+    #[doc(hidden)]
+    fn memory_collection(&self) -> &'static str;
+
     fn my(&self) -> bool {
         js_unwrap!(@{self.as_ref()}.my)
     }
@@ -177,11 +205,15 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         js_unwrap!(@{self.as_ref()}.owner.username)
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn pickup(&self, target: &Resource) -> ReturnCode {
+        record_intent!(self, Pickup);
         js_unwrap!(@{self.as_ref()}.pickup(@{target.as_ref()}))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn say(&self, msg: &str, public: bool) -> ReturnCode {
+        record_intent!(self, Say);
         js_unwrap!(@{self.as_ref()}.say(@{msg}, @{public}))
     }
 
@@ -189,7 +221,9 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         js_unwrap!(@{self.as_ref()}.saying)
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn suicide(&self) -> ReturnCode {
+        record_intent!(self, Suicide);
         js_unwrap!(@{self.as_ref()}.suicide())
     }
 
@@ -201,10 +235,12 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         Ok(ttl)
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn transfer_amount<T>(&self, target: &T, ty: ResourceType, amount: u32) -> ReturnCode
     where
         T: ?Sized + Transferable,
     {
+        record_intent!(self, Transfer, ty);
         js_unwrap!(@{self.as_ref()}.transfer(
             @{target.as_ref()},
             __resource_type_num_to_str(@{ty as u32}),
@@ -212,20 +248,24 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         ))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn transfer_all<T>(&self, target: &T, ty: ResourceType) -> ReturnCode
     where
         T: ?Sized + Transferable,
     {
+        record_intent!(self, Transfer, ty);
         js_unwrap!(@{self.as_ref()}.transfer(
             @{target.as_ref()},
             __resource_type_num_to_str(@{ty as u32})
         ))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn withdraw_amount<T>(&self, target: &T, ty: ResourceType, amount: u32) -> ReturnCode
     where
         T: ?Sized + Withdrawable,
     {
+        record_intent!(self, Withdraw, ty);
         js_unwrap!(@{self.as_ref()}.withdraw(
             @{target.as_ref()},
             __resource_type_num_to_str(@{ty as u32}),
@@ -233,10 +273,12 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
         ))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     fn withdraw_all<T>(&self, target: &T, ty: ResourceType) -> ReturnCode
     where
         T: ?Sized + Withdrawable,
     {
+        record_intent!(self, Withdraw, ty);
         js_unwrap!(@{self.as_ref()}.withdraw(
             @{target.as_ref()},
             __resource_type_num_to_str(@{ty as u32})
@@ -244,8 +286,17 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
     }
 }
 
-unsafe impl SharedCreepProperties for Creep {}
-unsafe impl SharedCreepProperties for PowerCreep {}
+unsafe impl SharedCreepProperties for Creep {
+    fn memory_collection(&self) -> &'static str {
+        "creeps"
+    }
+}
+
+unsafe impl SharedCreepProperties for PowerCreep {
+    fn memory_collection(&self) -> &'static str {
+        "powerCreeps"
+    }
+}
 
 pub struct MoveToOptions<'a, F>
 where
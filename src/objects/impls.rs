@@ -30,16 +30,21 @@ mod tombstone;
 
 pub use self::{
     creep::Bodypart,
+    flag::FlagPlacement,
+    power_creep::PowerCreepPlacement,
     room::{
         AttackEvent, AttackType, BuildEvent, Effect, Event, EventType, ExitEvent, FindOptions,
         HarvestEvent, HealEvent, HealType, LookResult, ObjectDestroyedEvent, Path,
-        PositionedLookResult, RepairEvent, ReserveControllerEvent, Step, UpgradeControllerEvent,
+        PositionedLookResult, RepairEvent, ReserveControllerEvent, SerializedPathError, Step,
+        UpgradeControllerEvent,
     },
     room_visual::{
         CircleStyle, FontStyle, LineDrawStyle, LineStyle, PolyStyle, RectStyle, RoomVisual,
         TextAlign, TextStyle, Visual,
     },
-    structure_controller::{Reservation, Sign},
+    structure_controller::{DowngradeUrgency, Reservation, Sign},
+    structure_nuker::LaunchNukeError,
     structure_portal::PortalDestination,
     structure_spawn::SpawnOptions,
+    structure_tower::{RefillPriority, TowerRefillPolicy},
 };
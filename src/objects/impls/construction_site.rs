@@ -13,7 +13,8 @@ simple_accessors! {
 }
 
 impl ConstructionSite {
-    pub fn owner_name(&self) -> String {
+    /// The name of the owner of this construction site, if any.
+    pub fn owner_name(&self) -> Option<String> {
         (js! {
             var self = @{self.as_ref()};
             if (self.owner) {
@@ -23,7 +24,7 @@ impl ConstructionSite {
             }
         })
         .try_into()
-        .expect("expected ConstructionSite.owner.username to be a non-null string")
+        .expect("expected ConstructionSite.owner.username to be a string")
     }
 
     pub fn remove(&self) -> ReturnCode {
@@ -18,9 +18,8 @@ impl Creep {
         for i in 0..len {
             let boost_v = js!(const b=@{self.as_ref()}.body[@{i}].boost||null;return b&&__resource_type_str_to_num(b););
             let boost = match boost_v {
-                Value::Number(_) => {
-                    Some(ResourceType::try_from(boost_v).expect("Creep boost resource unknown."))
-                }
+                // unknown boost resources are reported as unboosted rather than aborting
+                Value::Number(_) => ResourceType::try_from(boost_v).ok(),
                 _ => None,
             };
             let part: Part = js_unwrap!(__part_str_to_num(@{self.as_ref()}.body[@{i}].type));
@@ -36,7 +35,15 @@ impl Creep {
         body_parts
     }
 
+    /// Signs a controller with the given text.
+    ///
+    /// Use [`Room::needs_signing`] to avoid spending intents on controllers
+    /// which already carry the desired sign.
+    ///
+    /// [`Room::needs_signing`]: crate::objects::Room::needs_signing
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn sign_controller(&self, target: &StructureController, text: &str) -> ReturnCode {
+        record_intent!(self, SignController);
         js_unwrap!(@{self.as_ref()}.signController(@{target.as_ref()}, @{text}))
     }
 
@@ -44,14 +51,18 @@ impl Creep {
         js_unwrap!(@{self.as_ref()}.getActiveBodyparts(__part_num_to_str(@{ty as u32})))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn ranged_mass_attack(&self) -> ReturnCode {
+        record_intent!(self, RangedMassAttack);
         js_unwrap!(@{self.as_ref()}.rangedMassAttack())
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn transfer_amount<T>(&self, target: &T, ty: ResourceType, amount: u32) -> ReturnCode
     where
         T: ?Sized + Transferable,
     {
+        record_intent!(self, Transfer, ty);
         js_unwrap!(@{self.as_ref()}.transfer(
             @{target.as_ref()},
             __resource_type_num_to_str(@{ty as u32}),
@@ -59,20 +70,24 @@ impl Creep {
         ))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn transfer_all<T>(&self, target: &T, ty: ResourceType) -> ReturnCode
     where
         T: ?Sized + Transferable,
     {
+        record_intent!(self, Transfer, ty);
         js_unwrap!(@{self.as_ref()}.transfer(
             @{target.as_ref()},
             __resource_type_num_to_str(@{ty as u32})
         ))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn withdraw_amount<T>(&self, target: &T, ty: ResourceType, amount: u32) -> ReturnCode
     where
         T: ?Sized + Withdrawable,
     {
+        record_intent!(self, Withdraw, ty);
         js_unwrap!(@{self.as_ref()}.withdraw(
             @{target.as_ref()},
             __resource_type_num_to_str(@{ty as u32}),
@@ -80,10 +95,12 @@ impl Creep {
         ))
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn withdraw_all<T>(&self, target: &T, ty: ResourceType) -> ReturnCode
     where
         T: ?Sized + Withdrawable,
     {
+        record_intent!(self, Withdraw, ty);
         js_unwrap!(@{self.as_ref()}.withdraw(
             @{target.as_ref()},
             __resource_type_num_to_str(@{ty as u32})
@@ -108,25 +125,25 @@ simple_accessors! {
 
 creep_simple_generic_action! {
     impl Creep {
-        pub fn attack(Attackable) = attack();
-        pub fn dismantle(StructureProperties) = dismantle();
-        pub fn harvest(Harvestable) = harvest();
-        pub fn heal(SharedCreepProperties) = heal();
-        pub fn ranged_attack(Attackable) = rangedAttack();
-        pub fn ranged_heal(SharedCreepProperties) = rangedHeal();
-        pub fn repair(StructureProperties) = repair();
+        pub fn attack(Attackable) = attack() => Attack;
+        pub fn dismantle(StructureProperties) = dismantle() => Dismantle;
+        pub fn harvest(Harvestable) = harvest() => Harvest;
+        pub fn heal(SharedCreepProperties) = heal() => Heal;
+        pub fn ranged_attack(Attackable) = rangedAttack() => RangedAttack;
+        pub fn ranged_heal(SharedCreepProperties) = rangedHeal() => RangedHeal;
+        pub fn repair(StructureProperties) = repair() => Repair;
     }
 }
 
 creep_simple_concrete_action! {
     impl Creep {
-        pub fn attack_controller(StructureController) = attackController();
-        pub fn build(ConstructionSite) = build();
-        pub fn claim_controller(StructureController) = claimController();
-        pub fn generate_safe_mode(StructureController) = generateSafeMode();
-        pub fn move_pulled_by(Creep) = move();
-        pub fn pull(Creep) = pull();
-        pub fn reserve_controller(StructureController) = reserveController();
-        pub fn upgrade_controller(StructureController) = upgradeController();
+        pub fn attack_controller(StructureController) = attackController() => AttackController;
+        pub fn build(ConstructionSite) = build() => Build;
+        pub fn claim_controller(StructureController) = claimController() => ClaimController;
+        pub fn generate_safe_mode(StructureController) = generateSafeMode() => GenerateSafeMode;
+        pub fn move_pulled_by(Creep) = move() => Move;
+        pub fn pull(Creep) = pull() => Pull;
+        pub fn reserve_controller(StructureController) = reserveController() => ReserveController;
+        pub fn upgrade_controller(StructureController) = upgradeController() => UpgradeController;
     }
 }
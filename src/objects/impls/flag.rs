@@ -14,6 +14,47 @@ simple_accessors! {
     }
 }
 
+/// The outcome of [`Room::create_or_move_flag`].
+///
+/// [`Room::create_or_move_flag`]: crate::objects::Room::create_or_move_flag
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FlagPlacement {
+    /// A new flag was created with this name.
+    Created(String),
+    /// A flag with this name already existed, and was moved to the requested
+    /// position. Its colors are left unchanged.
+    MovedExisting(String),
+}
+
+impl FlagPlacement {
+    /// The name of the created or moved flag.
+    pub fn name(&self) -> &str {
+        match self {
+            FlagPlacement::Created(name) | FlagPlacement::MovedExisting(name) => name,
+        }
+    }
+
+    /// Resolves the result of `createFlag` into a placement, moving the
+    /// existing flag when the name was already taken.
+    pub(crate) fn resolve<F>(
+        name: &str,
+        created: Result<String, ReturnCode>,
+        move_existing: F,
+    ) -> Result<FlagPlacement, ReturnCode>
+    where
+        F: FnOnce() -> ReturnCode,
+    {
+        match created {
+            Ok(name) => Ok(FlagPlacement::Created(name)),
+            Err(ReturnCode::NameExists) => match move_existing() {
+                ReturnCode::Ok => Ok(FlagPlacement::MovedExisting(name.to_owned())),
+                code => Err(code),
+            },
+            Err(code) => Err(code),
+        }
+    }
+}
+
 impl Flag {
     /// Useful method for constructing Flag from the result of
     /// `Position.createFlag` or `Room.createFlag`.
@@ -56,9 +97,60 @@ impl Flag {
         }
     }
 
+    /// Moves the flag to `pos`, returning the game's return code instead of
+    /// discarding it like [`Flag::set_position`].
+    ///
+    /// The flag is only moved on the next tick.
+    pub fn try_set_position<T: HasPosition>(&self, pos: T) -> ReturnCode {
+        let pos = pos.pos();
+        js_unwrap!(@{self.as_ref()}.setPosition(pos_from_packed(@{pos.packed_repr()})))
+    }
+
     pub fn set_position_xy(&self, x: u32, y: u32) {
         js! { @(no_return)
             @{self.as_ref()}.setPosition(@{x}, @{y});
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::FlagPlacement;
+    use crate::constants::ReturnCode;
+
+    #[test]
+    fn created_flag() {
+        let placement = FlagPlacement::resolve("Flag1", Ok("Flag1".to_owned()), || {
+            panic!("nothing should be moved")
+        });
+        assert_eq!(placement, Ok(FlagPlacement::Created("Flag1".to_owned())));
+    }
+
+    #[test]
+    fn moved_existing_flag() {
+        let mut moved = false;
+        let placement = FlagPlacement::resolve("home", Err(ReturnCode::NameExists), || {
+            moved = true;
+            ReturnCode::Ok
+        });
+        assert!(moved);
+        assert_eq!(
+            placement,
+            Ok(FlagPlacement::MovedExisting("home".to_owned()))
+        );
+        assert_eq!(placement.unwrap().name(), "home");
+    }
+
+    #[test]
+    fn creation_errors() {
+        let placement = FlagPlacement::resolve("home", Err(ReturnCode::Full), || {
+            panic!("nothing should be moved")
+        });
+        assert_eq!(placement, Err(ReturnCode::Full));
+
+        // flag removed between the two calls
+        let placement =
+            FlagPlacement::resolve("home", Err(ReturnCode::NameExists), || ReturnCode::NotFound);
+        assert_eq!(placement, Err(ReturnCode::NotFound));
+    }
+}
@@ -1,3 +1,5 @@
+use serde::Deserialize;
+
 use crate::{
     constants::{PowerCreepClass, PowerType, ReturnCode},
     objects::{
@@ -28,10 +30,12 @@ impl PowerCreep {
         js_unwrap!((@{self.as_ref()}.powers[@{power_type as u32}] || {}).level)
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn use_power<T>(&self, power_type: PowerType, target: Option<&T>) -> ReturnCode
     where
         T: ?Sized + RoomObjectProperties,
     {
+        record_intent!(self, UsePower);
         match target {
             Some(v) => js_unwrap!(@{self.as_ref()}.usePower(@{power_type as u32}, @{v.as_ref()})),
             None => js_unwrap!(@{self.as_ref()}.usePower(@{power_type as u32})),
@@ -72,6 +76,24 @@ impl AccountPowerCreep {
         js_unwrap!(@{self.as_ref()}.upgrade(@{power_type as u32}))
     }
 
+    /// Reads this power creep's shard and account timers at once.
+    ///
+    /// This works whether the power creep is spawned in a visible room, on
+    /// another shard, or not spawned at all.
+    pub fn placement(&self) -> PowerCreepPlacement {
+        js_unwrap! {
+            (function(power_creep) {
+                return {
+                    name: power_creep.name,
+                    level: power_creep.level,
+                    shard: power_creep.shard,
+                    spawnCooldownTime: power_creep.spawnCooldownTime,
+                    deleteTime: power_creep.deleteTime
+                };
+            })(@{self.as_ref()})
+        }
+    }
+
     /// Convert this `AccountPowerCreep`, which can represent either a spawned
     /// or unspawned power creep, into a full `PowerCreep` object
     /// representation
@@ -111,13 +133,13 @@ simple_accessors! {
 
 creep_simple_generic_action! {
     impl PowerCreep {
-        pub fn renew(StructureProperties) = renew();
+        pub fn renew(StructureProperties) = renew() => Renew;
     }
 }
 
 creep_simple_concrete_action! {
     impl PowerCreep {
-        pub fn enable_room(StructureController) = enableRoom();
+        pub fn enable_room(StructureController) = enableRoom() => EnableRoom;
     }
 }
 
@@ -126,3 +148,104 @@ creep_simple_concrete_action! {
         pub fn spawn(StructurePowerSpawn) = spawn();
     }
 }
+
+/// A snapshot of where an account power creep is spawned and of its account
+/// timers, returned by [`AccountPowerCreep::placement`].
+///
+/// Timers are millisecond timestamps, comparable with JavaScript's
+/// `Date.now()`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerCreepPlacement {
+    pub name: String,
+    pub level: u32,
+    /// The shard this power creep is spawned on, or `None` if it isn't
+    /// spawned.
+    pub shard: Option<String>,
+    /// When this power creep can next be spawned or deleted, or `None` if it
+    /// is spawned.
+    pub spawn_cooldown_time: Option<u64>,
+    /// When this power creep will be permanently deleted, or `None` if it
+    /// isn't being deleted.
+    pub delete_time: Option<u64>,
+}
+
+js_deserializable!(PowerCreepPlacement);
+
+impl PowerCreepPlacement {
+    /// Whether this power creep is spawned on any shard.
+    pub fn is_spawned(&self) -> bool {
+        self.shard.is_some()
+    }
+
+    /// Milliseconds until this power creep can be spawned, given the current
+    /// time in milliseconds.
+    ///
+    /// Returns `Some(0)` if it can be spawned right away, and `None` if it is
+    /// already spawned.
+    pub fn spawn_ready_in(&self, now_ms: u64) -> Option<u64> {
+        if self.is_spawned() {
+            return None;
+        }
+        Some(
+            self.spawn_cooldown_time
+                .map_or(0, |time| time.saturating_sub(now_ms)),
+        )
+    }
+
+    /// Milliseconds until this power creep is permanently deleted, given the
+    /// current time in milliseconds, or `None` if it isn't being deleted.
+    pub fn deleted_in(&self, now_ms: u64) -> Option<u64> {
+        self.delete_time.map(|time| time.saturating_sub(now_ms))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PowerCreepPlacement;
+
+    #[test]
+    fn spawned_elsewhere() {
+        let placement: PowerCreepPlacement = serde_json::from_value(serde_json::json!({
+            "name": "operator",
+            "level": 12,
+            "shard": "shard2"
+        }))
+        .unwrap();
+
+        assert_eq!(placement.shard.as_deref(), Some("shard2"));
+        assert_eq!(placement.spawn_cooldown_time, None);
+        assert!(placement.is_spawned());
+        assert_eq!(placement.spawn_ready_in(1_600_000_000_000), None);
+        assert_eq!(placement.deleted_in(1_600_000_000_000), None);
+    }
+
+    #[test]
+    fn unspawned_and_deleting() {
+        let placement: PowerCreepPlacement = serde_json::from_value(serde_json::json!({
+            "name": "retired",
+            "level": 3,
+            "shard": null,
+            "spawnCooldownTime": 1_600_000_060_000u64,
+            "deleteTime": 1_600_086_400_000u64
+        }))
+        .unwrap();
+
+        assert!(!placement.is_spawned());
+        assert_eq!(placement.spawn_ready_in(1_600_000_000_000), Some(60_000));
+        assert_eq!(placement.spawn_ready_in(1_600_000_090_000), Some(0));
+        assert_eq!(placement.deleted_in(1_600_000_000_000), Some(86_400_000));
+    }
+
+    #[test]
+    fn never_spawned() {
+        let placement: PowerCreepPlacement = serde_json::from_value(serde_json::json!({
+            "name": "fresh",
+            "level": 0
+        }))
+        .unwrap();
+
+        assert_eq!(placement.spawn_ready_in(1_600_000_000_000), Some(0));
+        assert_eq!(placement.delete_time, None);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fmt, marker::PhantomData, mem, ops::Range};
+use std::{collections::HashMap, error::Error, fmt, marker::PhantomData, mem, ops::Range};
 
 use num_traits::FromPrimitive;
 use serde::{
@@ -12,17 +12,21 @@ use stdweb::{Reference, Value};
 
 use crate::{
     constants::{
-        Color, Direction, EffectType, ExitDirection, FindConstant, Look, LookConstant, PowerType,
-        ResourceType, ReturnCode, StructureType, Terrain,
+        find, Color, Direction, EffectType, ExitDirection, FindConstant, Look, LookConstant,
+        PowerType, ResourceType, ReturnCode, StructureType, Terrain,
     },
-    local::{Position, RoomName},
-    memory::MemoryReference,
+    diplomacy::RoomOwnership,
+    game,
+    local::{ObjectId, Position, RoomName},
+    memory::{self, MemoryReference},
     objects::{
-        ConstructionSite, Creep, Deposit, Flag, HasPosition, Mineral, Nuke, PowerCreep, Resource,
-        Room, RoomTerrain, RoomVisual, Ruin, Source, Structure, StructureController,
-        StructureStorage, StructureTerminal, Tombstone,
+        ConstructionSite, Creep, Deposit, Flag, FlagPlacement, HasId, HasPosition, HasStore,
+        Mineral, Nuke, OwnedStructureProperties, PowerCreep, Resource, Room, RoomTerrain,
+        RoomVisual, Ruin, Source, Structure, StructureController, StructureStorage,
+        StructureTerminal, Tombstone, TowerRefillPolicy,
     },
     pathfinder::{RoomCostResult, SingleRoomCostResult, CostMatrix},
+    planning::{self, LayoutItem, PlacementError},
     traits::{TryFrom, TryInto},
     ConversionError,
 };
@@ -58,6 +62,23 @@ impl Room {
         ))
     }
 
+    /// Places a construction site like [`Room::create_construction_site`],
+    /// returning [`PlacementError::SiteCapReached`] rather than
+    /// [`ReturnCode::Full`] once the global construction site cap is reached.
+    pub fn try_create_construction_site<T>(
+        &self,
+        at: &T,
+        ty: StructureType,
+    ) -> Result<(), PlacementError>
+    where
+        T: ?Sized + HasPosition,
+    {
+        match self.create_construction_site(at, ty) {
+            ReturnCode::Ok => Ok(()),
+            code => Err(PlacementError::from_return_code(code)),
+        }
+    }
+
     pub fn create_named_construction_site<T>(
         &self,
         at: &T,
@@ -79,6 +100,11 @@ impl Room {
         ))
     }
 
+    /// Creates a flag at the given position, returning its name.
+    ///
+    /// If a flag named `name` already exists, this fails with
+    /// [`ReturnCode::NameExists`] and the existing flag is left in place. Use
+    /// [`Room::create_or_move_flag`] to move it instead.
     pub fn create_flag<T>(
         &self,
         at: &T,
@@ -101,6 +127,59 @@ impl Room {
         .expect("expected Room.createFlag to return ReturnCode or String name")
     }
 
+    /// Creates a flag at the given coordinates in this room, returning its
+    /// name.
+    ///
+    /// See [`Room::create_flag`].
+    pub fn create_flag_xy(
+        &self,
+        x: u32,
+        y: u32,
+        name: &str,
+        main_color: Color,
+        secondary_color: Color,
+    ) -> Result<String, ReturnCode> {
+        Flag::interpret_creation_ret_value(js! {
+            return @{self.as_ref()}.createFlag(
+                @{x},
+                @{y},
+                @{name},
+                @{main_color as u32},
+                @{secondary_color as u32}
+            );
+        })
+        .expect("expected Room.createFlag to return ReturnCode or String name")
+    }
+
+    /// Creates a flag at the given position, or moves the existing flag named
+    /// `name` there if there already is one.
+    ///
+    /// Moving a flag only takes effect on the next tick, and keeps its
+    /// existing colors: `main_color` and `secondary_color` are only used when
+    /// creating a new flag, and are ignored when moving an existing one.
+    pub fn create_or_move_flag<T>(
+        &self,
+        at: &T,
+        name: &str,
+        main_color: Color,
+        secondary_color: Color,
+    ) -> Result<FlagPlacement, ReturnCode>
+    where
+        T: ?Sized + HasPosition,
+    {
+        let pos = at.pos();
+        let created = self.create_flag(&pos, name, main_color, secondary_color);
+        FlagPlacement::resolve(name, created, || match game::flags::get(name) {
+            Some(flag) => flag.try_set_position(pos),
+            None => ReturnCode::NotFound,
+        })
+    }
+
+    /// The flags in this room, a shortcut for `find(find::FLAGS)`.
+    pub fn flags(&self) -> Vec<Flag> {
+        self.find(find::FLAGS)
+    }
+
     pub fn find<T>(&self, ty: T) -> Vec<T::Item>
     where
         T: FindConstant,
@@ -108,6 +187,32 @@ impl Room {
         js_unwrap_ref!(@{self.as_ref()}.find(@{ty.find_code()}))
     }
 
+    /// Number of `WORK` parts needed to saturate each source in this room.
+    ///
+    /// See [`Source::work_parts_to_saturate`].
+    pub fn harvest_assignment_capacity(&self) -> HashMap<ObjectId<Source>, u32> {
+        self.find(find::SOURCES)
+            .into_iter()
+            .map(|source| (source.id(), source.work_parts_to_saturate()))
+            .collect()
+    }
+
+    /// Energy needed to refill the towers owned by you in this room, summing
+    /// the deficits given by [`TowerRefillPolicy::deficit`].
+    pub fn tower_energy_demand(&self, policy: &TowerRefillPolicy, threat_present: bool) -> u32 {
+        self.find(find::STRUCTURES)
+            .into_iter()
+            .filter_map(|structure| match structure {
+                Structure::Tower(tower) if tower.my() => Some(policy.deficit(
+                    tower.energy(),
+                    tower.store_capacity(Some(ResourceType::Energy)),
+                    threat_present,
+                )),
+                _ => None,
+            })
+            .sum()
+    }
+
     pub fn find_exit_to(&self, room: &Room) -> Result<ExitDirection, ReturnCode> {
         let code_val = js! {return @{self.as_ref()}.findExitTo(@{room.as_ref()});};
         let code_int: i32 = code_val.try_into().unwrap();
@@ -121,8 +226,21 @@ impl Room {
         }
     }
 
+    /// Gets this room's event log for the previous tick.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event log contains an event which can't be parsed, for
+    /// instance an event type added to the game after this crate was released.
+    /// Use [`Room::try_get_event_log`] to handle this case instead.
     pub fn get_event_log(&self) -> Vec<Event> {
-        serde_json::from_str(&self.get_event_log_raw()).expect("Malformed Event Log")
+        self.try_get_event_log().expect("Malformed Event Log")
+    }
+
+    /// Gets this room's event log for the previous tick, returning an error if
+    /// any of its events can't be parsed.
+    pub fn try_get_event_log(&self) -> Result<Vec<Event>, serde_json::Error> {
+        serde_json::from_str(&self.get_event_log_raw())
     }
 
     pub fn get_event_log_raw(&self) -> String {
@@ -250,6 +368,76 @@ impl Room {
         }
     }
 
+    /// Finds a path like [`Room::find_path`], returning it in the engine's
+    /// serialized format, which is cheaper to pass back from JavaScript.
+    ///
+    /// The path can be followed with
+    /// [`SharedCreepProperties::move_by_path_serialized`], or decoded with
+    /// [`Path::deserialize_steps`].
+    ///
+    /// [`SharedCreepProperties::move_by_path_serialized`]:
+    /// crate::objects::SharedCreepProperties::move_by_path_serialized
+    pub fn find_path_serialized<'a, 's, O, T, F>(
+        &'s self,
+        from_pos: &O,
+        to_pos: &T,
+        opts: FindOptions<'a, F, SingleRoomCostResult<'a>>,
+    ) -> String
+    where
+        O: ?Sized + HasPosition,
+        T: ?Sized + HasPosition,
+        F: FnMut(RoomName, CostMatrix<'a>) -> SingleRoomCostResult<'a> + 'a + 's,
+    {
+        self.find_path(from_pos, to_pos, opts.serialize(true)).into_serialized()
+    }
+
+    /// Hashes the types and positions of the structures and construction
+    /// sites in this room, to detect changes to its layout.
+    ///
+    /// The hash doesn't depend on the order the game lists objects in, and
+    /// is stable across compiles, so it can be kept in memory. See
+    /// [`planning::layout_hash`] for the algorithm.
+    ///
+    /// [`planning::layout_hash`]: crate::planning::layout_hash
+    pub fn layout_hash(&self) -> u64 {
+        let items: Vec<RawLayoutItem> = js_unwrap! {
+            (function(room) {
+                const items = [];
+                for (const structure of room.find(FIND_STRUCTURES)) {
+                    items.push([
+                        __structure_type_str_to_num(structure.structureType),
+                        structure.pos.x,
+                        structure.pos.y,
+                        false
+                    ]);
+                }
+                for (const site of room.find(FIND_CONSTRUCTION_SITES)) {
+                    items.push([
+                        __structure_type_str_to_num(site.structureType),
+                        site.pos.x,
+                        site.pos.y,
+                        true
+                    ]);
+                }
+                return items;
+            })(@{self.as_ref()})
+        };
+        planning::layout_hash(items.into_iter().map(
+            |RawLayoutItem(structure_type, x, y, construction_site)| LayoutItem {
+                structure_type,
+                x,
+                y,
+                construction_site,
+            },
+        ))
+    }
+
+    /// Whether the layout of this room changed since it hashed to
+    /// `previous_hash` with [`Room::layout_hash`].
+    pub fn layout_changed_since(&self, previous_hash: u64) -> bool {
+        self.layout_hash() != previous_hash
+    }
+
     pub fn look_for_at<T, U>(&self, ty: T, target: &U) -> Vec<T::Item>
     where
         T: LookConstant,
@@ -318,7 +506,46 @@ impl Room {
     }
 
     pub fn memory(&self) -> MemoryReference {
-        js_unwrap!(@{self.as_ref()}.memory)
+        memory::object_memory("rooms", &self.name().to_string())
+    }
+
+    /// Whether this room's controller should be signed by the player
+    /// `username` with `desired_text`.
+    ///
+    /// Returns `true` if the controller is unsigned or its sign needs
+    /// replacing according to [`Sign::needs_update`], and `false` for rooms
+    /// without a controller.
+    ///
+    /// [`Sign::needs_update`]: crate::objects::Sign::needs_update
+    pub fn needs_signing(&self, username: &str, desired_text: &str) -> bool {
+        match self.controller() {
+            Some(controller) => match controller.sign() {
+                Some(sign) => sign.needs_update(username, desired_text),
+                None => true,
+            },
+            None => false,
+        }
+    }
+
+    /// Who owns or reserves this room's controller, relative to the current
+    /// player and their allies.
+    ///
+    /// If the current player's username can't be determined with
+    /// [`game::my_username`], no room is considered theirs.
+    pub fn ownership(&self) -> RoomOwnership {
+        let controller = match self.controller() {
+            Some(controller) => controller,
+            None => return RoomOwnership::Unowned,
+        };
+        let my_username = game::my_username().ok();
+        RoomOwnership::classify(
+            controller.owner_name().as_deref(),
+            controller
+                .reservation()
+                .as_ref()
+                .map(|reservation| reservation.username.as_str()),
+            my_username.as_deref(),
+        )
     }
 
     pub fn name_local(&self) -> RoomName {
@@ -477,7 +704,7 @@ where
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Step {
     pub x: u32,
     pub y: u32,
@@ -489,6 +716,13 @@ pub struct Step {
 js_deserializable! {Step}
 js_serializable! {Step}
 
+/// A structure or construction site as passed from JavaScript by
+/// [`Room::layout_hash`].
+#[derive(Deserialize)]
+struct RawLayoutItem(StructureType, u8, u8, bool);
+
+js_deserializable! {RawLayoutItem}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum Path {
@@ -498,6 +732,105 @@ pub enum Path {
 
 js_deserializable! {Path}
 
+impl Path {
+    /// Encodes steps in the engine's serialized path format, the same as
+    /// [`Room::serialize_path`] without calling into JavaScript.
+    ///
+    /// The string starts with the two-digit x and y coordinates of the first
+    /// step, followed by the direction of every step as a single digit.
+    pub fn serialize_steps(steps: &[Step]) -> String {
+        let first = match steps.first() {
+            Some(first) => first,
+            None => return String::new(),
+        };
+        let mut path = format!("{:02}{:02}", first.x, first.y);
+        for step in steps {
+            path.push(char::from(b'0' + step.direction as u8));
+        }
+        path
+    }
+
+    /// Decodes a path in the engine's serialized format, the same as
+    /// [`Room::deserialize_path`] without calling into JavaScript.
+    pub fn deserialize_steps(path: &str) -> Result<Vec<Step>, SerializedPathError> {
+        if path.is_empty() {
+            return Ok(Vec::new());
+        }
+        let coordinate = |range: Range<usize>| {
+            path.get(range)
+                .and_then(|digits| digits.parse::<u32>().ok())
+                .ok_or(SerializedPathError::InvalidStart)
+        };
+        let mut x = coordinate(0..2)?;
+        let mut y = coordinate(2..4)?;
+
+        let mut steps = Vec::with_capacity(path.len() - 4);
+        for (index, c) in path.char_indices().skip(4) {
+            let direction = c
+                .to_digit(10)
+                .and_then(Direction::from_u32)
+                .ok_or(SerializedPathError::InvalidDirection { index, value: c })?;
+            let (dx, dy) = direction.offset();
+            // the first step is at the encoded coordinates
+            if index > 4 {
+                x = (x as i32 + dx) as u32;
+                y = (y as i32 + dy) as u32;
+            }
+            steps.push(Step {
+                x,
+                y,
+                dx,
+                dy,
+                direction,
+            });
+        }
+        Ok(steps)
+    }
+
+    /// The steps of this path, decoding it if it's serialized.
+    pub fn into_steps(self) -> Result<Vec<Step>, SerializedPathError> {
+        match self {
+            Path::Vectorized(steps) => Ok(steps),
+            Path::Serialized(path) => Path::deserialize_steps(&path),
+        }
+    }
+
+    /// This path in the engine's serialized format, encoding it if it's a
+    /// list of steps.
+    pub fn into_serialized(self) -> String {
+        match self {
+            Path::Vectorized(steps) => Path::serialize_steps(&steps),
+            Path::Serialized(path) => path,
+        }
+    }
+}
+
+/// Error decoding a path in the engine's serialized format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SerializedPathError {
+    /// The path doesn't start with the two-digit coordinates of its first step.
+    InvalidStart,
+    /// A character after the coordinates isn't a direction from 1 to 8.
+    InvalidDirection { index: usize, value: char },
+}
+
+impl fmt::Display for SerializedPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializedPathError::InvalidStart => {
+                write!(f, "serialized path doesn't start with coordinates")
+            }
+            SerializedPathError::InvalidDirection { index, value } => write!(
+                f,
+                "serialized path has invalid direction {:?} at index {}",
+                value, index
+            ),
+        }
+    }
+}
+
+impl Error for SerializedPathError {}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Event {
     pub event: EventType,
@@ -560,7 +893,10 @@ impl<'de> Deserialize<'de> for Event {
                                     data = match event_id {
                                         1 => Some(EventType::Attack(map.next_value()?)),
                                         2 => Some(EventType::ObjectDestroyed(map.next_value()?)),
-                                        3 => Some(EventType::AttackController),
+                                        3 => {
+                                            map.next_value::<de::IgnoredAny>()?;
+                                            Some(EventType::AttackController)
+                                        }
                                         4 => Some(EventType::Build(map.next_value()?)),
                                         5 => Some(EventType::Harvest(map.next_value()?)),
                                         6 => Some(EventType::Heal(map.next_value()?)),
@@ -830,3 +1166,195 @@ impl TryFrom<Value> for PositionedLookResult {
         Ok(PositionedLookResult { x, y, look_result })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use serde_json;
+
+    use super::{AttackType, Effect, Event, EventType, Path, SerializedPathError, Step};
+    use crate::constants::{Direction, EffectType, PowerType};
+
+    #[test]
+    fn event_parsing() {
+        let attack =
+            r#"{"event":1,"objectId":"a","data":{"targetId":"b","damage":30,"attackType":1}}"#;
+        let event: Event = serde_json::from_str(attack).unwrap();
+        assert_eq!(event.object_id, "a");
+        match event.event {
+            EventType::Attack(attack) => {
+                assert_eq!(attack.damage, 30);
+                assert_eq!(attack.attack_type, AttackType::Melee);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // data listed before the event type is buffered and parsed afterwards
+        let harvest = r#"{"data":{"targetId":"b","amount":10},"objectId":"a","event":5}"#;
+        let event: Event = serde_json::from_str(harvest).unwrap();
+        assert!(matches!(event.event, EventType::Harvest(_)));
+
+        let log =
+            r#"[{"event":3,"objectId":"a","data":{}},{"event":3,"objectId":"b","data":null}]"#;
+        let events: Vec<Event> = serde_json::from_str(log).unwrap();
+        assert_eq!(events.len(), 2);
+    }
+
+    #[test]
+    fn malformed_events_are_errors() {
+        let fixtures = [
+            "null",
+            "[]",
+            "{}",
+            r#"{"event":1,"objectId":"a"}"#,
+            r#"{"event":1,"data":{"targetId":"b","damage":30,"attackType":1}}"#,
+            r#"{"event":1,"objectId":"a","data":null}"#,
+            r#"{"event":1,"objectId":"a","data":{"targetId":"b","damage":"30","attackType":1}}"#,
+            r#"{"event":1,"objectId":"a","data":{"targetId":"b","damage":30,"attackType":99}}"#,
+            r#"{"data":{"targetId":"b","damage":-1,"attackType":1},"objectId":"a","event":1}"#,
+            r#"{"event":99,"objectId":"a","data":{}}"#,
+            r#"{"data":{},"objectId":"a","event":99}"#,
+            r#"{"event":"attack","objectId":"a","data":{}}"#,
+            r#"{"event":null,"objectId":"a","data":{}}"#,
+            r#"{"event":5,"objectId":7,"data":{"targetId":"b","amount":10}}"#,
+            r#"{"event":5,"event":5,"objectId":"a","data":{"targetId":"b","amount":10}}"#,
+            r#"{"event":12,"objectId":"a","data":{"targetId":"b","resourceType":"unobtainium","amount":1}}"#,
+        ];
+        for fixture in fixtures.iter() {
+            assert!(
+                serde_json::from_str::<Event>(fixture).is_err(),
+                "{} should not parse",
+                fixture
+            );
+        }
+    }
+
+    #[test]
+    fn effect_parsing() {
+        let effect: Effect =
+            serde_json::from_str(r#"{"effect":13,"level":3,"ticksRemaining":100}"#).unwrap();
+        assert_eq!(
+            effect.effect,
+            EffectType::PowerEffect(PowerType::RegenSource)
+        );
+        assert_eq!(effect.level, Some(3));
+
+        // natural effects have no level
+        let effect: Effect =
+            serde_json::from_str(r#"{"effect":1001,"ticksRemaining":5000}"#).unwrap();
+        assert_eq!(effect.level, None);
+        let effect: Effect =
+            serde_json::from_str(r#"{"effect":1002,"level":null,"ticksRemaining":1}"#).unwrap();
+        assert_eq!(effect.level, None);
+
+        // effects unknown to this crate are kept
+        let effect: Effect =
+            serde_json::from_str(r#"{"effect":9999,"ticksRemaining":100}"#).unwrap();
+        assert_eq!(effect.effect, EffectType::Unknown(9999));
+
+        let fixtures = [
+            "null",
+            "{}",
+            r#"{"effect":13,"level":3}"#,
+            r#"{"level":3,"ticksRemaining":100}"#,
+            r#"{"effect":null,"ticksRemaining":100}"#,
+            r#"{"effect":"regenSource","ticksRemaining":100}"#,
+            r#"{"effect":13,"level":300,"ticksRemaining":100}"#,
+            r#"{"effect":13,"level":1,"ticksRemaining":-1}"#,
+            r#"{"effect":13,"level":1,"ticksRemaining":null}"#,
+        ];
+        for fixture in fixtures.iter() {
+            assert!(
+                serde_json::from_str::<Effect>(fixture).is_err(),
+                "{} should not parse",
+                fixture
+            );
+        }
+    }
+
+    #[test]
+    fn path_parsing() {
+        let path: Path = serde_json::from_str(r#""2525""#).unwrap();
+        assert!(matches!(path, Path::Serialized(_)));
+        let path: Path =
+            serde_json::from_str(r#"[{"x":1,"y":2,"dx":1,"dy":0,"direction":3}]"#).unwrap();
+        assert!(matches!(path, Path::Vectorized(ref steps) if steps.len() == 1));
+
+        let fixtures = [
+            "null",
+            "12",
+            r#"[{"x":1,"y":2,"dx":1,"dy":0}]"#,
+            r#"[{"x":1,"y":2,"dx":1,"dy":0,"direction":9}]"#,
+            r#"[{"x":-1,"y":2,"dx":1,"dy":0,"direction":3}]"#,
+            r#"[null]"#,
+        ];
+        for fixture in fixtures.iter() {
+            assert!(
+                serde_json::from_str::<Path>(fixture).is_err(),
+                "{} should not parse",
+                fixture
+            );
+        }
+    }
+
+    #[test]
+    fn serialized_paths() {
+        // produced by `Room.findPath` with `serialize: true` from (25, 25)
+        // to (27, 23)
+        let path = "2624231";
+        let steps = Path::deserialize_steps(path).unwrap();
+        let step = |x, y, dx, dy, direction| Step {
+            x,
+            y,
+            dx,
+            dy,
+            direction,
+        };
+        assert_eq!(
+            steps,
+            vec![
+                step(26, 24, 1, -1, Direction::TopRight),
+                step(27, 24, 1, 0, Direction::Right),
+                step(27, 23, 0, -1, Direction::Top),
+            ]
+        );
+        assert_eq!(Path::serialize_steps(&steps), path);
+
+        // single digit coordinates are zero padded
+        let edge = "0548476";
+        let steps = Path::deserialize_steps(edge).unwrap();
+        assert_eq!(steps[0], step(5, 48, 1, 1, Direction::BottomRight));
+        assert_eq!(steps[2], step(3, 49, -1, 1, Direction::BottomLeft));
+        assert_eq!(Path::serialize_steps(&steps), edge);
+
+        assert_eq!(Path::deserialize_steps("").unwrap(), vec![]);
+        assert_eq!(Path::serialize_steps(&[]), "");
+        assert_eq!(Path::deserialize_steps("1020").unwrap(), vec![]);
+        assert_eq!(
+            Path::Serialized(path.to_owned()).into_steps().unwrap().len(),
+            3
+        );
+
+        assert_eq!(
+            Path::deserialize_steps("102"),
+            Err(SerializedPathError::InvalidStart)
+        );
+        assert_eq!(
+            Path::deserialize_steps("1x203"),
+            Err(SerializedPathError::InvalidStart)
+        );
+        assert_eq!(
+            Path::deserialize_steps("1020330"),
+            Err(SerializedPathError::InvalidDirection {
+                index: 6,
+                value: '0'
+            })
+        );
+        assert_eq!(
+            Path::deserialize_steps("10209"),
+            Err(SerializedPathError::InvalidDirection {
+                index: 4,
+                value: '9'
+            })
+        );
+    }
+}
@@ -2,7 +2,7 @@ use stdweb::UnsafeTypedArray;
 
 use crate::{
     constants::{ReturnCode, Terrain},
-    local::RoomName,
+    local::{LocalRoomTerrain, RoomName},
     objects::RoomTerrain,
     traits::TryInto,
 };
@@ -23,6 +23,15 @@ impl RoomTerrain {
         buffer
     }
 
+    /// Copies the room's terrain into a [`LocalRoomTerrain`], for looking up
+    /// many tiles without calling into JavaScript for each one.
+    pub fn get_local(&self) -> LocalRoomTerrain {
+        let mut bits = Box::new([0; 2500]);
+        self.get_raw_buffer_to_array(&mut bits)
+            .expect("Panic in get_local.");
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
     pub fn get_raw_buffer_to_vec<'a>(
         &self,
         buffer: &'a mut Vec<u8>,
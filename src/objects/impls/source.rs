@@ -1,4 +1,7 @@
-use crate::objects::Source;
+use crate::{
+    constants::{EffectType, PowerType, ENERGY_REGEN_TIME, HARVEST_POWER},
+    objects::{RoomObjectProperties, Source},
+};
 
 simple_accessors! {
     impl Source {
@@ -11,4 +14,88 @@ impl Source {
     pub fn ticks_to_regeneration(&self) -> u32 {
         js_unwrap!(Math.max(0, @{self.as_ref()}.ticksToRegeneration || 0))
     }
+
+    /// Number of `WORK` parts needed to harvest all of this source's energy
+    /// before it regenerates, taking an active `PWR_REGEN_SOURCE` effect into
+    /// account.
+    ///
+    /// See [`Source::work_parts_for_capacity`].
+    pub fn work_parts_to_saturate(&self) -> u32 {
+        let regen_level = self
+            .effects()
+            .into_iter()
+            .find(|e| e.effect == EffectType::PowerEffect(PowerType::RegenSource))
+            .and_then(|e| e.level);
+
+        Source::work_parts_for_capacity(self.energy_capacity(), regen_level)
+    }
+
+    /// Number of `WORK` parts needed to harvest `energy_capacity` energy every
+    /// [`ENERGY_REGEN_TIME`] ticks, with the `PWR_REGEN_SOURCE` effect at
+    /// `regen_source_level` adding to the energy produced.
+    ///
+    /// The capacity of a source is [`SOURCE_ENERGY_CAPACITY`] in owned or
+    /// reserved rooms, [`SOURCE_ENERGY_NEUTRAL_CAPACITY`] in unowned rooms and
+    /// [`SOURCE_ENERGY_KEEPER_CAPACITY`] in source keeper rooms.
+    ///
+    /// [`ENERGY_REGEN_TIME`]: crate::constants::ENERGY_REGEN_TIME
+    /// [`SOURCE_ENERGY_CAPACITY`]: crate::constants::SOURCE_ENERGY_CAPACITY
+    /// [`SOURCE_ENERGY_NEUTRAL_CAPACITY`]:
+    /// crate::constants::SOURCE_ENERGY_NEUTRAL_CAPACITY
+    /// [`SOURCE_ENERGY_KEEPER_CAPACITY`]:
+    /// crate::constants::SOURCE_ENERGY_KEEPER_CAPACITY
+    pub fn work_parts_for_capacity(energy_capacity: u32, regen_source_level: Option<u8>) -> u32 {
+        // POWER_INFO[PWR_REGEN_SOURCE]: effect: [50, 100, 150, 200, 250],
+        // period: 15
+        let regen_energy = match regen_source_level {
+            Some(level @ 1..=5) => u32::from(level) * 50 * (ENERGY_REGEN_TIME / 15),
+            _ => 0,
+        };
+        let energy_per_part = HARVEST_POWER * ENERGY_REGEN_TIME;
+
+        (energy_capacity + regen_energy + energy_per_part - 1) / energy_per_part
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        constants::{
+            SOURCE_ENERGY_CAPACITY, SOURCE_ENERGY_KEEPER_CAPACITY, SOURCE_ENERGY_NEUTRAL_CAPACITY,
+        },
+        objects::Source,
+    };
+
+    #[test]
+    fn work_parts_for_capacity() {
+        // owned or reserved
+        assert_eq!(
+            Source::work_parts_for_capacity(SOURCE_ENERGY_CAPACITY, None),
+            5
+        );
+        // unowned
+        assert_eq!(
+            Source::work_parts_for_capacity(SOURCE_ENERGY_NEUTRAL_CAPACITY, None),
+            3
+        );
+        // source keeper room
+        assert_eq!(
+            Source::work_parts_for_capacity(SOURCE_ENERGY_KEEPER_CAPACITY, None),
+            7
+        );
+    }
+
+    #[test]
+    fn work_parts_for_capacity_with_regen_source() {
+        // level 1 adds 50 energy every 15 ticks, 1000 energy per regeneration
+        assert_eq!(
+            Source::work_parts_for_capacity(SOURCE_ENERGY_CAPACITY, Some(1)),
+            7
+        );
+        // level 5 adds 5000 energy per regeneration
+        assert_eq!(
+            Source::work_parts_for_capacity(SOURCE_ENERGY_CAPACITY, Some(5)),
+            14
+        );
+    }
 }
@@ -1,6 +1,13 @@
 use stdweb::Value;
 
-use crate::{constants::ReturnCode, objects::StructureController};
+use crate::{
+    constants::{
+        controller_downgrade, controller_ticks_to_upgrade, ReturnCode,
+        CONTROLLER_DOWNGRADE_SAFEMODE_THRESHOLD, INVADER_USERNAME, SOURCE_KEEPER_USERNAME,
+        SYSTEM_USERNAME,
+    },
+    objects::StructureController,
+};
 
 simple_accessors! {
     impl StructureController {
@@ -30,7 +37,100 @@ pub struct Sign {
     pub datetime: String, // todo: use real date type
 }
 
+impl Sign {
+    /// Whether this sign was placed by the game rather than by a player, such
+    /// as the [`SIGN_PLANNED_AREA`] sign placed on rooms in a sector where a
+    /// novice or respawn area is planned.
+    ///
+    /// [`SIGN_PLANNED_AREA`]: crate::constants::SIGN_PLANNED_AREA
+    pub fn is_system(&self) -> bool {
+        matches!(
+            self.username.as_str(),
+            SYSTEM_USERNAME | INVADER_USERNAME | SOURCE_KEEPER_USERNAME
+        )
+    }
+
+    /// Whether a controller with this sign should be signed again by the
+    /// player `username` to show `desired_text`.
+    ///
+    /// Signs placed by the game always need replacing, as do signs with a
+    /// different text, or with the same text but placed by another player.
+    pub fn needs_update(&self, username: &str, desired_text: &str) -> bool {
+        self.is_system() || self.username != username || self.text != desired_text
+    }
+}
+
+/// How soon an owned controller needs upgrading to avoid losing a level, as
+/// returned by [`StructureController::downgrade_urgency`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DowngradeUrgency {
+    /// The downgrade timer is at 80% or more of its maximum.
+    Safe,
+    /// The downgrade timer is below 80% of its maximum.
+    Soon,
+    /// The downgrade timer is low enough that safe mode can't be activated:
+    /// below 50% of its maximum, minus
+    /// [`CONTROLLER_DOWNGRADE_SAFEMODE_THRESHOLD`].
+    Critical,
+}
+
+impl DowngradeUrgency {
+    /// Classifies a controller of level `level` with `ticks_to_downgrade`
+    /// ticks left on its downgrade timer, or returns `None` if the level is
+    /// not between 1 and 8.
+    pub fn classify(level: u32, ticks_to_downgrade: u32) -> Option<DowngradeUrgency> {
+        let max = controller_downgrade(level)?;
+        let safe_mode_blocked = (max / 2).saturating_sub(CONTROLLER_DOWNGRADE_SAFEMODE_THRESHOLD);
+        let urgency = if ticks_to_downgrade < safe_mode_blocked {
+            DowngradeUrgency::Critical
+        } else if u64::from(ticks_to_downgrade) * 5 < u64::from(max) * 4 {
+            DowngradeUrgency::Soon
+        } else {
+            DowngradeUrgency::Safe
+        };
+        Some(urgency)
+    }
+}
+
+/// The downgrade timer of a controller of level `level` as a fraction of its
+/// maximum for that level, or `None` if the level is not between 1 and 8.
+fn downgrade_fraction(level: u32, ticks_to_downgrade: u32) -> Option<f64> {
+    controller_downgrade(level).map(|max| f64::from(ticks_to_downgrade) / f64::from(max))
+}
+
 impl StructureController {
+    /// How soon this controller needs upgrading to avoid losing a level, or
+    /// `None` if it isn't owned, including when it's only reserved.
+    pub fn downgrade_urgency(&self) -> Option<DowngradeUrgency> {
+        let (level, ticks_to_downgrade) = self.downgrade_timer()?;
+        DowngradeUrgency::classify(level, ticks_to_downgrade)
+    }
+
+    /// The downgrade timer as a fraction of its maximum at this controller's
+    /// level, or `None` if it isn't owned.
+    pub fn downgrade_fraction(&self) -> Option<f64> {
+        let (level, ticks_to_downgrade) = self.downgrade_timer()?;
+        downgrade_fraction(level, ticks_to_downgrade)
+    }
+
+    /// The level and downgrade timer of this controller, or `None` if it isn't
+    /// owned, as unowned controllers have no downgrade timer.
+    fn downgrade_timer(&self) -> Option<(u32, u32)> {
+        match self.level() {
+            0 => None,
+            level => Some((level, self.ticks_to_downgrade())),
+        }
+    }
+
+    /// Ticks until this controller upgrades to the next level, when upgraded
+    /// by `upgrade_per_tick` control points each tick.
+    ///
+    /// Returns `None` if the controller isn't owned, is already at level 8,
+    /// or if `upgrade_per_tick` is 0.
+    pub fn ticks_to_upgrade(&self, upgrade_per_tick: u32) -> Option<u32> {
+        controller_ticks_to_upgrade(self.level(), self.progress()?, upgrade_per_tick)
+    }
+
     pub fn activate_safe_mode(&self) -> ReturnCode {
         js_unwrap! {@{self.as_ref()}.activateSafeMode()}
     }
@@ -63,3 +163,94 @@ impl StructureController {
         js_unwrap! {@{self.as_ref()}.unclaim()}
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{downgrade_fraction, DowngradeUrgency, Sign};
+    use crate::constants::{
+        controller_downgrade, controller_levels, controller_ticks_to_upgrade, SIGN_PLANNED_AREA,
+    };
+
+    fn sign(username: &str, text: &str) -> Sign {
+        Sign {
+            username: username.to_owned(),
+            text: text.to_owned(),
+            time: 16_662_604,
+            datetime: "Sat Jun 13 2020 12:00:00 GMT+0000".to_owned(),
+        }
+    }
+
+    #[test]
+    fn system_signs() {
+        assert!(sign("Screeps", SIGN_PLANNED_AREA).is_system());
+        assert!(sign("Invader", "").is_system());
+        assert!(sign("Source Keeper", "").is_system());
+        assert!(!sign("daboross", "mine").is_system());
+        // usernames are case sensitive
+        assert!(!sign("screeps", "not the system").is_system());
+    }
+
+    #[test]
+    fn sign_needs_update() {
+        assert!(sign("Screeps", "mine").needs_update("daboross", "mine"));
+        assert!(sign("daboross", "theirs").needs_update("daboross", "mine"));
+        assert!(sign("daboross", "old text").needs_update("daboross", "new text"));
+        // the same text signed by someone else is still their sign
+        assert!(sign("someone else", "mine").needs_update("daboross", "mine"));
+        assert!(!sign("daboross", "mine").needs_update("daboross", "mine"));
+    }
+
+    #[test]
+    fn downgrade_urgency() {
+        use DowngradeUrgency::*;
+
+        // (level, first tick count that is Soon, first that is Safe)
+        let boundaries = [
+            (1, 5000, 16_000),
+            (2, 0, 8000),
+            (3, 5000, 16_000),
+            (4, 15_000, 32_000),
+            (5, 35_000, 64_000),
+            (6, 55_000, 96_000),
+            (7, 70_000, 120_000),
+            (8, 95_000, 160_000),
+        ];
+        for &(level, soon, safe) in &boundaries {
+            if soon > 0 {
+                assert_eq!(DowngradeUrgency::classify(level, soon - 1), Some(Critical));
+            }
+            assert_eq!(DowngradeUrgency::classify(level, soon), Some(Soon));
+            assert_eq!(DowngradeUrgency::classify(level, safe - 1), Some(Soon));
+            assert_eq!(DowngradeUrgency::classify(level, safe), Some(Safe));
+        }
+
+        // unowned and reserved controllers are level 0
+        assert_eq!(DowngradeUrgency::classify(0, 0), None);
+        assert_eq!(DowngradeUrgency::classify(9, 1000), None);
+        assert!(Critical > Soon && Soon > Safe);
+
+        assert_eq!(downgrade_fraction(8, 150_000), Some(0.75));
+        assert_eq!(downgrade_fraction(2, 10_000), Some(1.0));
+        assert_eq!(downgrade_fraction(0, 10_000), None);
+    }
+
+    #[test]
+    fn level_progression() {
+        let progress_totals = [
+            200, 45_000, 135_000, 405_000, 1_215_000, 3_645_000, 10_935_000,
+        ];
+        for (level, &total) in (1..=7).zip(&progress_totals) {
+            assert_eq!(controller_levels(level), Some(total));
+        }
+        assert_eq!(controller_levels(0), None);
+        assert_eq!(controller_levels(8), None);
+        assert_eq!(controller_downgrade(8), Some(200_000));
+        assert_eq!(controller_downgrade(9), None);
+
+        assert_eq!(controller_ticks_to_upgrade(1, 0, 1), Some(200));
+        assert_eq!(controller_ticks_to_upgrade(3, 100_000, 15), Some(2334));
+        assert_eq!(controller_ticks_to_upgrade(7, 10_935_000, 15), Some(0));
+        assert_eq!(controller_ticks_to_upgrade(7, 0, 0), None);
+        assert_eq!(controller_ticks_to_upgrade(8, 0, 15), None);
+    }
+}
@@ -1,11 +1,98 @@
+use std::{error::Error, fmt};
+
 use crate::{
-    constants::ReturnCode,
+    constants::{ReturnCode, NUKE_RANGE},
+    local::RoomName,
     objects::{HasPosition, StructureNuker},
 };
 
+/// Error returned by [`StructureNuker::try_launch_nuke`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LaunchNukeError {
+    /// The target room is further than [`NUKE_RANGE`] rooms away; the nuke
+    /// wasn't launched.
+    OutOfRange {
+        /// Distance to the target room, in rooms.
+        distance: u32,
+    },
+    /// The game rejected the launch with this return code.
+    Rejected(ReturnCode),
+}
+
+impl fmt::Display for LaunchNukeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchNukeError::OutOfRange { distance } => write!(
+                f,
+                "target room is {} rooms away, further than the nuke range of {}",
+                distance, NUKE_RANGE
+            ),
+            LaunchNukeError::Rejected(code) => write!(f, "nuke launch rejected: {}", code),
+        }
+    }
+}
+
+impl Error for LaunchNukeError {}
+
+/// Checks that a nuker in `from` can reach `to`, measuring distance as the
+/// server does, without wrapping around the edge of the world.
+fn check_nuke_range(from: RoomName, to: RoomName) -> Result<(), LaunchNukeError> {
+    let distance = from.linear_distance_to(to);
+    if distance > NUKE_RANGE {
+        Err(LaunchNukeError::OutOfRange { distance })
+    } else {
+        Ok(())
+    }
+}
+
 impl StructureNuker {
     pub fn launch_nuke<T: HasPosition + ?Sized>(&self, target: &T) -> ReturnCode {
         let pos = target.pos();
         js_unwrap! {@{self.as_ref()}.launchNuke(pos_from_packed(@{pos.packed_repr()}))}
     }
+
+    /// Launches a nuke at `target` after checking that it's within
+    /// [`NUKE_RANGE`], so out of range targets are reported without issuing
+    /// the launch.
+    pub fn try_launch_nuke<T: HasPosition + ?Sized>(
+        &self,
+        target: &T,
+    ) -> Result<(), LaunchNukeError> {
+        check_nuke_range(self.pos().room_name(), target.pos().room_name())?;
+        match self.launch_nuke(target) {
+            ReturnCode::Ok => Ok(()),
+            code => Err(LaunchNukeError::Rejected(code)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_nuke_range, LaunchNukeError};
+    use crate::{constants::nuke_damage_at_range, local::RoomName};
+
+    #[test]
+    fn nuke_damage() {
+        assert_eq!(nuke_damage_at_range(0), 10_000_000);
+        assert_eq!(nuke_damage_at_range(1), 5_000_000);
+        assert_eq!(nuke_damage_at_range(2), 5_000_000);
+        assert_eq!(nuke_damage_at_range(3), 0);
+    }
+
+    #[test]
+    fn nuke_range() {
+        let room = |name: &str| RoomName::new(name).unwrap();
+
+        assert_eq!(check_nuke_range(room("W5N5"), room("W5N5")), Ok(()));
+        assert_eq!(check_nuke_range(room("W5N5"), room("E4N5")), Ok(()));
+        assert_eq!(
+            check_nuke_range(room("W5N5"), room("E5N5")),
+            Err(LaunchNukeError::OutOfRange { distance: 11 })
+        );
+        // nukes don't wrap around the edge of the world
+        assert_eq!(
+            check_nuke_range(room("W98N5"), room("E98N5")),
+            Err(LaunchNukeError::OutOfRange { distance: 197 })
+        );
+    }
 }
@@ -1,9 +1,14 @@
+use num_traits::FromPrimitive;
 use stdweb::Reference;
 
 use crate::{
-    constants::{Direction, Part, ReturnCode},
-    memory::MemoryReference,
-    objects::{Creep, HasEnergyForSpawn, SizedRoomObject, Spawning, StructureSpawn},
+    constants::{Direction, Part, ReturnCode, Terrain},
+    memory::{self, MemoryReference},
+    objects::{
+        Creep, HasEnergyForSpawn, HasPosition, LookResult, OwnedStructureProperties,
+        RoomObjectProperties, SizedRoomObject, Spawning, Structure, StructureProperties,
+        StructureSpawn,
+    },
     traits::TryInto,
 };
 
@@ -16,9 +21,16 @@ simple_accessors! {
 
 impl StructureSpawn {
     pub fn memory(&self) -> MemoryReference {
-        js_unwrap!(@{self.as_ref()}.memory)
+        memory::object_memory("spawns", &self.name())
     }
 
+    /// Starts spawning a creep with the given body and name.
+    ///
+    /// Use [`body::is_valid_body`] and [`body::body_cost`] to check a body
+    /// before spending the intent on it.
+    ///
+    /// [`body::is_valid_body`]: crate::body::is_valid_body
+    /// [`body::body_cost`]: crate::body::body_cost
     pub fn spawn_creep(&self, body: &[Part], name: &str) -> ReturnCode {
         let ints = body.iter().map(|p| *p as u32).collect::<Vec<u32>>();
         (js! {
@@ -30,6 +42,9 @@ impl StructureSpawn {
         .expect("expected StructureSpawn::spawnCreep to return an integer return code")
     }
 
+    /// Starts spawning a creep, with additional options.
+    ///
+    /// See [`StructureSpawn::spawn_creep`] for checking the body first.
     pub fn spawn_creep_with_options(
         &self,
         body: &[Part],
@@ -40,11 +55,6 @@ impl StructureSpawn {
 
         let js_opts = js!(return {dryRun: @{opts.dry_run}};);
 
-        if let Some(ref mem) = opts.memory {
-            js! { @(no_return)
-                @{&js_opts}.memory = @{mem.as_ref()};
-            }
-        }
         if !opts.energy_structures.is_empty() {
             js! { @(no_return)
                 @{&js_opts}.energyStructures = @{&opts.energy_structures};
@@ -55,13 +65,19 @@ impl StructureSpawn {
                 @{&js_opts}.directions = @{&opts.directions};
             }
         }
-        (js! {
+        let result: ReturnCode = (js! {
             var body = (@{body_ints}).map(__part_num_to_str);
 
             return @{self.as_ref()}.spawnCreep(body, @{name}, @{js_opts});
         })
         .try_into()
-        .expect("expected StructureSpawn::spawnCreep to return an integer return code")
+        .expect("expected StructureSpawn::spawnCreep to return an integer return code");
+
+        // set here rather than by the game, to go through the memory backend
+        if let (ReturnCode::Ok, false, Some(mem)) = (result, opts.dry_run, &opts.memory) {
+            memory::set_object_memory("creeps", name, mem);
+        }
+        result
     }
 
     // TODO: support actually using Spawning properties.
@@ -76,6 +92,76 @@ impl StructureSpawn {
     pub fn renew_creep(&self, target: &Creep) -> ReturnCode {
         js_unwrap! {@{self.as_ref()}.renewCreep(@{target.as_ref()})}
     }
+
+    /// Directions a newly spawned creep could currently leave this spawn in.
+    ///
+    /// A tile is considered blocked by walls, by obstacle structures (see
+    /// [`StructureType::is_obstacle`]), by ramparts which aren't yours and
+    /// aren't public, and by creeps and power creeps. A spawn whose adjacent
+    /// tiles are all blocked keeps the finished creep until one is freed.
+    ///
+    /// [`StructureType::is_obstacle`]: crate::constants::StructureType::is_obstacle
+    pub fn free_exit_directions(&self) -> Vec<Direction> {
+        let pos = self.pos();
+        let room = self
+            .room()
+            .expect("expected a spawn to always be in a visible room");
+        let (x, y) = (pos.x(), pos.y());
+
+        let mut blocked = [[false; 3]; 3];
+        let area = room.look_at_area(
+            y.saturating_sub(1),
+            x.saturating_sub(1),
+            (y + 1).min(49),
+            (x + 1).min(49),
+        );
+        for result in area {
+            let is_blocking = match result.look_result {
+                LookResult::Terrain(terrain) => terrain == Terrain::Wall,
+                LookResult::Creep(_) | LookResult::PowerCreep(_) => true,
+                LookResult::Structure(Structure::Rampart(rampart)) => {
+                    !rampart.my() && !rampart.is_public()
+                }
+                LookResult::Structure(structure) => structure.structure_type().is_obstacle(),
+                _ => false,
+            };
+            if is_blocking {
+                let column = (result.x + 1 - x) as usize;
+                let row = (result.y + 1 - y) as usize;
+                blocked[row][column] = true;
+            }
+        }
+
+        free_directions(x, y, |dx, dy| blocked[(dy + 1) as usize][(dx + 1) as usize])
+    }
+}
+
+/// Whether any of `free_directions` is allowed by `requested`, where an empty
+/// list allows every direction.
+fn can_exit(requested: &[u32], free_directions: &[Direction]) -> bool {
+    if requested.is_empty() {
+        !free_directions.is_empty()
+    } else {
+        free_directions
+            .iter()
+            .any(|d| requested.contains(&(*d as u32)))
+    }
+}
+
+/// Directions leading from `(x, y)` to a tile inside the room for which
+/// `blocked(dx, dy)` is false.
+fn free_directions<F>(x: u32, y: u32, blocked: F) -> Vec<Direction>
+where
+    F: Fn(i32, i32) -> bool,
+{
+    (1..=8)
+        .filter_map(Direction::from_u8)
+        .filter(|dir| {
+            let (dx, dy) = dir.offset();
+            let (tx, ty) = (x as i32 + dx, y as i32 + dy);
+            (0..50).contains(&tx) && (0..50).contains(&ty) && !blocked(dx, dy)
+        })
+        .collect()
 }
 
 #[derive(Default)]
@@ -115,6 +201,16 @@ impl SpawnOptions {
         self.directions = directions.iter().map(|d| *d as u32).collect();
         self
     }
+
+    /// Whether a creep spawned with these options could leave the spawn
+    /// through one of `free_directions`, usually obtained from
+    /// [`StructureSpawn::free_exit_directions`].
+    ///
+    /// Spawning while this is false leaves the spawn stalled until one of the
+    /// allowed directions is freed.
+    pub fn can_exit(&self, free_directions: &[Direction]) -> bool {
+        can_exit(&self.directions, free_directions)
+    }
 }
 
 simple_accessors! {
@@ -137,3 +233,46 @@ impl Spawning {
         js_unwrap!(@{self.as_ref()}.setDirections(@{int_dirs}))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{can_exit, free_directions};
+    use crate::constants::Direction::{self, *};
+
+    #[test]
+    fn bunker_surroundings() {
+        // walls to the left, an extension above and a creep below right
+        let blocked = |dx: i32, dy: i32| dx == -1 || (dx, dy) == (0, -1) || (dx, dy) == (1, 1);
+        assert_eq!(
+            free_directions(25, 25, blocked),
+            vec![TopRight, Right, Bottom]
+        );
+
+        // every neighbour occupied
+        assert_eq!(
+            free_directions(25, 25, |_, _| true),
+            Vec::<Direction>::new()
+        );
+    }
+
+    #[test]
+    fn room_edges() {
+        assert_eq!(
+            free_directions(0, 0, |_, _| false),
+            vec![Right, BottomRight, Bottom]
+        );
+        assert_eq!(
+            free_directions(49, 25, |_, _| false),
+            vec![Top, Bottom, BottomLeft, Left, TopLeft]
+        );
+    }
+
+    #[test]
+    fn requested_directions() {
+        let free = [Right, Bottom];
+        assert!(can_exit(&[], &free));
+        assert!(!can_exit(&[], &[]));
+        assert!(can_exit(&[Top as u32, Bottom as u32], &free));
+        assert!(!can_exit(&[Top as u32, Left as u32], &free));
+    }
+}
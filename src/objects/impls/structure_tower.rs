@@ -1,27 +1,182 @@
 use crate::{
-    constants::ReturnCode,
-    objects::{Attackable, SharedCreepProperties, StructureProperties, StructureTower},
+    constants::{ResourceType, ReturnCode},
+    objects::{Attackable, HasStore, SharedCreepProperties, StructureProperties, StructureTower},
 };
 
 impl StructureTower {
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn attack<T>(&self, target: &T) -> ReturnCode
     where
         T: Attackable,
     {
+        record_intent!(self, Attack);
         js_unwrap! { @{self.as_ref()}.attack( @{target.as_ref()} ) }
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn heal<T>(&self, target: &T) -> ReturnCode
     where
         T: SharedCreepProperties,
     {
+        record_intent!(self, Heal);
         js_unwrap! { @{self.as_ref()}.heal( @{target.as_ref()} ) }
     }
 
+    #[cfg_attr(feature = "debug-intents", track_caller)]
     pub fn repair<T>(&self, target: &T) -> ReturnCode
     where
         T: StructureProperties,
     {
+        record_intent!(self, Repair);
         js_unwrap! { @{self.as_ref()}.repair( @{target.as_ref()} ) }
     }
+
+    /// How urgently this tower should be refilled with energy, using the
+    /// default [`TowerRefillPolicy`].
+    pub fn refill_priority(&self, threat_present: bool) -> RefillPriority {
+        TowerRefillPolicy::default().priority_for(self, threat_present)
+    }
+}
+
+/// How urgently a tower needs energy, as decided by a [`TowerRefillPolicy`].
+///
+/// Variants are ordered from least to most urgent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RefillPriority {
+    /// The tower is full.
+    Full,
+    /// The tower has enough energy that refilling it can wait.
+    Low,
+    /// The tower should be refilled: it's at or below the idle threshold, or
+    /// hostiles are present and it isn't full.
+    Normal,
+    /// Hostiles are present and the tower is running out of energy.
+    Critical,
+}
+
+/// Energy thresholds deciding when towers should be refilled.
+///
+/// Keeping towers topped off after every shot wastes hauler trips, so towers
+/// above [`idle_above`] are only refilled when nothing else needs energy,
+/// unless hostiles are present.
+///
+/// [`idle_above`]: TowerRefillPolicy::idle_above
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TowerRefillPolicy {
+    /// Refilling towers with less energy than this is critical while hostiles
+    /// are present. Defaults to 500.
+    pub critical_below: u32,
+    /// Refilling towers with more energy than this is low priority while no
+    /// hostiles are present. Defaults to 800.
+    pub idle_above: u32,
+}
+
+impl Default for TowerRefillPolicy {
+    fn default() -> Self {
+        TowerRefillPolicy {
+            critical_below: 500,
+            idle_above: 800,
+        }
+    }
+}
+
+impl TowerRefillPolicy {
+    /// The refill priority of a tower holding `energy` out of `capacity`.
+    pub fn priority(&self, energy: u32, capacity: u32, threat_present: bool) -> RefillPriority {
+        if energy >= capacity {
+            RefillPriority::Full
+        } else if threat_present && energy < self.critical_below {
+            RefillPriority::Critical
+        } else if !threat_present && energy > self.idle_above {
+            RefillPriority::Low
+        } else {
+            RefillPriority::Normal
+        }
+    }
+
+    /// The refill priority of `tower`.
+    pub fn priority_for(&self, tower: &StructureTower, threat_present: bool) -> RefillPriority {
+        self.priority(
+            tower.energy(),
+            tower.store_capacity(Some(ResourceType::Energy)),
+            threat_present,
+        )
+    }
+
+    /// Energy needed to refill a tower holding `energy` out of `capacity`, or
+    /// 0 if it has more than [`idle_above`] energy while no hostiles are
+    /// present.
+    ///
+    /// [`idle_above`]: TowerRefillPolicy::idle_above
+    pub fn deficit(&self, energy: u32, capacity: u32, threat_present: bool) -> u32 {
+        if !threat_present && energy > self.idle_above {
+            0
+        } else {
+            capacity.saturating_sub(energy)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RefillPriority, TowerRefillPolicy};
+    use crate::constants::TOWER_CAPACITY;
+
+    #[test]
+    fn default_policy() {
+        let policy = TowerRefillPolicy::default();
+        let priorities = |threat_present| -> Vec<RefillPriority> {
+            [0, 499, 500, 800, 801, 990, 1000]
+                .iter()
+                .map(|&energy| policy.priority(energy, TOWER_CAPACITY, threat_present))
+                .collect()
+        };
+
+        use RefillPriority::*;
+        assert_eq!(
+            priorities(true),
+            vec![Critical, Critical, Normal, Normal, Normal, Normal, Full]
+        );
+        assert_eq!(
+            priorities(false),
+            vec![Normal, Normal, Normal, Normal, Low, Low, Full]
+        );
+        assert!(Critical > Normal && Normal > Low && Low > Full);
+    }
+
+    #[test]
+    fn custom_policy() {
+        let policy = TowerRefillPolicy {
+            critical_below: 200,
+            idle_above: 950,
+        };
+
+        assert_eq!(
+            policy.priority(300, TOWER_CAPACITY, true),
+            RefillPriority::Normal
+        );
+        assert_eq!(
+            policy.priority(900, TOWER_CAPACITY, false),
+            RefillPriority::Normal
+        );
+        assert_eq!(
+            policy.priority(960, TOWER_CAPACITY, false),
+            RefillPriority::Low
+        );
+
+        let towers = [
+            (100, TOWER_CAPACITY),
+            (950, TOWER_CAPACITY),
+            (990, TOWER_CAPACITY),
+        ];
+        let demand = |threat_present| -> u32 {
+            towers
+                .iter()
+                .map(|&(energy, capacity)| policy.deficit(energy, capacity, threat_present))
+                .sum()
+        };
+        assert_eq!(demand(false), 900 + 50);
+        // towers are topped off while hostiles are present
+        assert_eq!(demand(true), 900 + 50 + 10);
+    }
 }
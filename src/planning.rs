@@ -0,0 +1,760 @@
+//! Placing construction sites from a room plan.
+//!
+//! A [`RoomPlan`] is an ordered list of structures to build in a room. Calling
+//! [`place_sites`] each tick places as many of them as the global
+//! construction site cap and a per-tick budget allow, skipping tiles where the
+//! game would reject the site anyway. Tiles with a creep on them are deferred
+//! to a later tick, as a hostile creep stepping on a construction site destroys
+//! it, and a creep standing on a tile prevents building obstacles there.
+//!
+//! [`find_anchor`] picks where a layout of a given size fits in a room's
+//! terrain, the usual first step of planning a base, and [`layout_hash`]
+//! fingerprints the structures and construction sites of a room, so plans only
+//! need to be recomputed when the layout changes.
+use std::{collections::HashMap, error::Error, fmt};
+
+use crate::{
+    constants::{find, ReturnCode, StructureType, Terrain},
+    game,
+    local::{LocalRoomTerrain, Position},
+    objects::{HasPosition, Room},
+};
+
+/// A single structure in a [`RoomPlan`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PlannedStructure {
+    pub x: u8,
+    pub y: u8,
+    pub structure_type: StructureType,
+}
+
+/// An ordered list of structures to build in a single room.
+///
+/// Structures are placed in the order they were added, so higher priority
+/// structures should be added first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RoomPlan {
+    structures: Vec<PlannedStructure>,
+}
+
+impl RoomPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a structure at the end of this plan.
+    pub fn add(mut self, x: u8, y: u8, structure_type: StructureType) -> Self {
+        self.push(x, y, structure_type);
+        self
+    }
+
+    /// Adds a structure at the end of this plan.
+    pub fn push(&mut self, x: u8, y: u8, structure_type: StructureType) {
+        self.structures.push(PlannedStructure {
+            x,
+            y,
+            structure_type,
+        });
+    }
+
+    /// The planned structures, in priority order.
+    pub fn structures(&self) -> &[PlannedStructure] {
+        &self.structures
+    }
+}
+
+/// Reason for a planned structure not being placed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PlacementError {
+    /// The coordinates are outside of the room.
+    OutOfBounds,
+    /// The tile is on the edge of the room, where nothing can be built.
+    RoomEdge,
+    /// The tile is next to an exit, where only roads and containers can be
+    /// built.
+    NearExit,
+    /// The tile is a wall, where only roads can be built.
+    Wall,
+    /// The structure type can't be built by players.
+    NotBuildable,
+    /// The player already has [`MAX_CONSTRUCTION_SITES`] construction sites,
+    /// reported by the game as [`ReturnCode::Full`]. No more sites can be
+    /// placed this tick unless some are removed.
+    ///
+    /// [`MAX_CONSTRUCTION_SITES`]: crate::constants::MAX_CONSTRUCTION_SITES
+    SiteCapReached,
+    /// The game rejected the construction site with this return code.
+    Rejected(ReturnCode),
+}
+
+impl PlacementError {
+    /// Converts the return code of a failed attempt to place a construction
+    /// site, mapping [`ReturnCode::Full`] to
+    /// [`PlacementError::SiteCapReached`].
+    pub fn from_return_code(code: ReturnCode) -> Self {
+        match code {
+            ReturnCode::Full => PlacementError::SiteCapReached,
+            code => PlacementError::Rejected(code),
+        }
+    }
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlacementError::OutOfBounds => write!(f, "position is outside of the room"),
+            PlacementError::RoomEdge => write!(f, "position is on the room edge"),
+            PlacementError::NearExit => write!(f, "position is next to an exit"),
+            PlacementError::Wall => write!(f, "position is a wall"),
+            PlacementError::NotBuildable => write!(f, "structure type can't be built"),
+            PlacementError::SiteCapReached => write!(f, "construction site cap reached"),
+            PlacementError::Rejected(code) => {
+                write!(f, "construction site rejected with {:?}", code)
+            }
+        }
+    }
+}
+
+impl Error for PlacementError {}
+
+/// Checks whether a construction site for `structure_type` could be placed at
+/// the given coordinates, using the same terrain rules as the game.
+///
+/// This does not check for other structures already on the tile, nor the
+/// number of structures allowed at the room's controller level; the game
+/// reports those when placing the site.
+pub fn check_placement<T>(
+    x: u8,
+    y: u8,
+    structure_type: StructureType,
+    terrain: T,
+) -> Result<(), PlacementError>
+where
+    T: Fn(u8, u8) -> Terrain,
+{
+    if x >= 50 || y >= 50 {
+        return Err(PlacementError::OutOfBounds);
+    }
+    if structure_type.construction_cost().is_none() {
+        return Err(PlacementError::NotBuildable);
+    }
+    if x == 0 || y == 0 || x == 49 || y == 49 {
+        return Err(PlacementError::RoomEdge);
+    }
+    if structure_type != StructureType::Road && terrain(x, y) == Terrain::Wall {
+        return Err(PlacementError::Wall);
+    }
+    if structure_type != StructureType::Road && structure_type != StructureType::Container {
+        let near_exit = |edge_x: u8, edge_y: u8| terrain(edge_x, edge_y) != Terrain::Wall;
+        let exit_adjacent = (x == 1 && (y - 1..=y + 1).any(|ey| near_exit(0, ey)))
+            || (x == 48 && (y - 1..=y + 1).any(|ey| near_exit(49, ey)))
+            || (y == 1 && (x - 1..=x + 1).any(|ex| near_exit(ex, 0)))
+            || (y == 48 && (x - 1..=x + 1).any(|ex| near_exit(ex, 49)));
+        if exit_adjacent {
+            return Err(PlacementError::NearExit);
+        }
+    }
+    Ok(())
+}
+
+/// Who a creep standing on a tile belongs to, as recorded in
+/// [`CreepPositions`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CreepKind {
+    /// A creep or power creep of the current player.
+    Mine,
+    /// A creep or power creep of any other player, allies included, which
+    /// destroys construction sites it steps on.
+    Hostile,
+}
+
+/// The tiles of a room occupied by creeps and power creeps.
+///
+/// Built once per room per tick with [`CreepPositions::of_room`], this
+/// answers occupancy queries without calling into JavaScript.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CreepPositions {
+    tiles: HashMap<(u8, u8), CreepKind>,
+}
+
+impl CreepPositions {
+    /// Creates an index with no creeps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes the creeps and power creeps currently in `room`.
+    pub fn of_room(room: &Room) -> Self {
+        let mut positions = CreepPositions::new();
+        let mut add = |pos: Position, kind| positions.insert(pos.x() as u8, pos.y() as u8, kind);
+        for creep in room.find(find::MY_CREEPS) {
+            add(creep.pos(), CreepKind::Mine);
+        }
+        for creep in room.find(find::MY_POWER_CREEPS) {
+            add(creep.pos(), CreepKind::Mine);
+        }
+        for creep in room.find(find::HOSTILE_CREEPS) {
+            add(creep.pos(), CreepKind::Hostile);
+        }
+        for creep in room.find(find::HOSTILE_POWER_CREEPS) {
+            add(creep.pos(), CreepKind::Hostile);
+        }
+        positions
+    }
+
+    /// Records a creep standing at the given coordinates.
+    ///
+    /// Hostile creeps take precedence over the current player's creeps on the
+    /// same tile.
+    pub fn insert(&mut self, x: u8, y: u8, kind: CreepKind) {
+        let tile = self.tiles.entry((x, y)).or_insert(kind);
+        if kind == CreepKind::Hostile {
+            *tile = kind;
+        }
+    }
+
+    /// The creep at the given coordinates, if any.
+    pub fn get(&self, x: u8, y: u8) -> Option<CreepKind> {
+        self.tiles.get(&(x, y)).copied()
+    }
+
+    /// Whether a construction site at the given coordinates would be
+    /// destroyed by a hostile creep standing there.
+    pub fn is_stompable(&self, x: u8, y: u8) -> bool {
+        self.get(x, y) == Some(CreepKind::Hostile)
+    }
+
+    /// Whether a construction site for `structure_type` at the given
+    /// coordinates should wait for the creep on the tile to leave: always
+    /// for hostile creeps, and for the current player's creeps when the
+    /// structure is an obstacle, which can't be built under a creep.
+    pub fn should_defer(&self, x: u8, y: u8, structure_type: StructureType) -> bool {
+        match self.get(x, y) {
+            Some(CreepKind::Hostile) => true,
+            Some(CreepKind::Mine) => structure_type.is_obstacle(),
+            None => false,
+        }
+    }
+}
+
+/// Whether a construction site at `pos` would be destroyed by a hostile
+/// creep currently standing there.
+///
+/// To check many tiles of the same room, build a [`CreepPositions`] once
+/// instead.
+pub fn is_stompable(room: &Room, pos: Position) -> bool {
+    CreepPositions::of_room(room).is_stompable(pos.x() as u8, pos.y() as u8)
+}
+
+/// The outcome of a call to [`place_sites`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlacementReport {
+    /// Structures whose construction sites were placed this tick.
+    pub placed: Vec<PlannedStructure>,
+    /// Structures which can't be placed, along with the reason.
+    pub invalid: Vec<(PlannedStructure, PlacementError)>,
+    /// Valid structures which weren't placed because the budget or the global
+    /// construction site cap was reached, or because a creep stands on their
+    /// tile (see [`CreepPositions::should_defer`]).
+    pub deferred: Vec<PlannedStructure>,
+    /// Whether placement stopped at the global construction site cap, in which
+    /// case no other room can place sites this tick either.
+    pub site_cap_reached: bool,
+}
+
+/// Places construction sites for the structures in `plan`, in priority order.
+///
+/// At most `budget` sites are placed, and never more than
+/// [`game::construction_site_budget_remaining`]. Structures on tiles occupied
+/// by creeps are deferred, so they can be placed on a later tick.
+pub fn place_sites(room: &Room, plan: &RoomPlan, budget: u32) -> PlacementReport {
+    let room_name = room.name();
+    let terrain = room.get_terrain();
+    let creeps = CreepPositions::of_room(room);
+
+    place_with(
+        plan,
+        budget,
+        game::construction_site_budget_remaining(),
+        |x, y| terrain.get(u32::from(x), u32::from(y)),
+        &creeps,
+        |planned| {
+            let pos = Position::new(u32::from(planned.x), u32::from(planned.y), room_name);
+            room.create_construction_site(&pos, planned.structure_type)
+        },
+    )
+}
+
+fn place_with<T, P>(
+    plan: &RoomPlan,
+    budget: u32,
+    mut sites_remaining: u32,
+    terrain: T,
+    creeps: &CreepPositions,
+    mut place: P,
+) -> PlacementReport
+where
+    T: Fn(u8, u8) -> Terrain,
+    P: FnMut(&PlannedStructure) -> ReturnCode,
+{
+    let mut report = PlacementReport::default();
+
+    for planned in plan.structures() {
+        if let Err(e) = check_placement(planned.x, planned.y, planned.structure_type, &terrain) {
+            report.invalid.push((*planned, e));
+        } else if report.placed.len() as u32 >= budget
+            || creeps.should_defer(planned.x, planned.y, planned.structure_type)
+        {
+            report.deferred.push(*planned);
+        } else if report.placed.len() as u32 >= sites_remaining {
+            report.site_cap_reached = true;
+            report.deferred.push(*planned);
+        } else {
+            match place(planned) {
+                ReturnCode::Ok => report.placed.push(*planned),
+                ReturnCode::Full => {
+                    // other sites were placed since the count was read
+                    report.site_cap_reached = true;
+                    sites_remaining = report.placed.len() as u32;
+                    report.deferred.push(*planned);
+                }
+                code => report
+                    .invalid
+                    .push((*planned, PlacementError::Rejected(code))),
+            }
+        }
+    }
+
+    report
+}
+
+/// A structure or construction site, as hashed by [`layout_hash`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LayoutItem {
+    pub structure_type: StructureType,
+    pub x: u8,
+    pub y: u8,
+    /// Whether this is a construction site rather than a built structure.
+    pub construction_site: bool,
+}
+
+/// 64-bit FNV-1a offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// 64-bit FNV-1a prime.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Hashes a room's layout, independently of the order of `items`.
+///
+/// The hash is stable across compiles and versions of this crate, so it can
+/// be persisted. Each item is hashed with 64-bit FNV-1a over the game's
+/// `structureType` string, a zero byte, then one byte each for x, y and
+/// whether it's a construction site. The item hashes are sorted, and the
+/// layout hash is the FNV-1a hash of their little-endian bytes.
+///
+/// See [`Room::layout_hash`] for hashing a visible room.
+pub fn layout_hash<I>(items: I) -> u64
+where
+    I: IntoIterator<Item = LayoutItem>,
+{
+    let mut hashes: Vec<u64> = items
+        .into_iter()
+        .map(|item| {
+            let hash = fnv1a(FNV_OFFSET, item.structure_type.to_string().as_bytes());
+            fnv1a(hash, &[0, item.x, item.y, u8::from(item.construction_site)])
+        })
+        .collect();
+    hashes.sort_unstable();
+    hashes
+        .iter()
+        .fold(FNV_OFFSET, |hash, item| fnv1a(hash, &item.to_le_bytes()))
+}
+
+/// The Chebyshev distance from each tile of a room to the nearest tile where
+/// no structure other than roads and containers can be built, in row-major
+/// order.
+///
+/// Walls, the room edges and tiles next to exits count as unbuildable, as in
+/// [`check_placement`]. A square of `(2r + 1)²` tiles centered on a tile only
+/// covers buildable tiles when the distance at that tile is more than `r`.
+pub fn distance_transform(terrain: &LocalRoomTerrain) -> Vec<u8> {
+    let terrain = |x: u8, y: u8| terrain.get(u32::from(x), u32::from(y));
+    let mut distances = vec![0u8; 2500];
+    // two passes of the chessboard chamfer, each tile taking the distance of
+    // its already visited neighbors plus one
+    for y in 1..49 {
+        for x in 1..49 {
+            let i = y * 50 + x;
+            if check_placement(x as u8, y as u8, StructureType::Extension, terrain).is_ok() {
+                let neighbors = distances[i - 51]
+                    .min(distances[i - 50])
+                    .min(distances[i - 49])
+                    .min(distances[i - 1]);
+                distances[i] = neighbors + 1;
+            }
+        }
+    }
+    for y in (1..49).rev() {
+        for x in (1..49).rev() {
+            let i = y * 50 + x;
+            if distances[i] > 0 {
+                let neighbors = distances[i + 1]
+                    .min(distances[i + 49])
+                    .min(distances[i + 50])
+                    .min(distances[i + 51]);
+                distances[i] = distances[i].min(neighbors + 1);
+            }
+        }
+    }
+    distances
+}
+
+/// Picks the center of a square of `(2 * footprint_radius + 1)²` buildable
+/// tiles, such as the anchor of a bunker layout, or returns `None` if no such
+/// square fits in the room.
+///
+/// When `prefer_near` holds positions, such as the room's sources and
+/// controller, the center with the smallest total range to them is picked.
+/// Otherwise, the center with the most open space around it is picked, the
+/// closest to the middle of the room among equally open ones. Remaining ties
+/// go to the first center in row-major order.
+///
+/// Only terrain is considered: the square may cover sources, the controller
+/// or the mineral.
+pub fn find_anchor(
+    terrain: &LocalRoomTerrain,
+    footprint_radius: u8,
+    prefer_near: &[(u8, u8)],
+) -> Option<(u8, u8)> {
+    let distances = distance_transform(terrain);
+    let range = |(x1, y1): (u8, u8), (x2, y2): (u8, u8)| {
+        u32::from(x1.max(x2) - x1.min(x2)).max(u32::from(y1.max(y2) - y1.min(y2)))
+    };
+
+    (1..49u8)
+        .flat_map(|y| (1..49u8).map(move |x| (x, y)))
+        .filter_map(|(x, y)| {
+            let distance = distances[usize::from(y) * 50 + usize::from(x)];
+            if distance <= footprint_radius {
+                return None;
+            }
+            let closeness = u32::from(u8::MAX - distance);
+            let key = if prefer_near.is_empty() {
+                (closeness, range((x, y), (25, 25)))
+            } else {
+                let total: u32 = prefer_near.iter().map(|&pos| range((x, y), pos)).sum();
+                (total, closeness)
+            };
+            Some(((x, y), key))
+        })
+        .min_by_key(|&(_, key)| key)
+        .map(|(pos, _)| pos)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        check_placement, distance_transform, find_anchor, layout_hash, place_with, CreepKind,
+        CreepPositions, LayoutItem, PlacementError, RoomPlan,
+    };
+    use crate::{
+        constants::{ReturnCode, StructureType, Terrain},
+        local::LocalRoomTerrain,
+    };
+
+    /// Room with walls on every edge tile except an exit at (0, 20..=22), and a
+    /// wall at (10, 10).
+    fn terrain(x: u8, y: u8) -> Terrain {
+        if (x == 0 && (20..=22).contains(&y)) || !(x == 0 || y == 0 || x == 49 || y == 49) {
+            if x == 10 && y == 10 {
+                Terrain::Wall
+            } else {
+                Terrain::Plain
+            }
+        } else {
+            Terrain::Wall
+        }
+    }
+
+    #[test]
+    fn placement_rules() {
+        use StructureType::*;
+
+        assert_eq!(check_placement(25, 25, Spawn, terrain), Ok(()));
+        assert_eq!(
+            check_placement(50, 25, Road, terrain),
+            Err(PlacementError::OutOfBounds)
+        );
+        assert_eq!(
+            check_placement(0, 21, Road, terrain),
+            Err(PlacementError::RoomEdge)
+        );
+        assert_eq!(
+            check_placement(25, 25, Controller, terrain),
+            Err(PlacementError::NotBuildable)
+        );
+        assert_eq!(
+            check_placement(10, 10, Extension, terrain),
+            Err(PlacementError::Wall)
+        );
+        assert_eq!(check_placement(10, 10, Road, terrain), Ok(()));
+        assert_eq!(
+            check_placement(1, 23, Rampart, terrain),
+            Err(PlacementError::NearExit)
+        );
+        assert_eq!(check_placement(1, 23, Container, terrain), Ok(()));
+        // next to the edge, but not next to an exit tile
+        assert_eq!(check_placement(1, 24, Rampart, terrain), Ok(()));
+        assert_eq!(check_placement(48, 48, Tower, terrain), Ok(()));
+    }
+
+    #[test]
+    fn tight_budget() {
+        use StructureType::*;
+
+        let plan = RoomPlan::new()
+            .add(25, 25, Spawn)
+            .add(10, 10, Extension)
+            .add(26, 25, Extension)
+            .add(0, 0, Road)
+            .add(27, 25, Extension)
+            .add(28, 25, Tower)
+            .add(29, 25, Storage);
+
+        let mut calls = 0;
+        let report = place_with(&plan, 3, 100, terrain, &CreepPositions::new(), |planned| {
+            calls += 1;
+            // pretend a structure already occupies this tile
+            if planned.x == 26 {
+                ReturnCode::InvalidTarget
+            } else {
+                ReturnCode::Ok
+            }
+        });
+
+        assert_eq!(calls, 4);
+        let placed: Vec<_> = report.placed.iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(placed, vec![(25, 25), (27, 25), (28, 25)]);
+        let invalid: Vec<_> = report.invalid.iter().map(|(p, e)| (p.x, *e)).collect();
+        assert_eq!(
+            invalid,
+            vec![
+                (10, PlacementError::Wall),
+                (26, PlacementError::Rejected(ReturnCode::InvalidTarget)),
+                (0, PlacementError::RoomEdge),
+            ]
+        );
+        let deferred: Vec<_> = report.deferred.iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(deferred, vec![(29, 25)]);
+    }
+
+    #[test]
+    fn no_slots_left() {
+        let plan = RoomPlan::new().add(25, 25, StructureType::Road);
+        let report = place_with(&plan, 0, 100, terrain, &CreepPositions::new(), |_| {
+            panic!("nothing should be placed")
+        });
+
+        assert!(report.placed.is_empty());
+        assert_eq!(report.deferred.len(), 1);
+        assert!(!report.site_cap_reached);
+    }
+
+    #[test]
+    fn site_cap() {
+        use StructureType::*;
+
+        let plan = RoomPlan::new()
+            .add(25, 25, Road)
+            .add(26, 25, Road)
+            .add(27, 25, Road)
+            .add(10, 10, Extension);
+
+        // two sites left before the cap, but a budget of five
+        let report = place_with(&plan, 5, 2, terrain, &CreepPositions::new(), |_| {
+            ReturnCode::Ok
+        });
+        assert_eq!(report.placed.len(), 2);
+        assert_eq!(report.deferred.len(), 1);
+        assert_eq!(report.invalid.len(), 1);
+        assert!(report.site_cap_reached);
+
+        // sites placed elsewhere after the count was read
+        let mut calls = 0;
+        let report = place_with(&plan, 5, 50, terrain, &CreepPositions::new(), |_| {
+            calls += 1;
+            if calls == 1 {
+                ReturnCode::Ok
+            } else {
+                ReturnCode::Full
+            }
+        });
+        assert_eq!(calls, 2);
+        assert_eq!(report.placed.len(), 1);
+        assert_eq!(report.deferred.len(), 2);
+        assert!(report.site_cap_reached);
+    }
+
+    #[test]
+    fn return_code_errors() {
+        assert_eq!(
+            PlacementError::from_return_code(ReturnCode::Full),
+            PlacementError::SiteCapReached
+        );
+        assert_eq!(
+            PlacementError::from_return_code(ReturnCode::RclNotEnough),
+            PlacementError::Rejected(ReturnCode::RclNotEnough)
+        );
+        assert_eq!(
+            PlacementError::SiteCapReached.to_string(),
+            "construction site cap reached"
+        );
+    }
+
+    #[test]
+    fn occupied_tiles() {
+        use StructureType::*;
+
+        let mut creeps = CreepPositions::new();
+        creeps.insert(25, 25, CreepKind::Hostile);
+        creeps.insert(26, 25, CreepKind::Mine);
+        creeps.insert(27, 25, CreepKind::Mine);
+        creeps.insert(27, 25, CreepKind::Hostile);
+        creeps.insert(28, 25, CreepKind::Hostile);
+        creeps.insert(28, 25, CreepKind::Mine);
+        assert!(creeps.is_stompable(25, 25));
+        assert!(!creeps.is_stompable(26, 25));
+        assert!(creeps.is_stompable(27, 25));
+        assert!(creeps.is_stompable(28, 25));
+        assert!(!creeps.is_stompable(29, 25));
+
+        let plan = RoomPlan::new()
+            .add(25, 25, Road)
+            .add(26, 25, Extension)
+            .add(26, 25, Rampart)
+            .add(29, 25, Tower)
+            .add(10, 10, Extension);
+        let report = place_with(&plan, 5, 100, terrain, &creeps, |_| ReturnCode::Ok);
+
+        // a road under a hostile creep would be stomped, and an extension
+        // can't be built under the current player's creep, but a rampart can
+        let placed: Vec<_> = report.placed.iter().map(|p| p.structure_type).collect();
+        assert_eq!(placed, vec![Rampart, Tower]);
+        let deferred: Vec<_> = report.deferred.iter().map(|p| p.structure_type).collect();
+        assert_eq!(deferred, vec![Road, Extension]);
+        // invalid tiles stay invalid whether or not they're occupied
+        assert_eq!(report.invalid.len(), 1);
+
+        // once the creeps leave, deferred structures are placed
+        let report = place_with(&plan, 5, 100, terrain, &CreepPositions::new(), |_| {
+            ReturnCode::Ok
+        });
+        assert_eq!(report.placed.len(), 4);
+    }
+
+    #[test]
+    fn layout_hashing() {
+        let item = |structure_type, x, y, construction_site| LayoutItem {
+            structure_type,
+            x,
+            y,
+            construction_site,
+        };
+        let layout = vec![
+            item(StructureType::Spawn, 25, 25, false),
+            item(StructureType::Extension, 24, 26, false),
+            item(StructureType::Road, 25, 26, false),
+            item(StructureType::Extension, 26, 26, true),
+        ];
+        let hash = layout_hash(layout.clone());
+
+        // the empty layout hashes to the FNV-1a offset basis
+        assert_eq!(layout_hash(vec![]), 0xcbf2_9ce4_8422_2325);
+        assert_ne!(hash, layout_hash(vec![]));
+
+        let mut reversed = layout.clone();
+        reversed.reverse();
+        assert_eq!(layout_hash(reversed), hash);
+        let mut rotated = layout.clone();
+        rotated.rotate_left(1);
+        assert_eq!(layout_hash(rotated), hash);
+
+        let mut moved = layout.clone();
+        moved[1].x = 23;
+        assert_ne!(layout_hash(moved), hash);
+
+        // swapping the positions of two structures changes the layout
+        let mut swapped = layout.clone();
+        swapped[0].x = 24;
+        swapped[0].y = 26;
+        swapped[1].x = 25;
+        swapped[1].y = 25;
+        assert_ne!(layout_hash(swapped), hash);
+
+        let mut built = layout.clone();
+        built[3].construction_site = false;
+        assert_ne!(layout_hash(built), hash);
+
+        let mut duplicated = layout;
+        duplicated.push(item(StructureType::Road, 25, 26, false));
+        assert_ne!(layout_hash(duplicated), hash);
+    }
+
+    /// Terrain which is wall everywhere except in the given rectangles, as
+    /// `(x1, y1, x2, y2)` inclusive.
+    fn open_areas(areas: &[(u8, u8, u8, u8)]) -> LocalRoomTerrain {
+        let mut bits = Box::new([1; 2500]);
+        for &(x1, y1, x2, y2) in areas {
+            for y in y1..=y2 {
+                for x in x1..=x2 {
+                    bits[usize::from(y) * 50 + usize::from(x)] = 0;
+                }
+            }
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
+    #[test]
+    fn distances_to_obstacles() {
+        let plain = open_areas(&[(0, 0, 49, 49)]);
+        let distances = distance_transform(&plain);
+        assert_eq!(distances[0], 0);
+        // tiles next to exits aren't buildable either
+        assert_eq!(distances[50 + 1], 0);
+        assert_eq!(distances[2 * 50 + 2], 1);
+        assert_eq!(distances[25 * 50 + 25], 23);
+        assert_eq!(distances[47 * 50 + 30], 1);
+
+        let room = open_areas(&[(10, 10, 20, 16)]);
+        let distances = distance_transform(&room);
+        assert_eq!(distances[10 * 50 + 10], 1);
+        assert_eq!(distances[13 * 50 + 15], 4);
+        assert_eq!(distances[9 * 50 + 15], 0);
+    }
+
+    #[test]
+    fn anchor_selection() {
+        // the middle of the room is the most open spot
+        let plain = open_areas(&[(0, 0, 49, 49)]);
+        assert_eq!(find_anchor(&plain, 3, &[]), Some((25, 25)));
+
+        // an 11x11 area only fits a radius 5 square at its center, while a
+        // larger area elsewhere is more open
+        let room = open_areas(&[(5, 5, 15, 15), (25, 25, 45, 45)]);
+        assert_eq!(find_anchor(&room, 5, &[]), Some((35, 35)));
+        assert_eq!(find_anchor(&room, 11, &[]), None);
+        // near a source and the controller, the smaller area is preferred
+        assert_eq!(find_anchor(&room, 5, &[(8, 17), (3, 10)]), Some((10, 10)));
+
+        // the 11x7 area fits radius 3 squares centered on (13..=17, 13), and
+        // (17, 13) is closest to a source at (20, 13)
+        let room = open_areas(&[(10, 10, 20, 16)]);
+        assert_eq!(find_anchor(&room, 3, &[(20, 13)]), Some((17, 13)));
+        assert_eq!(find_anchor(&room, 4, &[(20, 13)]), None);
+    }
+}
@@ -0,0 +1,271 @@
+//! Attributing CPU usage to labeled sections of code.
+//!
+//! A [`CpuSampler`] keeps a stack of labels, pushed with
+//! [`CpuSampler::enter`] and popped with [`CpuSampler::exit`]. Each change of
+//! label records a checkpoint of the CPU used so far in a fixed-size ring
+//! buffer, so recording costs one call to [`cpu::get_used`] and no
+//! allocation. At the end of the tick, [`CpuSampler::end_tick`] attributes
+//! the CPU used between consecutive checkpoints to the label active in
+//! between, and updates a moving average of the usage of each label across
+//! ticks:
+//!
+//! ```no_run
+//! use screeps::profiling::CpuSampler;
+//!
+//! let mut sampler = CpuSampler::new(256);
+//! // each tick
+//! sampler.enter("spawning");
+//! // ...
+//! sampler.enter("pathfinding");
+//! // ...
+//! sampler.exit();
+//! // back to spawning
+//! sampler.exit();
+//! sampler.end_tick();
+//! for (label, average) in sampler.top() {
+//!     println!("{}: {:.2}", label, average);
+//! }
+//! ```
+//!
+//! The sampler is meant to be kept in the heap between ticks, such as in a
+//! `thread_local!`.
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::game::cpu;
+
+/// Label CPU usage is attributed to when no label has been entered.
+pub const UNLABELED: &str = "(unlabeled)";
+
+/// Number of labels returned by [`CpuSampler::top`].
+pub const TOP_LABELS: usize = 10;
+
+/// The CPU used so far this tick when `label` became the active label.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Checkpoint {
+    pub label: &'static str,
+    pub cpu: f64,
+}
+
+/// Records checkpoints of CPU usage and attributes it to labels, as
+/// described in the [module documentation][self].
+#[derive(Clone, Debug)]
+pub struct CpuSampler {
+    checkpoints: Vec<Checkpoint>,
+    capacity: usize,
+    /// Index the next checkpoint is written at, once the buffer is full.
+    next: usize,
+    stack: Vec<&'static str>,
+    smoothing: f64,
+    averages: HashMap<&'static str, f64>,
+}
+
+impl CpuSampler {
+    /// Creates a sampler keeping at most `capacity` checkpoints each tick,
+    /// weighing each tick by 0.2 in the moving averages.
+    ///
+    /// Once more checkpoints are recorded in a tick, the oldest are
+    /// overwritten and the CPU used before the oldest remaining checkpoint
+    /// goes unattributed.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        CpuSampler {
+            checkpoints: Vec::with_capacity(capacity),
+            capacity,
+            next: 0,
+            stack: Vec::new(),
+            smoothing: 0.2,
+            averages: HashMap::new(),
+        }
+    }
+
+    /// Sets the weight of each tick in the exponential moving averages,
+    /// between 0 and 1 - default 0.2. Higher values follow changes in usage
+    /// faster.
+    #[inline]
+    pub fn smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 1.0);
+        self
+    }
+
+    /// The active label: the last one entered and not yet exited.
+    pub fn current_label(&self) -> &'static str {
+        self.stack.last().copied().unwrap_or(UNLABELED)
+    }
+
+    /// Makes `label` the active label.
+    pub fn enter(&mut self, label: &'static str) {
+        self.enter_at(label, cpu::get_used());
+    }
+
+    /// Returns to the label active before the last [`CpuSampler::enter`].
+    pub fn exit(&mut self) {
+        self.exit_at(cpu::get_used());
+    }
+
+    /// Attributes the CPU used this tick, updates the moving averages and
+    /// returns the CPU used by each label this tick.
+    pub fn end_tick(&mut self) -> HashMap<&'static str, f64> {
+        self.end_tick_at(cpu::get_used())
+    }
+
+    /// Makes `label` the active label, `cpu` having been used so far this
+    /// tick.
+    pub fn enter_at(&mut self, label: &'static str, cpu: f64) {
+        self.stack.push(label);
+        self.record(label, cpu);
+    }
+
+    /// Returns to the label active before the last [`CpuSampler::enter_at`],
+    /// `cpu` having been used so far this tick.
+    pub fn exit_at(&mut self, cpu: f64) {
+        self.stack.pop();
+        self.record(self.current_label(), cpu);
+    }
+
+    /// Attributes the CPU used this tick, up to `cpu`, like
+    /// [`CpuSampler::end_tick`].
+    ///
+    /// CPU used before the first checkpoint of the tick is attributed to
+    /// [`UNLABELED`], unless checkpoints were overwritten. Labels still
+    /// active are exited, and labels which used nothing this tick have their
+    /// averages decay towards 0.
+    pub fn end_tick_at(&mut self, cpu: f64) -> HashMap<&'static str, f64> {
+        let mut used = HashMap::new();
+        let mut previous = if self.checkpoints.len() < self.capacity {
+            Checkpoint {
+                label: UNLABELED,
+                cpu: 0.0,
+            }
+        } else {
+            self.checkpoints[self.next]
+        };
+        let (older, newer) = self.checkpoints.split_at(self.next);
+        for &checkpoint in newer.iter().chain(older) {
+            *used.entry(previous.label).or_insert(0.0) += checkpoint.cpu - previous.cpu;
+            previous = checkpoint;
+        }
+        *used.entry(previous.label).or_insert(0.0) += cpu - previous.cpu;
+        used.retain(|_, used| *used > 0.0);
+
+        for (label, average) in self.averages.iter_mut() {
+            let used = used.get(label).copied().unwrap_or(0.0);
+            *average += self.smoothing * (used - *average);
+        }
+        for (&label, &used) in &used {
+            self.averages.entry(label).or_insert(used);
+        }
+
+        self.checkpoints.clear();
+        self.next = 0;
+        self.stack.clear();
+        used
+    }
+
+    /// The moving average of the CPU used by a label each tick, or `None` if
+    /// it hasn't used any yet.
+    pub fn average(&self, label: &str) -> Option<f64> {
+        self.averages.get(label).copied()
+    }
+
+    /// The [`TOP_LABELS`] labels with the highest average CPU usage, highest
+    /// first.
+    pub fn top(&self) -> Vec<(&'static str, f64)> {
+        let mut labels: Vec<(&'static str, f64)> = self
+            .averages
+            .iter()
+            .map(|(&label, &average)| (label, average))
+            .collect();
+        labels.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.0.cmp(b.0))
+        });
+        labels.truncate(TOP_LABELS);
+        labels
+    }
+
+    fn record(&mut self, label: &'static str, cpu: f64) {
+        let checkpoint = Checkpoint { label, cpu };
+        if self.checkpoints.len() < self.capacity {
+            self.checkpoints.push(checkpoint);
+        } else {
+            self.checkpoints[self.next] = checkpoint;
+            self.next = (self.next + 1) % self.capacity;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CpuSampler, UNLABELED};
+
+    #[test]
+    fn nested_attribution() {
+        let mut sampler = CpuSampler::new(16).smoothing(0.5);
+        sampler.enter_at("creeps", 1.0);
+        sampler.enter_at("pathfinding", 2.5);
+        assert_eq!(sampler.current_label(), "pathfinding");
+        sampler.exit_at(6.0);
+        sampler.exit_at(7.0);
+        sampler.enter_at("towers", 7.5);
+        // towers are still active at the end of the tick
+        let used = sampler.end_tick_at(8.0);
+
+        assert_eq!(used.len(), 4);
+        assert_eq!(used[UNLABELED], 1.5);
+        assert_eq!(used["creeps"], 2.5);
+        assert_eq!(used["pathfinding"], 3.5);
+        assert_eq!(used["towers"], 0.5);
+        assert_eq!(sampler.current_label(), UNLABELED);
+
+        // the next tick only runs creeps
+        sampler.enter_at("creeps", 0.5);
+        let used = sampler.end_tick_at(4.5);
+        assert_eq!(used["creeps"], 4.0);
+        assert_eq!(sampler.average("creeps"), Some(3.25));
+        assert_eq!(sampler.average("pathfinding"), Some(1.75));
+        assert_eq!(sampler.average("unknown"), None);
+
+        let top: Vec<&str> = sampler.top().iter().map(|&(label, _)| label).collect();
+        assert_eq!(top, vec!["creeps", "pathfinding", UNLABELED, "towers"]);
+    }
+
+    #[test]
+    fn ring_buffer_overflow() {
+        let mut sampler = CpuSampler::new(3);
+        sampler.enter_at("a", 1.0);
+        sampler.exit_at(2.0);
+        sampler.enter_at("b", 3.0);
+        sampler.exit_at(5.0);
+        // overwrites the first two checkpoints
+        sampler.enter_at("c", 6.0);
+        let used = sampler.end_tick_at(10.0);
+
+        assert_eq!(used.get("a"), None);
+        assert_eq!(used["b"], 2.0);
+        assert_eq!(used[UNLABELED], 1.0);
+        assert_eq!(used["c"], 4.0);
+    }
+
+    #[test]
+    fn top_labels() {
+        const LABELS: [&str; 12] = [
+            "l0", "l1", "l2", "l3", "l4", "l5", "l6", "l7", "l8", "l9", "l10", "l11",
+        ];
+        let mut sampler = CpuSampler::new(32);
+        let mut cpu = 0.0;
+        sampler.enter_at(LABELS[0], cpu);
+        for (i, &label) in LABELS.iter().enumerate().skip(1) {
+            cpu += i as f64;
+            sampler.exit_at(cpu);
+            sampler.enter_at(label, cpu);
+        }
+        sampler.end_tick_at(cpu + 20.0);
+
+        let top = sampler.top();
+        assert_eq!(top.len(), 10);
+        assert_eq!(top[0], ("l11", 20.0));
+        assert_eq!(top[1], ("l10", 11.0));
+        assert_eq!(top[9], ("l2", 3.0));
+    }
+}
@@ -0,0 +1,212 @@
+//! Planning which haulers should loot which ruins and tombstones.
+//!
+//! Ruins and tombstones disappear once they decay, taking their contents with
+//! them. [`plan`] pairs haulers with loot so the most valuable loot expiring
+//! soonest is collected first, skipping loot no hauler can reach in time.
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::{
+    constants::ResourceType,
+    local::Position,
+    objects::{CanDecay, HasStore},
+};
+
+/// A ruin, tombstone or other decaying object holding resources.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Loot {
+    pub pos: Position,
+    pub contents: HashMap<ResourceType, u32>,
+    pub ticks_to_decay: u32,
+}
+
+impl Loot {
+    /// Snapshots the position, contents and decay time of a decaying object
+    /// with a store, such as a [`Ruin`] or [`Tombstone`].
+    ///
+    /// [`Ruin`]: crate::objects::Ruin
+    /// [`Tombstone`]: crate::objects::Tombstone
+    pub fn from_object<T>(object: &T) -> Self
+    where
+        T: HasStore + CanDecay,
+    {
+        Loot {
+            pos: object.pos(),
+            contents: object
+                .store_types()
+                .into_iter()
+                .map(|ty| (ty, object.store_of(ty)))
+                .collect(),
+            ticks_to_decay: object.ticks_to_decay(),
+        }
+    }
+}
+
+/// A creep available to collect loot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Hauler {
+    pub pos: Position,
+    pub free_capacity: u32,
+}
+
+/// Pairs haulers with loot, valuing every resource unit equally.
+///
+/// See [`plan_with_values`].
+pub fn plan(loot: &[Loot], haulers: &[Hauler]) -> Vec<(usize, usize)> {
+    plan_with_values(loot, haulers, |_| 1.0)
+}
+
+/// Pairs haulers with loot, using `value` to weigh each unit of resource.
+///
+/// Returns `(hauler, loot)` index pairs, most urgent loot first. Loot is
+/// prioritized by the value a hauler could carry away divided by its ticks
+/// to decay, and each loot is given to the closest hauler with free capacity
+/// which can reach it before it decays. Each hauler is assigned to at most one
+/// loot and each loot to at most one hauler.
+///
+/// Travel time is estimated as the range between positions, so loot which
+/// requires a detour may still decay before the hauler arrives.
+pub fn plan_with_values<F>(loot: &[Loot], haulers: &[Hauler], value: F) -> Vec<(usize, usize)>
+where
+    F: Fn(ResourceType) -> f64,
+{
+    let total_value = |loot: &Loot| -> f64 {
+        loot.contents
+            .iter()
+            .map(|(ty, amount)| f64::from(*amount) * value(*ty))
+            .sum()
+    };
+    let max_capacity = haulers.iter().map(|h| h.free_capacity).max().unwrap_or(0);
+
+    let mut order: Vec<(usize, f64)> = loot
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.ticks_to_decay > 0)
+        .map(|(index, l)| {
+            let amount: u32 = l.contents.values().sum();
+            let value = total_value(l);
+            // only what a single hauler can carry counts towards priority
+            let carried = if amount > max_capacity {
+                value * f64::from(max_capacity) / f64::from(amount)
+            } else {
+                value
+            };
+            (index, carried / f64::from(l.ticks_to_decay))
+        })
+        .filter(|(_, priority)| *priority > 0.0)
+        .collect();
+    order.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    });
+
+    let mut assigned = vec![false; haulers.len()];
+    let mut result = Vec::new();
+
+    for (loot_index, _) in order {
+        let target = &loot[loot_index];
+        let closest = haulers
+            .iter()
+            .enumerate()
+            .filter(|(index, hauler)| !assigned[*index] && hauler.free_capacity > 0)
+            .map(|(index, hauler)| (index, hauler.pos.get_range_to(&target.pos)))
+            .filter(|(_, range)| *range <= target.ticks_to_decay)
+            .min_by_key(|(index, range)| (*range, *index));
+
+        if let Some((hauler_index, _)) = closest {
+            assigned[hauler_index] = true;
+            result.push((hauler_index, loot_index));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{plan, plan_with_values, Hauler, Loot};
+    use crate::{
+        constants::ResourceType,
+        local::{Position, RoomName},
+    };
+
+    fn pos(x: u32, y: u32) -> Position {
+        Position::new(x, y, RoomName::new("W3N7").unwrap())
+    }
+
+    fn loot(x: u32, y: u32, contents: &[(ResourceType, u32)], ticks_to_decay: u32) -> Loot {
+        Loot {
+            pos: pos(x, y),
+            contents: contents.iter().cloned().collect::<HashMap<_, _>>(),
+            ticks_to_decay,
+        }
+    }
+
+    fn hauler(x: u32, y: u32, free_capacity: u32) -> Hauler {
+        Hauler {
+            pos: pos(x, y),
+            free_capacity,
+        }
+    }
+
+    #[test]
+    fn expiring_loot_first() {
+        let loot = [
+            // stable ruin with plenty of energy
+            loot(10, 10, &[(ResourceType::Energy, 1000)], 400),
+            // tombstone about to decay
+            loot(20, 10, &[(ResourceType::Energy, 200)], 15),
+        ];
+        let haulers = [hauler(15, 10, 500)];
+
+        assert_eq!(plan(&loot, &haulers), vec![(0, 1)]);
+
+        let haulers = [hauler(15, 10, 500), hauler(40, 40, 500)];
+        assert_eq!(plan(&loot, &haulers), vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn unreachable_loot_is_dropped() {
+        let loot = [
+            loot(45, 45, &[(ResourceType::Energy, 300)], 10),
+            loot(5, 5, &[(ResourceType::Energy, 50)], 1000),
+        ];
+        let haulers = [hauler(5, 6, 200), hauler(30, 30, 200)];
+
+        // neither hauler can reach the expiring loot within 10 ticks
+        assert_eq!(plan(&loot, &haulers), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn empty_and_full() {
+        let loot = [
+            loot(10, 10, &[], 50),
+            loot(12, 10, &[(ResourceType::Energy, 50)], 0),
+            loot(14, 10, &[(ResourceType::Energy, 50)], 50),
+        ];
+        let haulers = [hauler(10, 11, 0), hauler(14, 11, 100)];
+
+        assert_eq!(plan(&loot, &haulers), vec![(1, 2)]);
+        assert_eq!(plan(&[], &haulers), vec![]);
+        assert_eq!(plan(&loot, &[]), vec![]);
+    }
+
+    #[test]
+    fn custom_values() {
+        let loot = [
+            loot(10, 10, &[(ResourceType::Energy, 500)], 100),
+            loot(30, 10, &[(ResourceType::CatalyzedGhodiumAcid, 100)], 100),
+        ];
+        let haulers = [hauler(20, 10, 1000)];
+
+        assert_eq!(plan(&loot, &haulers), vec![(0, 0)]);
+
+        let values = |ty| match ty {
+            ResourceType::Energy => 1.0,
+            _ => 20.0,
+        };
+        assert_eq!(plan_with_values(&loot, &haulers, values), vec![(0, 1)]);
+    }
+}
@@ -0,0 +1,318 @@
+//! Sharing CPU between the rooms of an empire.
+//!
+//! [`allocate_cpu`] splits a CPU budget between rooms by weight. For bots
+//! which can run either full or minimal logic in each room, a
+//! [`RoomScheduler`] tracks how much CPU each room's full logic uses and
+//! picks which rooms can run it each tick without exceeding the budget,
+//! rotating through the rooms which have to be skipped.
+//!
+//! The scheduler implements `Serialize` and `Deserialize`, so it can be kept
+//! in the heap between ticks and persisted with a
+//! [`MemoryBackend`][crate::memory::backend::MemoryBackend] to survive global
+//! resets.
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{game::cpu, local::RoomName};
+
+/// Splits `budget` between rooms in proportion to their weights.
+///
+/// Rooms with a weight of zero or less are allocated nothing.
+pub fn allocate_cpu(rooms: &[(RoomName, f64)], budget: f64) -> HashMap<RoomName, f64> {
+    let total_weight: f64 = rooms.iter().map(|&(_, weight)| weight.max(0.0)).sum();
+    rooms
+        .iter()
+        .map(|&(room, weight)| {
+            let share = if total_weight > 0.0 {
+                budget * weight.max(0.0) / total_weight
+            } else {
+                0.0
+            };
+            (room, share)
+        })
+        .collect()
+}
+
+/// Rooms chosen by [`RoomScheduler::schedule`] to run full or minimal logic
+/// this tick.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schedule {
+    /// Rooms which should run their full logic, highest priority first.
+    pub full: Vec<RoomName>,
+    /// Rooms which should only run minimal logic, such as defense.
+    pub minimal: Vec<RoomName>,
+}
+
+/// Usage tracked for a single room.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct RoomUsage {
+    /// Moving average of the CPU used by the room's full logic, `None` until
+    /// the first measurement.
+    average: Option<f64>,
+    /// Number of consecutive ticks the room's full logic was skipped.
+    skipped: u32,
+}
+
+/// Chooses which rooms run their full logic each tick, based on the CPU they
+/// used on previous ticks.
+///
+/// Each tick, call [`RoomScheduler::schedule`] with the rooms and their
+/// weights, then measure the CPU used by the full logic of each scheduled
+/// room with [`RoomScheduler::measure`] or [`RoomScheduler::record`].
+///
+/// Rooms are picked by weight, multiplied by one plus the number of
+/// consecutive ticks they were skipped, so skipped rooms gain priority until
+/// they get to run. Rooms which haven't been measured yet are assumed to use
+/// the average of all measured rooms.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoomScheduler {
+    smoothing: f64,
+    rooms: HashMap<RoomName, RoomUsage>,
+}
+
+impl Default for RoomScheduler {
+    fn default() -> Self {
+        RoomScheduler {
+            smoothing: 0.2,
+            rooms: HashMap::new(),
+        }
+    }
+}
+
+impl RoomScheduler {
+    /// Creates a scheduler with no recorded usage, weighing each new
+    /// measurement by 0.2 in the moving average.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the weight of each new measurement in the exponential moving
+    /// average, between 0 and 1 - default 0.2. Higher values follow changes
+    /// in usage faster.
+    #[inline]
+    pub fn smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Records the CPU used by a room's full logic this tick.
+    pub fn record(&mut self, room: RoomName, used: f64) {
+        let usage = self.rooms.entry(room).or_default();
+        usage.average = Some(match usage.average {
+            Some(average) => average + self.smoothing * (used - average),
+            None => used,
+        });
+    }
+
+    /// Runs `f`, recording the CPU it uses as the usage of `room`'s full
+    /// logic.
+    pub fn measure<F, R>(&mut self, room: RoomName, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let start = cpu::get_used();
+        let result = f();
+        self.record(room, cpu::get_used() - start);
+        result
+    }
+
+    /// The expected CPU usage of a room's full logic, or `None` if it hasn't
+    /// been measured.
+    pub fn average_usage(&self, room: RoomName) -> Option<f64> {
+        self.rooms.get(&room).and_then(|usage| usage.average)
+    }
+
+    /// Number of consecutive ticks a room's full logic has been skipped.
+    pub fn skipped_ticks(&self, room: RoomName) -> u32 {
+        self.rooms.get(&room).map_or(0, |usage| usage.skipped)
+    }
+
+    /// Chooses the rooms running full logic this tick, keeping their expected
+    /// usage within `budget`.
+    ///
+    /// Rooms are considered by priority, and each room whose expected usage
+    /// still fits in the remaining budget is scheduled. A room expected to
+    /// use more than the whole budget never runs its full logic.
+    pub fn schedule(&mut self, rooms: &[(RoomName, f64)], budget: f64) -> Schedule {
+        let measured: Vec<f64> = self.rooms.values().filter_map(|u| u.average).collect();
+        let default_usage = if measured.is_empty() {
+            0.0
+        } else {
+            measured.iter().sum::<f64>() / measured.len() as f64
+        };
+
+        let mut candidates: Vec<(RoomName, f64, f64)> = rooms
+            .iter()
+            .map(|&(room, weight)| {
+                let usage = self.rooms.get(&room).copied().unwrap_or_default();
+                let priority = weight.max(0.0) * f64::from(usage.skipped + 1);
+                (room, priority, usage.average.unwrap_or(default_usage))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+
+        let mut remaining = budget;
+        let mut schedule = Schedule::default();
+        for (room, _, expected) in candidates {
+            let usage = self.rooms.entry(room).or_default();
+            if expected <= remaining {
+                remaining -= expected;
+                usage.skipped = 0;
+                schedule.full.push(room);
+            } else {
+                usage.skipped += 1;
+                schedule.minimal.push(room);
+            }
+        }
+        schedule
+    }
+
+    /// Chooses the rooms running full logic this tick, keeping the total CPU
+    /// used within `Game.cpu.limit`.
+    ///
+    /// The budget is the CPU limit minus the CPU already used this tick and
+    /// `reserved`, which should cover the minimal logic of every room and any
+    /// work done after the rooms run.
+    pub fn schedule_for_tick(&mut self, rooms: &[(RoomName, f64)], reserved: f64) -> Schedule {
+        let budget = f64::from(cpu::limit()) - cpu::get_used() - reserved;
+        self.schedule(rooms, budget)
+    }
+
+    /// Forgets the usage of rooms not in `rooms`, such as rooms which were
+    /// lost.
+    pub fn retain(&mut self, rooms: &[RoomName]) {
+        self.rooms.retain(|room, _| rooms.contains(room));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{allocate_cpu, RoomScheduler};
+    use crate::local::RoomName;
+
+    fn room(name: &str) -> RoomName {
+        RoomName::new(name).unwrap()
+    }
+
+    #[test]
+    fn weighted_allocation() {
+        let rooms = [
+            (room("W1N1"), 3.0),
+            (room("W2N1"), 1.0),
+            (room("W3N1"), 0.0),
+        ];
+        let allocation = allocate_cpu(&rooms, 20.0);
+        let expected: HashMap<RoomName, f64> = vec![
+            (room("W1N1"), 15.0),
+            (room("W2N1"), 5.0),
+            (room("W3N1"), 0.0),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(allocation, expected);
+
+        let unweighted = allocate_cpu(&[(room("W1N1"), 0.0)], 20.0);
+        assert_eq!(unweighted[&room("W1N1")], 0.0);
+    }
+
+    #[test]
+    fn moving_average() {
+        let mut scheduler = RoomScheduler::new().smoothing(0.5);
+        let w1n1 = room("W1N1");
+        assert_eq!(scheduler.average_usage(w1n1), None);
+
+        scheduler.record(w1n1, 8.0);
+        assert_eq!(scheduler.average_usage(w1n1), Some(8.0));
+        scheduler.record(w1n1, 4.0);
+        assert_eq!(scheduler.average_usage(w1n1), Some(6.0));
+        scheduler.record(w1n1, 10.0);
+        assert_eq!(scheduler.average_usage(w1n1), Some(8.0));
+    }
+
+    #[test]
+    fn fair_skipping() {
+        let rooms: Vec<(RoomName, f64)> = ["W1N1", "W2N1", "W3N1", "W4N1"]
+            .iter()
+            .map(|name| (room(name), 1.0))
+            .collect();
+        let mut scheduler = RoomScheduler::new();
+        let mut runs: HashMap<RoomName, u32> = HashMap::new();
+
+        for tick in 0..20 {
+            let schedule = scheduler.schedule(&rooms, 25.0);
+            // only two rooms fit in the budget once usage is known
+            if tick > 0 {
+                assert_eq!(schedule.full.len(), 2);
+            }
+            for &room in &schedule.full {
+                *runs.entry(room).or_insert(0) += 1;
+                scheduler.record(room, 10.0);
+            }
+            for &(room, _) in &rooms {
+                assert!(scheduler.skipped_ticks(room) <= 2);
+            }
+        }
+
+        // all four rooms run on the first tick, then alternate in pairs
+        for &(room, _) in &rooms {
+            assert!(runs[&room] == 10 || runs[&room] == 11, "{:?}", runs);
+        }
+    }
+
+    #[test]
+    fn weighted_skipping() {
+        let main = room("E1S1");
+        let remotes = [room("E2S1"), room("E3S1"), room("E4S1")];
+        let mut rooms = vec![(main, 3.0)];
+        rooms.extend(remotes.iter().map(|&room| (room, 1.0)));
+
+        let mut scheduler = RoomScheduler::new();
+        scheduler.record(main, 12.0);
+        for &remote in &remotes {
+            scheduler.record(remote, 5.0);
+        }
+
+        let mut remote_runs = Vec::new();
+        for _ in 0..9 {
+            let schedule = scheduler.schedule(&rooms, 18.0);
+            let used: f64 = schedule
+                .full
+                .iter()
+                .map(|&room| scheduler.average_usage(room).unwrap())
+                .sum();
+            assert!(used <= 18.0);
+            assert_eq!(schedule.full[0], main);
+            assert_eq!(schedule.full.len(), 2);
+            assert_eq!(schedule.minimal.len(), 2);
+            remote_runs.push(schedule.full[1]);
+        }
+
+        // the remotes take turns
+        for window in remote_runs.chunks(3) {
+            let mut window = window.to_vec();
+            window.sort_by_key(|room| room.to_string());
+            assert_eq!(window, remotes.to_vec());
+        }
+    }
+
+    #[test]
+    fn serialized_state() {
+        let mut scheduler = RoomScheduler::new();
+        scheduler.record(room("W1N1"), 2.5);
+        scheduler.schedule(&[(room("W1N1"), 1.0), (room("W2N1"), 1.0)], 3.0);
+
+        let json = serde_json::to_string(&scheduler).unwrap();
+        let restored: RoomScheduler = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, scheduler);
+        assert_eq!(restored.skipped_ticks(room("W2N1")), 1);
+
+        let mut retained = restored;
+        retained.retain(&[room("W2N1")]);
+        assert_eq!(retained.average_usage(room("W1N1")), None);
+        assert_eq!(retained.skipped_ticks(room("W2N1")), 1);
+    }
+}
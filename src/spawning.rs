@@ -0,0 +1,345 @@
+//! Spawning replacements for creeps before they die.
+//!
+//! Creeps holding a post, such as miners or upgraders, leave it empty between
+//! their death and the arrival of their replacement unless the replacement
+//! is spawned early enough. A [`ReplacementScheduler`] keeps track of such
+//! creeps and requests each replacement once, as soon as it has to start
+//! spawning to reach the post when the old creep dies.
+//!
+//! The scheduler implements `Serialize` and `Deserialize`, so its pending
+//! requests survive global resets when persisted with a
+//! [`MemoryBackend`][crate::memory::backend::MemoryBackend].
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    body::body_spawn_time, constants::Part, game, local::RoomName, objects::SharedCreepProperties,
+};
+
+/// How to replace a creep, as registered with
+/// [`ReplacementScheduler::register`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Replacement {
+    /// The role of the creep, copied to spawn requests.
+    pub role: String,
+    /// The body to spawn the replacement with.
+    pub body: Vec<Part>,
+    /// The room the replacement is spawned in.
+    pub spawn_room: RoomName,
+    /// Ticks for the replacement to travel from its spawn to the post.
+    pub travel_time: u32,
+}
+
+impl Replacement {
+    /// Ticks between the start of the replacement's spawning and its arrival
+    /// at the post.
+    pub fn lead_time(&self) -> u32 {
+        body_spawn_time(&self.body) + self.travel_time
+    }
+}
+
+/// A replacement which should start spawning, returned by
+/// [`ReplacementScheduler::check`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpawnRequest {
+    /// The name of the creep being replaced.
+    pub replaces: String,
+    pub role: String,
+    pub body: Vec<Part>,
+    pub spawn_room: RoomName,
+    /// The game tick at which the creep being replaced dies, or the current
+    /// tick if it's already gone.
+    pub needed_by: u32,
+}
+
+/// A registered creep along with the state of its replacement.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Registration {
+    replacement: Replacement,
+    /// Whether a spawn request has been emitted for the replacement.
+    requested: bool,
+    /// Whether the creep has been found alive by a check.
+    #[serde(default)]
+    seen: bool,
+    /// The tick of the first check which didn't find the creep, while it
+    /// hasn't been seen yet.
+    missing_since: Option<u32>,
+}
+
+impl Registration {
+    /// Whether a creep not found at game tick `time` is gone, rather than
+    /// not having appeared yet after being registered as it started
+    /// spawning.
+    ///
+    /// Creeps never seen are given the time to spawn their body, plus a tick
+    /// for the creep to show up in the game.
+    fn is_gone(&mut self, time: u32) -> bool {
+        if self.seen {
+            return true;
+        }
+        let since = *self.missing_since.get_or_insert(time);
+        time - since > body_spawn_time(&self.replacement.body)
+    }
+}
+
+/// Requests replacements for dying creeps, once each.
+///
+/// Each tick, call [`ReplacementScheduler::check`] (or
+/// [`ReplacementScheduler::check_game`]) and spawn the returned requests.
+/// Creeps are forgotten once they're gone, so replacements have to be
+/// registered themselves once spawned to be replaced in turn.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplacementScheduler {
+    creeps: HashMap<String, Registration>,
+}
+
+impl ReplacementScheduler {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a creep to be replaced before it dies.
+    ///
+    /// Registering an already registered creep updates how it's replaced,
+    /// but doesn't request its replacement again if that was already done,
+    /// so creeps can be registered every tick.
+    pub fn register(&mut self, name: &str, replacement: Replacement) {
+        match self.creeps.get_mut(name) {
+            Some(registration) => registration.replacement = replacement,
+            None => {
+                self.creeps.insert(
+                    name.to_owned(),
+                    Registration {
+                        replacement,
+                        requested: false,
+                        seen: false,
+                        missing_since: None,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Stops replacing a creep.
+    pub fn unregister(&mut self, name: &str) {
+        self.creeps.remove(name);
+    }
+
+    /// Whether a creep is registered.
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.creeps.contains_key(name)
+    }
+
+    /// Whether the replacement of a creep has been requested.
+    pub fn is_requested(&self, name: &str) -> bool {
+        matches!(self.creeps.get(name), Some(registration) if registration.requested)
+    }
+
+    /// Marks a creep's replacement as not requested, such as when its spawn
+    /// request couldn't be carried out, so the next check requests it again.
+    pub fn cancel_request(&mut self, name: &str) {
+        if let Some(registration) = self.creeps.get_mut(name) {
+            registration.requested = false;
+        }
+    }
+
+    /// Requests the replacements which have to start spawning at game tick
+    /// `time`, given the ticks to live of each registered creep.
+    ///
+    /// `ticks_to_live` returns `None` for creeps which are gone. A
+    /// replacement is requested once the creep's ticks to live are no more
+    /// than its [`Replacement::lead_time`], or right away if the creep is
+    /// gone without one having been requested. Gone creeps are then
+    /// forgotten.
+    ///
+    /// A creep which has never been found is only considered gone once the
+    /// time to spawn its body has passed, plus a tick, since the first check
+    /// which didn't find it. Creeps can then be registered as soon as they
+    /// start spawning, before they appear in the game.
+    ///
+    /// Requests are sorted by the tick they're needed by, then by name.
+    pub fn check<F>(&mut self, time: u32, ticks_to_live: F) -> Vec<SpawnRequest>
+    where
+        F: Fn(&str) -> Option<u32>,
+    {
+        let mut requests = Vec::new();
+        self.creeps.retain(|name, registration| {
+            let ttl = ticks_to_live(name);
+            let due = match ttl {
+                Some(ttl) => {
+                    registration.seen = true;
+                    registration.missing_since = None;
+                    ttl <= registration.replacement.lead_time()
+                }
+                None if registration.is_gone(time) => true,
+                None => return true,
+            };
+            if due && !registration.requested {
+                registration.requested = true;
+                let replacement = &registration.replacement;
+                requests.push(SpawnRequest {
+                    replaces: name.clone(),
+                    role: replacement.role.clone(),
+                    body: replacement.body.clone(),
+                    spawn_room: replacement.spawn_room,
+                    needed_by: time + ttl.unwrap_or(0),
+                });
+            }
+            ttl.is_some()
+        });
+        requests.sort_by(|a, b| {
+            a.needed_by
+                .cmp(&b.needed_by)
+                .then_with(|| a.replaces.cmp(&b.replaces))
+        });
+        requests
+    }
+
+    /// Calls [`ReplacementScheduler::check`] with the current tick and the
+    /// creeps in `Game.creeps`.
+    ///
+    /// Creeps which are still spawning aren't due for replacement.
+    pub fn check_game(&mut self) -> Vec<SpawnRequest> {
+        self.check(game::time().ticks(), |name| {
+            game::creeps::get(name).map(|creep| creep.ticks_to_live().unwrap_or(u32::MAX))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{Replacement, ReplacementScheduler};
+    use crate::{constants::Part::*, local::RoomName};
+
+    fn miner() -> Replacement {
+        Replacement {
+            role: "miner".to_owned(),
+            body: vec![Work, Work, Work, Work, Work, Move],
+            spawn_room: RoomName::new("W1N1").unwrap(),
+            travel_time: 22,
+        }
+    }
+
+    #[test]
+    fn replacement_timing() {
+        let mut scheduler = ReplacementScheduler::new();
+        // 18 ticks of spawning and 22 of travel
+        assert_eq!(miner().lead_time(), 40);
+
+        let mut ttl = HashMap::new();
+        ttl.insert("miner1".to_owned(), 45);
+        let mut requested_at = Vec::new();
+        for time in 1000..1045 {
+            // registering every tick doesn't reset the request
+            scheduler.register("miner1", miner());
+            for request in scheduler.check(time, |name| ttl.get(name).copied()) {
+                assert_eq!(request.replaces, "miner1");
+                assert_eq!(request.role, "miner");
+                assert_eq!(request.needed_by, 1045);
+                requested_at.push(time);
+            }
+            *ttl.get_mut("miner1").unwrap() -= 1;
+        }
+        assert_eq!(requested_at, vec![1005]);
+        assert!(scheduler.is_requested("miner1"));
+
+        // once the old miner dies, it's forgotten
+        ttl.clear();
+        assert!(scheduler
+            .check(1045, |name| ttl.get(name).copied())
+            .is_empty());
+        assert!(!scheduler.is_registered("miner1"));
+    }
+
+    #[test]
+    fn pending_requests() {
+        let mut scheduler = ReplacementScheduler::new();
+        scheduler.register("miner1", miner());
+        scheduler.register("miner2", miner());
+        scheduler.register("killed", miner());
+        let ttl = |name: &str| match name {
+            "miner1" => Some(100),
+            "miner2" => Some(100),
+            "killed" => Some(100),
+            _ => None,
+        };
+        assert!(scheduler.check(99, ttl).is_empty());
+        let ttl = |name: &str| match name {
+            "miner1" => Some(30),
+            "miner2" => Some(20),
+            _ => None,
+        };
+
+        let requests = scheduler.check(100, ttl);
+        let replaced: Vec<&str> = requests.iter().map(|r| r.replaces.as_str()).collect();
+        assert_eq!(replaced, vec!["killed", "miner2", "miner1"]);
+        assert_eq!(requests[0].needed_by, 100);
+
+        // the replacement of miner2 is spawned and registered while miner2 is
+        // still alive: only one request each, and none yet for the new miner
+        scheduler.register("miner3", miner());
+        let ttl = |name: &str| match name {
+            "miner1" => Some(29),
+            "miner2" => Some(19),
+            "miner3" => Some(1500),
+            _ => None,
+        };
+        assert!(scheduler.check(101, ttl).is_empty());
+
+        // a failed spawn is requested again
+        scheduler.cancel_request("miner1");
+        let requests = scheduler.check(102, ttl);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].replaces, "miner1");
+
+        let json = serde_json::to_string(&scheduler).unwrap();
+        let restored: ReplacementScheduler = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, scheduler);
+        assert!(restored.is_requested("miner2"));
+        assert!(!restored.is_requested("miner3"));
+    }
+
+    #[test]
+    fn spawning_replacement() {
+        let mut scheduler = ReplacementScheduler::new();
+        // registered as it starts spawning, before it's in the game
+        scheduler.register("miner1", miner());
+        let mut ttl = HashMap::new();
+
+        // the 6 parts take 18 ticks to spawn: the creep isn't considered gone
+        // until 19 ticks after it was first missed
+        for time in 1000..1019 {
+            assert!(scheduler
+                .check(time, |name| ttl.get(name).copied())
+                .is_empty());
+            assert!(scheduler.is_registered("miner1"));
+        }
+        ttl.insert("miner1".to_owned(), 1500);
+        assert!(scheduler
+            .check(1019, |name| ttl.get(name).copied())
+            .is_empty());
+
+        // once seen, missing creeps are gone right away
+        ttl.clear();
+        let requests = scheduler.check(1020, |name| ttl.get(name).copied());
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].needed_by, 1020);
+        assert!(!scheduler.is_registered("miner1"));
+
+        // a creep which never shows up is replaced after the grace period
+        scheduler.register("miner2", miner());
+        for time in 2000..2019 {
+            assert!(scheduler
+                .check(time, |name| ttl.get(name).copied())
+                .is_empty());
+        }
+        let requests = scheduler.check(2019, |name| ttl.get(name).copied());
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].replaces, "miner2");
+        assert!(!scheduler.is_registered("miner2"));
+    }
+}
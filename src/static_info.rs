@@ -0,0 +1,390 @@
+//! Caching the features of rooms which never change.
+//!
+//! The position of a room's controller, sources and mineral, and the rooms
+//! its exits lead to, are fixed for the lifetime of the room. A
+//! [`RoomStaticInfo`] holds them in a compact form, and a [`StaticInfoCache`]
+//! keeps one for every room seen so far, looked up in order:
+//!
+//! 1. in the wasm heap, where the cache lives between ticks;
+//! 2. in a [`MemoryBackend`], usually a
+//!    [`SegmentBackend`][crate::memory::backend::SegmentBackend], loaded the
+//!    first time a room isn't found in the heap, to survive global resets;
+//! 3. from the room itself, captured the first time it's visible.
+//!
+//! Entries are only removed when explicitly invalidated. [`get`],
+//! [`invalidate`] and [`flush`] use a cache kept in the heap, persisted to
+//! the backend set with [`set_backend`]:
+//!
+//! ```no_run
+//! use screeps::{memory::backend::SegmentBackend, static_info};
+//!
+//! // at startup, with segment 5 made active on a previous tick
+//! static_info::set_backend(SegmentBackend::new(5));
+//!
+//! if let Some(info) = static_info::get("W1N1".parse().unwrap()) {
+//!     println!("sources at {:?}", info.sources);
+//! }
+//!
+//! // at the end of the tick
+//! static_info::flush().unwrap();
+//! ```
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, HashMap},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{find, Direction, ResourceType},
+    game,
+    local::RoomName,
+    memory::backend::{self, HeapBackend, MemoryBackend},
+    objects::{HasPosition, Room},
+};
+
+/// A room's mineral, as recorded in [`RoomStaticInfo`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StaticMineral {
+    pub x: u8,
+    pub y: u8,
+    pub mineral_type: ResourceType,
+}
+
+/// The features of a room which never change.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomStaticInfo {
+    /// The position of the controller, for rooms which have one.
+    pub controller: Option<(u8, u8)>,
+    /// The positions of the sources.
+    pub sources: Vec<(u8, u8)>,
+    /// The mineral, for rooms which have one.
+    pub mineral: Option<StaticMineral>,
+    /// The rooms each exit leads to, sorted by direction.
+    pub exits: Vec<(Direction, RoomName)>,
+}
+
+fn xy<T: HasPosition + ?Sized>(object: &T) -> (u8, u8) {
+    let pos = object.pos();
+    (pos.x() as u8, pos.y() as u8)
+}
+
+impl RoomStaticInfo {
+    /// Captures the static features of a visible room.
+    pub fn capture(room: &Room) -> Self {
+        let mut exits: Vec<(Direction, RoomName)> =
+            game::map::describe_exits(room.name()).into_iter().collect();
+        exits.sort_by_key(|&(direction, _)| direction as u8);
+        RoomStaticInfo {
+            controller: room.controller().map(|controller| xy(&controller)),
+            sources: room.find(find::SOURCES).iter().map(xy).collect(),
+            mineral: room
+                .find(find::MINERALS)
+                .first()
+                .map(|mineral| StaticMineral {
+                    x: xy(mineral).0,
+                    y: xy(mineral).1,
+                    mineral_type: mineral.mineral_type(),
+                }),
+            exits,
+        }
+    }
+}
+
+/// A cache of [`RoomStaticInfo`], as described in the
+/// [module documentation][self].
+pub struct StaticInfoCache {
+    rooms: HashMap<RoomName, RoomStaticInfo>,
+    backend: Box<dyn MemoryBackend>,
+    /// Whether the backend has been read yet.
+    loaded: bool,
+    /// Whether the heap has changes not yet written to the backend.
+    dirty: bool,
+}
+
+impl Default for StaticInfoCache {
+    fn default() -> Self {
+        Self::new(HeapBackend::new())
+    }
+}
+
+impl StaticInfoCache {
+    /// Creates an empty cache persisted to `backend`.
+    pub fn new<B: MemoryBackend + 'static>(backend: B) -> Self {
+        StaticInfoCache {
+            rooms: HashMap::new(),
+            backend: Box::new(backend),
+            loaded: false,
+            dirty: false,
+        }
+    }
+
+    /// Gets the static features of a room, or `None` if they aren't cached
+    /// and the room isn't visible.
+    pub fn get(&mut self, room_name: RoomName) -> Option<&RoomStaticInfo> {
+        self.get_with(room_name, |room_name| {
+            game::rooms::get(room_name).map(|room| RoomStaticInfo::capture(&room))
+        })
+    }
+
+    /// Gets the static features of a room, calling `capture` for rooms found
+    /// neither in the heap nor in the backend.
+    pub fn get_with<F>(&mut self, room_name: RoomName, capture: F) -> Option<&RoomStaticInfo>
+    where
+        F: FnOnce(RoomName) -> Option<RoomStaticInfo>,
+    {
+        if !self.rooms.contains_key(&room_name) && !self.loaded {
+            self.load();
+        }
+        match self.rooms.entry(room_name) {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let info = capture(room_name)?;
+                self.dirty = true;
+                Some(entry.insert(info))
+            }
+        }
+    }
+
+    /// Forgets the static features of a room, so they're captured again the
+    /// next time the room is visible.
+    pub fn invalidate(&mut self, room_name: RoomName) {
+        if !self.loaded {
+            self.load();
+        }
+        if self.rooms.remove(&room_name).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    /// Writes the cache to its backend, if it changed since it was last
+    /// written.
+    ///
+    /// Nothing is written until the backend could be read, such as a segment
+    /// which isn't active yet, so the rooms stored there aren't overwritten.
+    pub fn flush(&mut self) -> Result<(), serde_json::Error> {
+        if !self.loaded {
+            self.load();
+        }
+        if self.dirty && self.loaded {
+            backend::store(&mut *self.backend, &self.rooms)?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Merges the rooms stored in the backend into the heap, keeping the
+    /// heap's entries for rooms found in both.
+    fn load(&mut self) {
+        if !self.backend.is_available() {
+            return;
+        }
+        self.loaded = true;
+        match backend::load::<HashMap<RoomName, RoomStaticInfo>, _>(&mut *self.backend) {
+            Ok(Some(stored)) => {
+                for (room_name, info) in stored {
+                    self.rooms.entry(room_name).or_insert(info);
+                }
+            }
+            Ok(None) => (),
+            Err(e) => {
+                log::warn!("discarding unreadable static room info: {}", e);
+                self.dirty = true;
+            }
+        }
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<StaticInfoCache> = RefCell::new(StaticInfoCache::default());
+}
+
+/// Replaces the cache used by [`get`] with an empty one persisted to
+/// `backend`.
+///
+/// Until this is called, the cache is only kept in the heap.
+pub fn set_backend<B: MemoryBackend + 'static>(backend: B) {
+    CACHE.with(|cache| *cache.borrow_mut() = StaticInfoCache::new(backend));
+}
+
+/// Gets the static features of a room, or `None` if they aren't cached and
+/// the room isn't visible.
+pub fn get(room_name: RoomName) -> Option<RoomStaticInfo> {
+    CACHE.with(|cache| cache.borrow_mut().get(room_name).cloned())
+}
+
+/// Forgets the static features of a room, so they're captured again the next
+/// time the room is visible.
+pub fn invalidate(room_name: RoomName) {
+    CACHE.with(|cache| cache.borrow_mut().invalidate(room_name));
+}
+
+/// Writes the cache used by [`get`] to its backend, if it changed.
+pub fn flush() -> Result<(), serde_json::Error> {
+    CACHE.with(|cache| cache.borrow_mut().flush())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{RoomStaticInfo, StaticInfoCache, StaticMineral};
+    use crate::{
+        constants::{Direction, ResourceType},
+        local::RoomName,
+        memory::backend::MemoryBackend,
+    };
+
+    /// Backend shared with the test, counting reads.
+    #[derive(Clone, Default)]
+    struct SharedBackend {
+        data: Rc<RefCell<Option<String>>>,
+        reads: Rc<RefCell<u32>>,
+        /// Whether reads fail, like an inactive segment.
+        unavailable: Rc<RefCell<bool>>,
+    }
+
+    impl MemoryBackend for SharedBackend {
+        fn read(&mut self) -> Option<String> {
+            *self.reads.borrow_mut() += 1;
+            if *self.unavailable.borrow() {
+                None
+            } else {
+                self.data.borrow().clone()
+            }
+        }
+
+        fn write(&mut self, data: &str) {
+            *self.data.borrow_mut() = Some(data.to_owned());
+        }
+
+        fn is_available(&mut self) -> bool {
+            !*self.unavailable.borrow()
+        }
+    }
+
+    fn room(name: &str) -> RoomName {
+        RoomName::new(name).unwrap()
+    }
+
+    fn info(source_x: u8) -> RoomStaticInfo {
+        RoomStaticInfo {
+            controller: Some((20, 20)),
+            sources: vec![(source_x, 10)],
+            mineral: Some(StaticMineral {
+                x: 30,
+                y: 30,
+                mineral_type: ResourceType::Hydrogen,
+            }),
+            exits: vec![(Direction::Top, room("W1N2"))],
+        }
+    }
+
+    #[test]
+    fn lookup_order() {
+        let backend = SharedBackend::default();
+        let mut cache = StaticInfoCache::new(backend.clone());
+
+        // captured live the first time, then served from the heap
+        assert_eq!(
+            cache.get_with(room("W1N1"), |_| Some(info(5))),
+            Some(&info(5))
+        );
+        assert_eq!(*backend.reads.borrow(), 1);
+        let cached = cache.get_with(room("W1N1"), |_| panic!("captured again"));
+        assert_eq!(cached, Some(&info(5)));
+        assert_eq!(*backend.reads.borrow(), 1);
+
+        // rooms which aren't visible aren't cached
+        assert_eq!(cache.get_with(room("W2N1"), |_| None), None);
+        assert_eq!(*backend.reads.borrow(), 1);
+
+        cache.flush().unwrap();
+        assert!(backend.data.borrow().is_some());
+
+        // after a global reset, the backend is read once and preferred over
+        // capturing the room again
+        let mut cache = StaticInfoCache::new(backend.clone());
+        let restored = cache.get_with(room("W1N1"), |_| panic!("captured again"));
+        assert_eq!(restored, Some(&info(5)));
+        assert_eq!(
+            cache.get_with(room("W3N1"), |_| Some(info(7))),
+            Some(&info(7))
+        );
+        assert_eq!(*backend.reads.borrow(), 2);
+    }
+
+    #[test]
+    fn invalidation() {
+        let backend = SharedBackend::default();
+        let mut cache = StaticInfoCache::new(backend.clone());
+        cache.get_with(room("W1N1"), |_| Some(info(5)));
+        cache.flush().unwrap();
+        let written = backend.data.borrow().clone();
+
+        // nothing changed, nothing written
+        cache.get_with(room("W1N1"), |_| None);
+        *backend.data.borrow_mut() = None;
+        cache.flush().unwrap();
+        assert_eq!(*backend.data.borrow(), None);
+        *backend.data.borrow_mut() = written;
+
+        let mut cache = StaticInfoCache::new(backend.clone());
+        cache.invalidate(room("W1N1"));
+        assert_eq!(
+            cache.get_with(room("W1N1"), |_| Some(info(6))),
+            Some(&info(6))
+        );
+        cache.flush().unwrap();
+
+        let mut cache = StaticInfoCache::new(backend);
+        let stored = cache.get_with(room("W1N1"), |_| panic!("captured again"));
+        assert_eq!(stored, Some(&info(6)));
+    }
+
+    #[test]
+    fn unavailable_backend() {
+        let backend = SharedBackend::default();
+        let mut cache = StaticInfoCache::new(backend.clone());
+        cache.get_with(room("W1N1"), |_| Some(info(5)));
+        cache.flush().unwrap();
+        let stored = backend.data.borrow().clone();
+
+        // rooms captured while the backend can't be read aren't written over
+        // the ones it stores
+        *backend.unavailable.borrow_mut() = true;
+        let mut cache = StaticInfoCache::new(backend.clone());
+        assert_eq!(
+            cache.get_with(room("W2N1"), |_| Some(info(7))),
+            Some(&info(7))
+        );
+        cache.flush().unwrap();
+        assert_eq!(*backend.data.borrow(), stored);
+
+        // and are written along with them once it can
+        *backend.unavailable.borrow_mut() = false;
+        let restored = cache.get_with(room("W1N1"), |_| panic!("captured again"));
+        assert_eq!(restored, Some(&info(5)));
+        cache.flush().unwrap();
+        let mut cache = StaticInfoCache::new(backend);
+        assert_eq!(
+            cache.get_with(room("W2N1"), |_| panic!("captured again")),
+            Some(&info(7))
+        );
+    }
+
+    #[test]
+    fn corrupted_backend() {
+        let backend = SharedBackend::default();
+        *backend.data.borrow_mut() = Some("{\"W1N1\":".to_owned());
+        let mut cache = StaticInfoCache::new(backend.clone());
+
+        assert_eq!(
+            cache.get_with(room("W1N1"), |_| Some(info(5))),
+            Some(&info(5))
+        );
+        cache.flush().unwrap();
+        let data = backend.data.borrow().clone().unwrap();
+        assert!(data.starts_with("{\"W1N1\":{"));
+    }
+}
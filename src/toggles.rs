@@ -0,0 +1,543 @@
+//! Per-room switches for turning parts of a bot on and off from the console.
+//!
+//! A [`Registry`] lists the toggles a bot understands, each with a default
+//! value. Overrides for individual rooms are kept in memory, under
+//! [`MEMORY_KEY`], so they can be flipped from the game's console without
+//! redeploying, using the commands generated by [`console_set`] and
+//! [`console_reset`]:
+//!
+//! ```no_run
+//! use screeps::{toggles, RoomName};
+//!
+//! toggles::install(
+//!     toggles::Registry::new()
+//!         .bool("remotes", true, "mine remote rooms")
+//!         .number("wall_target", 1e6, "hits to repair walls to"),
+//! );
+//!
+//! // each tick
+//! for change in toggles::refresh() {
+//!     log::info!("{}", change);
+//! }
+//! let room = RoomName::new("W5N8").unwrap();
+//! if toggles::get(room, "remotes") {
+//!     // run remote mining
+//! }
+//!
+//! // prints `Memory.__toggles = Memory.__toggles || {}; ...`
+//! log::info!("{}", toggles::console_set(room, "remotes", false.into()));
+//! ```
+//!
+//! [`refresh`] reads the overrides from memory and reports the toggles whose
+//! value changed since the last refresh, so subsystems can react to them
+//! flipping, for instance by releasing the creeps they no longer need.
+use std::{cell::RefCell, collections::BTreeMap, fmt};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{local::RoomName, memory};
+
+/// Key of the top level memory object under which toggle overrides are
+/// stored, as an object of room names to objects of toggle names to values.
+pub const MEMORY_KEY: &str = "__toggles";
+
+/// The value of a toggle.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToggleValue {
+    Bool(bool),
+    Number(f64),
+}
+
+impl ToggleValue {
+    /// Whether `other` is of the same kind as this value, and so can
+    /// override it.
+    fn same_kind(self, other: ToggleValue) -> bool {
+        matches!(
+            (self, other),
+            (ToggleValue::Bool(_), ToggleValue::Bool(_))
+                | (ToggleValue::Number(_), ToggleValue::Number(_))
+        )
+    }
+}
+
+impl From<bool> for ToggleValue {
+    fn from(value: bool) -> Self {
+        ToggleValue::Bool(value)
+    }
+}
+
+impl From<f64> for ToggleValue {
+    fn from(value: f64) -> Self {
+        ToggleValue::Number(value)
+    }
+}
+
+impl fmt::Display for ToggleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToggleValue::Bool(value) => write!(f, "{}", value),
+            ToggleValue::Number(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// A toggle known to a [`Registry`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Toggle {
+    pub name: &'static str,
+    /// The value used in rooms without an override, which also decides
+    /// whether the toggle is a boolean or a number.
+    pub default: ToggleValue,
+    pub description: &'static str,
+}
+
+/// The toggles a bot understands.
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    toggles: Vec<Toggle>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// Adds a boolean toggle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a toggle named `name` was already added.
+    pub fn bool(self, name: &'static str, default: bool, description: &'static str) -> Self {
+        self.add(name, default.into(), description)
+    }
+
+    /// Adds a numeric toggle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a toggle named `name` was already added.
+    pub fn number(self, name: &'static str, default: f64, description: &'static str) -> Self {
+        self.add(name, default.into(), description)
+    }
+
+    fn add(mut self, name: &'static str, default: ToggleValue, description: &'static str) -> Self {
+        assert!(self.find(name).is_none(), "toggle `{}` added twice", name);
+        self.toggles.push(Toggle {
+            name,
+            default,
+            description,
+        });
+        self
+    }
+
+    /// The toggles added, in the order they were added.
+    pub fn toggles(&self) -> &[Toggle] {
+        &self.toggles
+    }
+
+    /// Finds the toggle named `name`.
+    pub fn find(&self, name: &str) -> Option<&Toggle> {
+        self.toggles.iter().find(|toggle| toggle.name == name)
+    }
+}
+
+/// A toggle whose value in a room changed in a [`Toggles::update`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToggleChange {
+    pub room: RoomName,
+    pub name: &'static str,
+    pub old: ToggleValue,
+    pub new: ToggleValue,
+}
+
+impl fmt::Display for ToggleChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "toggle `{}` in {} changed from {} to {}",
+            self.name, self.room, self.old, self.new
+        )
+    }
+}
+
+type Overrides = BTreeMap<RoomName, BTreeMap<&'static str, ToggleValue>>;
+
+/// The values of the toggles of a [`Registry`] in every room, with the
+/// overrides last read from memory.
+///
+/// The functions of this module use a thread local instance set up with
+/// [`install`]; this type can be used directly to keep toggles elsewhere.
+#[derive(Clone, Debug, Default)]
+pub struct Toggles {
+    registry: Registry,
+    overrides: Overrides,
+    changes: Vec<ToggleChange>,
+}
+
+impl Toggles {
+    /// Creates toggles with their default values in every room.
+    pub fn new(registry: Registry) -> Self {
+        Toggles {
+            registry,
+            overrides: Overrides::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// The value of the toggle named `name` in `room`, or `None` if no such
+    /// toggle is registered.
+    pub fn value(&self, room: RoomName, name: &str) -> Option<ToggleValue> {
+        let toggle = self.registry.find(name)?;
+        let value = self
+            .overrides
+            .get(&room)
+            .and_then(|overrides| overrides.get(toggle.name));
+        Some(value.copied().unwrap_or(toggle.default))
+    }
+
+    /// Whether the boolean toggle named `name` is on in `room`.
+    ///
+    /// Returns `false`, logging a warning, if `name` isn't a registered
+    /// boolean toggle.
+    pub fn get(&self, room: RoomName, name: &str) -> bool {
+        match self.value(room, name) {
+            Some(ToggleValue::Bool(value)) => value,
+            _ => {
+                warn!("toggle `{}` isn't a registered boolean toggle", name);
+                false
+            }
+        }
+    }
+
+    /// The value of the numeric toggle named `name` in `room`.
+    ///
+    /// Returns `0.0`, logging a warning, if `name` isn't a registered numeric
+    /// toggle.
+    pub fn number(&self, room: RoomName, name: &str) -> f64 {
+        match self.value(room, name) {
+            Some(ToggleValue::Number(value)) => value,
+            _ => {
+                warn!("toggle `{}` isn't a registered numeric toggle", name);
+                0.0
+            }
+        }
+    }
+
+    /// Replaces the overrides with those in `memory`, the contents of
+    /// `Memory[MEMORY_KEY]`, and returns the toggles whose value changed.
+    ///
+    /// Overrides for unknown rooms or toggles, or of the wrong kind of value,
+    /// are ignored with a warning. As toggles start out with their default
+    /// values, the first update reports every override which differs from the
+    /// default.
+    pub fn update(&mut self, memory: &Value) -> &[ToggleChange] {
+        let overrides = self.parse_overrides(memory);
+
+        let mut changes = Vec::new();
+        let rooms = self.overrides.keys().chain(overrides.keys());
+        let mut rooms: Vec<RoomName> = rooms.copied().collect();
+        rooms.sort();
+        rooms.dedup();
+        for room in rooms {
+            for toggle in &self.registry.toggles {
+                let value_in = |overrides: &Overrides| {
+                    overrides
+                        .get(&room)
+                        .and_then(|overrides| overrides.get(toggle.name))
+                        .copied()
+                        .unwrap_or(toggle.default)
+                };
+                let (old, new) = (value_in(&self.overrides), value_in(&overrides));
+                if old != new {
+                    changes.push(ToggleChange {
+                        room,
+                        name: toggle.name,
+                        old,
+                        new,
+                    });
+                }
+            }
+        }
+
+        self.overrides = overrides;
+        self.changes = changes;
+        &self.changes
+    }
+
+    fn parse_overrides(&self, memory: &Value) -> Overrides {
+        let mut overrides = Overrides::new();
+        let rooms = match memory {
+            Value::Object(rooms) => rooms,
+            Value::Null => return overrides,
+            other => {
+                warn!("expected toggle overrides object, found {}", other);
+                return overrides;
+            }
+        };
+
+        for (room, values) in rooms {
+            let room = match RoomName::new(room) {
+                Ok(room) => room,
+                Err(e) => {
+                    warn!("ignoring toggle overrides: {}", e);
+                    continue;
+                }
+            };
+            let values = match values {
+                Value::Object(values) => values,
+                other => {
+                    warn!("expected toggle overrides for {}, found {}", room, other);
+                    continue;
+                }
+            };
+            for (name, value) in values {
+                let toggle = match self.registry.find(name) {
+                    Some(toggle) => toggle,
+                    None => {
+                        warn!("ignoring override of unknown toggle `{}` in {}", name, room);
+                        continue;
+                    }
+                };
+                match ToggleValue::deserialize(value) {
+                    Ok(value) if toggle.default.same_kind(value) => {
+                        overrides
+                            .entry(room)
+                            .or_default()
+                            .insert(toggle.name, value);
+                    }
+                    _ => warn!(
+                        "ignoring override of toggle `{}` in {}: \
+                         expected a value like {}, found {}",
+                        name, room, toggle.default, value
+                    ),
+                }
+            }
+        }
+        overrides
+    }
+
+    /// The toggles whose value changed in the last [`Toggles::update`].
+    pub fn changes(&self) -> &[ToggleChange] {
+        &self.changes
+    }
+
+    /// Whether the toggle named `name` changed in `room` in the last
+    /// [`Toggles::update`].
+    pub fn changed(&self, room: RoomName, name: &str) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.room == room && change.name == name)
+    }
+}
+
+thread_local! {
+    static TOGGLES: RefCell<Toggles> = RefCell::new(Toggles::default());
+}
+
+/// Sets up the toggles of `registry`, with their default values until the
+/// next [`refresh`].
+pub fn install(registry: Registry) {
+    TOGGLES.with(|toggles| *toggles.borrow_mut() = Toggles::new(registry));
+}
+
+/// Reads the overrides from memory, returning the toggles whose value changed
+/// since the last refresh.
+///
+/// This should be called once per tick, before reading any toggles.
+pub fn refresh() -> Vec<ToggleChange> {
+    let root = memory::root();
+    let data: Option<String> = js_unwrap! {{
+        const overrides = @{root.as_ref()}[@{MEMORY_KEY}];
+        return overrides === undefined ? null : JSON.stringify(overrides);
+    }};
+    let memory = match data.map(|data| serde_json::from_str(&data)) {
+        Some(Ok(memory)) => memory,
+        Some(Err(e)) => {
+            warn!("couldn't parse toggle overrides: {}", e);
+            Value::Null
+        }
+        None => Value::Null,
+    };
+    TOGGLES.with(|toggles| toggles.borrow_mut().update(&memory).to_vec())
+}
+
+/// Whether the boolean toggle named `name` is on in `room`.
+///
+/// See [`Toggles::get`].
+pub fn get(room: RoomName, name: &str) -> bool {
+    TOGGLES.with(|toggles| toggles.borrow().get(room, name))
+}
+
+/// The value of the numeric toggle named `name` in `room`.
+///
+/// See [`Toggles::number`].
+pub fn number(room: RoomName, name: &str) -> f64 {
+    TOGGLES.with(|toggles| toggles.borrow().number(room, name))
+}
+
+/// Whether the toggle named `name` changed in `room` in the last [`refresh`].
+pub fn changed(room: RoomName, name: &str) -> bool {
+    TOGGLES.with(|toggles| toggles.borrow().changed(room, name))
+}
+
+/// A JavaScript statement to run in the game's console to override the
+/// toggle named `name` in `room` with `value`.
+///
+/// The statement edits the `Memory` global, so it has no effect when memory
+/// is stored in a backend selected with [`memory::set_backend`].
+pub fn console_set(room: RoomName, name: &str, value: ToggleValue) -> String {
+    let value = serde_json::to_string(&value).expect("toggle values serialize to JSON");
+    format!(
+        "Memory.{key} = Memory.{key} || {{}}; \
+         (Memory.{key}[{room}] = Memory.{key}[{room}] || {{}})[{name}] = {value};",
+        key = MEMORY_KEY,
+        room = js_string(&room.to_string()),
+        name = js_string(name),
+        value = value,
+    )
+}
+
+/// A JavaScript statement to run in the game's console to remove the override
+/// of the toggle named `name` in `room`, returning it to its default value.
+pub fn console_reset(room: RoomName, name: &str) -> String {
+    format!(
+        "if (Memory.{key} && Memory.{key}[{room}]) delete Memory.{key}[{room}][{name}];",
+        key = MEMORY_KEY,
+        room = js_string(&room.to_string()),
+        name = js_string(name),
+    )
+}
+
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).expect("strings serialize to JSON")
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{console_reset, console_set, Registry, ToggleChange, ToggleValue, Toggles};
+    use crate::local::RoomName;
+
+    fn toggles() -> Toggles {
+        Toggles::new(
+            Registry::new()
+                .bool("remotes", true, "mine remote rooms")
+                .bool("expand", false, "claim new rooms")
+                .number("wall_target", 1e6, "hits to repair walls to"),
+        )
+    }
+
+    fn room(name: &str) -> RoomName {
+        RoomName::new(name).unwrap()
+    }
+
+    #[test]
+    fn defaults() {
+        let mut toggles = toggles();
+        assert!(toggles.get(room("W5N8"), "remotes"));
+        assert!(!toggles.get(room("W5N8"), "expand"));
+        assert_eq!(toggles.number(room("W5N8"), "wall_target"), 1e6);
+
+        // unknown toggles, and toggles of the other kind
+        assert_eq!(toggles.value(room("W5N8"), "nukes"), None);
+        assert!(!toggles.get(room("W5N8"), "nukes"));
+        assert!(!toggles.get(room("W5N8"), "wall_target"));
+        assert_eq!(toggles.number(room("W5N8"), "remotes"), 0.0);
+
+        // nothing stored in memory
+        assert!(toggles.update(&json!(null)).is_empty());
+        assert!(toggles.get(room("W5N8"), "remotes"));
+    }
+
+    #[test]
+    fn overrides() {
+        let mut toggles = toggles();
+        toggles.update(&json!({
+            "W5N8": { "remotes": false, "wall_target": 2e7 },
+            "E1S1": { "expand": true },
+        }));
+        assert!(!toggles.get(room("W5N8"), "remotes"));
+        assert_eq!(toggles.number(room("W5N8"), "wall_target"), 2e7);
+        assert!(!toggles.get(room("W5N8"), "expand"));
+        assert!(toggles.get(room("E1S1"), "expand"));
+        assert!(toggles.get(room("E1S1"), "remotes"));
+        assert!(toggles.get(room("W1N1"), "remotes"));
+
+        // invalid overrides are ignored
+        toggles.update(&json!({
+            "W5N8": { "remotes": 0, "wall_target": "many", "nukes": true },
+            "not a room": { "remotes": false },
+            "E1S1": false,
+        }));
+        assert!(toggles.get(room("W5N8"), "remotes"));
+        assert_eq!(toggles.number(room("W5N8"), "wall_target"), 1e6);
+        assert!(!toggles.get(room("E1S1"), "expand"));
+    }
+
+    #[test]
+    fn change_notification() {
+        let mut toggles = toggles();
+        let changes = toggles.update(&json!({ "W5N8": { "remotes": false } }));
+        assert_eq!(
+            changes,
+            &[ToggleChange {
+                room: room("W5N8"),
+                name: "remotes",
+                old: ToggleValue::Bool(true),
+                new: ToggleValue::Bool(false),
+            }]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            "toggle `remotes` in W5N8 changed from true to false"
+        );
+        assert!(toggles.changed(room("W5N8"), "remotes"));
+        assert!(!toggles.changed(room("W5N8"), "expand"));
+
+        // unchanged overrides, or ones equal to the default, aren't changes
+        let changes = toggles.update(&json!({
+            "W5N8": { "remotes": false },
+            "E1S1": { "remotes": true },
+        }));
+        assert!(changes.is_empty());
+        assert!(!toggles.changed(room("W5N8"), "remotes"));
+
+        // removing the override returns to the default
+        let changes = toggles.update(&json!({
+            "W5N8": { "wall_target": 5e6 },
+        }));
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            (changes[0].name, changes[0].old, changes[0].new),
+            ("remotes", false.into(), true.into())
+        );
+        assert_eq!(
+            (changes[1].name, changes[1].old, changes[1].new),
+            ("wall_target", 1e6.into(), 5e6.into())
+        );
+    }
+
+    #[test]
+    fn console_commands() {
+        assert_eq!(
+            console_set(room("W5N8"), "remotes", false.into()),
+            "Memory.__toggles = Memory.__toggles || {}; (Memory.__toggles[\"W5N8\"] = \
+             Memory.__toggles[\"W5N8\"] || {})[\"remotes\"] = false;"
+        );
+        assert!(console_set(room("W5N8"), "wall_target", 2.5e6.into()).ends_with("= 2500000.0;"));
+        assert_eq!(
+            console_reset(room("W5N8"), "remotes"),
+            "if (Memory.__toggles && Memory.__toggles[\"W5N8\"]) \
+             delete Memory.__toggles[\"W5N8\"][\"remotes\"];"
+        );
+    }
+}
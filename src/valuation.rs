@@ -0,0 +1,233 @@
+//! Valuing resources in terms of energy.
+//!
+//! Planners weighing different resources against each other, such as
+//! [`salvage::plan_with_values`][crate::salvage::plan_with_values] or market
+//! arbitrage, need a value for each resource. [`ResourceValues`] is a table of
+//! such values, derived by default from the cost of producing each resource:
+//! compounds are worth the resources reacting to produce them, and
+//! commodities the components their factory recipe consumes.
+//!
+//! ```no_run
+//! use screeps::{valuation::ResourceValues, ResourceType};
+//!
+//! let mut values = ResourceValues::new();
+//! // catalyst is scarce on this shard
+//! values.set(ResourceType::Catalyst, 5.0);
+//! let derived = ResourceValues::derived(|resource| match resource {
+//!     ResourceType::Catalyst => Some(5.0),
+//!     _ => ResourceValues::default_base_value(resource),
+//! });
+//! assert!(derived.value_of(ResourceType::CatalyzedGhodiumAcid) > 5.0);
+//! ```
+use std::collections::HashMap;
+
+use crate::{
+    constants::{MarketResourceType, ResourceCategory, ResourceType},
+    objects::HasStore,
+};
+
+/// Values of resources, in energy.
+///
+/// Resources without a value, such as intershard resources unless set, are
+/// worth nothing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResourceValues {
+    values: HashMap<MarketResourceType, f64>,
+}
+
+impl Default for ResourceValues {
+    fn default() -> Self {
+        Self::derived(Self::default_base_value)
+    }
+}
+
+impl ResourceValues {
+    /// Creates a table derived from [`ResourceValues::default_base_value`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty table, where every resource is worth nothing until
+    /// set.
+    pub fn empty() -> Self {
+        ResourceValues {
+            values: HashMap::new(),
+        }
+    }
+
+    /// The default value of resources which can't be produced from other
+    /// resources: 1 for energy, power, base minerals and deposit resources, as
+    /// if each unit cost as much as a unit of energy to harvest.
+    ///
+    /// Returns `None` for other resources.
+    pub fn default_base_value(resource: ResourceType) -> Option<f64> {
+        match resource.category() {
+            ResourceCategory::Energy
+            | ResourceCategory::Power
+            | ResourceCategory::BaseMineral
+            | ResourceCategory::Deposit => Some(1.0),
+            _ => None,
+        }
+    }
+
+    /// Creates a table from the values of base resources, deriving the value
+    /// of every other resource from its production cost.
+    ///
+    /// Resources for which `base_value` returns a value keep it. The others
+    /// are valued by their recipe once all of its inputs are valued:
+    ///
+    /// - lab compounds are worth the sum of the two resources reacting to
+    ///   produce them, as one unit of each produces one unit of the compound;
+    /// - factory commodities are worth the total value of their components,
+    ///   energy included, divided by the amount produced.
+    ///
+    /// Resources whose inputs can't all be valued are left without a value.
+    pub fn derived<F>(base_value: F) -> Self
+    where
+        F: Fn(ResourceType) -> Option<f64>,
+    {
+        let mut values: HashMap<ResourceType, f64> = ResourceType::iter_values()
+            .filter_map(|resource| base_value(resource).map(|value| (resource, value)))
+            .collect();
+
+        // a resource is valued once its inputs are, so repeat until nothing
+        // new can be valued
+        loop {
+            let mut changed = false;
+            for resource in ResourceType::iter_values() {
+                if values.contains_key(&resource) {
+                    continue;
+                }
+                if let Some(value) = production_cost(resource, &values) {
+                    values.insert(resource, value);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        ResourceValues {
+            values: values
+                .into_iter()
+                .map(|(resource, value)| (resource.into(), value))
+                .collect(),
+        }
+    }
+
+    /// Overrides the value of a single resource.
+    ///
+    /// Values derived from this resource aren't updated; use
+    /// [`ResourceValues::derived`] to change base values.
+    pub fn set<T: Into<MarketResourceType>>(&mut self, resource: T, value: f64) {
+        self.values.insert(resource.into(), value);
+    }
+
+    /// The value of a resource, or `None` if it has none.
+    pub fn get<T: Into<MarketResourceType>>(&self, resource: T) -> Option<f64> {
+        self.values.get(&resource.into()).copied()
+    }
+
+    /// The value of one unit of a resource, 0 if it has none.
+    pub fn value_of<T: Into<MarketResourceType>>(&self, resource: T) -> f64 {
+        self.get(resource).unwrap_or(0.0)
+    }
+
+    /// The total value of the given amounts of resources.
+    pub fn value_of_contents<I>(&self, contents: I) -> f64
+    where
+        I: IntoIterator<Item = (ResourceType, u32)>,
+    {
+        contents
+            .into_iter()
+            .map(|(resource, amount)| self.value_of(resource) * f64::from(amount))
+            .sum()
+    }
+
+    /// The total value of the resources in an object's store.
+    pub fn value_of_store<T>(&self, object: &T) -> f64
+    where
+        T: ?Sized + HasStore,
+    {
+        self.value_of_contents(
+            object
+                .store_types()
+                .into_iter()
+                .map(|resource| (resource, object.store_of(resource))),
+        )
+    }
+}
+
+/// The cost of producing one unit of `resource` in a lab or factory, or
+/// `None` if it has no recipe or some inputs have no value yet.
+fn production_cost(resource: ResourceType, values: &HashMap<ResourceType, f64>) -> Option<f64> {
+    if let Some([lhs, rhs]) = resource.reaction_components() {
+        return Some(values.get(&lhs)? + values.get(&rhs)?);
+    }
+    let recipe = resource.commodity_recipe()?;
+    let mut total = 0.0;
+    for (component, &amount) in &recipe.components {
+        total += values.get(component)? * f64::from(amount);
+    }
+    Some(total / f64::from(recipe.amount))
+}
+
+#[cfg(test)]
+mod test {
+    use super::ResourceValues;
+    use crate::constants::{IntershardResourceType, ResourceType::*};
+
+    #[test]
+    fn derived_values() {
+        let values = ResourceValues::new();
+        assert_eq!(values.value_of(Energy), 1.0);
+        assert_eq!(values.value_of(Keanium), 1.0);
+
+        // 500 utrium and 200 energy for 100 bars
+        assert_eq!(values.value_of(UtriumBar), 7.0);
+        // 600 energy for 50 batteries
+        assert_eq!(values.value_of(Battery), 12.0);
+        // ZK + UL, then G + H, then GH + OH, then X + GH2O
+        assert_eq!(values.value_of(Ghodium), 4.0);
+        assert_eq!(values.value_of(GhodiumAcid), 7.0);
+        assert_eq!(values.value_of(CatalyzedGhodiumAcid), 8.0);
+
+        // commodities several steps from deposits are valued as well
+        assert!(values.value_of(Wire) > values.value_of(Metal));
+        assert!(values.get(Device).is_some());
+
+        assert_eq!(values.get(Ops), None);
+        assert_eq!(values.value_of(IntershardResourceType::Pixel), 0.0);
+        assert_eq!(
+            values.value_of_contents(vec![(Energy, 100), (UtriumBar, 10)]),
+            170.0
+        );
+    }
+
+    #[test]
+    fn overridden_values() {
+        let scarce_catalyst = ResourceValues::derived(|resource| match resource {
+            Catalyst => Some(10.0),
+            _ => ResourceValues::default_base_value(resource),
+        });
+        assert_eq!(scarce_catalyst.value_of(CatalyzedGhodiumAcid), 17.0);
+        assert_eq!(scarce_catalyst.value_of(GhodiumAcid), 7.0);
+        // purifier is made from catalyst
+        assert!(scarce_catalyst.value_of(Purifier) > ResourceValues::new().value_of(Purifier));
+
+        let mut values = ResourceValues::new();
+        values.set(IntershardResourceType::Pixel, 20_000.0);
+        values.set(Ghodium, 2.0);
+        assert_eq!(values.value_of(IntershardResourceType::Pixel), 20_000.0);
+        assert_eq!(values.value_of(Ghodium), 2.0);
+        // derived values aren't affected by overrides
+        assert_eq!(values.value_of(GhodiumAcid), 7.0);
+
+        let mut empty = ResourceValues::empty();
+        assert_eq!(empty.get(Energy), None);
+        empty.set(Energy, 0.5);
+        assert_eq!(empty.value_of_contents(vec![(Energy, 10)]), 5.0);
+    }
+}
@@ -0,0 +1,310 @@
+//! Layout helpers for drawing dashboards with [`RoomVisual`].
+//!
+//! [`Table`] lays out rows of text in columns, and [`bar`] draws progress
+//! bars. Both only produce visuals inside the room's bounds, so dashboards
+//! placed near the bottom or right edge are clipped instead of drawn outside
+//! the room.
+use crate::objects::{RectStyle, RoomVisual, TextAlign, TextStyle, Visual};
+
+/// The largest coordinate visible in a room; tiles are centered on integer
+/// coordinates from 0 to 49.
+const ROOM_EDGE: f32 = 49.5;
+
+/// A table of left-aligned text laid out in fixed width columns.
+///
+/// Each call to [`Table::row`] lays out one row below the previous one. Rows
+/// and cells starting outside of the room are dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// use screeps::{visuals::Table, RoomVisual};
+///
+/// let mut table = Table::new((1.0, 1.0), &[6.0, 4.0]);
+/// table.row(&["room", "rcl"]).row(&["W1N1", "8"]).row(&["W2N1", "5"]);
+/// table.draw(&RoomVisual::new(None));
+/// ```
+#[derive(Clone)]
+pub struct Table {
+    origin: (f32, f32),
+    col_widths: Vec<f32>,
+    row_height: f32,
+    style: TextStyle,
+    next_y: f32,
+    visuals: Vec<Visual>,
+}
+
+impl Table {
+    /// Creates an empty table whose first row's text baseline is at
+    /// `origin`, with columns of the given widths.
+    pub fn new(origin: (f32, f32), col_widths: &[f32]) -> Self {
+        Table {
+            origin,
+            col_widths: col_widths.to_vec(),
+            row_height: 1.0,
+            style: TextStyle::default().align(TextAlign::Left),
+            next_y: origin.1,
+            visuals: Vec::new(),
+        }
+    }
+
+    /// Sets the distance between rows, 1 by default.
+    pub fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    /// Sets the style of the table's text. The text is always left-aligned
+    /// regardless of the alignment of `style`.
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.style = style.align(TextAlign::Left);
+        self
+    }
+
+    /// Adds a row of cells below the previous one.
+    ///
+    /// Cells beyond the number of columns are ignored.
+    pub fn row(&mut self, cells: &[&str]) -> &mut Self {
+        let y = self.next_y;
+        self.next_y += self.row_height;
+        if y > ROOM_EDGE {
+            return self;
+        }
+
+        let mut x = self.origin.0;
+        for (cell, width) in cells.iter().zip(&self.col_widths) {
+            if x > ROOM_EDGE {
+                break;
+            }
+            if !cell.is_empty() {
+                self.visuals.push(Visual::text(
+                    x,
+                    y,
+                    (*cell).to_owned(),
+                    Some(self.style.clone()),
+                ));
+            }
+            x += width;
+        }
+        self
+    }
+
+    /// The y coordinate of the next row.
+    pub fn next_y(&self) -> f32 {
+        self.next_y
+    }
+
+    /// The visuals laid out so far.
+    pub fn visuals(&self) -> &[Visual] {
+        &self.visuals
+    }
+
+    /// Draws the rows laid out so far.
+    pub fn draw(&self, visual: &RoomVisual) {
+        visual.draw_multi(&self.visuals);
+    }
+}
+
+/// Style of a progress bar drawn by [`bar`].
+#[derive(Clone)]
+pub struct BarStyle {
+    height: f32,
+    background: RectStyle,
+    fill: RectStyle,
+}
+
+impl Default for BarStyle {
+    fn default() -> Self {
+        BarStyle {
+            height: 0.4,
+            background: RectStyle::default().fill("#555555").opacity(0.5),
+            fill: RectStyle::default().fill("#44bb44").opacity(0.8),
+        }
+    }
+}
+
+impl BarStyle {
+    /// Sets the height of the bar, 0.4 by default.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the style of the bar's background, covering its full width.
+    pub fn background(mut self, style: RectStyle) -> Self {
+        self.background = style;
+        self
+    }
+
+    /// Sets the style of the bar's filled portion.
+    pub fn fill(mut self, style: RectStyle) -> Self {
+        self.fill = style;
+        self
+    }
+}
+
+/// Lays out a progress bar with its top left corner at `origin`, filled from
+/// the left up to `fraction` of its `width`.
+///
+/// `fraction` is clamped between 0 and 1. The bar is clipped at the right
+/// edge of the room, and nothing is laid out if it starts outside the room.
+pub fn bar_visuals(origin: (f32, f32), width: f32, fraction: f32, style: &BarStyle) -> Vec<Visual> {
+    let (x, y) = origin;
+    if x > ROOM_EDGE || y > ROOM_EDGE || width <= 0.0 {
+        return Vec::new();
+    }
+    let fraction = if fraction.is_nan() {
+        0.0
+    } else {
+        fraction.clamp(0.0, 1.0)
+    };
+    let visible_width = width.min(ROOM_EDGE - x);
+    let filled_width = (width * fraction).min(visible_width);
+
+    let mut visuals = vec![Visual::rect(
+        x,
+        y,
+        visible_width,
+        style.height,
+        Some(style.background.clone()),
+    )];
+    if filled_width > 0.0 {
+        visuals.push(Visual::rect(
+            x,
+            y,
+            filled_width,
+            style.height,
+            Some(style.fill.clone()),
+        ));
+    }
+    visuals
+}
+
+/// Draws a progress bar, such as spawning or controller progress.
+///
+/// See [`bar_visuals`].
+pub fn bar(visual: &RoomVisual, origin: (f32, f32), width: f32, fraction: f32, style: &BarStyle) {
+    visual.draw_multi(&bar_visuals(origin, width, fraction, style));
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::{bar_visuals, BarStyle, Table};
+    use crate::objects::{RectStyle, TextStyle, Visual};
+
+    fn positions(visuals: &[Visual]) -> Vec<Value> {
+        visuals
+            .iter()
+            .map(|visual| {
+                let value = serde_json::to_value(visual).unwrap();
+                match value["t"].as_str().unwrap() {
+                    "t" => json!([value["text"], value["x"], value["y"]]),
+                    _ => json!([value["x"], value["y"], value["w"], value["h"]]),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn table_layout() {
+        let mut table = Table::new((1.0, 2.0), &[5.0, 3.0, 3.0]);
+        table
+            .row(&["room", "rcl", "energy"])
+            .row(&["W1N1", "8"])
+            .row(&["W12N3", "", "3000", "ignored"]);
+
+        assert_eq!(
+            positions(table.visuals()),
+            vec![
+                json!(["room", 1.0, 2.0]),
+                json!(["rcl", 6.0, 2.0]),
+                json!(["energy", 9.0, 2.0]),
+                json!(["W1N1", 1.0, 3.0]),
+                json!(["8", 6.0, 3.0]),
+                json!(["W12N3", 1.0, 4.0]),
+                json!(["3000", 9.0, 4.0]),
+            ]
+        );
+        assert_eq!(table.next_y(), 5.0);
+
+        let style = serde_json::to_value(&table.visuals()[0]).unwrap()["s"].clone();
+        assert_eq!(style, json!({ "align": "left" }));
+    }
+
+    #[test]
+    fn table_clipping() {
+        let mut table = Table::new((40.0, 47.0), &[6.0, 6.0])
+            .row_height(1.5)
+            .style(TextStyle::default().color("#ffffff"));
+        for _ in 0..4 {
+            table.row(&["a", "b"]);
+        }
+
+        // the second column starts at x = 46, rows start at 47, 48.5, 50, 51.5
+        assert_eq!(
+            positions(table.visuals()),
+            vec![
+                json!(["a", 40.0, 47.0]),
+                json!(["b", 46.0, 47.0]),
+                json!(["a", 40.0, 48.5]),
+                json!(["b", 46.0, 48.5]),
+            ]
+        );
+        let style = serde_json::to_value(&table.visuals()[0]).unwrap()["s"].clone();
+        assert_eq!(style, json!({ "color": "#ffffff", "align": "left" }));
+
+        let mut narrow = Table::new((45.0, 10.0), &[5.0, 5.0]);
+        narrow.row(&["left", "clipped"]);
+        assert_eq!(
+            positions(narrow.visuals()),
+            vec![json!(["left", 45.0, 10.0])]
+        );
+    }
+
+    #[test]
+    fn bar_layout() {
+        let style = BarStyle::default().height(0.5);
+
+        assert_eq!(
+            positions(&bar_visuals((10.0, 5.0), 4.0, 0.25, &style)),
+            vec![json!([10.0, 5.0, 4.0, 0.5]), json!([10.0, 5.0, 1.0, 0.5])]
+        );
+        // overfilled bars are clamped, empty bars only draw the background
+        assert_eq!(
+            positions(&bar_visuals((10.0, 5.0), 4.0, 1.5, &style)),
+            vec![json!([10.0, 5.0, 4.0, 0.5]), json!([10.0, 5.0, 4.0, 0.5])]
+        );
+        assert_eq!(
+            positions(&bar_visuals((10.0, 5.0), 4.0, -1.0, &style)),
+            vec![json!([10.0, 5.0, 4.0, 0.5])]
+        );
+        // clipped at the right edge of the room
+        assert_eq!(
+            positions(&bar_visuals((47.5, 5.0), 4.0, 0.75, &style)),
+            vec![json!([47.5, 5.0, 2.0, 0.5]), json!([47.5, 5.0, 2.0, 0.5])]
+        );
+        assert!(bar_visuals((50.0, 5.0), 4.0, 0.5, &style).is_empty());
+    }
+
+    #[test]
+    fn bar_style() {
+        let style = BarStyle::default()
+            .background(RectStyle::default().fill("#000000"))
+            .fill(RectStyle::default().fill("#ff0000").opacity(0.5));
+        let visuals = bar_visuals((0.0, 0.0), 10.0, 0.5, &style);
+
+        let styles: Vec<Value> = visuals
+            .iter()
+            .map(|visual| serde_json::to_value(visual).unwrap()["s"].clone())
+            .collect();
+        assert_eq!(
+            styles,
+            vec![
+                json!({ "fill": "#000000" }),
+                json!({ "fill": "#ff0000", "opacity": 0.5 }),
+            ]
+        );
+    }
+}